@@ -5,7 +5,7 @@ use lemmy_api_utils::{
   context::LemmyContext,
   notify::notify_mod_action,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::check_community_mod_action,
+  utils::{check_community_mod_action, check_expire_time},
 };
 use lemmy_db_schema::source::{
   comment::Comment,
@@ -26,6 +26,11 @@ pub async fn lock_comment(
   let comment_id = data.comment_id;
   let local_instance_id = local_user_view.person.instance_id;
   let locked = data.locked;
+  let expires_at = if locked {
+    check_expire_time(data.expires_at)?
+  } else {
+    None
+  };
 
   let orig_comment =
     CommentView::read(&mut context.pool(), comment_id, None, local_instance_id).await?;
@@ -51,6 +56,7 @@ pub async fn lock_comment(
     comment,
     orig_comment.community.id,
     locked,
+    expires_at,
     &data.reason,
   );
   let action = Modlog::create(&mut context.pool(), &[form]).await?;
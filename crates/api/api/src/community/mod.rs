@@ -22,6 +22,7 @@ pub mod follow;
 pub mod multi_community_follow;
 pub mod pending_follows;
 pub mod random;
+pub mod rule;
 pub mod tag;
 pub mod transfer;
 pub mod update_notifications;
@@ -0,0 +1,152 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use chrono::Utc;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{check_community_mod_action, is_admin, slur_regex},
+};
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::{
+    community::Community,
+    rule::{Rule, RuleInsertForm, RuleUpdateForm},
+  },
+};
+use lemmy_db_views_community::api::{CreateRule, DeleteRule, EditRule};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::SiteView;
+use lemmy_diesel_utils::{traits::Crud, utils::diesel_string_update};
+use lemmy_utils::{
+  error::LemmyResult,
+  utils::{
+    slurs::check_slurs,
+    validation::{check_api_elements_count, is_valid_body_field, is_valid_post_title},
+  },
+};
+use url::Url;
+use uuid::Uuid;
+
+pub async fn create_rule(
+  Json(data): Json<CreateRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<Rule>> {
+  let community = check_rule_permission(data.community_id, &local_user_view, &context).await?;
+  check_rule_fields(Some(&data.title), data.description.as_deref(), &context).await?;
+
+  let existing = Rule::list(&mut context.pool(), data.community_id).await?;
+  check_api_elements_count(existing.len())?;
+
+  let parent_ap_id = match &community {
+    Some(community) => community.ap_id.clone(),
+    None => SiteView::read_local(&mut context.pool()).await?.site.ap_id,
+  };
+  let ap_id = Url::parse(&format!("{}/rule/{}", parent_ap_id, Uuid::new_v4()))?;
+
+  let rule_form = RuleInsertForm {
+    description: data.description.clone(),
+    position: data.position,
+    ..RuleInsertForm::new(ap_id.into(), data.community_id, data.title.clone())
+  };
+  let rule = Rule::create(&mut context.pool(), &rule_form).await?;
+
+  send_update_community(community, &local_user_view, &context)?;
+
+  Ok(Json(rule))
+}
+
+pub async fn edit_rule(
+  Json(data): Json<EditRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<Rule>> {
+  let rule = Rule::read(&mut context.pool(), data.rule_id).await?;
+  let community = check_rule_permission(rule.community_id, &local_user_view, &context).await?;
+  check_rule_fields(data.title.as_deref(), data.description.as_deref(), &context).await?;
+
+  let rule_form = RuleUpdateForm {
+    title: data.title.clone(),
+    description: diesel_string_update(data.description.as_deref()),
+    position: data.position,
+    updated_at: Some(Some(Utc::now())),
+    ..Default::default()
+  };
+  let rule = Rule::update(&mut context.pool(), data.rule_id, &rule_form).await?;
+
+  send_update_community(community, &local_user_view, &context)?;
+
+  Ok(Json(rule))
+}
+
+pub async fn delete_rule(
+  Json(data): Json<DeleteRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<Rule>> {
+  let rule = Rule::read(&mut context.pool(), data.rule_id).await?;
+  let community = check_rule_permission(rule.community_id, &local_user_view, &context).await?;
+
+  // Soft delete the rule, so that existing reports and modlog entries can still reference it
+  let rule_form = RuleUpdateForm {
+    updated_at: Some(Some(Utc::now())),
+    deleted: Some(data.delete),
+    ..Default::default()
+  };
+  let rule = Rule::update(&mut context.pool(), data.rule_id, &rule_form).await?;
+
+  send_update_community(community, &local_user_view, &context)?;
+
+  Ok(Json(rule))
+}
+
+/// Community rules can be managed by community mods, instance rules only by admins.
+async fn check_rule_permission(
+  community_id: Option<CommunityId>,
+  local_user_view: &LocalUserView,
+  context: &LemmyContext,
+) -> LemmyResult<Option<Community>> {
+  match community_id {
+    Some(community_id) => {
+      let community = Community::read(&mut context.pool(), community_id).await?;
+      check_community_mod_action(local_user_view, &community, false, &mut context.pool()).await?;
+      Ok(Some(community))
+    }
+    None => {
+      is_admin(local_user_view)?;
+      Ok(None)
+    }
+  }
+}
+
+async fn check_rule_fields(
+  title: Option<&str>,
+  description: Option<&str>,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let slur_regex = slur_regex(context).await?;
+  if let Some(title) = title {
+    is_valid_post_title(title)?;
+    check_slurs(title, &slur_regex)?;
+  }
+  if let Some(description) = description {
+    is_valid_body_field(description, false)?;
+    check_slurs(description, &slur_regex)?;
+  }
+  Ok(())
+}
+
+/// Community rules are federated as part of the group, so send an update for it.
+fn send_update_community(
+  community: Option<Community>,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  if let Some(community) = community {
+    ActivityChannel::submit_activity(
+      SendActivityData::UpdateCommunity(local_user_view.person.clone(), community),
+      context,
+    )?;
+  }
+  Ok(())
+}
@@ -5,7 +5,7 @@ use lemmy_api_utils::{
   context::LemmyContext,
   utils::{check_private_instance, is_mod_or_admin_opt, read_site_for_actor},
 };
-use lemmy_db_schema::source::actor_language::CommunityLanguage;
+use lemmy_db_schema::source::{actor_language::CommunityLanguage, rule::Rule};
 use lemmy_db_views_community::{
   CommunityView,
   api::{GetCommunity, GetCommunityResponse},
@@ -56,11 +56,13 @@ pub async fn get_community(
 
   let community_id = community_view.community.id;
   let discussion_languages = CommunityLanguage::read(&mut context.pool(), community_id).await?;
+  let rules = Rule::list(&mut context.pool(), Some(community_id)).await?;
 
   Ok(Json(GetCommunityResponse {
     community_view,
     site,
     moderators,
     discussion_languages,
+    rules,
   }))
 }
@@ -1,5 +1,6 @@
 use activitypub_federation::{config::Data, fetch::object_id::ObjectId, traits::Object};
 use actix_web::web::Json;
+use chrono::Utc;
 use futures::{StreamExt, future::try_join_all};
 use itertools::Itertools;
 use lemmy_api_utils::{context::LemmyContext, utils::check_local_user_valid};
@@ -81,6 +82,11 @@ pub async fn import_settings(
       .settings
       .as_ref()
       .map(|s| s.send_notifications_to_email),
+    email_notification_frequency: data
+      .settings
+      .as_ref()
+      .map(|s| s.email_notification_frequency),
+    last_email_digest_at: data.settings.as_ref().map(|_| Utc::now()),
     show_bot_accounts: data.settings.as_ref().map(|s| s.show_bot_accounts),
     show_read_posts: data.settings.as_ref().map(|s| s.show_read_posts),
     open_links_in_new_tab: data.settings.as_ref().map(|s| s.open_links_in_new_tab),
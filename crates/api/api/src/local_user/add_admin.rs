@@ -3,7 +3,9 @@ use lemmy_api_utils::{context::LemmyContext, notify::notify_mod_action, utils::i
 use lemmy_db_schema::source::{
   local_user::{LocalUser, LocalUserUpdateForm},
   modlog::{Modlog, ModlogInsertForm},
+  person::Person,
 };
+use lemmy_db_schema_file::PersonId;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_person::{
   PersonView,
@@ -38,34 +40,47 @@ pub async fn add_admin(
     }
   }
 
+  set_admin(
+    data.person_id,
+    data.added,
+    &local_user_view.person,
+    &context,
+  )
+  .await?;
+
+  let admins = PersonView::list_admins(
+    Some(my_person_id),
+    local_user_view.person.instance_id,
+    &mut context.pool(),
+  )
+  .await?;
+
+  Ok(Json(AddAdminResponse { admins }))
+}
+
+/// Adds or removes an admin, without permission checks. Also used by the admin command line.
+pub async fn set_admin(
+  person_id: PersonId,
+  added: bool,
+  moderator: &Person,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
   // Make sure that the person_id added is local
-  let added_local_user = LocalUserView::read_person(&mut context.pool(), data.person_id).await?;
+  let added_local_user = LocalUserView::read_person(&mut context.pool(), person_id).await?;
 
   LocalUser::update(
     &mut context.pool(),
     added_local_user.local_user.id,
     &LocalUserUpdateForm {
-      admin: Some(data.added),
+      admin: Some(added),
       ..Default::default()
     },
   )
   .await?;
 
   // Mod tables
-  let form = ModlogInsertForm::admin_add(
-    &local_user_view.person,
-    added_local_user.person.id,
-    !data.added,
-  );
+  let form = ModlogInsertForm::admin_add(moderator, added_local_user.person.id, !added);
   let action = Modlog::create(&mut context.pool(), &[form]).await?;
-  notify_mod_action(action.clone(), &context);
-
-  let admins = PersonView::list_admins(
-    Some(my_person_id),
-    local_user_view.person.instance_id,
-    &mut context.pool(),
-  )
-  .await?;
-
-  Ok(Json(AddAdminResponse { admins }))
+  notify_mod_action(action, context);
+  Ok(())
 }
@@ -11,6 +11,7 @@ use lemmy_db_schema::{
     instance::{InstanceActions, InstanceBanForm},
     local_user::LocalUser,
     modlog::{Modlog, ModlogInsertForm},
+    person::Person,
   },
   traits::Bannable,
 };
@@ -19,6 +20,7 @@ use lemmy_db_views_person::{
   PersonView,
   api::{BanPerson, PersonResponse},
 };
+use lemmy_diesel_utils::traits::Crud;
 use lemmy_utils::{
   error::{LemmyErrorType, LemmyResult},
   utils::validation::is_valid_body_field,
@@ -38,15 +40,32 @@ pub async fn ban_from_site(
   // Also make sure you're a higher admin than the target
   LocalUser::is_higher_admin_check(&mut context.pool(), my_person_id, vec![data.person_id]).await?;
 
+  ban_person_from_site(&data, local_user_view.person, &context).await?;
+
+  let person_view = PersonView::read(
+    &mut context.pool(),
+    data.person_id,
+    Some(my_person_id),
+    local_instance_id,
+    true,
+  )
+  .await?;
+
+  Ok(Json(PersonResponse { person_view }))
+}
+
+/// Bans or unbans a person from the local instance, without permission checks. Also used by the
+/// admin command line.
+pub async fn ban_person_from_site(
+  data: &BanPerson,
+  moderator: Person,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
   is_valid_body_field(&data.reason, false)?;
 
   let expires_at = check_expire_time(data.expires_at)?;
 
-  let form = InstanceBanForm::new(
-    data.person_id,
-    local_user_view.person.instance_id,
-    expires_at,
-  );
+  let form = InstanceBanForm::new(data.person_id, moderator.instance_id, expires_at);
   if data.ban {
     InstanceActions::ban(&mut context.pool(), &form).await?;
   } else {
@@ -55,49 +74,41 @@ pub async fn ban_from_site(
 
   // Mod tables - create ban entry first so bulk actions can reference it as parent
   let form = ModlogInsertForm::admin_ban(
-    &local_user_view.person,
+    &moderator,
     data.person_id,
     data.ban,
     expires_at,
     &data.reason,
   );
   let action = Modlog::create(&mut context.pool(), &[form]).await?;
-  notify_mod_action(action.clone(), &context);
+  notify_mod_action(action.clone(), context);
 
   // Remove their data if that's desired
   if data.remove_or_restore_data.unwrap_or(false) {
     let removed = data.ban;
     remove_or_restore_user_data(
-      my_person_id,
+      moderator.id,
       data.person_id,
       removed,
       &data.reason,
       action.first().ok_or(LemmyErrorType::NotFound)?.id,
-      &context,
+      context,
     )
     .await?;
   };
 
-  let person_view = PersonView::read(
-    &mut context.pool(),
-    data.person_id,
-    Some(my_person_id),
-    local_instance_id,
-    true,
-  )
-  .await?;
-
+  let banned_user = Person::read(&mut context.pool(), data.person_id).await?;
   ActivityChannel::submit_activity(
     SendActivityData::BanFromSite {
-      moderator: local_user_view.person,
-      banned_user: person_view.person.clone(),
+      moderator,
+      banned_user,
       reason: data.reason.clone(),
       remove_or_restore_data: data.remove_or_restore_data,
       ban: data.ban,
       expires_at: data.expires_at,
     },
-    &context,
+    context,
   )?;
 
-  Ok(Json(PersonResponse { person_view }))
+  Ok(())
 }
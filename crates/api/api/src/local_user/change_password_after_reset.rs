@@ -1,9 +1,12 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_utils::{context::LemmyContext, utils::password_length_check};
-use lemmy_db_schema::source::{
-  local_user::LocalUser,
-  login_token::LoginToken,
-  password_reset_request::PasswordResetRequest,
+use lemmy_db_schema::{
+  newtypes::LocalUserId,
+  source::{
+    local_user::LocalUser,
+    login_token::LoginToken,
+    password_reset_request::PasswordResetRequest,
+  },
 };
 use lemmy_db_views_site::api::{PasswordChangeAfterReset, SuccessResponse};
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
@@ -18,18 +21,27 @@ pub async fn change_password_after_reset(
     .await?
     .local_user_id;
 
-  password_length_check(&data.password)?;
-
   // Make sure passwords match
   if data.password != data.password_verify {
     return Err(LemmyErrorType::PasswordsDoNotMatch.into());
   }
 
+  set_password(local_user_id, &data.password, &context).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
+
+/// Updates the password and logs out all sessions. Also used by the admin command line.
+pub async fn set_password(
+  local_user_id: LocalUserId,
+  password: &str,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  password_length_check(password)?;
+
   // Update the user with the new password
-  let password = data.password.clone();
-  LocalUser::update_password(&mut context.pool(), local_user_id, &password).await?;
+  LocalUser::update_password(&mut context.pool(), local_user_id, password).await?;
 
   LoginToken::invalidate_all(&mut context.pool(), local_user_id).await?;
-
-  Ok(Json(SuccessResponse::default()))
+  Ok(())
 }
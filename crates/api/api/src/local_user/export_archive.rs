@@ -0,0 +1,560 @@
+use crate::local_user::export_data::build_export_data;
+use activitypub_federation::config::Data;
+use actix_web::{
+  HttpResponse,
+  http::header::{ContentDisposition, DispositionParam, DispositionType},
+  web::{Bytes, Json, Query},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::stream::try_unfold;
+use hmac::{Hmac, Mac};
+use lemmy_api_utils::{context::LemmyContext, request::read_original_image};
+use lemmy_db_schema::{
+  newtypes::DataExportId,
+  source::{
+    community::CommunityActions,
+    data_export::{DataExport, DataExportInsertForm},
+    login_token::LoginToken,
+    post::PostActions,
+    private_message::PrivateMessage,
+    secret::Secret,
+  },
+};
+use lemmy_db_schema_file::enums::{
+  CommunityNotificationsMode,
+  EmailNotificationFrequency,
+  PostNotificationsMode,
+};
+use lemmy_db_views_local_image::LocalImageView;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_person_liked_combined::impls::PersonLikedCombinedQuery;
+use lemmy_db_views_post_comment_combined::PostCommentCombinedView;
+use lemmy_db_views_site::api::{DataExportResponse, DownloadDataExport};
+use lemmy_email::account::send_data_export_email;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  spawn_try_task,
+};
+use serde::Serialize;
+use sha2::Sha256;
+use std::{fmt::Write as _, fs::File, io::Write, path::PathBuf};
+use tokio::{io::AsyncReadExt, task::spawn_blocking};
+use tracing::{info, warn};
+use url::Url;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+/// A new export can only be requested once the previous one is this old, unless it failed.
+const REQUEST_INTERVAL: TimeDelta = TimeDelta::days(1);
+
+/// Exports run in the background of the server process, so one which is still incomplete after
+/// this long was interrupted, for example by a restart.
+const INTERRUPTED_AFTER: TimeDelta = TimeDelta::hours(1);
+
+/// How long a download link stays valid.
+const DOWNLOAD_LINK_VALIDITY: TimeDelta = TimeDelta::days(1);
+
+/// Chunk size for streaming the archive to the client.
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Starts building an archive with all your data in the background. You get an email once it is
+/// ready, the status can also be checked with [`get_data_export`].
+pub async fn create_data_export(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<DataExportResponse>> {
+  let local_user_id = local_user_view.local_user.id;
+  let latest = DataExport::read_latest(&mut context.pool(), local_user_id).await?;
+  if let Some(latest) = latest
+    && latest.error.is_none()
+    && latest.published_at > Utc::now() - REQUEST_INTERVAL
+  {
+    if latest.completed_at.is_some() || latest.published_at > Utc::now() - INTERRUPTED_AFTER {
+      return Err(LemmyErrorType::DataExportAlreadyRequested.into());
+    }
+    // Treat it as failed, so that it isn't shown as running forever
+    let error = Some("Export was interrupted".to_string());
+    DataExport::finish(&mut context.pool(), latest.id, error).await?;
+  }
+
+  let form = DataExportInsertForm::new(local_user_id);
+  let data_export = DataExport::create(&mut context.pool(), &form).await?;
+
+  let data_export_ = data_export.clone();
+  spawn_try_task(async move { run_data_export(data_export_, local_user_view, &context).await });
+
+  Ok(Json(DataExportResponse {
+    data_export: Some(data_export),
+    download_url: None,
+  }))
+}
+
+/// Returns your latest data export, with a fresh download link if the archive is ready.
+pub async fn get_data_export(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<DataExportResponse>> {
+  let data_export =
+    DataExport::read_latest(&mut context.pool(), local_user_view.local_user.id).await?;
+  let download_url = data_export
+    .as_ref()
+    .filter(|e| e.is_ready())
+    .map(|e| download_url(e.id, &context))
+    .transpose()?;
+
+  Ok(Json(DataExportResponse {
+    data_export,
+    download_url,
+  }))
+}
+
+/// Serves the archive for a signed download link. This works without login, so that the link from
+/// the email can be opened directly.
+pub async fn download_data_export(
+  Query(params): Query<DownloadDataExport>,
+  context: Data<LemmyContext>,
+) -> LemmyResult<HttpResponse> {
+  verify_download(&params, context.secret())?;
+  let data_export = DataExport::read(&mut context.pool(), params.id).await?;
+  if !data_export.is_ready() {
+    return Err(LemmyErrorType::NotFound.into());
+  }
+
+  let file = tokio::fs::File::open(data_export.archive_path(context.settings())).await?;
+  let len = file.metadata().await?.len();
+  let body = try_unfold(file, |mut file| async move {
+    let mut buf = vec![0; DOWNLOAD_CHUNK_SIZE];
+    let read = file.read(&mut buf).await?;
+    if read == 0 {
+      return Ok(None);
+    }
+    buf.truncate(read);
+    Ok::<_, std::io::Error>(Some((Bytes::from(buf), file)))
+  });
+
+  let filename = format!(
+    "{}-data-export-{}.zip",
+    context.settings().hostname,
+    data_export.id.0
+  );
+  Ok(
+    HttpResponse::Ok()
+      .content_type("application/zip")
+      .insert_header(ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(filename)],
+      })
+      .no_chunking(len)
+      .streaming(body),
+  )
+}
+
+async fn run_data_export(
+  data_export: DataExport,
+  local_user_view: LocalUserView,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  info!("Starting data export for {}", local_user_view.person.name);
+  let path = data_export.archive_path(context.settings());
+  let res = write_archive(path.clone(), &local_user_view, context).await;
+  if res.is_err() {
+    tokio::fs::remove_file(&path).await.ok();
+  }
+  let error = res.as_ref().err().map(ToString::to_string);
+  DataExport::finish(&mut context.pool(), data_export.id, error).await?;
+  res?;
+
+  info!("Finished data export for {}", local_user_view.person.name);
+  if local_user_view.local_user.email.is_some() {
+    let url = download_url(data_export.id, context)?;
+    send_data_export_email(
+      &local_user_view,
+      url.as_str(),
+      &mut context.pool(),
+      context.settings(),
+    )
+    .await?;
+  }
+  Ok(())
+}
+
+#[derive(Serialize)]
+struct Vote {
+  ap_id: Url,
+  upvote: bool,
+  voted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct NotificationSettings {
+  send_notifications_to_email: bool,
+  email_notification_frequency: EmailNotificationFrequency,
+  communities: Vec<CommunityNotifications>,
+  posts: Vec<PostNotifications>,
+}
+
+#[derive(Serialize)]
+struct CommunityNotifications {
+  community: Url,
+  mode: CommunityNotificationsMode,
+}
+
+#[derive(Serialize)]
+struct PostNotifications {
+  post: Url,
+  mode: PostNotificationsMode,
+}
+
+/// JSON files in the archive, in the order in which they are written, with a description for the
+/// index page.
+const JSON_FILES: [(&str, &str); 5] = [
+  (
+    "data.json",
+    "Your posts and comments, notifications, liked and read posts, moderated communities and \
+     account settings",
+  ),
+  ("votes.json", "Your votes on posts and comments"),
+  (
+    "private_messages.json",
+    "Private messages which you sent or received",
+  ),
+  (
+    "logins.json",
+    "Active logins with their IP address and browser",
+  ),
+  (
+    "notification_settings.json",
+    "Email notifications and notification settings for communities and posts",
+  ),
+];
+
+async fn write_archive(
+  path: PathBuf,
+  local_user_view: &LocalUserView,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let local_user = &local_user_view.local_user;
+  let person_id = local_user_view.person.id;
+  let pool = &mut context.pool();
+
+  let data = build_export_data(local_user_view.clone(), context).await?;
+
+  let votes: Vec<_> = PersonLikedCombinedQuery {
+    no_limit: Some(true),
+    ..PersonLikedCombinedQuery::default()
+  }
+  .list(pool, local_user_view)
+  .await?
+  .into_iter()
+  .filter_map(|u| match u {
+    PostCommentCombinedView::Post(pv) => {
+      let actions = pv.post_actions?;
+      Some(Vote {
+        ap_id: pv.post.ap_id.into(),
+        upvote: actions.vote_is_upvote?,
+        voted_at: actions.voted_at,
+      })
+    }
+    PostCommentCombinedView::Comment(cv) => {
+      let actions = cv.comment_actions?;
+      Some(Vote {
+        ap_id: cv.comment.ap_id.into(),
+        upvote: actions.vote_is_upvote?,
+        voted_at: actions.voted_at,
+      })
+    }
+  })
+  .collect();
+
+  let private_messages = PrivateMessage::list_for_person(pool, person_id).await?;
+
+  // Login tokens themselves are not serialized
+  let logins = LoginToken::list(pool, local_user.id).await?;
+
+  let notification_settings = NotificationSettings {
+    send_notifications_to_email: local_user.send_notifications_to_email,
+    email_notification_frequency: local_user.email_notification_frequency,
+    communities: CommunityActions::list_notification_states(person_id, pool)
+      .await?
+      .into_iter()
+      .map(|(community, mode)| CommunityNotifications {
+        community: community.into(),
+        mode,
+      })
+      .collect(),
+    posts: PostActions::list_notification_states(person_id, pool)
+      .await?
+      .into_iter()
+      .map(|(post, mode)| PostNotifications {
+        post: post.into(),
+        mode,
+      })
+      .collect(),
+  };
+
+  // Thumbnails are generated from links in posts, so only include images which were uploaded
+  let images = LocalImageView::get_all_by_person_id(pool, person_id)
+    .await?
+    .into_iter()
+    .filter(|i| i.local_image.thumbnail_for_post_id.is_none());
+
+  if let Some(dir) = path.parent() {
+    tokio::fs::create_dir_all(dir).await?;
+  }
+  let mut zip = spawn_blocking(move || File::create(path).map(ZipWriter::new)).await??;
+  let json_files = [
+    serde_json::to_vec_pretty(&data)?,
+    serde_json::to_vec_pretty(&votes)?,
+    serde_json::to_vec_pretty(&private_messages)?,
+    serde_json::to_vec_pretty(&logins)?,
+    serde_json::to_vec_pretty(&notification_settings)?,
+  ];
+  for ((name, _), data) in JSON_FILES.iter().zip(json_files) {
+    zip = add_file(zip, name.to_string(), data, CompressionMethod::Deflated).await?;
+  }
+
+  let mut image_files = vec![];
+  for image in images {
+    let alias = image.local_image.pictrs_alias;
+    match read_original_image(&alias, context).await {
+      Ok(Some(data)) => {
+        let name = format!("images/{alias}");
+        // Images are already compressed
+        zip = add_file(zip, name.clone(), data.to_vec(), CompressionMethod::Stored).await?;
+        image_files.push(name);
+      }
+      Ok(None) => warn!("Image {alias} for data export not found"),
+      Err(e) => warn!("Failed to read image {alias} for data export: {e}"),
+    }
+  }
+
+  let index = index_html(local_user_view, &image_files, context);
+  zip = add_file(
+    zip,
+    "index.html".to_string(),
+    index.into_bytes(),
+    CompressionMethod::Deflated,
+  )
+  .await?;
+  spawn_blocking(move || zip.finish()).await??;
+  Ok(())
+}
+
+/// Writes a file into the archive. File IO is blocking, so this runs on a separate thread.
+async fn add_file(
+  mut zip: ZipWriter<File>,
+  name: String,
+  data: Vec<u8>,
+  compression: CompressionMethod,
+) -> LemmyResult<ZipWriter<File>> {
+  spawn_blocking(move || {
+    let options = SimpleFileOptions::default().compression_method(compression);
+    zip.start_file(name, options)?;
+    zip.write_all(&data)?;
+    LemmyResult::Ok(zip)
+  })
+  .await?
+}
+
+/// Overview page which links to all files in the archive.
+fn index_html(
+  local_user_view: &LocalUserView,
+  image_files: &[String],
+  context: &LemmyContext,
+) -> String {
+  let mut files = JSON_FILES
+    .iter()
+    .fold(String::new(), |mut out, (name, description)| {
+      let _ = write!(out, "<li><a href=\"{name}\">{name}</a>: {description}</li>");
+      out
+    });
+  let images = image_files.iter().fold(String::new(), |mut out, name| {
+    let name = escape_html(name);
+    let _ = write!(out, "<li><a href=\"{name}\">{name}</a></li>");
+    out
+  });
+  if !images.is_empty() {
+    let _ = write!(files, "<li>Uploaded images:<ul>{images}</ul></li>");
+  }
+  format!(
+    "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Data export</title></head><body>\
+     <h1>Data export for {} from {}</h1><p>Created at {}</p><ul>{files}</ul></body></html>",
+    local_user_view.person.name,
+    context.settings().hostname,
+    Utc::now().to_rfc2822()
+  )
+}
+
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// Builds a download link for the archive, which is signed with the instance secret.
+fn download_url(id: DataExportId, context: &LemmyContext) -> LemmyResult<Url> {
+  let expires = (Utc::now() + DOWNLOAD_LINK_VALIDITY).timestamp();
+  let signature = signature(id, expires, context.secret());
+  let mut url = Url::parse(&format!(
+    "{}/api/v4/account/data/export/archive/download",
+    context.settings().get_protocol_and_hostname()
+  ))?;
+  url
+    .query_pairs_mut()
+    .append_pair("id", &id.0.to_string())
+    .append_pair("expires", &expires.to_string())
+    .append_pair("signature", &signature);
+  Ok(url)
+}
+
+fn verify_download(params: &DownloadDataExport, secret: &Secret) -> LemmyResult<()> {
+  if params.expires < Utc::now().timestamp() {
+    return Err(LemmyErrorType::InvalidDataExportLink.into());
+  }
+  let signature = decode_hex(&params.signature).ok_or(LemmyErrorType::InvalidDataExportLink)?;
+  sign(params.id, params.expires, secret)
+    .verify_slice(&signature)
+    .map_err(|_| LemmyErrorType::InvalidDataExportLink)?;
+  Ok(())
+}
+
+/// Hex encoded signature for a download link.
+fn signature(id: DataExportId, expires: i64, secret: &Secret) -> String {
+  sign(id, expires, secret)
+    .finalize()
+    .into_bytes()
+    .iter()
+    .fold(String::new(), |mut out, b| {
+      let _ = write!(out, "{b:02x}");
+      out
+    })
+}
+
+fn sign(id: DataExportId, expires: i64, secret: &Secret) -> Hmac<Sha256> {
+  // HMAC accepts keys of any length
+  #[expect(clippy::expect_used)]
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret.jwt_secret.as_bytes()).expect("hmac key");
+  mac.update(format!("data_export.{}.{expires}", id.0).as_bytes());
+  mac
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+  if hex.len() % 2 != 0 {
+    return None;
+  }
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_db_schema::{
+    source::{
+      person::{Person, PersonInsertForm},
+      private_message::PrivateMessageInsertForm,
+    },
+    test_data::TestData,
+  };
+  use lemmy_diesel_utils::traits::Crud;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use std::io::Read;
+  use zip::ZipArchive;
+
+  fn test_secret(jwt_secret: &str) -> Secret {
+    Secret {
+      id: 1,
+      jwt_secret: jwt_secret.to_string().into(),
+    }
+  }
+
+  fn signed_params(id: DataExportId, expires: i64, secret: &Secret) -> DownloadDataExport {
+    DownloadDataExport {
+      id,
+      expires,
+      signature: signature(id, expires, secret),
+    }
+  }
+
+  #[test]
+  fn test_verify_download() {
+    let secret = test_secret("my_secret");
+    let expires = (Utc::now() + DOWNLOAD_LINK_VALIDITY).timestamp();
+    let params = signed_params(DataExportId(5), expires, &secret);
+    assert!(verify_download(&params, &secret).is_ok());
+
+    // Link for a different export, or signed with another secret
+    let other_id = DownloadDataExport {
+      id: DataExportId(6),
+      ..params.clone()
+    };
+    assert!(verify_download(&other_id, &secret).is_err());
+    assert!(verify_download(&params, &test_secret("other_secret")).is_err());
+
+    // Expiry can't be extended
+    let extended = DownloadDataExport {
+      expires: expires + 3600,
+      ..params
+    };
+    assert!(verify_download(&extended, &secret).is_err());
+
+    let expired = signed_params(DataExportId(5), Utc::now().timestamp() - 1, &secret);
+    assert!(verify_download(&expired, &secret).is_err());
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_write_archive() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let data = TestData::create(pool).await?;
+
+    let user = LocalUserView::create_test_user(pool, "archive_user", "my bio", false).await?;
+    let recipient_form = PersonInsertForm::test_form(user.person.instance_id, "archive_recipient");
+    let recipient = Person::create(pool, &recipient_form).await?;
+    let message_form =
+      PrivateMessageInsertForm::new(user.person.id, recipient.id, "archived message".into());
+    PrivateMessage::create(pool, &message_form).await?;
+
+    let path = std::env::temp_dir().join(format!("lemmy_test_export_{}.zip", user.local_user.id.0));
+    write_archive(path.clone(), &user, &context).await?;
+
+    let mut archive = ZipArchive::new(File::open(&path)?)?;
+    let mut names: Vec<_> = archive.file_names().map(ToString::to_string).collect();
+    names.sort();
+    assert_eq!(
+      vec![
+        "data.json",
+        "index.html",
+        "logins.json",
+        "notification_settings.json",
+        "private_messages.json",
+        "votes.json",
+      ],
+      names
+    );
+
+    let mut private_messages = String::new();
+    archive
+      .by_name("private_messages.json")?
+      .read_to_string(&mut private_messages)?;
+    assert!(private_messages.contains("archived message"));
+
+    let mut index = String::new();
+    archive.by_name("index.html")?.read_to_string(&mut index)?;
+    assert!(index.contains("Data export for archive_user"));
+    for (name, _) in JSON_FILES {
+      assert!(index.contains(&format!("<a href=\"{name}\">")));
+    }
+
+    std::fs::remove_file(&path)?;
+    Person::delete(pool, recipient.id).await?;
+    Person::delete(pool, user.person.id).await?;
+    data.delete(pool).await?;
+    Ok(())
+  }
+}
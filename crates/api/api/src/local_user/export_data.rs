@@ -18,6 +18,14 @@ pub async fn export_data(
   context: Data<LemmyContext>,
   local_user_view: LocalUserView,
 ) -> LemmyResult<Json<ExportDataResponse>> {
+  Ok(Json(build_export_data(local_user_view, &context).await?))
+}
+
+/// Collects the data for [`export_data`], which is also included in the full export archive.
+pub(crate) async fn build_export_data(
+  local_user_view: LocalUserView,
+  context: &LemmyContext,
+) -> LemmyResult<ExportDataResponse> {
   use PostOrCommentOrPrivateMessage::*;
 
   let local_instance_id = local_user_view.person.instance_id;
@@ -88,12 +96,12 @@ pub async fn export_data(
   let settings =
     user_backup_list_to_user_settings_backup(local_user_view, &mut context.pool()).await?;
 
-  Ok(Json(ExportDataResponse {
+  Ok(ExportDataResponse {
     notifications,
     content,
     liked,
     read_posts,
     moderates,
     settings,
-  }))
+  })
 }
@@ -0,0 +1,39 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_db_schema::source::feed_token::FeedToken;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::FeedTokenResponse;
+use lemmy_utils::error::LemmyResult;
+use uuid::Uuid;
+
+pub async fn get_feed_token(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<FeedTokenResponse>> {
+  let feed_token = FeedToken::read(&mut context.pool(), local_user_view.local_user.id).await?;
+
+  Ok(Json(FeedTokenResponse { feed_token }))
+}
+
+/// Generates a new feed token. Feed urls with the previous token stop working.
+pub async fn regenerate_feed_token(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<FeedTokenResponse>> {
+  let token = Uuid::new_v4().simple().to_string();
+  let feed_token =
+    FeedToken::regenerate(&mut context.pool(), local_user_view.local_user.id, token).await?;
+
+  Ok(Json(FeedTokenResponse {
+    feed_token: Some(feed_token),
+  }))
+}
+
+pub async fn revoke_feed_token(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<FeedTokenResponse>> {
+  FeedToken::revoke(&mut context.pool(), local_user_view.local_user.id).await?;
+
+  Ok(Json(FeedTokenResponse { feed_token: None }))
+}
@@ -1,21 +1,30 @@
 use actix_web::web::{Data, Json, Query};
-use lemmy_api_utils::context::LemmyContext;
-use lemmy_db_views_local_image::{LocalImageView, api::ListMedia};
+use lemmy_api_utils::{context::LemmyContext, utils::image_upload_quota};
+use lemmy_db_views_local_image::{
+  LocalImageView,
+  api::{ListMedia, ListMediaResponse},
+};
 use lemmy_db_views_local_user::LocalUserView;
-use lemmy_diesel_utils::pagination::PagedResponse;
+use lemmy_db_views_site::SiteView;
 use lemmy_utils::error::LemmyResult;
 
 pub async fn list_media(
   Query(data): Query<ListMedia>,
   context: Data<LemmyContext>,
   local_user_view: LocalUserView,
-) -> LemmyResult<Json<PagedResponse<LocalImageView>>> {
-  let images = LocalImageView::get_all_paged_by_person_id(
+) -> LemmyResult<Json<ListMediaResponse>> {
+  let local_site = SiteView::read_local(&mut context.pool()).await?.local_site;
+  let media = LocalImageView::get_all_paged_by_person_id(
     &mut context.pool(),
     local_user_view.person.id,
     data.page_cursor,
     data.limit,
   )
   .await?;
-  Ok(Json(images))
+  let local_user = &local_user_view.local_user;
+  Ok(Json(ListMediaResponse {
+    media,
+    upload_bytes: local_user.image_upload_bytes,
+    upload_quota_bytes: image_upload_quota(local_user, &local_site),
+  }))
 }
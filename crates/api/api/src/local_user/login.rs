@@ -7,7 +7,12 @@ use bcrypt::verify;
 use lemmy_api_utils::{
   claims::Claims,
   context::LemmyContext,
-  utils::{check_email_verified, check_local_user_deleted, check_registration_application},
+  utils::{
+    audit_admin_login,
+    check_email_verified,
+    check_local_user_deleted,
+    check_registration_application,
+  },
 };
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::{
@@ -53,6 +58,8 @@ pub async fn login(
     )?;
   }
 
+  audit_admin_login(&local_user_view.local_user, &req, &context).await?;
+
   let jwt = Claims::generate(
     local_user_view.local_user.id,
     data.stay_logged_in,
@@ -4,7 +4,9 @@ pub mod block;
 pub mod change_password;
 pub mod change_password_after_reset;
 pub mod donation_dialog_shown;
+pub mod export_archive;
 pub mod export_data;
+pub mod feed_token;
 pub mod generate_totp_secret;
 pub mod get_captcha;
 pub mod list_hidden;
@@ -20,6 +22,8 @@ pub mod notifications;
 pub mod resend_verification_email;
 pub mod reset_password;
 pub mod save_settings;
+pub mod set_image_upload_quota;
+pub mod set_rate_limit_override;
 pub mod unread_counts;
 pub mod update_totp;
 pub mod user_block_instance;
@@ -19,6 +19,9 @@ pub async fn list_notifications(
     page_cursor: data.page_cursor,
     hide_modlog_names: Some(hide_modlog_names),
     creator_id: data.creator_id,
+    published_after: None,
+    published_after_id: None,
+    oldest_first: None,
     limit: data.limit,
     no_limit: None,
   }
@@ -1,5 +1,6 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use chrono::Utc;
 use lemmy_api_utils::{
   context::LemmyContext,
   utils::{check_local_user_valid, get_url_blocklist, process_markdown_opt, slur_regex},
@@ -119,6 +120,13 @@ pub async fn save_user_settings(
 
   let default_comment_sort_type = data.default_comment_sort_type;
 
+  // Start the next digest from now on, so that it doesn't repeat notifications which were already
+  // sent with the previous frequency.
+  let email_notification_frequency = data
+    .email_notification_frequency
+    .filter(|f| *f != local_user_view.local_user.email_notification_frequency);
+  let last_email_digest_at = email_notification_frequency.map(|_| Utc::now());
+
   let person_form = PersonUpdateForm {
     display_name,
     bio,
@@ -156,6 +164,8 @@ pub async fn save_user_settings(
     show_avatars: data.show_avatars,
     show_read_posts: data.show_read_posts,
     send_notifications_to_email: data.send_notifications_to_email,
+    email_notification_frequency,
+    last_email_digest_at,
     show_nsfw: data.show_nsfw,
     blur_nsfw: data.blur_nsfw,
     show_bot_accounts: data.show_bot_accounts,
@@ -0,0 +1,52 @@
+use actix_web::web::{Data, Json};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  local_user::{LocalUser, LocalUserUpdateForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_local_user::{LocalUserView, api::SetImageUploadQuota};
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_diesel_utils::connection::get_conn;
+use lemmy_utils::error::LemmyResult;
+use serde_json::json;
+
+pub async fn set_image_upload_quota(
+  Json(data): Json<SetImageUploadQuota>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  // Make sure that the person is local
+  let target = LocalUserView::read_person(&mut context.pool(), data.person_id).await?;
+
+  let form = LocalUserUpdateForm {
+    image_upload_quota_bytes: Some(data.quota_bytes),
+    ..Default::default()
+  };
+  let audit_form = AdminAuditLogInsertForm::new(
+    AdminAuditKind::SetImageUploadQuota,
+    local_user_view.person.id,
+  )
+  .target(data.person_id.0)
+  .changed(
+    &json!({ "quota_bytes": target.local_user.image_upload_quota_bytes }),
+    &json!({ "quota_bytes": data.quota_bytes }),
+  )?;
+
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        LocalUser::update(&mut conn.into(), target.local_user.id, &form).await?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await
+      }
+      .scope_boxed()
+    })
+    .await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
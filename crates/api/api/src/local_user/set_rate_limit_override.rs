@@ -0,0 +1,63 @@
+use actix_web::web::{Data, Json};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  local_user::{LocalUser, LocalUserUpdateForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_local_user::{LocalUserView, api::SetRateLimitOverride};
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_diesel_utils::connection::get_conn;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use serde_json::json;
+
+pub async fn set_rate_limit_override(
+  Json(data): Json<SetRateLimitOverride>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  if data.multiplier.is_some_and(|m| m < 1) {
+    return Err(LemmyErrorType::InvalidRateLimitMultiplier.into());
+  }
+
+  // Make sure that the person is local
+  let target = LocalUserView::read_person(&mut context.pool(), data.person_id).await?;
+
+  let form = LocalUserUpdateForm {
+    rate_limit_multiplier: data.multiplier,
+    rate_limit_exempt: data.exempt,
+    ..Default::default()
+  };
+  let audit_form = AdminAuditLogInsertForm::new(
+    AdminAuditKind::SetRateLimitOverride,
+    local_user_view.person.id,
+  )
+  .target(data.person_id.0)
+  .changed(
+    &json!({
+      "multiplier": target.local_user.rate_limit_multiplier,
+      "exempt": target.local_user.rate_limit_exempt,
+    }),
+    &json!({
+      "multiplier": data.multiplier.unwrap_or(target.local_user.rate_limit_multiplier),
+      "exempt": data.exempt.unwrap_or(target.local_user.rate_limit_exempt),
+    }),
+  )?;
+
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        LocalUser::update(&mut conn.into(), target.local_user.id, &form).await?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await
+      }
+      .scope_boxed()
+    })
+    .await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -49,7 +49,7 @@ pub async fn verify_email(
     .await?;
   }
 
-  send_email_verified_email(&local_user_view, context.settings())?;
+  send_email_verified_email(&local_user_view, &mut context.pool(), context.settings()).await?;
 
   Ok(Json(SuccessResponse::default()))
 }
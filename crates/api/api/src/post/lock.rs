@@ -5,7 +5,7 @@ use lemmy_api_utils::{
   context::LemmyContext,
   notify::notify_mod_action,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::check_community_mod_action,
+  utils::{check_community_mod_action, check_expire_time},
 };
 use lemmy_db_schema::source::{
   modlog::{Modlog, ModlogInsertForm},
@@ -47,6 +47,11 @@ pub async fn lock_post(
   // Update the post
   let post_id = data.post_id;
   let locked = data.locked;
+  let expires_at = if locked {
+    check_expire_time(data.expires_at)?
+  } else {
+    None
+  };
   let post = Post::update(
     &mut context.pool(),
     post_id,
@@ -62,6 +67,7 @@ pub async fn lock_post(
     local_user_view.person.id,
     &orig_post.post,
     locked,
+    expires_at,
     &data.reason,
   );
   let action = Modlog::create(&mut context.pool(), &[form]).await?;
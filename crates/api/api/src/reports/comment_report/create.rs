@@ -10,6 +10,7 @@ use lemmy_api_utils::{
     check_comment_deleted_or_removed,
     check_community_user_action,
     check_local_user_valid,
+    check_rule_in_community,
     slur_regex,
   },
 };
@@ -59,12 +60,18 @@ pub async fn create_comment_report(
   // Don't allow creating reports for removed / deleted comments
   check_comment_deleted_or_removed(&comment_view.comment)?;
 
+  let rule =
+    check_rule_in_community(data.rule_id, comment_view.community.id, &mut context.pool()).await?;
+
   let report_form = CommentReportForm {
     creator_id: person.id,
     comment_id,
     original_comment_text: comment_view.comment.content,
     reason,
-    violates_instance_rules: data.violates_instance_rules.unwrap_or_default(),
+    // Reports for instance rules are only shown to local admins
+    violates_instance_rules: data.violates_instance_rules.unwrap_or_default()
+      || rule.as_ref().is_some_and(|r| r.community_id.is_none()),
+    rule_id: rule.as_ref().map(|r| r.id),
   };
 
   let report = CommentReport::report(&mut context.pool(), &report_form).await?;
@@ -92,6 +99,7 @@ pub async fn create_comment_report(
         actor: local_user_view.person,
         receiver: Either::Right(comment_view.community),
         reason: data.reason.clone(),
+        rule: rule.map(|r| r.ap_id.into()),
       },
       &context,
     )?;
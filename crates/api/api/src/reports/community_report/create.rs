@@ -81,6 +81,7 @@ pub async fn create_community_report(
       actor: local_user_view.person,
       receiver: Either::Left(site),
       reason: data.reason.clone(),
+      rule: None,
     },
     &context,
   )?;
@@ -10,6 +10,7 @@ use lemmy_api_utils::{
     check_community_user_action,
     check_local_user_valid,
     check_post_deleted_or_removed,
+    check_rule_in_community,
     slur_regex,
   },
 };
@@ -54,6 +55,9 @@ pub async fn create_post_report(
 
   check_post_deleted_or_removed(&orig_post.post)?;
 
+  let rule =
+    check_rule_in_community(data.rule_id, orig_post.community.id, &mut context.pool()).await?;
+
   let report_form = PostReportForm {
     creator_id: person.id,
     post_id,
@@ -61,7 +65,10 @@ pub async fn create_post_report(
     original_post_url: orig_post.post.url,
     original_post_body: orig_post.post.body,
     reason,
-    violates_instance_rules: data.violates_instance_rules.unwrap_or_default(),
+    // Reports for instance rules are only shown to local admins
+    violates_instance_rules: data.violates_instance_rules.unwrap_or_default()
+      || rule.as_ref().is_some_and(|r| r.community_id.is_none()),
+    rule_id: rule.as_ref().map(|r| r.id),
   };
 
   let report = PostReport::report(&mut context.pool(), &report_form).await?;
@@ -89,6 +96,7 @@ pub async fn create_post_report(
         actor: local_user_view.person,
         receiver: Either::Right(orig_post.community),
         reason: data.reason.clone(),
+        rule: rule.map(|r| r.ap_id.into()),
       },
       &context,
     )?;
@@ -1,13 +1,17 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
 use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
   federation_allowlist::{FederationAllowList, FederationAllowListForm},
   instance::Instance,
   modlog::{Modlog, ModlogInsertForm},
 };
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::{FederatedInstanceView, api::AdminAllowInstanceParams};
+use lemmy_diesel_utils::connection::get_conn;
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 
 pub async fn admin_allow_instance(
@@ -25,20 +29,36 @@ pub async fn admin_allow_instance(
   let instance_id = Instance::read_or_create(&mut context.pool(), &data.instance)
     .await?
     .id;
-  let form = FederationAllowListForm::new(instance_id);
-  if data.allow {
-    FederationAllowList::allow(&mut context.pool(), &form).await?;
-  } else {
-    FederationAllowList::unallow(&mut context.pool(), instance_id).await?;
-  }
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        let form = FederationAllowListForm::new(instance_id);
+        if data.allow {
+          FederationAllowList::allow(&mut conn.into(), &form).await?;
+        } else {
+          FederationAllowList::unallow(&mut conn.into(), instance_id).await?;
+        }
+
+        let form = ModlogInsertForm::admin_allow_instance(
+          local_user_view.person.id,
+          instance_id,
+          data.allow,
+          &data.reason,
+        );
+        Modlog::create(&mut conn.into(), &[form]).await?;
 
-  let form = ModlogInsertForm::admin_allow_instance(
-    local_user_view.person.id,
-    instance_id,
-    data.allow,
-    &data.reason,
-  );
-  Modlog::create(&mut context.pool(), &[form]).await?;
+        let audit_form =
+          AdminAuditLogInsertForm::new(AdminAuditKind::AllowInstance, local_user_view.person.id)
+            .target(&data.instance)
+            .after(&data)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(())
+      }
+      .scope_boxed()
+    })
+    .await?;
 
   Ok(Json(
     FederatedInstanceView::read(&mut context.pool(), instance_id).await?,
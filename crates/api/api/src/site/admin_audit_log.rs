@@ -0,0 +1,26 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::admin_audit_log::AdminAuditLog;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ListAdminAuditLog;
+use lemmy_diesel_utils::pagination::PagedResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_admin_audit_log(
+  Query(data): Query<ListAdminAuditLog>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PagedResponse<AdminAuditLog>>> {
+  is_admin(&local_user_view)?;
+
+  let entries = AdminAuditLog::list(
+    &mut context.pool(),
+    data.kind,
+    data.admin_person_id,
+    data.page_cursor,
+    data.limit,
+  )
+  .await?;
+
+  Ok(Json(entries))
+}
@@ -1,16 +1,20 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{
   context::LemmyContext,
   utils::{check_expire_time, is_admin},
 };
 use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
   federation_blocklist::{FederationBlockList, FederationBlockListForm},
   instance::Instance,
   modlog::{Modlog, ModlogInsertForm},
 };
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::{FederatedInstanceView, api::AdminBlockInstanceParams};
+use lemmy_diesel_utils::connection::get_conn;
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 
 pub async fn admin_block_instance(
@@ -31,21 +35,37 @@ pub async fn admin_block_instance(
     .await?
     .id;
 
-  let form = FederationBlockListForm::new(instance_id, expires_at);
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        let form = FederationBlockListForm::new(instance_id, expires_at);
 
-  if data.block {
-    FederationBlockList::block(&mut context.pool(), &form).await?;
-  } else {
-    FederationBlockList::unblock(&mut context.pool(), instance_id).await?;
-  }
+        if data.block {
+          FederationBlockList::block(&mut conn.into(), &form).await?;
+        } else {
+          FederationBlockList::unblock(&mut conn.into(), instance_id).await?;
+        }
+
+        let form = ModlogInsertForm::admin_block_instance(
+          local_user_view.person.id,
+          instance_id,
+          data.block,
+          &data.reason,
+        );
+        Modlog::create(&mut conn.into(), &[form]).await?;
 
-  let form = ModlogInsertForm::admin_block_instance(
-    local_user_view.person.id,
-    instance_id,
-    data.block,
-    &data.reason,
-  );
-  Modlog::create(&mut context.pool(), &[form]).await?;
+        let audit_form =
+          AdminAuditLogInsertForm::new(AdminAuditKind::BlockInstance, local_user_view.person.id)
+            .target(&data.instance)
+            .after(&data)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(())
+      }
+      .scope_boxed()
+    })
+    .await?;
 
   Ok(Json(
     FederatedInstanceView::read(&mut context.pool(), instance_id).await?,
@@ -0,0 +1,25 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::email_queue::EmailQueue;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ListEmailQueue;
+use lemmy_diesel_utils::pagination::PagedResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_email_queue(
+  Query(data): Query<ListEmailQueue>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PagedResponse<EmailQueue>>> {
+  is_admin(&local_user_view)?;
+
+  let emails = EmailQueue::list(
+    &mut context.pool(),
+    data.failed_only.unwrap_or_default(),
+    data.page_cursor,
+    data.limit,
+  )
+  .await?;
+
+  Ok(Json(emails))
+}
@@ -0,0 +1,19 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::email_queue::EmailQueue;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::RetryEmail;
+use lemmy_email::queue::retry_email;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn retry_queued_email(
+  Json(data): Json<RetryEmail>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<EmailQueue>> {
+  is_admin(&local_user_view)?;
+
+  let email = retry_email(&mut context.pool(), data.id).await?;
+
+  Ok(Json(email))
+}
@@ -0,0 +1,59 @@
+use actix_web::web::{Data, Json};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  image_hash::{format_image_hash, parse_image_hash},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  images::{ImageDetails, ImageHashBlock, ImageHashBlockForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_local_image::api::BlockImageHash;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_diesel_utils::connection::get_conn;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn block_image_hash(
+  Json(data): Json<BlockImageHash>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let hash = match (&data.hash, &data.image_url) {
+    (Some(hash), _) => parse_image_hash(hash)?,
+    // The hash is stored when an image is uploaded or proxied
+    (None, Some(image_url)) => {
+      ImageDetails::read_phash(&mut context.pool(), image_url.clone().into())
+        .await?
+        .ok_or(LemmyErrorType::NotFound)?
+    }
+    (None, None) => return Err(LemmyErrorType::InvalidImageHash.into()),
+  };
+
+  let audit_form =
+    AdminAuditLogInsertForm::new(AdminAuditKind::BlockImageHash, local_user_view.person.id)
+      .target(format_image_hash(hash))
+      .after(&data)?;
+  let form = ImageHashBlockForm {
+    hash,
+    reason: data.reason,
+  };
+
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        ImageHashBlock::create(&mut conn.into(), &[form]).await?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await
+      }
+      .scope_boxed()
+    })
+    .await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
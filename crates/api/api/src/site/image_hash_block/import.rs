@@ -0,0 +1,52 @@
+use actix_web::web::{Data, Json};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use lemmy_api_utils::{context::LemmyContext, image_hash::parse_image_hash, utils::is_admin};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  images::{ImageHashBlock, ImageHashBlockForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_local_image::api::ImageHashBlocklist;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_diesel_utils::connection::get_conn;
+use lemmy_utils::error::LemmyResult;
+
+/// Adds all hashes from an exported blocklist. Hashes which are already blocked are skipped.
+pub async fn import_image_hash_blocks(
+  Json(data): Json<ImageHashBlocklist>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let audit_form = AdminAuditLogInsertForm::new(
+    AdminAuditKind::ImportImageHashBlocks,
+    local_user_view.person.id,
+  )
+  .after(&data)?;
+  let forms = data
+    .image_hash_blocks
+    .into_iter()
+    .map(|b| {
+      Ok(ImageHashBlockForm {
+        hash: parse_image_hash(&b.hash)?,
+        reason: b.reason,
+      })
+    })
+    .collect::<LemmyResult<Vec<_>>>()?;
+
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        ImageHashBlock::create(&mut conn.into(), &forms).await?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await
+      }
+      .scope_boxed()
+    })
+    .await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
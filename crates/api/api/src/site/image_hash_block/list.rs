@@ -0,0 +1,25 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, image_hash::format_image_hash, utils::is_admin};
+use lemmy_db_schema::source::images::ImageHashBlock;
+use lemmy_db_views_local_image::api::{ImageHashBlockItem, ImageHashBlocklist};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+/// Returns the full image hash blocklist, in the same format which is used for importing.
+pub async fn list_image_hash_blocks(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ImageHashBlocklist>> {
+  is_admin(&local_user_view)?;
+
+  let image_hash_blocks = ImageHashBlock::list(&mut context.pool())
+    .await?
+    .into_iter()
+    .map(|b| ImageHashBlockItem {
+      hash: format_image_hash(b.hash),
+      reason: b.reason,
+    })
+    .collect();
+
+  Ok(Json(ImageHashBlocklist { image_hash_blocks }))
+}
@@ -0,0 +1,4 @@
+pub mod block;
+pub mod import;
+pub mod list;
+pub mod unblock;
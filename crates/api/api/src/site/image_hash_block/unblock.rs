@@ -0,0 +1,41 @@
+use actix_web::web::{Data, Json};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use lemmy_api_utils::{context::LemmyContext, image_hash::parse_image_hash, utils::is_admin};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  images::ImageHashBlock,
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_local_image::api::UnblockImageHash;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_diesel_utils::connection::get_conn;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn unblock_image_hash(
+  Json(data): Json<UnblockImageHash>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let hash = parse_image_hash(&data.hash)?;
+  let audit_form =
+    AdminAuditLogInsertForm::new(AdminAuditKind::UnblockImageHash, local_user_view.person.id)
+      .target(&data.hash)
+      .after(&data)?;
+
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        ImageHashBlock::delete(&mut conn.into(), hash).await?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await
+      }
+      .scope_boxed()
+    })
+    .await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -1,8 +1,14 @@
 pub mod admin_allow_instance;
+pub mod admin_audit_log;
 pub mod admin_block_instance;
 pub mod admin_list_users;
+pub mod email_queue;
 pub mod federated_instances;
+pub mod image_hash_block;
 pub mod list_all_media;
 pub mod mod_log;
 pub mod purge;
 pub mod registration_applications;
+pub mod reload_config;
+pub mod retention_report;
+pub mod scheduled_task;
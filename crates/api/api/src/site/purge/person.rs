@@ -14,6 +14,7 @@ use lemmy_db_schema::{
   },
   traits::Bannable,
 };
+use lemmy_db_schema_file::PersonId;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_person::api::PurgePerson;
 use lemmy_db_views_site::api::SuccessResponse;
@@ -25,8 +26,6 @@ pub async fn purge_person(
   context: Data<LemmyContext>,
   local_user_view: LocalUserView,
 ) -> LemmyResult<Json<SuccessResponse>> {
-  let local_instance_id = local_user_view.person.instance_id;
-
   // Only let admin purge an item
   is_admin(&local_user_view)?;
 
@@ -38,33 +37,51 @@ pub async fn purge_person(
   )
   .await?;
 
-  let person = Person::read(&mut context.pool(), data.person_id).await?;
+  purge_person_internal(
+    data.person_id,
+    &data.reason,
+    local_user_view.person,
+    &context,
+  )
+  .await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
+
+/// Purges a person without permission checks. Also used by the admin command line.
+pub async fn purge_person_internal(
+  person_id: PersonId,
+  reason: &str,
+  moderator: Person,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let person = Person::read(&mut context.pool(), person_id).await?;
 
   ActivityChannel::submit_activity(
     SendActivityData::BanFromSite {
-      moderator: local_user_view.person.clone(),
+      moderator: moderator.clone(),
       banned_user: person,
-      reason: data.reason.clone(),
+      reason: reason.to_string(),
       remove_or_restore_data: Some(true),
       ban: true,
       expires_at: None,
     },
-    &context,
+    context,
   )?;
 
   // Clear profile data.
-  purge_user_account(data.person_id, local_instance_id, &context).await?;
+  purge_user_account(person_id, moderator.instance_id, context).await?;
 
   // Keep person record, but mark as banned to prevent login or refetching from home instance.
   InstanceActions::ban(
     &mut context.pool(),
-    &InstanceBanForm::new(data.person_id, local_instance_id, None),
+    &InstanceBanForm::new(person_id, moderator.instance_id, None),
   )
   .await?;
 
   // Mod tables
-  let form = ModlogInsertForm::admin_purge_person(local_user_view.person.id, &data.reason);
+  let form = ModlogInsertForm::admin_purge_person(moderator.id, reason);
   Modlog::create(&mut context.pool(), &[form]).await?;
 
-  Ok(Json(SuccessResponse::default()))
+  Ok(())
 }
@@ -0,0 +1,28 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ReloadConfigResponse;
+use lemmy_utils::{error::LemmyResult, settings::reload_settings};
+
+/// Only reloads the config of the process which handles this request. With multiple processes,
+/// send SIGHUP to each of them instead.
+pub async fn reload_config(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ReloadConfigResponse>> {
+  is_admin(&local_user_view)?;
+
+  let restart_required = reload_settings()?
+    .into_iter()
+    .map(ToString::to_string)
+    .collect::<Vec<String>>();
+
+  let audit_form =
+    AdminAuditLogInsertForm::new(AdminAuditKind::ReloadConfig, local_user_view.person.id)
+      .after(&restart_required)?;
+  AdminAuditLog::create(&mut context.pool(), &audit_form).await?;
+
+  Ok(Json(ReloadConfigResponse { restart_required }))
+}
@@ -0,0 +1,61 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::{
+  source::{
+    activity::{ReceivedActivity, SentActivity},
+    login_token::LoginToken,
+    notification::Notification,
+    post::{Post, PostActions},
+  },
+  utils::retention_cutoff,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::{SiteView, api::RetentionReport};
+use lemmy_utils::error::LemmyResult;
+
+/// Dry run of the data retention tasks, with the current retention settings.
+pub async fn get_retention_report(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RetentionReport>> {
+  is_admin(&local_user_view)?;
+
+  let pool = &mut context.pool();
+  let local_site = SiteView::read_local(pool).await?.local_site;
+
+  let sent_activities = SentActivity::count_older_than(
+    pool,
+    retention_cutoff(local_site.sent_activity_retention_days),
+  )
+  .await?;
+  let received_activities = ReceivedActivity::count_older_than(
+    pool,
+    retention_cutoff(local_site.received_activity_retention_days),
+  )
+  .await?;
+  let login_ips = match local_site.login_ip_retention_days {
+    Some(days) => Some(LoginToken::count_old_ips(pool, retention_cutoff(days)).await?),
+    None => None,
+  };
+  let post_read_states = match local_site.post_read_retention_days {
+    Some(days) => Some(PostActions::count_old_read_state(pool, retention_cutoff(days)).await?),
+    None => None,
+  };
+  let notifications = match local_site.notification_retention_days {
+    Some(days) => Some(Notification::count_older_than(pool, retention_cutoff(days)).await?),
+    None => None,
+  };
+  let remote_posts = match local_site.remote_content_retention_days {
+    Some(days) => Some(Post::count_old_remote(pool, retention_cutoff(days)).await?),
+    None => None,
+  };
+
+  Ok(Json(RetentionReport {
+    sent_activities,
+    received_activities,
+    login_ips,
+    post_read_states,
+    notifications,
+    remote_posts,
+  }))
+}
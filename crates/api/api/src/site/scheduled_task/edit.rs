@@ -0,0 +1,52 @@
+use actix_web::web::{Data, Json};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  scheduled_task_status::ScheduledTaskStatus,
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::EditScheduledTask;
+use lemmy_diesel_utils::connection::get_conn;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// The scheduler checks for due tasks once per minute, so shorter intervals have no effect.
+const MIN_INTERVAL_SECONDS: i32 = 60;
+
+pub async fn edit_scheduled_task(
+  Json(data): Json<EditScheduledTask>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ScheduledTaskStatus>> {
+  is_admin(&local_user_view)?;
+
+  if data.interval_seconds < MIN_INTERVAL_SECONDS {
+    return Err(LemmyErrorType::InvalidScheduledTaskInterval.into());
+  }
+
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let status = conn
+    .run_transaction(|conn| {
+      async move {
+        let old_status = ScheduledTaskStatus::read(&mut conn.into(), &data.name).await?;
+        let status =
+          ScheduledTaskStatus::update_interval(&mut conn.into(), &data.name, data.interval_seconds)
+            .await?;
+
+        let audit_form = AdminAuditLogInsertForm::new(
+          AdminAuditKind::EditScheduledTask,
+          local_user_view.person.id,
+        )
+        .target(&status.name)
+        .changed(&old_status.interval_seconds, &status.interval_seconds)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(status)
+      }
+      .scope_boxed()
+    })
+    .await?;
+
+  Ok(Json(status))
+}
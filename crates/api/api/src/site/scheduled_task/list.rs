@@ -0,0 +1,17 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::scheduled_task_status::ScheduledTaskStatus;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ListScheduledTasksResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_scheduled_tasks(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListScheduledTasksResponse>> {
+  is_admin(&local_user_view)?;
+
+  let scheduled_tasks = ScheduledTaskStatus::list(&mut context.pool()).await?;
+
+  Ok(Json(ListScheduledTasksResponse { scheduled_tasks }))
+}
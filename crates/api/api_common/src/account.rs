@@ -4,14 +4,21 @@ pub use lemmy_db_views_person_saved_combined::ListPersonSaved;
 pub use lemmy_db_views_post_comment_combined::PostCommentCombinedView;
 pub use lemmy_db_views_site::api::{DeleteAccount, MyUserInfo, SaveUserSettings};
 pub mod auth {
-  pub use lemmy_db_schema::source::login_token::LoginToken;
+  pub use lemmy_db_schema::source::{
+    data_export::DataExport,
+    feed_token::FeedToken,
+    login_token::LoginToken,
+  };
   pub use lemmy_db_views_registration_applications::api::{CaptchaAnswer, Register};
   pub use lemmy_db_views_site::api::{
     CaptchaResponse,
     ChangePassword,
+    DataExportResponse,
+    DownloadDataExport,
     EditTotp,
     EditTotpResponse,
     ExportDataResponse,
+    FeedTokenResponse,
     GenerateTotpSecretResponse,
     GetCaptchaResponse,
     ListLoginsResponse,
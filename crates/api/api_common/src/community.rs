@@ -1,9 +1,10 @@
 pub use lemmy_db_schema::{
-  newtypes::{CommunityId, CommunityTagId, MultiCommunityId},
+  newtypes::{CommunityId, CommunityTagId, MultiCommunityId, RuleId},
   source::{
     community::{Community, CommunityActions},
     community_tag::{CommunityTag, CommunityTagsView},
     multi_community::{MultiCommunity, MultiCommunityFollow},
+    rule::Rule,
   },
 };
 pub use lemmy_db_schema_file::enums::CommunityVisibility;
@@ -46,10 +47,13 @@ pub mod actions {
       BanFromCommunity,
       CommunityIdQuery,
       CreateCommunityTag,
+      CreateRule,
       DeleteCommunity,
       DeleteCommunityTag,
+      DeleteRule,
       EditCommunity,
       EditCommunityTag,
+      EditRule,
       PurgeCommunity,
       RemoveCommunity,
       TransferCommunity,
@@ -1,5 +1,16 @@
 pub use lemmy_db_schema::source::images::{ImageDetails, LocalImage, RemoteImage};
 pub use lemmy_db_views_local_image::{
   LocalImageView,
-  api::{DeleteImageParams, ImageGetParams, ImageProxyParams, ListMedia, UploadImageResponse},
+  api::{
+    BlockImageHash,
+    DeleteImageParams,
+    ImageGetParams,
+    ImageHashBlockItem,
+    ImageHashBlocklist,
+    ImageProxyParams,
+    ListMedia,
+    ListMediaResponse,
+    UnblockImageHash,
+    UploadImageResponse,
+  },
 };
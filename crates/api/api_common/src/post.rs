@@ -1,7 +1,10 @@
 pub use lemmy_db_schema::{
   PostFeatureType,
   newtypes::PostId,
-  source::post::{Post, PostActions, PostInsertForm, PostLikeForm},
+  source::{
+    post::{Post, PostActions, PostInsertForm, PostLikeForm},
+    post_gallery_image::{PostGalleryImage, PostGalleryView},
+  },
 };
 pub use lemmy_db_schema_file::enums::{PostListingMode, PostNotificationsMode};
 pub use lemmy_db_views_post::{
@@ -23,6 +26,7 @@ pub mod actions {
     DeletePost,
     EditPost,
     EditPostNotifications,
+    GalleryImage,
     HidePost,
     MarkManyPostsAsRead,
     MarkPostAsRead,
@@ -14,11 +14,34 @@ pub use lemmy_db_views_site::{
 };
 
 pub mod administration {
-  pub use lemmy_db_views_local_user::api::AdminListUsers;
+  pub use lemmy_db_schema::{
+    newtypes::{AdminAuditLogId, EmailQueueId},
+    source::{
+      admin_audit_log::AdminAuditLog,
+      email_queue::EmailQueue,
+      scheduled_task_status::ScheduledTaskStatus,
+    },
+  };
+  pub use lemmy_db_schema_file::enums::AdminAuditKind;
+  pub use lemmy_db_views_local_user::api::{
+    AdminListUsers,
+    SetImageUploadQuota,
+    SetRateLimitOverride,
+  };
   pub use lemmy_db_views_person::api::{AddAdmin, AddAdminResponse};
   pub use lemmy_db_views_registration_applications::api::{
     ApproveRegistrationApplication,
     ListRegistrationApplications,
   };
-  pub use lemmy_db_views_site::api::{CreateSite, EditSite};
+  pub use lemmy_db_views_site::api::{
+    CreateSite,
+    EditScheduledTask,
+    EditSite,
+    ListAdminAuditLog,
+    ListEmailQueue,
+    ListScheduledTasksResponse,
+    ReloadConfigResponse,
+    RetentionReport,
+    RetryEmail,
+  };
 }
@@ -5,7 +5,7 @@ use lemmy_api_utils::{
   context::LemmyContext,
   notify::notify_mod_action,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::check_community_mod_action,
+  utils::{check_community_mod_action, check_expire_time, check_rule_in_community},
 };
 use lemmy_db_schema::{
   source::{
@@ -61,6 +61,17 @@ pub async fn remove_comment(
     return Err(LemmyErrorType::CouldntUpdate.into());
   }
 
+  let rule_id =
+    check_rule_in_community(data.rule_id, orig_comment.community.id, &mut context.pool())
+      .await?
+      .map(|r| r.id);
+
+  let expires_at = if data.remove_children.unwrap_or(data.removed) {
+    check_expire_time(data.expires_at)?
+  } else {
+    None
+  };
+
   let (updated_comment, forms) = if let Some(remove_children) = data.remove_children {
     let updated_comments: Vec<Comment> = Comment::update_removed_for_comment_and_children(
       &mut context.pool(),
@@ -85,7 +96,9 @@ pub async fn remove_comment(
           comment,
           orig_comment.community.id,
           remove_children,
+          expires_at,
           &data.reason,
+          rule_id,
           None,
         )
       })
@@ -125,7 +138,9 @@ pub async fn remove_comment(
       &orig_comment.comment,
       orig_comment.community.id,
       removed,
+      expires_at,
       &data.reason,
+      rule_id,
       None,
     );
 
@@ -110,6 +110,7 @@ pub async fn create_community(
     moderators_url: Some(generate_moderators_url(&community_ap_id)?),
     featured_url: Some(generate_featured_url(&community_ap_id)?),
     posting_restricted_to_mods: data.posting_restricted_to_mods,
+    require_alt_text: data.require_alt_text,
     visibility: data.visibility,
     ..CommunityInsertForm::new(
       site.instance_id,
@@ -92,6 +92,7 @@ pub async fn edit_community(
     summary,
     nsfw: data.nsfw,
     posting_restricted_to_mods: data.posting_restricted_to_mods,
+    require_alt_text: data.require_alt_text,
     visibility: data.visibility,
     updated_at: Some(Some(Utc::now())),
     ..Default::default()
@@ -1,16 +1,19 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
 use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
   custom_emoji::{CustomEmoji, CustomEmojiInsertForm},
   custom_emoji_keyword::CustomEmojiKeyword,
 };
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_custom_emoji::{
   CustomEmojiView,
   api::{CreateCustomEmoji, CustomEmojiResponse},
 };
 use lemmy_db_views_local_user::LocalUserView;
-use lemmy_diesel_utils::traits::Crud;
+use lemmy_diesel_utils::{connection::get_conn, traits::Crud};
 use lemmy_utils::error::LemmyResult;
 
 pub async fn create_custom_emoji(
@@ -27,10 +30,29 @@ pub async fn create_custom_emoji(
     alt_text: data.alt_text.clone(),
     category: data.category.clone(),
   };
-  let emoji = CustomEmoji::create(&mut context.pool(), &emoji_form).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let view = conn
+    .run_transaction(|conn| {
+      async move {
+        let emoji = CustomEmoji::create(&mut conn.into(), &emoji_form).await?;
 
-  CustomEmojiKeyword::create_from_keywords(&mut context.pool(), emoji.id, &data.keywords).await?;
+        CustomEmojiKeyword::create_from_keywords(&mut conn.into(), emoji.id, &data.keywords)
+          .await?;
 
-  let view = CustomEmojiView::get(&mut context.pool(), emoji.id).await?;
+        let view = CustomEmojiView::get(&mut conn.into(), emoji.id).await?;
+
+        let audit_form = AdminAuditLogInsertForm::new(
+          AdminAuditKind::CreateCustomEmoji,
+          local_user_view.person.id,
+        )
+        .target(&view.custom_emoji.shortcode)
+        .after(&view)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(view)
+      }
+      .scope_boxed()
+    })
+    .await?;
   Ok(Json(CustomEmojiResponse { custom_emoji: view }))
 }
@@ -1,11 +1,16 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
-use lemmy_db_schema::source::custom_emoji::CustomEmoji;
-use lemmy_db_views_custom_emoji::api::DeleteCustomEmoji;
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  custom_emoji::CustomEmoji,
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
+use lemmy_db_views_custom_emoji::{CustomEmojiView, api::DeleteCustomEmoji};
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::api::SuccessResponse;
-use lemmy_diesel_utils::traits::Crud;
+use lemmy_diesel_utils::{connection::get_conn, traits::Crud};
 use lemmy_utils::error::LemmyResult;
 
 pub async fn delete_custom_emoji(
@@ -16,7 +21,26 @@ pub async fn delete_custom_emoji(
   // Make sure user is an admin
   is_admin(&local_user_view)?;
 
-  CustomEmoji::delete(&mut context.pool(), data.id).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        let view = CustomEmojiView::get(&mut conn.into(), data.id).await?;
+        CustomEmoji::delete(&mut conn.into(), data.id).await?;
+
+        let audit_form = AdminAuditLogInsertForm::new(
+          AdminAuditKind::DeleteCustomEmoji,
+          local_user_view.person.id,
+        )
+        .target(&view.custom_emoji.shortcode)
+        .before(&view)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(())
+      }
+      .scope_boxed()
+    })
+    .await?;
 
   Ok(Json(SuccessResponse::default()))
 }
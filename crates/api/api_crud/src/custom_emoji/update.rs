@@ -1,16 +1,19 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
 use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
   custom_emoji::{CustomEmoji, CustomEmojiUpdateForm},
   custom_emoji_keyword::CustomEmojiKeyword,
 };
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_custom_emoji::{
   CustomEmojiView,
   api::{CustomEmojiResponse, EditCustomEmoji},
 };
 use lemmy_db_views_local_user::LocalUserView;
-use lemmy_diesel_utils::traits::Crud;
+use lemmy_diesel_utils::{connection::get_conn, traits::Crud};
 use lemmy_utils::error::LemmyResult;
 
 pub async fn edit_custom_emoji(
@@ -30,14 +33,31 @@ pub async fn edit_custom_emoji(
     alt_text: data.alt_text.clone(),
     category: data.category.clone(),
   };
-  let emoji = CustomEmoji::update(&mut context.pool(), data.id, &emoji_form).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let view = conn
+    .run_transaction(|conn| {
+      async move {
+        let old_view = CustomEmojiView::get(&mut conn.into(), data.id).await?;
+        let emoji = CustomEmoji::update(&mut conn.into(), data.id, &emoji_form).await?;
 
-  // Delete the existing keywords, and recreate
-  if let Some(keywords) = &data.keywords {
-    CustomEmojiKeyword::delete(&mut context.pool(), data.id).await?;
-    CustomEmojiKeyword::create_from_keywords(&mut context.pool(), emoji.id, keywords).await?;
-  }
+        // Delete the existing keywords, and recreate
+        if let Some(keywords) = &data.keywords {
+          CustomEmojiKeyword::delete(&mut conn.into(), data.id).await?;
+          CustomEmojiKeyword::create_from_keywords(&mut conn.into(), emoji.id, keywords).await?;
+        }
 
-  let view = CustomEmojiView::get(&mut context.pool(), emoji.id).await?;
+        let view = CustomEmojiView::get(&mut conn.into(), emoji.id).await?;
+
+        let audit_form =
+          AdminAuditLogInsertForm::new(AdminAuditKind::EditCustomEmoji, local_user_view.person.id)
+            .target(&view.custom_emoji.shortcode)
+            .changed(&old_view, &view)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(view)
+      }
+      .scope_boxed()
+    })
+    .await?;
   Ok(Json(CustomEmojiResponse { custom_emoji: view }))
 }
@@ -0,0 +1,64 @@
+use crate::{comment::create::create_comment, private_message::create::create_private_message};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_db_schema::source::comment::Comment;
+use lemmy_db_views_comment::api::CreateComment;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_private_message::api::CreatePrivateMessage;
+use lemmy_diesel_utils::traits::Crud;
+use lemmy_email::reply::{ReplyTarget, parse_email_reply};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Handles a reply to a notification email, which was received for the signed `recipient`
+/// address. The reply is created through the same code path as the API, so that it is validated,
+/// passed to plugins and federated.
+pub async fn receive_email_reply(
+  recipient: String,
+  raw: Vec<u8>,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let reply = parse_email_reply(&recipient, &raw, context.secret())?;
+  let local_user_view = LocalUserView::read(&mut context.pool(), reply.local_user_id).await?;
+
+  // The reply address is only sent to the user's own email, but check the sender anyway so
+  // that a leaked address can't be used by others.
+  let sender_matches = local_user_view
+    .local_user
+    .email
+    .as_ref()
+    .is_some_and(|email| email.eq_ignore_ascii_case(&reply.from));
+  if !sender_matches {
+    return Err(LemmyErrorType::InvalidEmailReply.into());
+  }
+
+  match reply.target {
+    ReplyTarget::Comment(comment_id) => {
+      let parent = Comment::read(&mut context.pool(), comment_id).await?;
+      let data = CreateComment {
+        content: reply.content,
+        post_id: parent.post_id,
+        parent_id: Some(parent.id),
+        language_id: None,
+      };
+      create_comment(Json(data), context, local_user_view).await?;
+    }
+    ReplyTarget::Post(post_id) => {
+      let data = CreateComment {
+        content: reply.content,
+        post_id,
+        parent_id: None,
+        language_id: None,
+      };
+      create_comment(Json(data), context, local_user_view).await?;
+    }
+    ReplyTarget::Person(recipient_id) => {
+      let data = CreatePrivateMessage {
+        content: reply.content,
+        recipient_id,
+      };
+      create_private_message(Json(data), context, local_user_view).await?;
+    }
+  }
+  Ok(())
+}
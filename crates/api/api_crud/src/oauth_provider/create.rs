@@ -1,10 +1,15 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
-use lemmy_db_schema::source::oauth_provider::{AdminOAuthProvider, OAuthProviderInsertForm};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  oauth_provider::{AdminOAuthProvider, OAuthProviderInsertForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::api::CreateOAuthProvider;
-use lemmy_diesel_utils::traits::Crud;
+use lemmy_diesel_utils::{connection::get_conn, traits::Crud};
 use lemmy_utils::error::LemmyError;
 use url::Url;
 
@@ -32,7 +37,26 @@ pub async fn create_oauth_provider(
     use_pkce: data.use_pkce,
     enabled: data.enabled,
   };
-  let oauth_provider =
-    AdminOAuthProvider::create(&mut context.pool(), &oauth_provider_form).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let oauth_provider = conn
+    .run_transaction(|conn| {
+      async move {
+        let oauth_provider =
+          AdminOAuthProvider::create(&mut conn.into(), &oauth_provider_form).await?;
+
+        // The client secret is never serialized, so it doesn't end up in the audit log
+        let audit_form = AdminAuditLogInsertForm::new(
+          AdminAuditKind::CreateOauthProvider,
+          local_user_view.person.id,
+        )
+        .target(oauth_provider.id.0)
+        .after(&oauth_provider)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(oauth_provider)
+      }
+      .scope_boxed()
+    })
+    .await?;
   Ok(Json(oauth_provider))
 }
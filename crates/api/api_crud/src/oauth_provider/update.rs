@@ -1,11 +1,17 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use chrono::Utc;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
-use lemmy_db_schema::source::oauth_provider::{AdminOAuthProvider, OAuthProviderUpdateForm};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  oauth_provider::{AdminOAuthProvider, OAuthProviderUpdateForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::api::EditOAuthProvider;
 use lemmy_diesel_utils::{
+  connection::get_conn,
   traits::Crud,
   utils::{diesel_required_string_update, diesel_required_url_update},
 };
@@ -37,8 +43,27 @@ pub async fn edit_oauth_provider(
     updated_at: Some(Some(Utc::now())),
   };
 
-  let update_result =
-    AdminOAuthProvider::update(&mut context.pool(), data.id, &oauth_provider_form).await?;
-  let oauth_provider = AdminOAuthProvider::read(&mut context.pool(), update_result.id).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let oauth_provider = conn
+    .run_transaction(|conn| {
+      async move {
+        let old_oauth_provider = AdminOAuthProvider::read(&mut conn.into(), data.id).await?;
+        let update_result =
+          AdminOAuthProvider::update(&mut conn.into(), data.id, &oauth_provider_form).await?;
+        let oauth_provider = AdminOAuthProvider::read(&mut conn.into(), update_result.id).await?;
+
+        let audit_form = AdminAuditLogInsertForm::new(
+          AdminAuditKind::EditOauthProvider,
+          local_user_view.person.id,
+        )
+        .target(oauth_provider.id.0)
+        .changed(&old_oauth_provider, &oauth_provider)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(oauth_provider)
+      }
+      .scope_boxed()
+    })
+    .await?;
   Ok(Json(oauth_provider))
 }
@@ -1,4 +1,4 @@
-use super::convert_published_time;
+use super::{check_gallery, convert_published_time, proxy_gallery, update_post_gallery};
 use crate::community_use_pending;
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
@@ -14,6 +14,7 @@ use lemmy_api_utils::{
     check_nsfw_allowed,
     get_url_blocklist,
     honeypot_check,
+    is_image_url,
     process_markdown_opt,
     send_webmention,
     slur_regex,
@@ -65,7 +66,8 @@ pub async fn create_post(
     &context,
   )
   .await?;
-  let url = diesel_url_create(data.url.as_deref())?;
+  let mut url = diesel_url_create(data.url.as_deref())?;
+  let mut alt_text = data.alt_text.clone();
   let custom_thumbnail = diesel_url_create(data.custom_thumbnail.as_deref())?;
   check_nsfw_allowed(data.nsfw, Some(&local_site))?;
 
@@ -80,10 +82,6 @@ pub async fn create_post(
     is_valid_url(custom_thumbnail)?;
   }
 
-  if let Some(alt_text) = &data.alt_text {
-    is_valid_alt_text_field(alt_text)?;
-  }
-
   if let Some(body) = &body {
     is_valid_body_field(body, true)?;
   }
@@ -98,6 +96,23 @@ pub async fn create_post(
   let community = &community_view.community;
   check_community_user_action(&local_user_view, community, &mut context.pool()).await?;
 
+  let require_alt_text = local_site.require_alt_text || community.require_alt_text;
+  let gallery = data.gallery.clone().unwrap_or_default();
+  check_gallery(&gallery, require_alt_text, &slur_regex, &url_blocklist)?;
+  let gallery = proxy_gallery(&gallery, &local_site, &context).await?;
+
+  // Use the first gallery image as url, for clients which don't support galleries
+  if let Some(first_image) = gallery.first()
+    && url.is_none()
+  {
+    url = diesel_url_create(Some(&first_image.url))?;
+    alt_text = alt_text.or_else(|| first_image.alt_text.clone());
+  }
+  is_valid_alt_text_field(
+    alt_text.as_deref(),
+    require_alt_text && url.as_deref().is_some_and(is_image_url),
+  )?;
+
   // Ensure that all posts in NSFW communities are marked as NSFW
   let nsfw = if community.nsfw {
     Some(true)
@@ -120,7 +135,7 @@ pub async fn create_post(
   let mut post_form = PostInsertForm {
     url,
     body,
-    alt_text: data.alt_text.clone(),
+    alt_text,
     nsfw,
     language_id: data.language_id,
     federation_pending: Some(community_use_pending(community, &context).await),
@@ -147,6 +162,9 @@ pub async fn create_post(
   if let Some(tags) = &data.tags {
     update_post_tags(&inserted_post, tags, &context).await?;
   }
+  if !gallery.is_empty() {
+    update_post_gallery(inserted_post.id, &gallery, &context).await?;
+  }
 
   let community_id = community.id;
   let federate_post = if scheduled_publish_time_at.is_none() {
@@ -1,8 +1,34 @@
 use chrono::{DateTime, TimeZone, Utc};
-use lemmy_api_utils::context::LemmyContext;
-use lemmy_db_schema::source::post::Post;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  utils::{is_image_url, proxy_image_link},
+};
+use lemmy_db_schema::{
+  newtypes::PostId,
+  source::{
+    local_site::LocalSite,
+    post::Post,
+    post_gallery_image::{PostGalleryImage, PostGalleryImageForm},
+  },
+};
 use lemmy_db_views_local_user::LocalUserView;
-use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use lemmy_db_views_post::api::GalleryImage;
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  utils::{
+    slurs::check_slurs,
+    validation::{
+      clean_url,
+      is_url_blocked,
+      is_valid_alt_text_field,
+      is_valid_gallery_caption,
+      is_valid_gallery_length,
+      is_valid_url,
+    },
+  },
+};
+use regex::{Regex, RegexSet};
+use url::Url;
 
 pub mod create;
 pub mod delete;
@@ -36,3 +62,212 @@ async fn convert_published_time(
     Ok(None)
   }
 }
+
+/// Validates the images of a gallery post. Alt text is required under the same conditions as for
+/// the post url.
+fn check_gallery(
+  gallery: &[GalleryImage],
+  require_alt_text: bool,
+  slur_regex: &Regex,
+  url_blocklist: &RegexSet,
+) -> LemmyResult<()> {
+  is_valid_gallery_length(gallery.len())?;
+  for image in gallery {
+    let url = gallery_image_url(image)?;
+    is_url_blocked(&url, url_blocklist)?;
+    is_valid_url(&url)?;
+    is_valid_alt_text_field(
+      image.alt_text.as_deref(),
+      require_alt_text && is_image_url(&url),
+    )?;
+    if let Some(caption) = &image.caption {
+      check_slurs(caption, slur_regex)?;
+      is_valid_gallery_caption(caption)?;
+    }
+  }
+  Ok(())
+}
+
+/// Proxies the gallery images like the post url, which also rejects images on the blocklist.
+/// Returns the gallery as it should be stored.
+async fn proxy_gallery(
+  gallery: &[GalleryImage],
+  local_site: &LocalSite,
+  context: &LemmyContext,
+) -> LemmyResult<Vec<GalleryImage>> {
+  let mut proxied = vec![];
+  for image in gallery {
+    let url = proxy_image_link(gallery_image_url(image)?, local_site, false, context).await?;
+    proxied.push(GalleryImage {
+      url: url.to_string(),
+      ..image.clone()
+    });
+  }
+  Ok(proxied)
+}
+
+async fn update_post_gallery(
+  post_id: PostId,
+  gallery: &[GalleryImage],
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let forms = gallery
+    .iter()
+    .zip(0..)
+    .map(|(image, position)| {
+      Ok(PostGalleryImageForm::new(
+        post_id,
+        position,
+        gallery_image_url(image)?.into(),
+        image.alt_text.clone(),
+        image.caption.clone(),
+      ))
+    })
+    .collect::<LemmyResult<Vec<_>>>()?;
+  PostGalleryImage::replace(&mut context.pool(), post_id, &forms).await?;
+  Ok(())
+}
+
+fn gallery_image_url(image: &GalleryImage) -> LemmyResult<Url> {
+  let url = Url::parse(&image.url).with_lemmy_type(LemmyErrorType::InvalidUrl)?;
+  Ok(clean_url(&url))
+}
+
+#[cfg(test)]
+#[expect(clippy::indexing_slicing)]
+mod tests {
+  use super::{create::create_post, update::edit_post};
+  use actix_web::web::Json;
+  use lemmy_api_utils::context::LemmyContext;
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      person::Person,
+    },
+    test_data::TestData,
+  };
+  use lemmy_db_views_local_user::LocalUserView;
+  use lemmy_db_views_post::api::{CreatePost, EditPost, GalleryImage};
+  use lemmy_diesel_utils::{dburl::DbUrl, traits::Crud};
+  use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  fn image(url: &str, alt_text: &str) -> GalleryImage {
+    GalleryImage {
+      url: url.to_string(),
+      alt_text: Some(alt_text.to_string()),
+      caption: None,
+    }
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_create_edit_gallery() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let data = TestData::create(pool).await?;
+    let user = LocalUserView::create_test_user(pool, "gallery_user", "", false).await?;
+    let community_form = CommunityInsertForm::new(
+      user.person.instance_id,
+      "gallery".to_string(),
+      "gallery".to_string(),
+      "pubkey".to_string(),
+    );
+    let community = Community::create(pool, &community_form).await?;
+
+    // The first gallery image is used as post url
+    let form = CreatePost {
+      name: "Gallery".to_string(),
+      community_id: community.id,
+      gallery: Some(vec![
+        image("https://example.com/1.png", "first"),
+        GalleryImage {
+          caption: Some("A caption".to_string()),
+          ..image("https://example.com/2.png", "second")
+        },
+      ]),
+      ..Default::default()
+    };
+    let post_view = create_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    let gallery = &post_view.gallery.0;
+    assert_eq!(2, gallery.len());
+    assert_eq!(post_view.post.url.as_ref(), Some(&gallery[0].url));
+    assert_eq!(Some("first"), post_view.post.alt_text.as_deref());
+    assert_eq!(Some("A caption"), gallery[1].caption.as_deref());
+
+    // Captions are length checked
+    let form = CreatePost {
+      name: "Gallery".to_string(),
+      community_id: community.id,
+      gallery: Some(vec![GalleryImage {
+        caption: Some("a".repeat(2000)),
+        ..image("https://example.com/1.png", "first")
+      }]),
+      ..Default::default()
+    };
+    let res = create_post(Json(form), context.clone(), user.clone()).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::GalleryCaptionLengthOverflow));
+
+    // Editing the gallery also changes the post url
+    let form = EditPost {
+      post_id: post_view.post.id,
+      gallery: Some(vec![image("https://example.com/3.png", "third")]),
+      ..Default::default()
+    };
+    let post_view = edit_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    let gallery = &post_view.gallery.0;
+    assert_eq!(1, gallery.len());
+    assert_eq!(post_view.post.url.as_ref(), Some(&gallery[0].url));
+    assert_eq!(Some("third"), post_view.post.alt_text.as_deref());
+
+    // Removing all images also removes the url
+    let form = EditPost {
+      post_id: post_view.post.id,
+      gallery: Some(vec![]),
+      ..Default::default()
+    };
+    let post_view = edit_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    assert!(post_view.gallery.0.is_empty());
+    assert_eq!(None, post_view.post.url);
+    assert_eq!(None, post_view.post.alt_text);
+
+    // A separate post url is kept when the gallery changes
+    let article: DbUrl = Url::parse("https://example.com/article")?.into();
+    let form = CreatePost {
+      name: "Link with gallery".to_string(),
+      community_id: community.id,
+      url: Some(article.to_string()),
+      gallery: Some(vec![image("https://example.com/1.png", "first")]),
+      ..Default::default()
+    };
+    let post_view = create_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    let form = EditPost {
+      post_id: post_view.post.id,
+      gallery: Some(vec![]),
+      ..Default::default()
+    };
+    let post_view = edit_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    assert_eq!(Some(article), post_view.post.url);
+
+    Person::delete(pool, user.person.id).await?;
+    data.delete(pool).await?;
+    Ok(())
+  }
+}
@@ -5,7 +5,7 @@ use lemmy_api_utils::{
   context::LemmyContext,
   notify::notify_mod_action,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::check_community_mod_action,
+  utils::{check_community_mod_action, check_expire_time, check_rule_in_community},
 };
 use lemmy_db_schema::{
   source::{
@@ -31,6 +31,11 @@ pub async fn remove_post(
 ) -> LemmyResult<Json<PostResponse>> {
   let post_id = data.post_id;
   let remove_post = data.remove_children.unwrap_or(data.removed);
+  let expires_at = if remove_post {
+    check_expire_time(data.expires_at)?
+  } else {
+    None
+  };
 
   // We cannot use PostView to avoid a database read here, as it doesn't return removed items
   // by default. So we would have to pass in `is_mod_or_admin`, but that is impossible without
@@ -39,6 +44,9 @@ pub async fn remove_post(
   let community = Community::read(&mut context.pool(), orig_post.community_id).await?;
 
   check_community_mod_action(&local_user_view, &community, false, &mut context.pool()).await?;
+  let rule_id = check_rule_in_community(data.rule_id, community.id, &mut context.pool())
+    .await?
+    .map(|r| r.id);
 
   LocalUser::is_higher_mod_or_admin_check(
     &mut context.pool(),
@@ -67,7 +75,9 @@ pub async fn remove_post(
     local_user_view.person.id,
     &post,
     remove_post,
+    expires_at,
     &data.reason,
+    rule_id,
     None,
   );
   let action = Modlog::create(&mut context.pool(), &[form]).await?;
@@ -87,7 +97,9 @@ pub async fn remove_post(
           comment,
           community.id,
           remove_children,
+          expires_at,
           &data.reason,
+          rule_id,
           None,
         )
       })
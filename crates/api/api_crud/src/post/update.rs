@@ -1,4 +1,4 @@
-use super::convert_published_time;
+use super::{check_gallery, convert_published_time, proxy_gallery, update_post_gallery};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use chrono::Utc;
@@ -13,6 +13,7 @@ use lemmy_api_utils::{
     check_community_user_action,
     check_nsfw_allowed,
     get_url_blocklist,
+    is_image_url,
     process_markdown_opt,
     send_webmention,
     slur_regex,
@@ -34,7 +35,7 @@ use lemmy_db_views_post::{
 use lemmy_db_views_site::SiteView;
 use lemmy_diesel_utils::{
   traits::Crud,
-  utils::{diesel_string_update, diesel_url_update},
+  utils::{diesel_string_update, diesel_url_create, diesel_url_update},
 };
 use lemmy_utils::{
   error::{LemmyErrorType, LemmyResult},
@@ -58,7 +59,7 @@ pub async fn edit_post(
 ) -> LemmyResult<Json<PostResponse>> {
   let local_site = SiteView::read_local(&mut context.pool()).await?.local_site;
   let local_instance_id = local_user_view.person.instance_id;
-  let url = diesel_url_update(data.url.as_deref())?;
+  let mut url = diesel_url_update(data.url.as_deref())?;
 
   let custom_thumbnail = diesel_url_update(data.custom_thumbnail.as_deref())?;
 
@@ -80,7 +81,7 @@ pub async fn edit_post(
 
   check_nsfw_allowed(data.nsfw, Some(&local_site))?;
 
-  let alt_text = diesel_string_update(data.alt_text.as_deref());
+  let mut alt_text = diesel_string_update(data.alt_text.as_deref());
 
   if let Some(name) = &data.name {
     is_valid_post_title(name)?;
@@ -91,10 +92,6 @@ pub async fn edit_post(
     is_valid_body_field(body, true)?;
   }
 
-  if let Some(Some(alt_text)) = &alt_text {
-    is_valid_alt_text_field(alt_text)?;
-  }
-
   if let Some(Some(url)) = &url {
     is_url_blocked(url, &url_blocklist)?;
     is_valid_url(url)?;
@@ -127,6 +124,45 @@ pub async fn edit_post(
     return Err(LemmyErrorType::NoPostEditAllowed.into());
   }
 
+  let require_alt_text = local_site.require_alt_text || orig_post.community.require_alt_text;
+  let gallery = match &data.gallery {
+    Some(gallery) => {
+      check_gallery(gallery, require_alt_text, &slur_regex, &url_blocklist)?;
+      Some(proxy_gallery(gallery, &local_site, &context).await?)
+    }
+    None => None,
+  };
+
+  // If the post url is the first gallery image (or missing), keep it in sync with the gallery.
+  // When all gallery images are removed, the url is removed too.
+  let orig_first_image = orig_post.gallery.0.first().map(|i| &i.url);
+  let url_from_gallery =
+    orig_post.post.url.is_none() || orig_post.post.url.as_ref() == orig_first_image;
+  if let Some(gallery) = &gallery
+    && data.url.is_none()
+    && url_from_gallery
+  {
+    let first_image = gallery.first();
+    url = Some(diesel_url_create(first_image.map(|i| i.url.as_str()))?);
+    if data.alt_text.is_none() {
+      alt_text = Some(first_image.and_then(|i| i.alt_text.clone()));
+    }
+  }
+
+  // Check the alt text of the post as it will be after the edit
+  let new_url = match &url {
+    Some(url) => url.as_deref(),
+    None => orig_post.post.url.as_deref(),
+  };
+  let new_alt_text = match &alt_text {
+    Some(alt_text) => alt_text.as_deref(),
+    None => orig_post.post.alt_text.as_deref(),
+  };
+  is_valid_alt_text_field(
+    new_alt_text,
+    require_alt_text && new_url.is_some_and(is_image_url),
+  )?;
+
   // handle changes to scheduled_publish_time
   let scheduled_publish_time_at = match (
     orig_post.post.scheduled_publish_time_at,
@@ -168,6 +204,9 @@ pub async fn edit_post(
   if let Some(tags) = &data.tags {
     update_post_tags(&orig_post.post, tags, &context).await?;
   }
+  if let Some(gallery) = &gallery {
+    update_post_gallery(post_id, gallery, &context).await?;
+  }
 
   NotifyData::new(
     updated_post.clone(),
@@ -1,5 +1,9 @@
 use super::not_zero;
-use crate::site::{application_question_check, site_default_post_listing_type_check};
+use crate::site::{
+  application_question_check,
+  retention_days_check,
+  site_default_post_listing_type_check,
+};
 use activitypub_federation::{config::Data, http_signatures::generate_actor_keypair};
 use actix_web::web::Json;
 use chrono::Utc;
@@ -133,6 +137,14 @@ pub async fn create_site(
     image_max_upload_size: data.image_max_upload_size,
     image_allow_video_uploads: data.image_allow_video_uploads,
     image_upload_disabled: data.image_upload_disabled,
+    image_upload_quota_bytes: diesel_opt_number_update(data.image_upload_quota_bytes),
+    require_alt_text: data.require_alt_text,
+    sent_activity_retention_days: data.sent_activity_retention_days,
+    received_activity_retention_days: data.received_activity_retention_days,
+    login_ip_retention_days: diesel_opt_number_update(data.login_ip_retention_days),
+    post_read_retention_days: diesel_opt_number_update(data.post_read_retention_days),
+    notification_retention_days: diesel_opt_number_update(data.notification_retention_days),
+    remote_content_retention_days: diesel_opt_number_update(data.remote_content_retention_days),
   };
 
   LocalSite::update(&mut context.pool(), &local_site_form).await?;
@@ -154,6 +166,16 @@ pub async fn create_site(
     import_user_settings_interval_seconds: not_zero(
       data.rate_limit_import_user_settings_interval_seconds,
     ),
+    vote_max_requests: data.rate_limit_vote_max_requests,
+    vote_interval_seconds: not_zero(data.rate_limit_vote_interval_seconds),
+    report_max_requests: data.rate_limit_report_max_requests,
+    report_interval_seconds: not_zero(data.rate_limit_report_interval_seconds),
+    follow_max_requests: data.rate_limit_follow_max_requests,
+    follow_interval_seconds: not_zero(data.rate_limit_follow_interval_seconds),
+    private_message_max_requests: data.rate_limit_private_message_max_requests,
+    private_message_interval_seconds: not_zero(data.rate_limit_private_message_interval_seconds),
+    federation_inbox_max_requests: data.rate_limit_federation_inbox_max_requests,
+    federation_inbox_interval_seconds: not_zero(data.rate_limit_federation_inbox_interval_seconds),
     updated_at: Some(Some(Utc::now())),
   };
 
@@ -193,6 +215,19 @@ fn validate_create_payload(local_site: &LocalSite, create_site: &CreateSite) ->
 
   site_default_post_listing_type_check(&create_site.default_post_listing_type)?;
 
+  retention_days_check(
+    &[
+      create_site.sent_activity_retention_days,
+      create_site.received_activity_retention_days,
+    ],
+    &[
+      create_site.login_ip_retention_days,
+      create_site.post_read_retention_days,
+      create_site.notification_retention_days,
+      create_site.remote_content_retention_days,
+    ],
+  )?;
+
   // Ensure that the sidebar has fewer than the max num characters...
   if let Some(sidebar) = &create_site.sidebar {
     is_valid_body_field(sidebar, false)?;
@@ -40,6 +40,30 @@ pub fn application_question_check(
   }
 }
 
+/// Longer retention periods can't be represented as timestamps, and wouldn't be useful anyway.
+const MAX_RETENTION_DAYS: i32 = 36500;
+
+/// Checks that retention periods are in range. Activities need to be kept for at least a day,
+/// the other periods can be set to zero to keep the data forever.
+pub fn retention_days_check(
+  activity_days: &[Option<i32>],
+  optional_days: &[Option<i32>],
+) -> LemmyResult<()> {
+  let activity_days_valid = activity_days
+    .iter()
+    .flatten()
+    .all(|days| (1..=MAX_RETENTION_DAYS).contains(days));
+  let optional_days_valid = optional_days
+    .iter()
+    .flatten()
+    .all(|days| (0..=MAX_RETENTION_DAYS).contains(days));
+  if activity_days_valid && optional_days_valid {
+    Ok(())
+  } else {
+    Err(LemmyErrorType::InvalidRetentionPeriod.into())
+  }
+}
+
 fn not_zero(val: Option<i32>) -> Option<i32> {
   match val {
     Some(0) => None,
@@ -50,7 +74,12 @@ fn not_zero(val: Option<i32>) -> Option<i32> {
 #[cfg(test)]
 mod tests {
 
-  use crate::site::{application_question_check, not_zero, site_default_post_listing_type_check};
+  use crate::site::{
+    application_question_check,
+    not_zero,
+    retention_days_check,
+    site_default_post_listing_type_check,
+  };
   use lemmy_db_schema_file::enums::{ListingType, RegistrationMode};
 
   #[test]
@@ -114,6 +143,16 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_retention_days_check() {
+    assert!(retention_days_check(&[None, Some(1)], &[None, Some(0), Some(30)]).is_ok());
+    assert!(retention_days_check(&[Some(0)], &[]).is_err());
+    assert!(retention_days_check(&[], &[Some(-1)]).is_err());
+    assert!(retention_days_check(&[Some(36500)], &[Some(36500)]).is_ok());
+    assert!(retention_days_check(&[Some(i32::MAX)], &[]).is_err());
+    assert!(retention_days_check(&[], &[Some(36501)]).is_err());
+  }
+
   #[test]
   fn test_not_zero() {
     assert_eq!(None, not_zero(None));
@@ -9,6 +9,7 @@ use lemmy_db_schema::source::{
   local_site_url_blocklist::LocalSiteUrlBlocklist,
   oauth_provider::AdminOAuthProvider,
   registration_application::RegistrationApplication,
+  rule::Rule,
   tagline::Tagline,
 };
 use lemmy_db_views_local_user::LocalUserView;
@@ -45,6 +46,7 @@ async fn read_site(context: &LemmyContext) -> LemmyResult<GetSiteResponse> {
   let discussion_languages = SiteLanguage::read_local_raw(&mut context.pool()).await?;
   let blocked_urls = LocalSiteUrlBlocklist::get_all(&mut context.pool()).await?;
   let tagline = Tagline::get_random(&mut context.pool()).await.ok();
+  let rules = Rule::list(&mut context.pool(), None).await?;
   let admin_oauth_providers = AdminOAuthProvider::get_all(&mut context.pool()).await?;
   let oauth_providers =
     AdminOAuthProvider::convert_providers_to_public(admin_oauth_providers.clone());
@@ -67,5 +69,6 @@ async fn read_site(context: &LemmyContext) -> LemmyResult<GetSiteResponse> {
     active_plugins: plugin_metadata(),
     last_application_duration_seconds,
     captcha_enabled: is_captcha_plugin_loaded(),
+    rules,
   })
 }
@@ -1,8 +1,13 @@
 use super::not_zero;
-use crate::site::{application_question_check, site_default_post_listing_type_check};
+use crate::site::{
+  application_question_check,
+  retention_days_check,
+  site_default_post_listing_type_check,
+};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use chrono::Utc;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{
   context::LemmyContext,
   utils::{
@@ -17,6 +22,7 @@ use lemmy_db_schema::{
   newtypes::MultiCommunityId,
   source::{
     actor_language::SiteLanguage,
+    admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
     local_site::{LocalSite, LocalSiteUpdateForm},
     local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitUpdateForm},
     local_site_url_blocklist::LocalSiteUrlBlocklist,
@@ -24,13 +30,14 @@ use lemmy_db_schema::{
     site::{Site, SiteUpdateForm},
   },
 };
-use lemmy_db_schema_file::enums::RegistrationMode;
+use lemmy_db_schema_file::enums::{AdminAuditKind, RegistrationMode};
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::{
   SiteView,
   api::{EditSite, SiteResponse},
 };
 use lemmy_diesel_utils::{
+  connection::get_conn,
   traits::Crud,
   utils::{diesel_opt_number_update, diesel_string_update},
 };
@@ -53,18 +60,22 @@ pub async fn edit_site(
   context: Data<LemmyContext>,
   local_user_view: LocalUserView,
 ) -> LemmyResult<Json<SiteResponse>> {
-  let site_view = SiteView::read_local(&mut context.pool()).await?;
-  let local_site = site_view.local_site;
-  let site = site_view.site;
+  let old_site_view = SiteView::read_local(&mut context.pool()).await?;
+  let local_site = old_site_view.local_site.clone();
+  let site = old_site_view.site.clone();
 
   // Make sure user is an admin; other types of users should not update site data...
   is_admin(&local_user_view)?;
 
   validate_update_payload(&local_site, &data)?;
 
-  if let Some(discussion_languages) = data.discussion_languages.clone() {
-    SiteLanguage::update(&mut context.pool(), discussion_languages.clone(), &site).await?;
-  }
+  // If this validation changes it must be synced with
+  // lemmy_utils::utils::markdown::create_url_blocklist_test_regex_set.
+  let parsed_urls = data
+    .blocked_urls
+    .as_ref()
+    .map(check_urls_are_valid)
+    .transpose()?;
 
   let slur_regex = slur_regex(&context).await?;
   let url_blocklist = get_url_blocklist(&context).await?;
@@ -96,12 +107,6 @@ pub async fn edit_site(
     ..Default::default()
   };
 
-  Site::update(&mut context.pool(), site.id, &site_form)
-    .await
-    // Ignore errors for all these, so as to not throw errors if no update occurs
-    // Diesel will throw an error for empty update forms
-    .ok();
-
   let local_site_form = LocalSiteUpdateForm {
     site_setup: None,
     federation_signed_fetch: data.federation_signed_fetch,
@@ -140,12 +145,16 @@ pub async fn edit_site(
     image_max_upload_size: data.image_max_upload_size,
     image_allow_video_uploads: data.image_allow_video_uploads,
     image_upload_disabled: data.image_upload_disabled,
+    image_upload_quota_bytes: diesel_opt_number_update(data.image_upload_quota_bytes),
+    require_alt_text: data.require_alt_text,
+    sent_activity_retention_days: data.sent_activity_retention_days,
+    received_activity_retention_days: data.received_activity_retention_days,
+    login_ip_retention_days: diesel_opt_number_update(data.login_ip_retention_days),
+    post_read_retention_days: diesel_opt_number_update(data.post_read_retention_days),
+    notification_retention_days: diesel_opt_number_update(data.notification_retention_days),
+    remote_content_retention_days: diesel_opt_number_update(data.remote_content_retention_days),
   };
 
-  let update_local_site = LocalSite::update(&mut context.pool(), &local_site_form)
-    .await
-    .ok();
-
   let local_site_rate_limit_form = LocalSiteRateLimitUpdateForm {
     message_max_requests: data.rate_limit_message_max_requests,
     message_interval_seconds: not_zero(data.rate_limit_message_interval_seconds),
@@ -163,45 +172,96 @@ pub async fn edit_site(
     import_user_settings_interval_seconds: not_zero(
       data.rate_limit_import_user_settings_interval_seconds,
     ),
+    vote_max_requests: data.rate_limit_vote_max_requests,
+    vote_interval_seconds: not_zero(data.rate_limit_vote_interval_seconds),
+    report_max_requests: data.rate_limit_report_max_requests,
+    report_interval_seconds: not_zero(data.rate_limit_report_interval_seconds),
+    follow_max_requests: data.rate_limit_follow_max_requests,
+    follow_interval_seconds: not_zero(data.rate_limit_follow_interval_seconds),
+    private_message_max_requests: data.rate_limit_private_message_max_requests,
+    private_message_interval_seconds: not_zero(data.rate_limit_private_message_interval_seconds),
+    federation_inbox_max_requests: data.rate_limit_federation_inbox_max_requests,
+    federation_inbox_interval_seconds: not_zero(data.rate_limit_federation_inbox_interval_seconds),
     updated_at: Some(Some(Utc::now())),
   };
 
-  LocalSiteRateLimit::update(&mut context.pool(), &local_site_rate_limit_form)
-    .await
-    .ok();
+  // The changes are written together with the audit log entry, so that neither can exist without
+  // the other.
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let site_view = conn
+    .run_transaction(|conn| {
+      async move {
+        let old_site_view = SiteView::read_local_uncached(&mut conn.into()).await?;
 
-  if let Some(url_blocklist) = data.blocked_urls.clone() {
-    // If this validation changes it must be synced with
-    // lemmy_utils::utils::markdown::create_url_blocklist_test_regex_set.
-    let parsed_urls = check_urls_are_valid(&url_blocklist)?;
-    LocalSiteUrlBlocklist::replace(&mut context.pool(), parsed_urls).await?;
-  }
+        if let Some(discussion_languages) = data.discussion_languages.clone() {
+          SiteLanguage::update(&mut conn.into(), discussion_languages, &site).await?;
+        }
 
-  // TODO can't think of a better way to do this.
-  // If the server suddenly requires email verification, or required applications, no old users
-  // will be able to log in. It really only wants this to be a requirement for NEW signups.
-  // So if it was set from false, to true, you need to update all current users columns to be
-  // verified.
+        // Ignore errors for all these, so as to not throw errors if no update occurs
+        // Diesel will throw an error for empty update forms
+        Site::update(&mut conn.into(), site.id, &site_form)
+          .await
+          .ok();
+        let update_local_site = LocalSite::update(&mut conn.into(), &local_site_form)
+          .await
+          .ok();
+        LocalSiteRateLimit::update(&mut conn.into(), &local_site_rate_limit_form)
+          .await
+          .ok();
 
-  let old_require_application =
-    local_site.registration_mode == RegistrationMode::RequireApplication;
-  let new_require_application = update_local_site
-    .as_ref()
-    .map(|ols| ols.registration_mode == RegistrationMode::RequireApplication)
-    .unwrap_or(false);
-  if !old_require_application && new_require_application {
-    LocalUser::set_all_users_registration_applications_accepted(&mut context.pool()).await?;
-  }
+        if let Some(parsed_urls) = parsed_urls {
+          let old_urls: Vec<String> = LocalSiteUrlBlocklist::get_all(&mut conn.into())
+            .await?
+            .into_iter()
+            .map(|b| b.url)
+            .collect();
+          LocalSiteUrlBlocklist::replace(&mut conn.into(), parsed_urls.clone()).await?;
 
-  let new_require_email_verification = update_local_site
-    .as_ref()
-    .map(|ols| ols.require_email_verification)
-    .unwrap_or(false);
-  if !local_site.require_email_verification && new_require_email_verification {
-    LocalUser::set_all_users_email_verified(&mut context.pool()).await?;
-  }
+          let audit_form = AdminAuditLogInsertForm::new(
+            AdminAuditKind::EditUrlBlocklist,
+            local_user_view.person.id,
+          )
+          .before(&old_urls)?
+          .after(&parsed_urls)?;
+          AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        }
+
+        // TODO can't think of a better way to do this.
+        // If the server suddenly requires email verification, or required applications, no old
+        // users will be able to log in. It really only wants this to be a requirement for NEW
+        // signups. So if it was set from false, to true, you need to update all current users
+        // columns to be verified.
+
+        let old_require_application =
+          local_site.registration_mode == RegistrationMode::RequireApplication;
+        let new_require_application = update_local_site
+          .as_ref()
+          .map(|ols| ols.registration_mode == RegistrationMode::RequireApplication)
+          .unwrap_or(false);
+        if !old_require_application && new_require_application {
+          LocalUser::set_all_users_registration_applications_accepted(&mut conn.into()).await?;
+        }
 
-  let site_view = SiteView::read_local(&mut context.pool()).await?;
+        let new_require_email_verification = update_local_site
+          .as_ref()
+          .map(|ols| ols.require_email_verification)
+          .unwrap_or(false);
+        if !local_site.require_email_verification && new_require_email_verification {
+          LocalUser::set_all_users_email_verified(&mut conn.into()).await?;
+        }
+
+        let site_view = SiteView::read_local_uncached(&mut conn.into()).await?;
+
+        let audit_form =
+          AdminAuditLogInsertForm::new(AdminAuditKind::EditSite, local_user_view.person.id)
+            .changed(&old_site_view, &site_view)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(site_view)
+      }
+      .scope_boxed()
+    })
+    .await?;
 
   let rate_limit_config =
     local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
@@ -233,6 +293,19 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
 
   site_default_post_listing_type_check(&edit_site.default_post_listing_type)?;
 
+  retention_days_check(
+    &[
+      edit_site.sent_activity_retention_days,
+      edit_site.received_activity_retention_days,
+    ],
+    &[
+      edit_site.login_ip_retention_days,
+      edit_site.post_read_retention_days,
+      edit_site.notification_retention_days,
+      edit_site.remote_content_retention_days,
+    ],
+  )?;
+
   // Ensure that the sidebar has fewer than the max num characters...
   if let Some(sidebar) = &edit_site.sidebar {
     is_valid_body_field(sidebar, false)?;
@@ -1,16 +1,21 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{
   context::LemmyContext,
   utils::{get_url_blocklist, is_admin, process_markdown, slur_regex},
 };
-use lemmy_db_schema::source::tagline::{Tagline, TaglineInsertForm};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  tagline::{Tagline, TaglineInsertForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::{
   SiteView,
   api::{CreateTagline, TaglineResponse},
 };
-use lemmy_diesel_utils::traits::Crud;
+use lemmy_diesel_utils::{connection::get_conn, traits::Crud};
 use lemmy_utils::error::LemmyError;
 
 pub async fn create_tagline(
@@ -35,7 +40,23 @@ pub async fn create_tagline(
 
   let tagline_form = TaglineInsertForm { content };
 
-  let tagline = Tagline::create(&mut context.pool(), &tagline_form).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let tagline = conn
+    .run_transaction(|conn| {
+      async move {
+        let tagline = Tagline::create(&mut conn.into(), &tagline_form).await?;
+
+        let audit_form =
+          AdminAuditLogInsertForm::new(AdminAuditKind::CreateTagline, local_user_view.person.id)
+            .target(tagline.id.0)
+            .after(&tagline)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(tagline)
+      }
+      .scope_boxed()
+    })
+    .await?;
 
   Ok(Json(TaglineResponse { tagline }))
 }
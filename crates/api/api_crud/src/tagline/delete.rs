@@ -1,10 +1,15 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
-use lemmy_db_schema::source::tagline::Tagline;
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  tagline::Tagline,
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::api::{DeleteTagline, SuccessResponse};
-use lemmy_diesel_utils::traits::Crud;
+use lemmy_diesel_utils::{connection::get_conn, traits::Crud};
 use lemmy_utils::error::LemmyError;
 
 pub async fn delete_tagline(
@@ -15,7 +20,24 @@ pub async fn delete_tagline(
   // Make sure user is an admin
   is_admin(&local_user_view)?;
 
-  Tagline::delete(&mut context.pool(), data.id).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .run_transaction(|conn| {
+      async move {
+        let tagline = Tagline::read(&mut conn.into(), data.id).await?;
+        Tagline::delete(&mut conn.into(), data.id).await?;
+
+        let audit_form =
+          AdminAuditLogInsertForm::new(AdminAuditKind::DeleteTagline, local_user_view.person.id)
+            .target(tagline.id.0)
+            .before(&tagline)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(())
+      }
+      .scope_boxed()
+    })
+    .await?;
 
   Ok(Json(SuccessResponse::default()))
 }
@@ -1,17 +1,22 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use chrono::Utc;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use lemmy_api_utils::{
   context::LemmyContext,
   utils::{get_url_blocklist, is_admin, process_markdown, slur_regex},
 };
-use lemmy_db_schema::source::tagline::{Tagline, TaglineUpdateForm};
+use lemmy_db_schema::source::{
+  admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
+  tagline::{Tagline, TaglineUpdateForm},
+};
+use lemmy_db_schema_file::enums::AdminAuditKind;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::{
   SiteView,
   api::{EditTagline, TaglineResponse},
 };
-use lemmy_diesel_utils::traits::Crud;
+use lemmy_diesel_utils::{connection::get_conn, traits::Crud};
 use lemmy_utils::error::LemmyError;
 
 pub async fn edit_tagline(
@@ -39,7 +44,24 @@ pub async fn edit_tagline(
     updated_at: Some(Some(Utc::now())),
   };
 
-  let tagline = Tagline::update(&mut context.pool(), data.id, &tagline_form).await?;
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let tagline = conn
+    .run_transaction(|conn| {
+      async move {
+        let old_tagline = Tagline::read(&mut conn.into(), data.id).await?;
+        let tagline = Tagline::update(&mut conn.into(), data.id, &tagline_form).await?;
+
+        let audit_form =
+          AdminAuditLogInsertForm::new(AdminAuditKind::EditTagline, local_user_view.person.id)
+            .target(tagline.id.0)
+            .changed(&old_tagline, &tagline)?;
+        AdminAuditLog::create(&mut conn.into(), &audit_form).await?;
+        Ok(tagline)
+      }
+      .scope_boxed()
+    })
+    .await?;
 
   Ok(Json(TaglineResponse { tagline }))
 }
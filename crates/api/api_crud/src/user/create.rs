@@ -10,6 +10,7 @@ use lemmy_api_utils::{
   context::LemmyContext,
   plugins::{is_captcha_plugin_loaded, plugin_validate_captcha},
   utils::{
+    audit_admin_login,
     check_email_verified,
     check_local_user_valid,
     check_registration_application,
@@ -225,6 +226,57 @@ pub async fn register(
   Ok(Json(login_response))
 }
 
+/// Creates an account without captcha, registration application or email verification. Used by
+/// the admin command line.
+pub async fn create_user_account(
+  username: String,
+  password: String,
+  email: Option<String>,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<LocalUserView> {
+  let pool = &mut context.pool();
+  password_length_check(&password)?;
+  let slur_regex = slur_regex(context).await?;
+  check_slurs(&username, &slur_regex)?;
+  Person::check_username_taken(pool, &username).await?;
+  if let Some(email) = &email {
+    LocalUser::check_is_email_taken(pool, email).await?;
+  }
+
+  let site_view = SiteView::read_local(pool).await?;
+  let conn = &mut get_conn(pool).await?;
+  let tx_context = context.clone();
+  conn
+    .run_transaction(|conn| {
+      async move {
+        let person = create_person(username, &site_view, &tx_context, conn).await?;
+        let local_user_form = LocalUserInsertForm {
+          email: email.as_deref().map(str::to_lowercase),
+          email_verified: Some(true),
+          accepted_application: Some(true),
+          ..LocalUserInsertForm::new(person.id, Some(password))
+        };
+        let local_user = create_local_user(
+          conn,
+          vec![],
+          local_user_form,
+          &site_view.local_site,
+          &tx_context,
+        )
+        .await?;
+
+        Ok(LocalUserView {
+          person,
+          local_user,
+          banned: false,
+          ban_expires_at: None,
+        })
+      }
+      .scope_boxed()
+    })
+    .await
+}
+
 pub async fn authenticate_with_oauth(
   Json(data): Json<AuthenticateWithOauth>,
   req: HttpRequest,
@@ -454,6 +506,7 @@ pub async fn authenticate_with_oauth(
   };
 
   if !login_response.registration_created && !login_response.verify_email_sent {
+    audit_admin_login(&local_user, &req, &context).await?;
     let jwt = Claims::generate(local_user.id, data.stay_logged_in, req, &context).await?;
     login_response.jwt = Some(jwt);
   }
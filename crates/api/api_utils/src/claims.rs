@@ -7,7 +7,10 @@ use lemmy_db_schema::{
   source::login_token::{LoginToken, LoginTokenCreateForm},
 };
 use lemmy_diesel_utils::sensitive::SensitiveString;
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  rate_limit::client_ip,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
@@ -59,10 +62,7 @@ impl Claims {
     let secret = &context.secret().jwt_secret;
     let key = EncodingKey::from_secret(secret.as_ref());
     let token: SensitiveString = encode(&Header::default(), &my_claims, &key)?.into();
-    let ip = req
-      .connection_info()
-      .realip_remote_addr()
-      .map(ToString::to_string);
+    let ip = client_ip(&req);
     let user_agent = req
       .headers()
       .get(USER_AGENT)
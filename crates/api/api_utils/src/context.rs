@@ -1,10 +1,15 @@
 use crate::request::client_builder;
 use activitypub_federation::config::{Data, FederationConfig};
 use lemmy_db_schema::source::secret::Secret;
-use lemmy_diesel_utils::connection::{ActualDbPool, DbPool, build_db_pool_for_tests};
+use lemmy_diesel_utils::connection::{
+  ActualDbPool,
+  DbPool,
+  ReadReplicaPools,
+  build_db_pool_for_tests,
+};
 use lemmy_utils::{
   rate_limit::RateLimit,
-  settings::{SETTINGS, structs::Settings},
+  settings::{SETTINGS, current_settings, structs::Settings},
 };
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use std::sync::Arc;
@@ -12,6 +17,7 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct LemmyContext {
   pool: ActualDbPool,
+  read_replicas: ReadReplicaPools,
   client: Arc<ClientWithMiddleware>,
   /// Pictrs requests must bypass proxy. Unfortunately no_proxy can only be set on ClientBuilder
   /// and not on RequestBuilder, so we need a separate client here.
@@ -23,6 +29,7 @@ pub struct LemmyContext {
 impl LemmyContext {
   pub fn create(
     pool: ActualDbPool,
+    read_replicas: ReadReplicaPools,
     client: ClientWithMiddleware,
     pictrs_client: ClientWithMiddleware,
     secret: Secret,
@@ -30,14 +37,16 @@ impl LemmyContext {
   ) -> LemmyContext {
     LemmyContext {
       pool,
+      read_replicas,
       client: Arc::new(client),
       pictrs_client: Arc::new(pictrs_client),
       secret: Arc::new(secret),
       rate_limit_cell,
     }
   }
+  /// Listing queries in this pool are sent to a read replica, if any are configured.
   pub fn pool(&self) -> DbPool<'_> {
-    DbPool::Pool(&self.pool)
+    self.read_replicas.with_primary(&self.pool)
   }
   pub fn inner_pool(&self) -> &ActualDbPool {
     &self.pool
@@ -49,7 +58,7 @@ impl LemmyContext {
     &self.pictrs_client
   }
   pub fn settings(&self) -> &'static Settings {
-    &SETTINGS
+    current_settings()
   }
   pub fn secret(&self) -> &Secret {
     &self.secret
@@ -78,6 +87,7 @@ impl LemmyContext {
 
     let context = LemmyContext::create(
       pool,
+      ReadReplicaPools::default(),
       client.clone(),
       client,
       secret,
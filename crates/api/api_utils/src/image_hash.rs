@@ -0,0 +1,136 @@
+//! Perceptual image hashes, used to recognize images from the [`ImageHashBlock`] list even if they
+//! were resized or re-encoded.
+use crate::{context::LemmyContext, media::decode};
+use bytes::Bytes;
+use image::{DynamicImage, imageops::FilterType};
+use lemmy_db_schema::source::images::ImageHashBlock;
+use lemmy_utils::{
+  CACHE_DURATION_FEDERATION,
+  CacheLock,
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+};
+use moka::future::Cache;
+use std::sync::LazyLock;
+
+/// Images are considered the same if their hashes differ in at most this many bits.
+const MAX_HASH_DISTANCE: u32 = 8;
+
+/// Calculates the difference hash of an image, by comparing the brightness of neighbouring pixels
+/// in a small grayscale version.
+pub fn perceptual_hash(image: &DynamicImage) -> i64 {
+  let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+  let mut hash = 0u64;
+  for y in 0..8 {
+    for x in 0..8 {
+      let [left] = small.get_pixel(x, y).0;
+      let [right] = small.get_pixel(x + 1, y).0;
+      hash = (hash << 1) | u64::from(left < right);
+    }
+  }
+  i64::from_be_bytes(hash.to_be_bytes())
+}
+
+/// Decodes an image and returns its perceptual hash.
+pub async fn hash_image_data(data: Bytes) -> LemmyResult<i64> {
+  tokio::task::spawn_blocking(move || {
+    let format = image::guess_format(&data).with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+    let image = decode(&data, format)?;
+    Ok(perceptual_hash(&image))
+  })
+  .await?
+}
+
+/// Formats a hash as hex string, which is used for the API.
+pub fn format_image_hash(hash: i64) -> String {
+  format!("{:016x}", u64::from_be_bytes(hash.to_be_bytes()))
+}
+
+pub fn parse_image_hash(hash: &str) -> LemmyResult<i64> {
+  if hash.len() != 16 {
+    return Err(LemmyErrorType::InvalidImageHash.into());
+  }
+  let hash = u64::from_str_radix(hash, 16).with_lemmy_type(LemmyErrorType::InvalidImageHash)?;
+  Ok(i64::from_be_bytes(hash.to_be_bytes()))
+}
+
+/// Returns an error if the hash is similar to one on the blocklist.
+pub async fn check_image_hash(hash: i64, context: &LemmyContext) -> LemmyResult<()> {
+  static BLOCKLIST: CacheLock<Vec<i64>> = LazyLock::new(|| {
+    Cache::builder()
+      .max_capacity(1)
+      .time_to_live(CACHE_DURATION_FEDERATION)
+      .build()
+  });
+
+  let blocklist = BLOCKLIST
+    .try_get_with::<_, LemmyError>((), async {
+      let blocks = ImageHashBlock::list(&mut context.pool()).await?;
+      Ok(blocks.into_iter().map(|b| b.hash).collect())
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to load image hash blocklist due to `{}`", e))?;
+
+  if blocklist.iter().any(|b| is_similar(hash, *b)) {
+    Err(LemmyErrorType::ImageBlocked.into())
+  } else {
+    Ok(())
+  }
+}
+
+/// Returns true if the result failed because the image is on the blocklist.
+pub fn is_image_blocked<T>(res: &LemmyResult<T>) -> bool {
+  res
+    .as_ref()
+    .is_err_and(|e| e.error_type == LemmyErrorType::ImageBlocked)
+}
+
+fn is_similar(a: i64, b: i64) -> bool {
+  (a ^ b).count_ones() <= MAX_HASH_DISTANCE
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{Rgb, RgbImage};
+  use pretty_assertions::assert_eq;
+
+  fn test_image(width: u32, height: u32) -> DynamicImage {
+    let image = RgbImage::from_fn(width, height, |x, y| {
+      // Bright spot in the upper left, with a vertical stripe
+      let distance = (x * 255 / width) + (y * 255 / height);
+      let value = u8::try_from(255u32.saturating_sub(distance)).unwrap_or_default();
+      if (width / 2..width * 5 / 8).contains(&x) {
+        Rgb([255, 255, 255])
+      } else {
+        Rgb([value, value, value])
+      }
+    });
+    DynamicImage::ImageRgb8(image)
+  }
+
+  #[test]
+  fn test_perceptual_hash() {
+    let hash = perceptual_hash(&test_image(400, 300));
+
+    // Resizing and re-encoding doesn't change the hash much
+    let resized = perceptual_hash(&test_image(400, 300).resize(120, 90, FilterType::Nearest));
+    assert!(is_similar(hash, resized));
+    let grayscale = perceptual_hash(&DynamicImage::ImageLuma8(test_image(400, 300).to_luma8()));
+    assert!(is_similar(hash, grayscale));
+
+    // A different image has a different hash
+    let flipped = perceptual_hash(&test_image(400, 300).fliph());
+    assert!(!is_similar(hash, flipped));
+  }
+
+  #[test]
+  fn test_format_image_hash() -> LemmyResult<()> {
+    for hash in [0, 1, -1, i64::MIN, 0x0123_4567_89ab_cdef] {
+      assert_eq!(hash, parse_image_hash(&format_image_hash(hash))?);
+    }
+    assert_eq!("ffffffffffffffff", format_image_hash(-1));
+    assert!(parse_image_hash("xyz").is_err());
+    assert!(parse_image_hash("0123456789abcdeg").is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,305 @@
+//! Removes EXIF (including GPS location), XMP, IPTC and text metadata from uploaded images. This
+//! is done by Lemmy for all storage backends, so it doesn't depend on the pict-rs configuration.
+//!
+//! The metadata is removed from the file container without decoding the image, so that quality and
+//! animations are preserved. Only images with an EXIF orientation are re-encoded, as the rotation
+//! needs to be applied to the pixels before the metadata can be removed. Other image formats are
+//! rejected, as their metadata can't be removed.
+use crate::media::{decode_limits, encode};
+use bytes::Bytes;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, metadata::Orientation};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use std::io::Cursor;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Returns the image without metadata. With `allow_video`, videos are returned unchanged. They are
+/// only passed on to pict-rs, which transcodes them without metadata.
+pub fn strip_metadata(data: Bytes, allow_video: bool) -> LemmyResult<Bytes> {
+  let Ok(format) = image::guess_format(&data) else {
+    if allow_video && is_video(&data) {
+      return Ok(data);
+    }
+    return Err(LemmyErrorType::NotAnImageType.into());
+  };
+  let strip = match format {
+    ImageFormat::Jpeg => strip_jpeg,
+    ImageFormat::Png => strip_png,
+    ImageFormat::WebP => strip_webp,
+    // Doesn't support EXIF, and the orientation can't be set
+    ImageFormat::Gif => {
+      return strip_gif(&data)
+        .map(Into::into)
+        .ok_or_else(|| LemmyErrorType::NotAnImageType.into());
+    }
+    _ => return Err(LemmyErrorType::NotAnImageType.into()),
+  };
+
+  let mut decoder = ImageReader::with_format(Cursor::new(&data), format)
+    .into_decoder()
+    .with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+  decoder
+    .set_limits(decode_limits())
+    .with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+  let orientation = decoder.orientation()?;
+  if orientation != Orientation::NoTransforms {
+    let mut image =
+      DynamicImage::from_decoder(decoder).with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+    image.apply_orientation(orientation);
+    return Ok(encode(&image, format)?.into());
+  }
+
+  strip(&data)
+    .map(Into::into)
+    .ok_or_else(|| LemmyErrorType::NotAnImageType.into())
+}
+
+/// Detects MP4, MOV and WebM/Matroska files by their header.
+fn is_video(data: &[u8]) -> bool {
+  data.get(4..8) == Some(b"ftyp") || data.starts_with(b"\x1A\x45\xDF\xA3")
+}
+
+/// Removes the APP1 (EXIF and XMP), APP13 (IPTC), comment and multi-picture index segments. The
+/// image data is copied unchanged until the end of image marker. Anything after it, like further
+/// images of a multi-picture file with their own metadata, is removed.
+fn strip_jpeg(data: &[u8]) -> Option<Vec<u8>> {
+  let mut out = data.get(..2)?.to_vec();
+  let mut pos = 2;
+  loop {
+    let &[0xFF, marker] = data.get(pos..pos + 2)? else {
+      return None;
+    };
+    // End of image
+    if marker == 0xD9 {
+      out.extend_from_slice(data.get(pos..pos + 2)?);
+      return Some(out);
+    }
+    // Start of scan. The entropy coded data can't contain another marker 0xFFD9, as 0xFF bytes
+    // are followed by 0x00 or a restart marker there.
+    if marker == 0xDA {
+      let scan = data.get(pos..)?;
+      let end = scan
+        .windows(2)
+        .position(|w| w == [0xFF, 0xD9])
+        .map_or(scan.len(), |eoi| eoi + 2);
+      out.extend_from_slice(scan.get(..end)?);
+      return Some(out);
+    }
+    let &[len_high, len_low] = data.get(pos + 2..pos + 4)? else {
+      return None;
+    };
+    let end = pos + 2 + usize::from(u16::from_be_bytes([len_high, len_low]));
+    let segment = data.get(pos..end)?;
+    let is_mpf = marker == 0xE2 && segment.get(4..8) == Some(b"MPF\0");
+    if !matches!(marker, 0xE1 | 0xED | 0xFE) && !is_mpf {
+      out.extend_from_slice(segment);
+    }
+    pos = end;
+  }
+}
+
+/// Removes the EXIF, text and modification time chunks.
+fn strip_png(data: &[u8]) -> Option<Vec<u8>> {
+  let mut out = data.get(..PNG_SIGNATURE.len())?.to_vec();
+  let mut pos = PNG_SIGNATURE.len();
+  while pos < data.len() {
+    let len = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?);
+    // Length, chunk type, data and checksum
+    let end = pos + 12 + usize::try_from(len).ok()?;
+    let chunk_type = data.get(pos + 4..pos + 8)?;
+    if !matches!(chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+      out.extend_from_slice(data.get(pos..end)?);
+    }
+    pos = end;
+  }
+  Some(out)
+}
+
+/// Removes the EXIF and XMP chunks, and the corresponding flags in the extended header.
+fn strip_webp(data: &[u8]) -> Option<Vec<u8>> {
+  let mut out = data.get(..12)?.to_vec();
+  let mut pos = 12;
+  while pos < data.len() {
+    let len = u32::from_le_bytes(data.get(pos + 4..pos + 8)?.try_into().ok()?);
+    let len = usize::try_from(len).ok()?;
+    // Chunks are padded to an even size
+    let end = (pos + 8 + len + len % 2).min(data.len());
+    let chunk = data.get(pos..end)?;
+    match chunk.get(..4)? {
+      b"EXIF" | b"XMP " => {}
+      b"VP8X" => {
+        let mut chunk = chunk.to_vec();
+        if let Some(flags) = chunk.get_mut(8) {
+          *flags &= !0b1100;
+        }
+        out.extend_from_slice(&chunk);
+      }
+      _ => out.extend_from_slice(chunk),
+    }
+    pos = end;
+  }
+  // Update the file size in the RIFF header
+  let riff_size = u32::try_from(out.len() - 8).ok()?;
+  out.splice(4..8, riff_size.to_le_bytes());
+  Some(out)
+}
+
+/// Removes comment and application extensions (like XMP), except for the ones which control
+/// animations. Anything after the trailer is removed too.
+fn strip_gif(data: &[u8]) -> Option<Vec<u8>> {
+  // Header and logical screen descriptor, followed by the global color table if there is one
+  let &flags = data.get(10)?;
+  let mut pos = 13 + color_table_size(flags);
+  let mut out = data.get(..pos)?.to_vec();
+  loop {
+    match *data.get(pos)? {
+      // Trailer
+      0x3B => {
+        out.push(0x3B);
+        return Some(out);
+      }
+      // Image descriptor, followed by the local color table, LZW code size and image data
+      0x2C => {
+        let &flags = data.get(pos + 9)?;
+        let end = gif_sub_blocks_end(data, pos + 11 + color_table_size(flags))?;
+        out.extend_from_slice(data.get(pos..end)?);
+        pos = end;
+      }
+      // Extension with label and sub-blocks
+      0x21 => {
+        let &label = data.get(pos + 1)?;
+        let end = gif_sub_blocks_end(data, pos + 2)?;
+        let is_animation = data
+          .get(pos + 3..pos + 14)
+          .is_some_and(|id| id == b"NETSCAPE2.0" || id == b"ANIMEXTS1.0");
+        if !matches!(label, 0xFE | 0xFF) || (label == 0xFF && is_animation) {
+          out.extend_from_slice(data.get(pos..end)?);
+        }
+        pos = end;
+      }
+      _ => return None,
+    }
+  }
+}
+
+/// Size in bytes of a GIF color table, given the packed flags of the descriptor.
+fn color_table_size(flags: u8) -> usize {
+  if flags & 0x80 == 0 {
+    0
+  } else {
+    3 << ((flags & 0x07) + 1)
+  }
+}
+
+/// Returns the position after a sequence of GIF sub-blocks, which ends with an empty block.
+fn gif_sub_blocks_end(data: &[u8], mut pos: usize) -> Option<usize> {
+  loop {
+    let &len = data.get(pos)?;
+    pos += 1 + usize::from(len);
+    if len == 0 {
+      return Some(pos);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{Rgb, RgbImage};
+  use pretty_assertions::assert_eq;
+
+  const EXIF: &[u8] = b"Exif\0\0MM\0*GPS position";
+
+  fn test_image(format: ImageFormat) -> LemmyResult<Vec<u8>> {
+    let image = RgbImage::from_fn(16, 8, |x, _| Rgb([0, 0, u8::try_from(x * 16).unwrap_or(0)]));
+    Ok(encode(&DynamicImage::ImageRgb8(image), format)?)
+  }
+
+  fn contains(data: &[u8], needle: &[u8]) -> bool {
+    data.windows(needle.len()).any(|w| w == needle)
+  }
+
+  #[test]
+  fn test_strip_jpeg() -> LemmyResult<()> {
+    let image = test_image(ImageFormat::Jpeg)?;
+    // Insert an APP1 segment after the start of image marker
+    let len = u16::try_from(EXIF.len() + 2)?.to_be_bytes();
+    let (start, rest) = image.split_at(2);
+    let with_exif = [start, &[0xFF, 0xE1], &len, EXIF, rest].concat();
+    assert!(contains(&with_exif, EXIF));
+
+    let stripped = strip_metadata(with_exif.into(), false)?;
+    assert!(!contains(&stripped, EXIF));
+    assert_eq!(image, stripped.to_vec());
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_jpeg_multi_picture() -> LemmyResult<()> {
+    let image = test_image(ImageFormat::Jpeg)?;
+    // Secondary images of a multi-picture file follow the first one, with their own metadata
+    let len = u16::try_from(EXIF.len() + 2)?.to_be_bytes();
+    let (start, rest) = image.split_at(2);
+    let secondary = [start, &[0xFF, 0xE1], &len, EXIF, rest].concat();
+    let multi_picture = [image.as_slice(), &secondary].concat();
+
+    let stripped = strip_metadata(multi_picture.into(), false)?;
+    assert!(!contains(&stripped, EXIF));
+    assert_eq!(image, stripped.to_vec());
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_png() -> LemmyResult<()> {
+    let image = test_image(ImageFormat::Png)?;
+    // Insert an eXIf chunk after the header, the checksum isn't verified
+    let len = u32::try_from(EXIF.len())?.to_be_bytes();
+    let (header, rest) = image.split_at(PNG_SIGNATURE.len() + 25);
+    let with_exif = [header, &len, b"eXIf", EXIF, &[0; 4], rest].concat();
+
+    let stripped = strip_metadata(with_exif.into(), false)?;
+    assert!(!contains(&stripped, EXIF));
+    assert_eq!(image, stripped.to_vec());
+    image::load_from_memory(&stripped)?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_webp() -> LemmyResult<()> {
+    let image = test_image(ImageFormat::WebP)?;
+    let len = u32::try_from(EXIF.len())?.to_le_bytes();
+    let mut with_exif = [&image, b"EXIF", &len, EXIF].concat();
+    let riff_size = u32::try_from(with_exif.len() - 8)?.to_le_bytes();
+    with_exif.splice(4..8, riff_size);
+
+    let stripped = strip_metadata(with_exif.into(), false)?;
+    assert!(!contains(&stripped, EXIF));
+    assert_eq!(image, stripped.to_vec());
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_gif() -> LemmyResult<()> {
+    let image = test_image(ImageFormat::Gif)?;
+    // Insert a comment extension before the trailer
+    let (rest, trailer) = image.split_at(image.len() - 1);
+    let len = u8::try_from(EXIF.len())?;
+    let with_comment = [rest, &[0x21, 0xFE, len], EXIF, &[0], trailer].concat();
+
+    let stripped = strip_metadata(with_comment.into(), false)?;
+    assert!(!contains(&stripped, EXIF));
+    assert_eq!(image, stripped.to_vec());
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_other_formats() -> LemmyResult<()> {
+    let video = Bytes::from_static(b"\0\0\0\x18ftypmp42");
+    assert_eq!(video, strip_metadata(video.clone(), true)?);
+    assert!(strip_metadata(video, false).is_err());
+    // Tiff can't be stripped
+    let tiff = Bytes::from_static(b"II*\0\x08\0\0\0");
+    assert!(strip_metadata(tiff, true).is_err());
+    Ok(())
+  }
+}
@@ -1,6 +1,9 @@
 pub mod build_response;
 pub mod claims;
 pub mod context;
+pub mod image_hash;
+pub mod image_metadata;
+pub mod media;
 pub mod notify;
 pub mod plugins;
 pub mod request;
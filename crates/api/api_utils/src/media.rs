@@ -0,0 +1,522 @@
+//! Native image storage, which is used instead of pict-rs if
+//! [`lemmy_utils::settings::structs::MediaConfig::backend`] is set to `filesystem` or `s3`.
+//!
+//! Uploaded images are stored under their alias (a random uuid with file extension). Resized and
+//! converted variants are generated on first access and stored under `variants/`, proxied remote
+//! images under `proxy/`. Proxied images are fetched again once they are older than
+//! [`PROXY_CACHE_DURATION`], and removed by a scheduled task.
+use crate::{
+  context::LemmyContext,
+  image_hash::{check_image_hash, perceptual_hash},
+  image_metadata::strip_metadata,
+  request::PictrsFileDetails,
+};
+use bytes::Bytes;
+use chrono::{TimeDelta, Utc};
+use futures::{StreamExt, TryStreamExt};
+use image::{DynamicImage, ImageFormat, ImageReader, Limits, imageops::FilterType};
+use lemmy_db_schema::{
+  newtypes::PostId,
+  source::images::{LocalImage, LocalImageForm},
+};
+use lemmy_db_schema_file::PersonId;
+use lemmy_utils::{
+  REQWEST_TIMEOUT,
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  settings::structs::{MediaBackend, Settings},
+};
+use object_store::{
+  ObjectStore,
+  PutPayload,
+  aws::AmazonS3Builder,
+  local::LocalFileSystem,
+  path::Path,
+};
+use std::{
+  io::Cursor,
+  sync::{Arc, OnceLock},
+};
+use tracing::info;
+use url::Url;
+use uuid::Uuid;
+
+static STORE: OnceLock<Arc<dyn ObjectStore>> = OnceLock::new();
+
+/// Largest size of resized variants, larger requested sizes are served with this size instead.
+const MAX_VARIANT_SIZE: u32 = 2048;
+
+/// Sizes in which resized variants are generated. Requested sizes are rounded up to the next of
+/// these, so that only a few variants are stored per image.
+const VARIANT_SIZES: [u32; 6] = [64, 128, 256, 512, 1024, MAX_VARIANT_SIZE];
+
+/// Images with a larger width or height are rejected without decoding them.
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+
+/// Maximum memory which may be allocated while decoding an image.
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// How long proxied remote images are kept in the store.
+pub const PROXY_CACHE_DURATION: TimeDelta = TimeDelta::days(7);
+
+/// Returns true if images are handled by Lemmy itself instead of pict-rs.
+pub fn is_native_media(settings: &Settings) -> bool {
+  settings.media.backend != MediaBackend::Pictrs
+}
+
+fn store(settings: &Settings) -> LemmyResult<Arc<dyn ObjectStore>> {
+  if let Some(store) = STORE.get() {
+    return Ok(store.clone());
+  }
+  let media = &settings.media;
+  let store: Arc<dyn ObjectStore> = match media.backend {
+    MediaBackend::Filesystem => {
+      std::fs::create_dir_all(&media.path)?;
+      Arc::new(LocalFileSystem::new_with_prefix(&media.path)?)
+    }
+    MediaBackend::S3 => {
+      let s3 = media
+        .s3
+        .as_ref()
+        .ok_or(LemmyErrorType::ImageUploadDisabled)?;
+      let mut builder = AmazonS3Builder::new()
+        .with_bucket_name(&s3.bucket)
+        .with_region(&s3.region)
+        .with_access_key_id(&s3.access_key_id)
+        .with_secret_access_key(&s3.secret_access_key);
+      if let Some(endpoint) = &s3.endpoint {
+        builder = builder
+          .with_endpoint(endpoint)
+          .with_allow_http(endpoint.starts_with("http://"));
+      }
+      Arc::new(builder.build()?)
+    }
+    MediaBackend::Pictrs => return Err(LemmyErrorType::ImageUploadDisabled.into()),
+  };
+  Ok(STORE.get_or_init(|| store).clone())
+}
+
+/// Checks that the storage backend is reachable.
+pub async fn media_health(settings: &Settings) -> LemmyResult<()> {
+  store(settings)?.list_with_delimiter(None).await?;
+  Ok(())
+}
+
+/// An image which was stored by [`store_image`].
+pub struct StoredImage {
+  pub alias: String,
+  pub url: Url,
+  /// File size in bytes.
+  pub size: i64,
+}
+
+/// Validates and downscales an image, then saves it in the store and in the `local_image` table.
+pub async fn store_image(
+  data: Bytes,
+  max_size: i32,
+  allow_animation: bool,
+  person_id: PersonId,
+  thumbnail_for_post_id: Option<PostId>,
+  context: &LemmyContext,
+) -> LemmyResult<StoredImage> {
+  let max_size = u32::try_from(max_size)?;
+  let (data, details, format) =
+    tokio::task::spawn_blocking(move || process_upload(data, max_size, allow_animation)).await??;
+  check_image_hash(
+    details.phash.ok_or(LemmyErrorType::NotAnImageType)?,
+    context,
+  )
+  .await?;
+
+  let alias = format!("{}.{}", Uuid::new_v4(), extension(format));
+  let size = i64::try_from(data.len())?;
+  store(context.settings())?
+    .put(&Path::from(alias.as_str()), PutPayload::from(data))
+    .await?;
+
+  let url = Url::parse(&format!(
+    "{}/api/v4/image/{alias}",
+    context.settings().get_protocol_and_hostname()
+  ))?;
+  let form = LocalImageForm {
+    pictrs_alias: alias.clone(),
+    person_id,
+    thumbnail_for_post_id: Some(thumbnail_for_post_id),
+    size,
+  };
+  let details_form = details.build_image_details_form(&url);
+  LocalImage::create(&mut context.pool(), &form, &details_form).await?;
+
+  Ok(StoredImage { alias, url, size })
+}
+
+/// Reads a stored image, optionally resized to `max_size` and converted to `file_type`. Returns
+/// the image data and content type, or `None` if the image doesn't exist.
+pub async fn read_image(
+  alias: &str,
+  max_size: Option<i32>,
+  file_type: Option<&str>,
+  settings: &Settings,
+) -> LemmyResult<Option<(Bytes, &'static str)>> {
+  // Aliases are generated by Lemmy and never contain a path
+  if alias.contains('/') {
+    return Ok(None);
+  }
+  read_variant(alias, max_size, file_type, settings).await
+}
+
+/// Same as [`read_image`] for a remote image. The image is fetched on first access and cached in
+/// the store.
+pub async fn read_proxied_image(
+  url: &Url,
+  max_size: Option<i32>,
+  file_type: Option<&str>,
+  context: &LemmyContext,
+) -> LemmyResult<(Bytes, &'static str)> {
+  let key = fetch_proxied_image(url, context).await?;
+  read_variant(&key, max_size, file_type, context.settings())
+    .await?
+    .ok_or_else(|| LemmyErrorType::NotFound.into())
+}
+
+/// Returns the details of a remote image, which is fetched and cached for proxying.
+pub async fn proxied_image_details(
+  url: &Url,
+  context: &LemmyContext,
+) -> LemmyResult<PictrsFileDetails> {
+  let key = fetch_proxied_image(url, context).await?;
+  let data = get(&key, context.settings())
+    .await?
+    .ok_or(LemmyErrorType::NotFound)?;
+  tokio::task::spawn_blocking(move || image_details(&data)).await?
+}
+
+/// Downloads a remote image and stores it as a new local image, used for post thumbnails.
+pub async fn store_remote_image(
+  url: &Url,
+  max_size: i32,
+  person_id: PersonId,
+  post_id: PostId,
+  context: &LemmyContext,
+) -> LemmyResult<StoredImage> {
+  let data = fetch_remote(url, context).await?;
+  store_image(data, max_size, true, person_id, Some(post_id), context).await
+}
+
+/// Removes an image with all its variants from the store, and deletes the database row.
+pub async fn delete_image(alias: &str, context: &LemmyContext) -> LemmyResult<()> {
+  if alias.contains('/') {
+    return Err(LemmyErrorType::NotFound.into());
+  }
+  let store = store(context.settings())?;
+  delete_with_variants(&store, alias).await?;
+
+  // Doesn't fail if the row was already removed
+  LocalImage::delete_by_aliases(&mut context.pool(), &[alias.to_string()]).await?;
+  Ok(())
+}
+
+/// Removes proxied remote images which are older than [`PROXY_CACHE_DURATION`], along with their
+/// variants. They are fetched again when requested.
+pub async fn delete_expired_proxied_images(settings: &Settings) -> LemmyResult<()> {
+  if !is_native_media(settings) {
+    return Ok(());
+  }
+  let store = store(settings)?;
+  let expired_before = Utc::now() - PROXY_CACHE_DURATION;
+  let expired: Vec<_> = store
+    .list(Some(&Path::from("proxy")))
+    .try_filter(|meta| std::future::ready(meta.last_modified < expired_before))
+    .map_ok(|meta| meta.location)
+    .try_collect()
+    .await?;
+  for key in &expired {
+    delete_with_variants(&store, key.as_ref()).await?;
+  }
+  info!("Deleted {} expired proxied images", expired.len());
+  Ok(())
+}
+
+/// Deletes the stored image with this key and all variants which were generated from it.
+async fn delete_with_variants(store: &Arc<dyn ObjectStore>, key: &str) -> LemmyResult<()> {
+  let variants = Path::from(format!("variants/{key}"));
+  let variant_paths = store
+    .list(Some(&variants))
+    .map_ok(|meta| meta.location)
+    .boxed();
+  store
+    .delete_stream(variant_paths)
+    .try_collect::<Vec<_>>()
+    .await?;
+  match store.delete(&Path::from(key)).await {
+    Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+    Err(e) => Err(e.into()),
+  }
+}
+
+async fn read_variant(
+  key: &str,
+  max_size: Option<i32>,
+  file_type: Option<&str>,
+  settings: &Settings,
+) -> LemmyResult<Option<(Bytes, &'static str)>> {
+  let Some(original) = get(key, settings).await? else {
+    return Ok(None);
+  };
+  let format = image::guess_format(&original).with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+  let target_format = file_type.and_then(output_format).unwrap_or(format);
+  if max_size.is_none() && target_format == format {
+    return Ok(Some((original, format.to_mime_type())));
+  }
+
+  let size = max_size.map(u32::try_from).transpose()?.map(variant_size);
+  let variant_key = format!(
+    "variants/{key}/{}.{}",
+    size.map_or_else(|| "original".to_string(), |s| s.to_string()),
+    extension(target_format)
+  );
+  if let Some(variant) = get(&variant_key, settings).await? {
+    return Ok(Some((variant, target_format.to_mime_type())));
+  }
+
+  let variant: Bytes = tokio::task::spawn_blocking(move || -> LemmyResult<Vec<u8>> {
+    let image = decode(&original, format)?;
+    let image = match size {
+      Some(size) => downscale(image, size),
+      None => image,
+    };
+    encode(&image, target_format)
+  })
+  .await??
+  .into();
+  store(settings)?
+    .put(&Path::from(variant_key), PutPayload::from(variant.clone()))
+    .await?;
+  Ok(Some((variant, target_format.to_mime_type())))
+}
+
+/// Fetches a remote image into the store if it isn't there yet, and returns its key.
+async fn fetch_proxied_image(url: &Url, context: &LemmyContext) -> LemmyResult<String> {
+  let key = format!(
+    "proxy/{}",
+    Uuid::new_v5(&Uuid::NAMESPACE_URL, url.as_str().as_bytes())
+  );
+  let store = store(context.settings())?;
+  match store.head(&Path::from(key.as_str())).await {
+    Ok(meta) if meta.last_modified > Utc::now() - PROXY_CACHE_DURATION => return Ok(key),
+    // Variants of the expired image would be served otherwise
+    Ok(_) => delete_with_variants(&store, &key).await?,
+    Err(object_store::Error::NotFound { .. }) => {}
+    Err(e) => return Err(e.into()),
+  }
+
+  let data = fetch_remote(url, context).await?;
+  image::guess_format(&data).with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+  store
+    .put(&Path::from(key.as_str()), PutPayload::from(data))
+    .await?;
+  Ok(key)
+}
+
+async fn fetch_remote(url: &Url, context: &LemmyContext) -> LemmyResult<Bytes> {
+  let max_bytes = context.settings().media.max_upload_bytes;
+  let response = context
+    .client()
+    .get(url.as_str())
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?;
+  let mut stream = response.bytes_stream();
+  let mut data = vec![];
+  while let Some(chunk) = stream.next().await {
+    data.extend_from_slice(&chunk?);
+    if data.len() > max_bytes {
+      return Err(LemmyErrorType::ImageTooLarge.into());
+    }
+  }
+  Ok(data.into())
+}
+
+async fn get(key: &str, settings: &Settings) -> LemmyResult<Option<Bytes>> {
+  match store(settings)?.get(&Path::from(key)).await {
+    Ok(res) => Ok(Some(res.bytes().await?)),
+    Err(object_store::Error::NotFound { .. }) => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Checks that the upload is an image, removes its metadata and downscales it if necessary.
+/// Returns the data to store, its details and format.
+fn process_upload(
+  data: Bytes,
+  max_size: u32,
+  allow_animation: bool,
+) -> LemmyResult<(Bytes, PictrsFileDetails, ImageFormat)> {
+  let data = strip_metadata(data, false)?;
+  let format = image::guess_format(&data).with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+  if output_format(extension(format)).is_none() {
+    return Err(LemmyErrorType::NotAnImageType.into());
+  }
+  let image = decode(&data, format)?;
+
+  let too_large = image.width() > max_size || image.height() > max_size;
+  // Decoding only keeps the first frame, so this also removes the animation
+  let flatten = format == ImageFormat::Gif && !allow_animation;
+  if !too_large && !flatten {
+    let details = details_for(&image, format);
+    return Ok((data, details, format));
+  }
+
+  let format = if flatten { ImageFormat::Png } else { format };
+  let image = downscale(image, max_size);
+  let data = encode(&image, format)?;
+  Ok((data.into(), details_for(&image, format), format))
+}
+
+fn image_details(data: &[u8]) -> LemmyResult<PictrsFileDetails> {
+  let format = image::guess_format(data).with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+  let image = decode(data, format)?;
+  Ok(details_for(&image, format))
+}
+
+fn details_for(image: &DynamicImage, format: ImageFormat) -> PictrsFileDetails {
+  let preview = image.thumbnail(32, 32).to_rgba8();
+  let blurhash = blurhash::encode(4, 3, preview.width(), preview.height(), preview.as_raw()).ok();
+  PictrsFileDetails {
+    width: image.width().try_into().unwrap_or(u16::MAX),
+    height: image.height().try_into().unwrap_or(u16::MAX),
+    content_type: format.to_mime_type().to_string(),
+    created_at: Utc::now(),
+    blurhash,
+    phash: Some(perceptual_hash(image)),
+  }
+}
+
+/// Limits for decoding images, so that small files with huge dimensions can't use up all memory.
+pub(crate) fn decode_limits() -> Limits {
+  let mut limits = Limits::default();
+  limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+  limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+  limits.max_alloc = Some(MAX_DECODE_ALLOC);
+  limits
+}
+
+/// Decodes an image with [`decode_limits`].
+pub(crate) fn decode(data: &[u8], format: ImageFormat) -> LemmyResult<DynamicImage> {
+  let mut reader = ImageReader::with_format(Cursor::new(data), format);
+  reader.limits(decode_limits());
+  reader
+    .decode()
+    .with_lemmy_type(LemmyErrorType::NotAnImageType)
+}
+
+/// Rounds the requested size up to one of [`VARIANT_SIZES`].
+fn variant_size(requested: u32) -> u32 {
+  VARIANT_SIZES
+    .into_iter()
+    .find(|s| *s >= requested)
+    .unwrap_or(MAX_VARIANT_SIZE)
+}
+
+/// Resizes the image to fit into `max_size`, keeping the aspect ratio. Smaller images are not
+/// changed.
+fn downscale(image: DynamicImage, max_size: u32) -> DynamicImage {
+  if image.width() <= max_size && image.height() <= max_size {
+    return image;
+  }
+  image.resize(max_size, max_size, FilterType::Lanczos3)
+}
+
+pub(crate) fn encode(image: &DynamicImage, format: ImageFormat) -> LemmyResult<Vec<u8>> {
+  let mut data = Cursor::new(vec![]);
+  // Jpeg doesn't support transparency, and the other encoders only support 8 bit colors
+  let image = match format {
+    ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8()),
+    _ => DynamicImage::ImageRgba8(image.to_rgba8()),
+  };
+  image.write_to(&mut data, format)?;
+  Ok(data.into_inner())
+}
+
+/// Output formats which are supported by the native backends.
+fn output_format(file_type: &str) -> Option<ImageFormat> {
+  match file_type.to_ascii_lowercase().as_str() {
+    "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+    "png" => Some(ImageFormat::Png),
+    "gif" => Some(ImageFormat::Gif),
+    "webp" => Some(ImageFormat::WebP),
+    _ => None,
+  }
+}
+
+fn extension(format: ImageFormat) -> &'static str {
+  format.extensions_str().first().copied().unwrap_or("bin")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::RgbaImage;
+  use pretty_assertions::assert_eq;
+
+  fn test_image(width: u32, height: u32, format: ImageFormat) -> LemmyResult<Bytes> {
+    let image = DynamicImage::ImageRgba8(RgbaImage::new(width, height));
+    Ok(encode(&image, format)?.into())
+  }
+
+  #[test]
+  fn test_process_upload() -> LemmyResult<()> {
+    // Small images are stored unchanged
+    let data = test_image(100, 50, ImageFormat::Png)?;
+    let (stored, details, format) = process_upload(data.clone(), 200, true)?;
+    assert_eq!(data, stored);
+    assert_eq!(ImageFormat::Png, format);
+    assert_eq!((100, 50), (details.width, details.height));
+    assert_eq!("image/png", details.content_type);
+    assert!(details.blurhash.is_some());
+
+    // Large images are downscaled with the same aspect ratio
+    let data = test_image(400, 200, ImageFormat::Jpeg)?;
+    let (_, details, format) = process_upload(data, 200, true)?;
+    assert_eq!(ImageFormat::Jpeg, format);
+    assert_eq!((200, 100), (details.width, details.height));
+
+    // Gifs are converted to png if animations are not allowed
+    let data = test_image(10, 10, ImageFormat::Gif)?;
+    let (_, details, format) = process_upload(data, 200, false)?;
+    assert_eq!(ImageFormat::Png, format);
+    assert_eq!("image/png", details.content_type);
+
+    // Other files are rejected
+    let data = Bytes::from_static(b"not an image");
+    assert!(process_upload(data, 200, true).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_variant_size() {
+    assert_eq!(64, variant_size(1));
+    assert_eq!(256, variant_size(256));
+    assert_eq!(512, variant_size(257));
+    assert_eq!(MAX_VARIANT_SIZE, variant_size(100_000));
+  }
+
+  #[test]
+  fn test_decode_limits() -> LemmyResult<()> {
+    let data = test_image(MAX_IMAGE_DIMENSION + 1, 1, ImageFormat::Png)?;
+    assert!(decode(&data, ImageFormat::Png).is_err());
+    assert!(process_upload(data, 200, true).is_err());
+
+    let data = test_image(MAX_IMAGE_DIMENSION, 1, ImageFormat::Png)?;
+    assert!(decode(&data, ImageFormat::Png).is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn test_output_format() {
+    assert_eq!(Some(ImageFormat::Jpeg), output_format("JPG"));
+    assert_eq!(Some(ImageFormat::WebP), output_format("webp"));
+    assert_eq!(None, output_format("avif"));
+    assert_eq!("jpg", extension(ImageFormat::Jpeg));
+  }
+}
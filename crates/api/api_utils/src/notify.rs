@@ -19,7 +19,10 @@ use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_private_message::PrivateMessageView;
 use lemmy_db_views_site::SiteView;
 use lemmy_diesel_utils::{dburl::DbUrl, traits::Crud};
-use lemmy_email::notifications::{NotificationEmailData, send_notification_email};
+use lemmy_email::{
+  notifications::{NotificationEmailData, send_notification_email},
+  reply::{ReplyTarget, reply_address},
+};
 use lemmy_utils::{
   error::{LemmyErrorType, LemmyResult},
   spawn_try_task,
@@ -115,7 +118,25 @@ impl NotifyData {
       };
 
       if self.do_send_email {
-        send_notification_email(user_view, c.local_url, c.data, context.settings());
+        let target = match &self.comment {
+          Some(comment) => ReplyTarget::Comment(comment.id),
+          None => ReplyTarget::Post(self.post.id),
+        };
+        let reply_to = reply_address(
+          target,
+          user_view.local_user.id,
+          context.secret(),
+          context.settings(),
+        );
+        send_notification_email(
+          user_view,
+          c.local_url,
+          c.data,
+          reply_to,
+          &mut context.pool(),
+          context.settings(),
+        )
+        .await?;
       }
     }
     if !forms.is_empty() {
@@ -309,12 +330,21 @@ async fn notify_private_message_internal(
         sender: &view.creator,
         content: &view.private_message.content,
       };
+      let reply_to = reply_address(
+        ReplyTarget::Person(view.creator.id),
+        local_recipient.local_user.id,
+        context.secret(),
+        context.settings(),
+      );
       send_notification_email(
         local_recipient,
         view.private_message.local_url(context.settings())?,
         d,
+        reply_to,
+        &mut context.pool(),
         context.settings(),
-      );
+      )
+      .await?;
     }
   }
   Ok(())
@@ -363,8 +393,11 @@ pub fn notify_mod_action(actions: Vec<Modlog>, context: &LemmyContext) {
         local_recipient,
         Url::parse(&modlog_url)?.into(),
         d,
+        None,
+        &mut context.pool(),
         context.settings(),
-      );
+      )
+      .await?;
     }
     Ok(())
   })
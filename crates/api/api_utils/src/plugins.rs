@@ -20,14 +20,19 @@ use lemmy_diesel_utils::traits::Crud;
 use lemmy_utils::{
   VERSION,
   error::{LemmyError, LemmyErrorType, LemmyResult},
-  settings::{SETTINGS, structs::PluginSettings},
+  metrics::{plugin_call_failed, plugin_call_timer},
+  settings::{
+    SETTINGS,
+    current_settings,
+    structs::{PluginSettings, Settings},
+  },
 };
 use serde::{Deserialize, Serialize};
 use std::{
   env::var,
-  ops::Deref,
   path::PathBuf,
-  sync::{LazyLock, OnceLock},
+  ptr,
+  sync::{Mutex, RwLock},
   time::Duration,
 };
 use tokio::task::spawn_blocking;
@@ -93,10 +98,7 @@ async fn call_captcha_plugin<
 
   spawn_blocking(move || {
     if let Some(p) = captcha_plugin.pool.get(GET_PLUGIN_TIMEOUT)? {
-      let res = p
-        .call(name, params)
-        .map_err(|e| LemmyErrorType::PluginError(e.to_string()))?;
-      return Ok(res);
+      return call_plugin(&p, &captcha_plugin.filename, name, params);
     }
     Err(LemmyErrorType::PluginError("plugin not loaded".to_string()).into())
   })
@@ -115,9 +117,7 @@ where
   for p in plugins.plugins {
     if let Some(plugin) = p.get(name)? {
       let params: Json<T> = data.clone().into();
-      plugin
-        .call::<Json<T>, ()>(name, params)
-        .map_err(|e| LemmyErrorType::PluginError(e.to_string()))?;
+      call_plugin::<Json<T>, ()>(&plugin, &p.filename, name, params)?;
     }
   }
   Ok(())
@@ -137,10 +137,7 @@ where
     let mut res: Json<T> = data.into();
     for p in plugins.plugins {
       if let Some(plugin) = p.get(name)? {
-        let r = plugin
-          .call(name, res)
-          .map_err(|e| LemmyErrorType::PluginError(e.to_string()))?;
-        res = r;
+        res = call_plugin(&plugin, &p.filename, name, res)?;
       }
     }
     Ok::<_, LemmyError>(res.0)
@@ -148,38 +145,65 @@ where
   .await?
 }
 
+/// Calls a plugin function, and records its duration and errors in the metrics.
+fn call_plugin<'a, T: ToBytes<'a>, R: FromBytesOwned>(
+  plugin: &PoolPlugin,
+  filename: &str,
+  name: &'static str,
+  params: T,
+) -> LemmyResult<R> {
+  let _timer = plugin_call_timer(filename, name);
+  plugin.call(name, params).map_err(|e| {
+    plugin_call_failed(filename, name);
+    LemmyErrorType::PluginError(e.to_string()).into()
+  })
+}
+
+#[expect(clippy::expect_used)]
 pub fn plugin_metadata() -> Vec<PluginMetadata> {
-  static METADATA: OnceLock<Vec<PluginMetadata>> = OnceLock::new();
-  if let Some(m) = METADATA.get() {
+  // Settings for which the metadata was loaded, so that it is loaded again after a reload
+  static METADATA: RwLock<Option<(&'static Settings, Vec<PluginMetadata>)>> = RwLock::new(None);
+  static LOADING: Mutex<()> = Mutex::new(());
+  let settings = current_settings();
+  if let Some((s, m)) = &*METADATA.read().expect("read metadata")
+    && ptr::eq(*s, settings)
+  {
     m.clone()
   } else {
     // Loading metadata can take multiple seconds. Do this in background task to avoid blocking
     // /api/v4/site endpoint.
-    std::thread::spawn(|| {
-      METADATA.get_or_init(|| {
-        let mut metadata = vec![];
-        for plugin in LemmyPlugins::get_or_init().plugins {
-          let run = match plugin.pool.get(GET_PLUGIN_TIMEOUT) {
-            Ok(p) => p,
-            Err(e) => {
-              error!("Failed to load plugin {}: {e}", plugin.filename);
-              continue;
-            }
-          };
-          let m = run.and_then(|run| run.call("metadata", 0).ok());
-          if let Some(m) = m {
-            metadata.push(m);
-          } else {
-            // Failed to load plugin metadata, use placeholder
-            metadata.push(PluginMetadata {
-              name: plugin.filename,
-              url: None,
-              description: None,
-            });
+    std::thread::spawn(move || {
+      let _loading = LOADING.lock().expect("lock metadata loading");
+      if METADATA
+        .read()
+        .expect("read metadata")
+        .as_ref()
+        .is_some_and(|(s, _)| ptr::eq(*s, settings))
+      {
+        return;
+      }
+      let mut metadata = vec![];
+      for plugin in LemmyPlugins::get_or_init().plugins {
+        let run = match plugin.pool.get(GET_PLUGIN_TIMEOUT) {
+          Ok(p) => p,
+          Err(e) => {
+            error!("Failed to load plugin {}: {e}", plugin.filename);
+            continue;
           }
+        };
+        let m = run.and_then(|run| run.call("metadata", 0).ok());
+        if let Some(m) = m {
+          metadata.push(m);
+        } else {
+          // Failed to load plugin metadata, use placeholder
+          metadata.push(PluginMetadata {
+            name: plugin.filename,
+            url: None,
+            description: None,
+          });
         }
-        metadata
-      });
+      }
+      *METADATA.write().expect("write metadata") = Some((settings, metadata));
     });
     // Return empty metadata until loading is finished
     vec![]
@@ -262,45 +286,62 @@ impl LemmyPlugin {
 }
 
 impl LemmyPlugins {
-  /// Load and initialize all plugins
+  /// Load and initialize all plugins. They are loaded again after the settings are reloaded.
+  #[expect(clippy::expect_used)]
   fn get_or_init() -> Self {
-    static PLUGINS: LazyLock<LemmyPlugins> = LazyLock::new(|| {
-      let mut plugins: Vec<_> = SETTINGS
-        .plugins
-        .iter()
-        .flat_map(|p| {
-          LemmyPlugin::init(p.clone())
-            .inspect_err(|e| warn!("Failed to load plugin {}: {e}", p.file))
-            .ok()
-        })
-        .collect();
+    static PLUGINS: RwLock<Option<(&'static Settings, LemmyPlugins)>> = RwLock::new(None);
+    let settings = current_settings();
+    if let Some((s, plugins)) = &*PLUGINS.read().expect("read plugins")
+      && ptr::eq(*s, settings)
+    {
+      return plugins.clone();
+    }
+    let mut lock = PLUGINS.write().expect("write plugins");
+    // Another thread may have loaded them in the meantime
+    if let Some((s, plugins)) = &*lock
+      && ptr::eq(*s, settings)
+    {
+      return plugins.clone();
+    }
+    let plugins = Self::init(&settings.plugins);
+    *lock = Some((settings, plugins.clone()));
+    plugins
+  }
+
+  fn init(settings: &[PluginSettings]) -> Self {
+    let mut plugins: Vec<_> = settings
+      .iter()
+      .flat_map(|p| {
+        LemmyPlugin::init(p.clone())
+          .inspect_err(|e| warn!("Failed to load plugin {}: {e}", p.file))
+          .ok()
+      })
+      .collect();
 
-      let mut captcha_plugin = None;
-      for (i, p) in plugins.iter().enumerate() {
-        let is_captcha = p
+    let mut captcha_plugin = None;
+    for (i, p) in plugins.iter().enumerate() {
+      let is_captcha = p
+        .pool
+        .function_exists("validate_captcha", GET_PLUGIN_TIMEOUT)
+        .unwrap_or_default()
+        && p
           .pool
           .function_exists("validate_captcha", GET_PLUGIN_TIMEOUT)
-          .unwrap_or_default()
-          && p
-            .pool
-            .function_exists("validate_captcha", GET_PLUGIN_TIMEOUT)
-            .unwrap_or_default();
-        if is_captcha {
-          captcha_plugin = Some(plugins.remove(i));
-          break;
-        }
+          .unwrap_or_default();
+      if is_captcha {
+        captcha_plugin = Some(plugins.remove(i));
+        break;
       }
+    }
 
-      // Need to put captcha plugin back in so it can be shown in the active plugins list.
-      if let Some(captcha_plugin) = &captcha_plugin {
-        plugins.push(captcha_plugin.clone());
-      }
-      LemmyPlugins {
-        plugins,
-        captcha_plugin,
-      }
-    });
-    PLUGINS.deref().clone()
+    // Need to put captcha plugin back in so it can be shown in the active plugins list.
+    if let Some(captcha_plugin) = &captcha_plugin {
+      plugins.push(captcha_plugin.clone());
+    }
+    LemmyPlugins {
+      plugins,
+      captcha_plugin,
+    }
   }
 
   /// Return early if no plugin is loaded for the given hook name
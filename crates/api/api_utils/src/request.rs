@@ -1,16 +1,25 @@
 use crate::{
   context::LemmyContext,
+  image_hash::{check_image_hash, hash_image_data, is_image_blocked},
+  media::{self, is_native_media},
+  notify::notify_mod_action,
   send_activity::{ActivityChannel, SendActivityData},
   utils::proxy_image_link,
 };
 use activitypub_federation::config::Data;
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use encoding_rs::{Encoding, UTF_8};
 use futures::StreamExt;
-use lemmy_db_schema::source::{
-  images::{ImageDetailsInsertForm, LocalImage, LocalImageForm},
-  local_site::LocalSite,
-  post::{Post, PostUpdateForm},
+use lemmy_db_schema::{
+  source::{
+    images::{ImageDetailsInsertForm, LocalImage, LocalImageForm},
+    local_site::LocalSite,
+    modlog::{Modlog, ModlogInsertForm},
+    post::{Post, PostUpdateForm},
+    post_report::{PostReport, PostReportForm},
+  },
+  traits::Reportable,
 };
 use lemmy_db_schema_file::enums::ImageMode;
 use lemmy_db_views_post::api::{LinkMetadata, OpenGraphData};
@@ -27,7 +36,8 @@ use reqwest::{
   Client,
   ClientBuilder,
   Response,
-  header::{CONTENT_TYPE, LOCATION, RANGE},
+  StatusCode,
+  header::{CONTENT_LENGTH, CONTENT_TYPE, LOCATION, RANGE},
   redirect::Policy,
 };
 use reqwest_middleware::ClientWithMiddleware;
@@ -223,9 +233,11 @@ pub async fn generate_post_link_metadata(
 
   // Proxy the post url itself if it is an image
   let url = if let (true, Some(url)) = (is_image_post, post.url.clone()) {
-    Some(Some(
-      proxy_image_link(url.into(), &local_site, false, &context).await?,
-    ))
+    let res = proxy_image_link(url.into(), &local_site, false, &context).await;
+    if is_image_blocked(&res) {
+      return remove_post_with_blocked_image(&post, &context).await;
+    }
+    Some(Some(res?))
   } else {
     None
   };
@@ -239,14 +251,20 @@ pub async fn generate_post_link_metadata(
   // Attempt to generate a thumbnail depending on the instance settings. Either by proxying,
   // storing image persistently in pict-rs or returning the remote url directly as thumbnail.
   let thumbnail_url = if let (false, Some(url)) = (is_image_post, custom_thumbnail) {
-    proxy_image_link(url.clone(), &local_site, true, &context)
-      .await
+    let res = proxy_image_link(url.clone(), &local_site, true, &context).await;
+    if is_image_blocked(&res) {
+      return remove_post_with_blocked_image(&post, &context).await;
+    }
+    res
       .map_err(|e| warn!("Failed to proxy thumbnail: {e}"))
       .ok()
       .or(Some(url.into()))
   } else if let (true, Some(url)) = (allow_generate_thumbnail, image_url.clone()) {
-    generate_pictrs_thumbnail(&post, &url, &local_site, &context)
-      .await
+    let res = generate_pictrs_thumbnail(&post, &url, &local_site, &context).await;
+    if is_image_blocked(&res) {
+      return remove_post_with_blocked_image(&post, &context).await;
+    }
+    res
       .map_err(|e| warn!("Failed to generate thumbnail: {e}"))
       .ok()
       .map(Into::into)
@@ -273,6 +291,36 @@ pub async fn generate_post_link_metadata(
   Ok(())
 }
 
+/// Removes a post whose image is on the image hash blocklist, and reports it to the admins. The
+/// removal is only local and done by the system account.
+async fn remove_post_with_blocked_image(post: &Post, context: &LemmyContext) -> LemmyResult<()> {
+  let reason = "Image matches the image hash blocklist";
+  let system_account = SiteView::read_system_account(&mut context.pool()).await?;
+  let form = PostUpdateForm {
+    removed: Some(true),
+    ..Default::default()
+  };
+  let post = Post::update(&mut context.pool(), post.id, &form).await?;
+
+  let form =
+    ModlogInsertForm::mod_remove_post(system_account.id, &post, true, None, reason, None, None);
+  let action = Modlog::create(&mut context.pool(), &[form]).await?;
+  notify_mod_action(action, context);
+
+  let report_form = PostReportForm {
+    creator_id: system_account.id,
+    post_id: post.id,
+    original_post_name: post.name,
+    original_post_url: post.url,
+    original_post_body: post.body,
+    reason: reason.to_string(),
+    violates_instance_rules: true,
+    rule_id: None,
+  };
+  PostReport::report(&mut context.pool(), &report_form).await?;
+  Ok(())
+}
+
 /// Extract site metadata from HTML Opengraph attributes.
 fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraphData> {
   let html = String::from_utf8_lossy(html_bytes);
@@ -381,6 +429,9 @@ pub struct PictrsFileDetails {
   pub content_type: String,
   pub created_at: DateTime<Utc>,
   pub blurhash: Option<String>,
+  /// Not returned by pict-rs, needs to be calculated separately.
+  #[serde(skip)]
+  pub phash: Option<i64>,
 }
 
 impl PictrsFileDetails {
@@ -393,6 +444,7 @@ impl PictrsFileDetails {
       height: self.height.into(),
       content_type: self.content_type.clone(),
       blurhash: self.blurhash.clone(),
+      phash: self.phash,
     }
   }
 }
@@ -424,6 +476,9 @@ pub async fn purge_image_from_pictrs_url(
 }
 
 pub async fn purge_image_from_pictrs(alias: &str, context: &LemmyContext) -> LemmyResult<()> {
+  if is_native_media(context.settings()) {
+    return media::delete_image(alias, context).await;
+  }
   let pictrs_config = context.settings().pictrs()?;
   let purge_url = format!("{}internal/purge?alias={}", pictrs_config.url, alias);
 
@@ -463,6 +518,9 @@ pub async fn purge_image_from_pictrs(alias: &str, context: &LemmyContext) -> Lem
 /// alias. Callers MUST check if the user has permission to delete the alias
 /// before calling this function (the user is an admin or the image belongs to the user).
 pub async fn delete_image_alias(alias: &str, context: &LemmyContext) -> LemmyResult<()> {
+  if is_native_media(context.settings()) {
+    return media::delete_image(alias, context).await;
+  }
   let pictrs_config = context.settings().pictrs()?;
   let url = format!("{}internal/delete?alias={}", pictrs_config.url, &alias);
 
@@ -483,7 +541,8 @@ pub async fn delete_image_alias(alias: &str, context: &LemmyContext) -> LemmyRes
   Ok(())
 }
 
-/// Retrieves the image with local pict-rs and generates a thumbnail. Returns the thumbnail url.
+/// Retrieves the image with local pict-rs or the native media backend, and generates a thumbnail.
+/// Returns the thumbnail url.
 async fn generate_pictrs_thumbnail(
   post: &Post,
   image_url: &Url,
@@ -502,6 +561,19 @@ async fn generate_pictrs_thumbnail(
     _ => {}
   };
 
+  if is_native_media(context.settings()) {
+    let image = media::store_remote_image(
+      image_url,
+      local_site.image_max_thumbnail_size,
+      // For thumbnails, the person_id is the post creator
+      post.creator_id,
+      post.id,
+      context,
+    )
+    .await?;
+    return Ok(image.url);
+  }
+
   // fetch remote non-pictrs images for persistent thumbnail link
   let fetch_url = format!(
     "{}image/download?url={}&resize={}",
@@ -525,22 +597,118 @@ async fn generate_pictrs_thumbnail(
     .first()
     .ok_or(LemmyErrorType::PictrsResponseError(res.msg))?;
 
+  let hash_and_size = async {
+    let phash = pictrs_image_hash(&image.file, context).await?;
+    check_image_hash(phash, context).await?;
+    LemmyResult::Ok((phash, pictrs_image_size(&image.file, context).await?))
+  }
+  .await;
+  let (phash, size) = match hash_and_size {
+    Ok(hash_and_size) => hash_and_size,
+    Err(e) => {
+      purge_image_from_pictrs(&image.file, context).await.ok();
+      return Err(e);
+    }
+  };
+
   let form = LocalImageForm {
     pictrs_alias: image.file.clone(),
     // For thumbnails, the person_id is the post creator
     person_id: post.creator_id,
     thumbnail_for_post_id: Some(Some(post.id)),
+    size,
   };
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
   let thumbnail_url = image.image_url(&protocol_and_hostname)?;
 
   // Also store the details for the image
-  let details_form = image.details.build_image_details_form(&thumbnail_url);
+  let mut details_form = image.details.build_image_details_form(&thumbnail_url);
+  details_form.phash = Some(phash);
   LocalImage::create(&mut context.pool(), &form, &details_form).await?;
 
   Ok(thumbnail_url)
 }
 
+/// Returns the file size in bytes of an image which is stored in pict-rs.
+pub async fn pictrs_image_size(alias: &str, context: &LemmyContext) -> LemmyResult<i64> {
+  let url = format!("{}image/original/{alias}", context.settings().pictrs()?.url);
+  let res = context
+    .pictrs_client()
+    .head(&url)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?;
+  let size = res
+    .headers()
+    .get(CONTENT_LENGTH)
+    .ok_or(LemmyErrorType::NotFound)?
+    .to_str()?
+    .parse()?;
+  Ok(size)
+}
+
+/// Size of the thumbnails which are generated for perceptual hashes.
+const HASH_THUMBNAIL_SIZE: u32 = 256;
+
+/// Returns the perceptual hash of an image which is stored in pict-rs. Fails if no hash can be
+/// calculated, so that uploads which can't be checked against the blocklist are rejected.
+pub async fn pictrs_image_hash(alias: &str, context: &LemmyContext) -> LemmyResult<i64> {
+  pictrs_thumbnail_hash(&[("src", alias)], context)
+    .await
+    .with_lemmy_type(LemmyErrorType::PictrsInvalidImageUpload(
+      "Failed to hash image".to_string(),
+    ))
+}
+
+/// Hashes a small PNG thumbnail which pict-rs generates from an image or video given by `source`,
+/// as the original may be in a format which can't be decoded here.
+async fn pictrs_thumbnail_hash(
+  source: &[(&str, &str)],
+  context: &LemmyContext,
+) -> LemmyResult<i64> {
+  let url = format!("{}image/process.png", context.settings().pictrs()?.url);
+  let data = context
+    .pictrs_client()
+    .get(&url)
+    .query(&[("thumbnail", HASH_THUMBNAIL_SIZE)])
+    .query(source)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?
+    .bytes()
+    .await?;
+  hash_image_data(data).await
+}
+
+/// Downloads the original file of a local image from pict-rs or the native media store. Returns
+/// `None` if the image doesn't exist anymore.
+pub async fn read_original_image(
+  alias: &str,
+  context: &LemmyContext,
+) -> LemmyResult<Option<Bytes>> {
+  if is_native_media(context.settings()) {
+    let image = media::read_image(alias, None, None, context.settings()).await?;
+    return Ok(image.map(|(data, _)| data));
+  }
+  let url = format!(
+    "{}image/original/{}",
+    context.settings().pictrs()?.url,
+    encode(alias)
+  );
+  let res = context
+    .pictrs_client()
+    .get(&url)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?;
+  if res.status() == StatusCode::NOT_FOUND {
+    return Ok(None);
+  }
+  Ok(Some(res.error_for_status()?.bytes().await?))
+}
+
 /// Fetches the image details for pictrs proxied images
 ///
 /// We don't need to check for image mode, as that's already been done
@@ -548,6 +716,9 @@ pub async fn fetch_pictrs_proxied_image_details(
   image_url: &Url,
   context: &LemmyContext,
 ) -> LemmyResult<PictrsFileDetails> {
+  if is_native_media(context.settings()) {
+    return media::proxied_image_details(image_url, context).await;
+  }
   let pictrs_url = context.settings().pictrs()?.url;
   let encoded_image_url = encode(image_url.as_str());
 
@@ -565,7 +736,7 @@ pub async fn fetch_pictrs_proxied_image_details(
 
   let details_url = format!("{pictrs_url}image/details/original?proxy={encoded_image_url}");
 
-  let res = context
+  let mut res: PictrsFileDetails = context
     .pictrs_client()
     .get(&details_url)
     .timeout(REQWEST_TIMEOUT)
@@ -574,6 +745,10 @@ pub async fn fetch_pictrs_proxied_image_details(
     .error_for_status()?
     .json()
     .await?;
+  // Pict-rs might fail to generate a thumbnail, in which case the remote image is not checked
+  res.phash = pictrs_thumbnail_hash(&[("proxy", image_url.as_str())], context)
+    .await
+    .ok();
 
   Ok(res)
 }
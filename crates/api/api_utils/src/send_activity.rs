@@ -102,6 +102,7 @@ pub enum SendActivityData {
     actor: Person,
     receiver: Either<Site, Community>,
     reason: String,
+    rule: Option<Url>,
   },
   SendResolveReport {
     object_id: Url,
@@ -1,6 +1,7 @@
 use crate::{
   claims::Claims,
   context::LemmyContext,
+  image_hash::check_image_hash,
   request::{delete_image_alias, fetch_pictrs_proxied_image_details, purge_image_from_pictrs_url},
 };
 use actix_web::{HttpRequest, http::header::Header};
@@ -8,8 +9,9 @@ use actix_web_httpauth::headers::authorization::{Authorization, Bearer};
 use chrono::{DateTime, Days, Local, TimeZone, Utc};
 use enum_map::{EnumMap, enum_map};
 use lemmy_db_schema::{
-  newtypes::{CommunityId, CommunityTagId, ModlogId, PostId, PostOrCommentId},
+  newtypes::{CommunityId, CommunityTagId, ModlogId, PostId, PostOrCommentId, RuleId},
   source::{
+    admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm},
     comment::{Comment, CommentActions, CommentLikeForm},
     community::{Community, CommunityActions, CommunityUpdateForm},
     community_tag::{CommunityTag, PostCommunityTag},
@@ -18,12 +20,14 @@ use lemmy_db_schema::{
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    local_user::LocalUser,
     modlog::{Modlog, ModlogInsertForm},
     oauth_account::OAuthAccount,
     person::{Person, PersonUpdateForm},
     post::{Post, PostActions, PostLikeForm, PostReadCommentsForm},
     private_message::PrivateMessage,
     registration_application::RegistrationApplication,
+    rule::Rule,
     site::Site,
   },
   traits::Likeable,
@@ -31,7 +35,7 @@ use lemmy_db_schema::{
 use lemmy_db_schema_file::{
   InstanceId,
   PersonId,
-  enums::{FederationMode, ImageMode, RegistrationMode},
+  enums::{AdminAuditKind, FederationMode, ImageMode, RegistrationMode},
 };
 use lemmy_db_views_community_follower_approval::PendingFollowerView;
 use lemmy_db_views_community_moderator::{CommunityModeratorView, CommunityPersonBanView};
@@ -51,7 +55,7 @@ use lemmy_utils::{
     LemmyResult,
     UntranslatedError,
   },
-  rate_limit::{ActionType, BucketConfig},
+  rate_limit::{ActionType, BucketConfig, client_ip},
   settings::SETTINGS,
   spawn_try_task,
   utils::{
@@ -155,6 +159,23 @@ pub fn is_admin(local_user_view: &LocalUserView) -> LemmyResult<()> {
   }
 }
 
+/// Logins of admin accounts are recorded in the audit log, together with the client ip.
+pub async fn audit_admin_login(
+  local_user: &LocalUser,
+  req: &HttpRequest,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  if !local_user.admin {
+    return Ok(());
+  }
+  let mut form = AdminAuditLogInsertForm::new(AdminAuditKind::AdminLogin, local_user.person_id);
+  if let Some(ip) = client_ip(req) {
+    form = form.target(ip);
+  }
+  AdminAuditLog::create(&mut context.pool(), &form).await?;
+  Ok(())
+}
+
 pub fn is_top_mod(
   local_user_view: &LocalUserView,
   community_mods: &[CommunityModeratorView],
@@ -355,6 +376,17 @@ pub fn check_private_instance(
   }
 }
 
+/// Returns the storage quota of the user in bytes, or `None` if they are not limited. The user's
+/// own quota takes precedence over the instance default, and admins are not limited.
+pub fn image_upload_quota(local_user: &LocalUser, local_site: &LocalSite) -> Option<i64> {
+  if local_user.admin {
+    return None;
+  }
+  local_user
+    .image_upload_quota_bytes
+    .or(local_site.image_upload_quota_bytes)
+}
+
 /// If private messages are disabled, dont allow them to be sent / received
 pub fn check_private_messages_enabled(local_user_view: &LocalUserView) -> Result<(), LemmyError> {
   if !local_user_view.local_user.enable_private_messages {
@@ -393,6 +425,11 @@ pub fn local_site_rate_limit_to_rate_limit_config(
     ActionType::Comment => (l.comment_max_requests, l.comment_interval_seconds),
     ActionType::Search => (l.search_max_requests, l.search_interval_seconds),
     ActionType::ImportUserSettings => (l.import_user_settings_max_requests, l.import_user_settings_interval_seconds),
+    ActionType::Vote => (l.vote_max_requests, l.vote_interval_seconds),
+    ActionType::Report => (l.report_max_requests, l.report_interval_seconds),
+    ActionType::Follow => (l.follow_max_requests, l.follow_interval_seconds),
+    ActionType::PrivateMessage => (l.private_message_max_requests, l.private_message_interval_seconds),
+    ActionType::FederationInbox => (l.federation_inbox_max_requests, l.federation_inbox_interval_seconds),
   }
   .map(|_key, (max_requests, interval)| BucketConfig {
     max_requests: u32::try_from(max_requests).unwrap_or(0),
@@ -459,6 +496,14 @@ pub fn check_nsfw_allowed(nsfw: Option<bool>, local_site: Option<&LocalSite>) ->
   Ok(())
 }
 
+/// Returns true if the url points to an image, judging by the file extension. Used for new posts,
+/// whose content type is only fetched later.
+pub fn is_image_url(url: &Url) -> bool {
+  mime_guess::from_path(url.path())
+    .first()
+    .is_some_and(|m| m.type_() == mime::IMAGE)
+}
+
 /// Read the site for an ap_id.
 ///
 /// Used for GetCommunityResponse and GetPersonDetails
@@ -620,7 +665,9 @@ async fn create_modlog_entries_for_removed_or_restored_posts(
         mod_person_id,
         post,
         removed,
+        None,
         reason,
+        None,
         Some(bulk_action_parent_id),
       )
     })
@@ -650,7 +697,9 @@ async fn create_modlog_entries_for_removed_or_restored_comments(
       comment,
       community_id,
       removed,
+      None,
       reason,
+      None,
       Some(bulk_action_parent_id),
     );
     forms.push(form);
@@ -679,7 +728,9 @@ async fn create_modlog_entries_for_removed_or_restored_comments_in_community(
         comment,
         community_id,
         removed,
+        None,
         reason,
+        None,
         Some(bulk_action_parent_id),
       )
     })
@@ -847,18 +898,26 @@ pub async fn process_markdown(
 
   if local_site.image_mode == ImageMode::ProxyAllImages {
     let (text, links) = markdown_rewrite_image_links(text);
-    RemoteImage::create(&mut context.pool(), links.clone()).await?;
 
-    // Create images and image detail rows
-    for link in links {
-      // Insert image details for the remote image
-      let details_res = fetch_pictrs_proxied_image_details(&link, context).await;
+    // Fetch image details for the remote images, and reject blocked images before they can be
+    // proxied.
+    let mut details_forms = vec![];
+    for link in &links {
+      let details_res = fetch_pictrs_proxied_image_details(link, context).await;
       if let Ok(details) = details_res {
-        let proxied = build_proxied_image_url(&link, false, local_site, context)?;
-        let details_form = details.build_image_details_form(&proxied);
-        ImageDetails::create(&mut context.pool(), &details_form).await?;
+        if let Some(phash) = details.phash {
+          check_image_hash(phash, context).await?;
+        }
+        let proxied = build_proxied_image_url(link, false, local_site, context)?;
+        details_forms.push(details.build_image_details_form(&proxied));
       }
     }
+
+    // Create images and image detail rows
+    RemoteImage::create(&mut context.pool(), links).await?;
+    for details_form in details_forms {
+      ImageDetails::create(&mut context.pool(), &details_form).await?;
+    }
     Ok(text)
   } else {
     Ok(text)
@@ -894,12 +953,14 @@ async fn proxy_image_link_internal(
   if link.domain() == Some(&context.settings().hostname) {
     Ok(link.into())
   } else if local_site.image_mode == ImageMode::ProxyAllImages {
-    RemoteImage::create(&mut context.pool(), vec![link.clone()]).await?;
-
     let proxied = build_proxied_image_url(&link, is_thumbnail, local_site, context)?;
     // This should fail softly, since pictrs might not even be running
     let details_res = fetch_pictrs_proxied_image_details(&link, context).await;
+    if let Some(phash) = details_res.as_ref().ok().and_then(|d| d.phash) {
+      check_image_hash(phash, context).await?;
+    }
 
+    RemoteImage::create(&mut context.pool(), vec![link.clone()]).await?;
     if let Ok(details) = details_res {
       let details_form = details.build_image_details_form(&proxied);
       ImageDetails::create(&mut context.pool(), &details_form).await?;
@@ -1034,6 +1095,23 @@ pub async fn update_post_tags(
   Ok(())
 }
 
+/// Checks that a report or removal in the given community may reference this rule. This is the
+/// case for rules of the community itself, and for rules of the local instance.
+pub async fn check_rule_in_community(
+  rule_id: Option<RuleId>,
+  community_id: CommunityId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<Option<Rule>> {
+  let Some(rule_id) = rule_id else {
+    return Ok(None);
+  };
+  let rule = Rule::read(pool, rule_id).await?;
+  if rule.deleted || rule.community_id.is_some_and(|c| c != community_id) {
+    return Err(LemmyErrorType::RuleNotInCommunity.into());
+  }
+  Ok(Some(rule))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1054,6 +1132,21 @@ mod tests {
     assert!(password_length_check("looooooooooooooooooooooooooooooooooooooooooooooooooooooooooong").is_err());
   }
 
+  #[test]
+  fn test_is_image_url() -> LemmyResult<()> {
+    assert!(is_image_url(&Url::parse(
+      "https://example.com/image/cat.JPG"
+    )?));
+    assert!(is_image_url(&Url::parse(
+      "https://lemmy.example/api/v4/image/1f4e.webp?max_size=200"
+    )?));
+    assert!(!is_image_url(&Url::parse(
+      "https://example.com/article.html"
+    )?));
+    assert!(!is_image_url(&Url::parse("https://example.com/")?));
+    Ok(())
+  }
+
   #[test]
   fn honeypot() {
     assert!(honeypot_check(&None).is_ok());
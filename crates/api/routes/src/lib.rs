@@ -16,6 +16,7 @@ use lemmy_api::{
     multi_community_follow::follow_multi_community,
     pending_follows::{approve::post_pending_follows_approve, list::get_pending_follows_list},
     random::get_random_community,
+    rule::{create_rule, delete_rule, edit_rule},
     tag::{create_community_tag, delete_community_tag, edit_community_tag},
     transfer::transfer_community,
     update_notifications::edit_community_notifications,
@@ -38,7 +39,9 @@ use lemmy_api::{
     change_password::change_password,
     change_password_after_reset::change_password_after_reset,
     donation_dialog_shown::donation_dialog_shown,
+    export_archive::{create_data_export, download_data_export, get_data_export},
     export_data::export_data,
+    feed_token::{get_feed_token, regenerate_feed_token, revoke_feed_token},
     generate_totp_secret::generate_totp_secret,
     get_captcha::get_captcha,
     list_hidden::list_person_hidden,
@@ -58,6 +61,8 @@ use lemmy_api::{
     resend_verification_email::resend_verification_email,
     reset_password::reset_password,
     save_settings::save_user_settings,
+    set_image_upload_quota::set_image_upload_quota,
+    set_rate_limit_override::set_rate_limit_override,
     unread_counts::get_unread_counts,
     update_totp::edit_totp,
     user_block_instance::{user_block_instance_communities, user_block_instance_persons},
@@ -87,9 +92,17 @@ use lemmy_api::{
   },
   site::{
     admin_allow_instance::admin_allow_instance,
+    admin_audit_log::list_admin_audit_log,
     admin_block_instance::admin_block_instance,
     admin_list_users::admin_list_users,
+    email_queue::{list::list_email_queue, retry::retry_queued_email},
     federated_instances::get_federated_instances,
+    image_hash_block::{
+      block::block_image_hash,
+      import::import_image_hash_blocks,
+      list::list_image_hash_blocks,
+      unblock::unblock_image_hash,
+    },
     list_all_media::list_all_media,
     mod_log::get_mod_log,
     purge::{
@@ -103,6 +116,9 @@ use lemmy_api::{
       get::get_registration_application,
       list::list_registration_applications,
     },
+    reload_config::reload_config,
+    retention_report::get_retention_report,
+    scheduled_task::{edit::edit_scheduled_task, list::list_scheduled_tasks},
   },
 };
 use lemmy_api_crud::{
@@ -221,6 +237,16 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .wrap(rate_limit.register())
           .route(post().to(create_community)),
       )
+      .service(
+        resource("/community/follow")
+          .wrap(rate_limit.follow())
+          .route(post().to(follow_community)),
+      )
+      .service(
+        resource("/community/report")
+          .wrap(rate_limit.report())
+          .route(post().to(create_community_report)),
+      )
       .service(
         scope("/community")
           .route("", get().to(get_community))
@@ -228,8 +254,6 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("", delete().to(delete_community))
           .route("/random", get().to(get_random_community))
           .route("/list", get().to(list_communities))
-          .route("/follow", post().to(follow_community))
-          .route("/report", post().to(create_community_report))
           .route("/report/resolve", put().to(resolve_community_report))
           // Mod Actions
           .route("/remove", post().to(remove_community))
@@ -243,6 +267,9 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("/tag", post().to(create_community_tag))
           .route("/tag", put().to(edit_community_tag))
           .route("/tag", delete().to(delete_community_tag))
+          .route("/rule", post().to(create_rule))
+          .route("/rule", put().to(edit_rule))
+          .route("/rule", delete().to(delete_rule))
           .route("/notifications", post().to(edit_community_notifications))
           .service(
             scope("/pending_follows")
@@ -250,6 +277,11 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
               .route("/approve", post().to(post_pending_follows_approve)),
           ),
       )
+      .service(
+        resource("/multi_community/follow")
+          .wrap(rate_limit.follow())
+          .route(post().to(follow_multi_community)),
+      )
       .service(
         scope("/multi_community")
           .route("", post().to(create_multi_community))
@@ -257,8 +289,7 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("", get().to(read_multi_community))
           .route("/entry", post().to(create_multi_community_entry))
           .route("/entry", delete().to(delete_multi_community_entry))
-          .route("/list", get().to(list_multi_communities))
-          .route("/follow", post().to(follow_multi_community)),
+          .route("/list", get().to(list_multi_communities)),
       )
       .route("/federated_instances", get().to(get_federated_instances))
       // Post
@@ -274,6 +305,16 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .wrap(rate_limit.search())
           .route(get().to(get_link_metadata)),
       )
+      .service(
+        resource("/post/like")
+          .wrap(rate_limit.vote())
+          .route(post().to(like_post)),
+      )
+      .service(
+        resource("/post/report")
+          .wrap(rate_limit.report())
+          .route(post().to(create_post_report)),
+      )
       .service(
         scope("/post")
           .route("", get().to(get_post))
@@ -286,10 +327,8 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("/lock", post().to(lock_post))
           .route("/feature", post().to(feature_post))
           .route("/list", get().to(list_posts))
-          .route("/like", post().to(like_post))
           .route("/like/list", get().to(list_post_likes))
           .route("/save", put().to(save_post))
-          .route("/report", post().to(create_post_report))
           .route("/report/resolve", put().to(resolve_post_report))
           .route("/notifications", post().to(edit_post_notifications))
           .route("/mod_edit", put().to(mod_edit_post))
@@ -303,6 +342,16 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .wrap(rate_limit.comment())
           .route(post().to(create_comment)),
       )
+      .service(
+        resource("/comment/like")
+          .wrap(rate_limit.vote())
+          .route(post().to(like_comment)),
+      )
+      .service(
+        resource("/comment/report")
+          .wrap(rate_limit.report())
+          .route(post().to(create_comment_report)),
+      )
       .service(
         scope("/comment")
           .route("", get().to(get_comment))
@@ -310,23 +359,30 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("", delete().to(delete_comment))
           .route("/remove", post().to(remove_comment))
           .route("/distinguish", post().to(distinguish_comment))
-          .route("/like", post().to(like_comment))
           .route("/like/list", get().to(list_comment_likes))
           .route("/save", put().to(save_comment))
           .route("/lock", post().to(lock_comment))
           .route("/list", get().to(list_comments))
           .route("/list/slim", get().to(list_comments_slim))
           .route("/warn", post().to(create_comment_warning))
-          .route("/report", post().to(create_comment_report))
           .route("/report/resolve", put().to(resolve_comment_report)),
       )
       // Private Message
+      .service(
+        resource("/private_message")
+          .guard(guard::Post())
+          .wrap(rate_limit.private_message())
+          .route(post().to(create_private_message)),
+      )
+      .service(
+        resource("/private_message/report")
+          .wrap(rate_limit.report())
+          .route(post().to(create_pm_report)),
+      )
       .service(
         scope("/private_message")
-          .route("", post().to(create_private_message))
           .route("", put().to(edit_private_message))
           .route("", delete().to(delete_private_message))
-          .route("/report", post().to(create_pm_report))
           .route("/report/resolve", put().to(resolve_pm_report)),
       )
       // Reports
@@ -372,6 +428,12 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           )
           .route("", delete().to(delete_account))
           .route("/login/list", get().to(list_logins))
+          .service(
+            scope("/feed_token")
+              .route("", get().to(get_feed_token))
+              .route("", post().to(regenerate_feed_token))
+              .route("", delete().to(revoke_feed_token)),
+          )
           .route("/validate_auth", get().to(validate_auth))
           .route("/donation_dialog_shown", post().to(donation_dialog_shown))
           .route("/avatar", post().to(upload_user_avatar))
@@ -404,6 +466,19 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             resource("/data/export")
               .wrap(rate_limit.import_user_settings())
               .route(get().to(export_data)),
+          )
+          .service(
+            resource("/data/export/archive")
+              .route(get().to(get_data_export))
+              .route(
+                post()
+                  .wrap(rate_limit.import_user_settings())
+                  .to(create_data_export),
+              ),
+          )
+          .route(
+            "/data/export/archive/download",
+            get().to(download_data_export),
           ),
       )
       // User actions
@@ -439,6 +514,28 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           )
           .route("/ban", post().to(ban_from_site))
           .route("/users", get().to(admin_list_users))
+          .route("/image_upload_quota", post().to(set_image_upload_quota))
+          .route("/rate_limit_override", post().to(set_rate_limit_override))
+          .service(
+            scope("/image_hash_block")
+              .route("", post().to(block_image_hash))
+              .route("", delete().to(unblock_image_hash))
+              .route("/list", get().to(list_image_hash_blocks))
+              .route("/import", post().to(import_image_hash_blocks)),
+          )
+          .service(
+            scope("/email_queue")
+              .route("/list", get().to(list_email_queue))
+              .route("/retry", post().to(retry_queued_email)),
+          )
+          .service(
+            scope("/scheduled_task")
+              .route("", put().to(edit_scheduled_task))
+              .route("/list", get().to(list_scheduled_tasks)),
+          )
+          .route("/reload_config", post().to(reload_config))
+          .route("/audit_log", get().to(list_admin_audit_log))
+          .route("/retention_report", get().to(get_retention_report))
           .service(
             scope("/instance")
               .route("/block", post().to(admin_block_instance))
@@ -66,7 +66,7 @@ impl Activity for LockPageOrNote {
         };
         Post::update(&mut context.pool(), post.id, &form).await?;
 
-        let form = ModlogInsertForm::mod_lock_post(actor.id, &post, true, &reason);
+        let form = ModlogInsertForm::mod_lock_post(actor.id, &post, true, None, &reason);
         let action = Modlog::create(&mut context.pool(), &[form]).await?;
         notify_mod_action(action, context);
       }
@@ -78,7 +78,7 @@ impl Activity for LockPageOrNote {
           .community_id;
 
         let form =
-          ModlogInsertForm::mod_lock_comment(actor.id, &comment, community_id, true, &reason);
+          ModlogInsertForm::mod_lock_comment(actor.id, &comment, community_id, true, None, &reason);
         let action = Modlog::create(&mut context.pool(), &[form]).await?;
         notify_mod_action(action, context);
       }
@@ -124,7 +124,7 @@ impl Activity for UndoLockPageOrNote {
 
         Post::update(&mut context.pool(), post.id, &form).await?;
 
-        let form = ModlogInsertForm::mod_lock_post(actor.id, &post, false, &reason);
+        let form = ModlogInsertForm::mod_lock_post(actor.id, &post, false, None, &reason);
         let action = Modlog::create(&mut context.pool(), &[form]).await?;
         notify_mod_action(action, context);
       }
@@ -136,8 +136,14 @@ impl Activity for UndoLockPageOrNote {
           .await?
           .community_id;
 
-        let form =
-          ModlogInsertForm::mod_lock_comment(actor.id, &comment, community_id, false, &reason);
+        let form = ModlogInsertForm::mod_lock_comment(
+          actor.id,
+          &comment,
+          community_id,
+          false,
+          None,
+          &reason,
+        );
         let action = Modlog::create(&mut context.pool(), &[form]).await?;
         notify_mod_action(action, context);
       }
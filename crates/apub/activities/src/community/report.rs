@@ -35,12 +35,14 @@ use lemmy_apub_objects::{
   utils::functions::{verify_person_in_community, verify_person_in_site_or_community},
 };
 use lemmy_db_schema::{
+  newtypes::{CommunityId, RuleId},
   source::{
     comment_report::{CommentReport, CommentReportForm},
     community::Community,
     community_report::{CommunityReport, CommunityReportForm},
     post::Post,
     post_report::{PostReport, PostReportForm},
+    rule::Rule,
   },
   traits::Reportable,
 };
@@ -54,6 +56,7 @@ impl Report {
     actor: &ApubPerson,
     receiver: &Either<ApubSite, ApubCommunity>,
     reason: Option<String>,
+    rule: Option<Url>,
     context: &Data<LemmyContext>,
   ) -> LemmyResult<Self> {
     let kind = FlagType::Flag;
@@ -67,6 +70,7 @@ impl Report {
       kind,
       id: id.clone(),
       audience: receiver.as_ref().right().map(|c| c.ap_id.clone().into()),
+      rule,
     })
   }
 
@@ -75,13 +79,27 @@ impl Report {
     actor: &ApubPerson,
     receiver: &Either<ApubSite, ApubCommunity>,
     reason: String,
+    rule: Option<Url>,
     context: Data<LemmyContext>,
   ) -> LemmyResult<()> {
-    let report = Self::new(&object_id, actor, receiver, Some(reason), &context)?;
+    let report = Self::new(&object_id, actor, receiver, Some(reason), rule, &context)?;
     let inboxes = report_inboxes(object_id, receiver, actor, &context).await?;
 
     send_lemmy_activity(&context, report, actor, inboxes, false).await
   }
+
+  /// Returns the referenced rule, if it is one of the rules of the reported community. Unknown
+  /// rules are ignored so that the report is still received.
+  async fn rule_id(
+    &self,
+    community_id: CommunityId,
+    context: &Data<LemmyContext>,
+  ) -> Option<RuleId> {
+    let rule = Rule::read_apub(&mut context.pool(), &self.rule.clone()?.into())
+      .await
+      .ok()?;
+    (rule.community_id == Some(community_id)).then_some(rule.id)
+  }
 }
 
 #[async_trait::async_trait]
@@ -138,16 +156,19 @@ impl Activity for Report {
           reason,
           original_post_body: post.body.clone(),
           violates_instance_rules: false,
+          rule_id: self.rule_id(post.community_id, context).await,
         };
         PostReport::report(&mut context.pool(), &report_form).await?;
       }
       ReportableObjects::Left(PostOrComment::Right(comment)) => {
+        let post = Post::read(&mut context.pool(), comment.post_id).await?;
         let report_form = CommentReportForm {
           creator_id: actor.id,
           comment_id: comment.id,
           original_comment_text: comment.content.clone(),
           reason,
           violates_instance_rules: false,
+          rule_id: self.rule_id(post.community_id, context).await,
         };
         CommentReport::report(&mut context.pool(), &report_form).await?;
       }
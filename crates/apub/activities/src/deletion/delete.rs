@@ -137,7 +137,8 @@ pub(crate) async fn receive_remove_action(
     }
     DeletableObjects::Post(post) => {
       PostReport::resolve_all_for_object(&mut context.pool(), post.id, actor.id).await?;
-      let form = ModlogInsertForm::mod_remove_post(actor.id, &post, true, &reason, None);
+      let form =
+        ModlogInsertForm::mod_remove_post(actor.id, &post, true, None, &reason, None, None);
       let action = Modlog::create(&mut context.pool(), &[form]).await?;
       notify_mod_action(action, context.app_data());
       let post = Post::update(
@@ -166,8 +167,10 @@ pub(crate) async fn receive_remove_action(
               comment,
               post.community_id,
               true,
+              None,
               &reason,
               None,
+              None,
             )
           })
           .collect();
@@ -203,8 +206,10 @@ pub(crate) async fn receive_remove_action(
               comment,
               community_id,
               true,
+              None,
               &reason,
               None,
+              None,
             )
           })
           .collect();
@@ -218,8 +223,10 @@ pub(crate) async fn receive_remove_action(
           &comment,
           community_id,
           true,
+          None,
           &reason,
           None,
+          None,
         );
         let action = Modlog::create(&mut context.pool(), &[form]).await?;
         notify_mod_action(action, context.app_data());
@@ -120,7 +120,8 @@ impl UndoDelete {
         .await?;
       }
       DeletableObjects::Post(post) => {
-        let form = ModlogInsertForm::mod_remove_post(actor.id, &post, false, &reason, None);
+        let form =
+          ModlogInsertForm::mod_remove_post(actor.id, &post, false, None, &reason, None, None);
         let action = Modlog::create(&mut context.pool(), &[form]).await?;
         notify_mod_action(action, context.app_data());
         Post::update(
@@ -148,8 +149,10 @@ impl UndoDelete {
                 comment,
                 post.community_id,
                 false,
+                None,
                 &reason,
                 None,
+                None,
               )
             })
             .collect();
@@ -180,8 +183,10 @@ impl UndoDelete {
               comment,
               community_id,
               false,
+              None,
               &reason,
               None,
+              None,
             );
             forms.push(form);
           }
@@ -196,8 +201,10 @@ impl UndoDelete {
             &comment,
             community_id,
             false,
+            None,
             &reason,
             None,
+            None,
           );
           let action = Modlog::create(&mut context.pool(), &[form]).await?;
           notify_mod_action(action, context.app_data());
@@ -348,12 +348,14 @@ pub async fn match_outgoing_activities(
         actor,
         receiver,
         reason,
+        rule,
       } => {
         Report::send(
           ObjectId::from(object_id),
           &actor.into(),
           &receiver.map_either(Into::into, Into::into),
           reason,
+          rule,
           context,
         )
         .await
@@ -29,6 +29,9 @@ pub struct Report {
   pub(crate) kind: FlagType,
   pub(crate) id: Url,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// One of the rules federated with the community, which the object violates
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) rule: Option<Url>,
 }
 
 impl Report {
@@ -5,6 +5,7 @@ use activitypub_federation::{
     signing_actor,
   },
   config::Data,
+  error::Error as FederationError,
   traits::{Activity, Object},
 };
 use actix_web::{
@@ -24,7 +25,8 @@ use lemmy_db_schema_file::{InstanceId, enums::CommunityVisibility};
 use lemmy_db_views_community_follower_approval::PendingFollowerView;
 use lemmy_utils::{
   FEDERATION_CONTEXT,
-  error::{LemmyErrorExt, LemmyErrorType, LemmyResult, UntranslatedError},
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult, UntranslatedError},
+  metrics::{InboxFailureReason, inbox_activity},
 };
 use serde::Deserialize;
 use std::time::Duration;
@@ -46,6 +48,9 @@ pub async fn shared_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
+  let kind = serde_json::from_slice::<ActivityKind>(&body)
+    .map(|a| a.kind)
+    .unwrap_or_default();
   let receive_fut =
     receive_activity_with_hook::<SharedInboxActivities, UserOrCommunity, LemmyContext>(
       request, body, Dummy, &data,
@@ -54,9 +59,36 @@ pub async fn shared_inbox(
   // avoid taking a long time to process an incoming activity when a required data fetch times out.
   // In this case our own instance would timeout and be marked as dead by the sender. Better to
   // consider the activity broken and move on.
-  timeout(INCOMING_ACTIVITY_TIMEOUT, receive_fut)
+  let res = timeout(INCOMING_ACTIVITY_TIMEOUT, receive_fut)
     .await
-    .with_lemmy_type(UntranslatedError::InboxTimeout.into())?
+    .with_lemmy_type(UntranslatedError::InboxTimeout.into())
+    .and_then(|res| res);
+  inbox_activity(&kind, res.as_ref().err().map(inbox_failure_reason));
+  res
+}
+
+/// Only used for metrics. Errors from the federation library are kept as the cause, while
+/// activities which Lemmy rejects during verification return an untranslated error.
+fn inbox_failure_reason(err: &LemmyError) -> InboxFailureReason {
+  match (&err.error_type, err.cause.downcast_ref::<FederationError>()) {
+    (LemmyErrorType::UntranslatedError(Some(UntranslatedError::InboxTimeout)), _) => {
+      InboxFailureReason::Timeout
+    }
+    (
+      _,
+      Some(FederationError::ActivitySignatureInvalid | FederationError::ActivityBodyDigestInvalid),
+    ) => InboxFailureReason::Signature,
+    (_, Some(FederationError::UrlVerificationError(_)))
+    | (LemmyErrorType::UntranslatedError(Some(_)), _) => InboxFailureReason::Verify,
+    _ => InboxFailureReason::Other,
+  }
+}
+
+/// Only used for metrics, the activity itself is parsed by the federation library.
+#[derive(Deserialize)]
+struct ActivityKind {
+  #[serde(rename = "type")]
+  kind: String,
 }
 
 struct Dummy;
@@ -21,8 +21,9 @@ use actix_web::{
   http::{Method, header},
   web,
 };
+use lemmy_utils::rate_limit::RateLimit;
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
   cfg
     .route("/", web::get().to(get_apub_site_http))
     .route("/site_outbox", web::get().to(get_apub_site_outbox))
@@ -78,6 +79,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
   cfg.service(
     web::scope("")
       .guard(InboxRequestGuard)
+      .wrap(rate_limit.federation_inbox())
       .route("/inbox", web::post().to(shared_inbox)),
   );
 }
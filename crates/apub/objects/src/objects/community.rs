@@ -1,6 +1,6 @@
 use crate::{
   objects::instance::fetch_instance_actor_for_object,
-  protocol::{group::Group, tags::ApubCommunityTag},
+  protocol::{group::Group, rule::ApubCommunityRule, tags::ApubCommunityTag},
   utils::{
     functions::{
       GetActorType,
@@ -36,6 +36,7 @@ use lemmy_db_schema::{
     actor_language::CommunityLanguage,
     community::{Community, CommunityInsertForm, CommunityUpdateForm},
     community_tag::CommunityTag,
+    rule::Rule,
   },
   traits::ApubActor,
 };
@@ -114,6 +115,7 @@ impl Object for ApubCommunity {
     let langs = CommunityLanguage::read(&mut data.pool(), community_id).await?;
     let language = LanguageTag::new_multiple(langs, &mut data.pool()).await?;
     let community_tags = CommunityTag::read_for_community(&mut data.pool(), community_id).await?;
+    let rules = Rule::list(&mut data.pool(), Some(community_id)).await?;
     let group = Group {
       kind: GroupType::Group,
       id: self.id().clone().into(),
@@ -145,6 +147,7 @@ impl Object for ApubCommunity {
         .into_iter()
         .map(ApubCommunityTag::to_json)
         .collect(),
+      rules: rules.into_iter().map(ApubCommunityRule::to_json).collect(),
     };
     Ok(group)
   }
@@ -156,6 +159,10 @@ impl Object for ApubCommunity {
   ) -> LemmyResult<()> {
     check_apub_id_valid_with_strictness(group.id.inner(), true, context).await?;
     verify_domains_match(expected_domain, group.id.inner())?;
+    // Rules are upserted by id, so they must belong to the same instance as the community
+    for rule in &group.rules {
+      verify_domains_match(group.id.inner(), &rule.id)?;
+    }
 
     // Doesnt call verify_is_remote_object() because the community might be edited by a
     // remote mod. This is safe as we validate `expected_domain`.
@@ -250,6 +257,14 @@ impl Object for ApubCommunity {
     let existing_tags = CommunityTag::read_for_community(&mut context.pool(), community.id).await?;
     CommunityTag::update_many(&mut context.pool(), new_tags, existing_tags).await?;
 
+    let new_rules = group
+      .rules
+      .iter()
+      .map(|r| r.to_insert_form(community.id))
+      .collect();
+    let existing_rules = Rule::list(&mut context.pool(), Some(community.id)).await?;
+    Rule::update_many(&mut context.pool(), new_rules, existing_rules).await?;
+
     let community: ApubCommunity = community.into();
 
     // These collections are not necessary for Lemmy to work, so ignore errors. Reset request count
@@ -30,12 +30,14 @@ use chrono::Utc;
 use html2text::{from_read_with_decorator, render::TrivialDecorator};
 use lemmy_api_utils::{
   context::LemmyContext,
+  image_hash::is_image_blocked,
   plugins::{plugin_hook_after, plugin_hook_before},
   request::generate_post_link_metadata,
   utils::{
     check_nsfw_allowed,
     get_url_blocklist,
     process_markdown_opt,
+    proxy_image_link,
     slur_regex,
     update_post_tags,
   },
@@ -46,6 +48,7 @@ use lemmy_db_schema::source::{
   local_site::LocalSite,
   person::Person,
   post::{Post, PostInsertForm, PostUpdateForm},
+  post_gallery_image::{PostGalleryImage, PostGalleryImageForm},
 };
 use lemmy_db_views_community_moderator::CommunityModeratorView;
 use lemmy_db_views_site::SiteView;
@@ -56,11 +59,19 @@ use lemmy_utils::{
   utils::{
     markdown::markdown_to_html,
     slurs::remove_slurs,
-    validation::{is_url_blocked, is_valid_url},
+    validation::{
+      is_url_blocked,
+      is_valid_gallery_length,
+      is_valid_url,
+      truncate_alt_text,
+      truncate_gallery_caption,
+    },
   },
 };
+use regex::Regex;
 use std::{collections::HashSet, ops::Deref};
 use stringreader::StringReader;
+use tracing::warn;
 use url::Url;
 
 const MAX_TITLE_LENGTH: usize = 200;
@@ -126,9 +137,13 @@ impl Object for ApubPost {
     let community = Community::read(&mut context.pool(), community_id).await?;
     let language = Some(LanguageTag::new_single(self.language_id, &mut context.pool()).await?);
 
-    let attachment = self
+    // Gallery images are sent as additional attachments. The post url is usually the first
+    // gallery image, in that case it is not sent twice.
+    let gallery = PostGalleryImage::read_for_post(&mut context.pool(), self.id).await?;
+    let mut attachment: Vec<Attachment> = self
       .url
       .clone()
+      .filter(|url| gallery.first().map(|i| &i.url) != Some(url))
       .map(|url| {
         Attachment::new(
           url.into(),
@@ -138,6 +153,7 @@ impl Object for ApubPost {
       })
       .into_iter()
       .collect();
+    attachment.extend(gallery.into_iter().map(Attachment::from));
 
     // Add tags defined by community and applied to this post
     let mut tags: Vec<ApubTag> = CommunityTag::read_for_post(&mut context.pool(), self.id)
@@ -276,6 +292,24 @@ impl Object for ApubPost {
 
     let alt_text = first_attachment.cloned().and_then(Attachment::alt_text);
 
+    // Posts with multiple attachments (eg from Pixelfed or Mastodon) are stored as gallery, so
+    // that no images are lost.
+    let gallery: Vec<Attachment> = if page.attachment.len() > 1 {
+      page
+        .attachment
+        .iter()
+        .filter(|a| a.is_image())
+        .filter(|a| {
+          let url = a.clone().url();
+          is_url_blocked(&url, &url_blocklist).is_ok() && is_valid_url(&url).is_ok()
+        })
+        .cloned()
+        .collect()
+    } else {
+      vec![]
+    };
+    is_valid_gallery_length(gallery.len())?;
+
     let body = read_from_string_or_source_opt(&page.content, &page.media_type, &page.source);
     let body =
       process_markdown_opt(&body, &slur_regex, &url_blocklist, &local_site, context).await?;
@@ -310,6 +344,7 @@ impl Object for ApubPost {
     plugin_hook_after("federated_post_after_receive", &post);
 
     update_apub_post_tags(&page, &post, context).await?;
+    update_apub_post_gallery(gallery, &post, &local_site, &slur_regex, context).await?;
 
     let post_ = post.clone();
     let context_ = context.clone();
@@ -349,6 +384,41 @@ pub async fn update_apub_post_tags(
   Ok(())
 }
 
+/// Stores the gallery images of a remote post. Images are proxied like the post url, and images
+/// on the blocklist or which fail to be proxied are left out.
+async fn update_apub_post_gallery(
+  gallery: Vec<Attachment>,
+  post: &Post,
+  local_site: &LocalSite,
+  slur_regex: &Regex,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let mut forms = vec![];
+  for attachment in gallery {
+    let caption = attachment
+      .caption()
+      .map(|c| truncate_gallery_caption(&remove_slurs(&c, slur_regex)));
+    let alt_text = attachment.clone().alt_text().map(|a| truncate_alt_text(&a));
+    let url = proxy_image_link(attachment.url(), local_site, false, context).await;
+    if is_image_blocked(&url) {
+      continue;
+    }
+    let url = match url {
+      Ok(url) => url,
+      Err(e) => {
+        warn!("Failed to proxy gallery image of post {}: {e}", post.ap_id);
+        continue;
+      }
+    };
+    let position = forms.len().try_into()?;
+    forms.push(PostGalleryImageForm::new(
+      post.id, position, url, alt_text, caption,
+    ));
+  }
+  PostGalleryImage::replace(&mut context.pool(), post.id, &forms).await?;
+  Ok(())
+}
+
 pub async fn post_nsfw(
   page: &Page,
   community: &Community,
@@ -382,7 +452,11 @@ mod tests {
     objects::ApubPerson,
     utils::test::{file_to_json_object, parse_lemmy_community, parse_lemmy_person},
   };
-  use lemmy_db_schema::{source::instance::Instance, test_data::TestData};
+  use lemmy_db_schema::{
+    newtypes::PostId,
+    source::{instance::Instance, person::PersonInsertForm, post_gallery_image::PostGalleryImage},
+    test_data::TestData,
+  };
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -431,4 +505,57 @@ mod tests {
     Instance::delete_all(&mut context.pool()).await?;
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  #[expect(clippy::indexing_slicing)]
+  async fn test_parse_pixelfed_gallery() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let test_data = TestData::create(&mut context.pool()).await?;
+    parse_lemmy_community(&context).await?;
+    let instance = Instance::read_or_create(&mut context.pool(), "pixelfed.example").await?;
+    let person_form = PersonInsertForm {
+      ap_id: Some(Url::parse("https://pixelfed.example/users/photographer")?.into()),
+      ..PersonInsertForm::test_form(instance.id, "photographer")
+    };
+    Person::create(&mut context.pool(), &person_form).await?;
+
+    let mut page: Page = file_to_json_object("../apub/assets/pixelfed/objects/note.json")?;
+    // Remote alt text and captions are shortened to the local limits
+    let long_text = "a".repeat(2000);
+    page.attachment.push(Attachment::from(PostGalleryImage {
+      post_id: PostId(0),
+      position: 0,
+      url: Url::parse("https://pixelfed.example/storage/m/_v2/1/lake_4.jpg")?.into(),
+      alt_text: Some(long_text.clone()),
+      caption: Some(long_text),
+      published_at: Utc::now(),
+    }));
+    let post = ApubPost::from_json(page.clone(), &context).await?;
+
+    let gallery = PostGalleryImage::read_for_post(&mut context.pool(), post.id).await?;
+    assert_eq!(
+      vec![0, 1, 2, 3],
+      gallery.iter().map(|i| i.position).collect::<Vec<_>>()
+    );
+    assert_eq!(
+      Some("Fog above a lake at sunrise"),
+      gallery[0].alt_text.as_deref()
+    );
+    assert_eq!(None, gallery[2].alt_text);
+    let text_len = |t: &Option<String>| t.as_ref().map(|t| t.chars().count());
+    assert_eq!(Some(1500), text_len(&gallery[3].alt_text));
+    assert_eq!(Some(1500), text_len(&gallery[3].caption));
+
+    // An update with a single attachment removes the gallery
+    page.attachment.truncate(1);
+    page.updated = Some(Utc::now());
+    ApubPost::from_json(page, &context).await?;
+    let gallery = PostGalleryImage::read_for_post(&mut context.pool(), post.id).await?;
+    assert!(gallery.is_empty());
+
+    test_data.delete(&mut context.pool()).await?;
+    Instance::delete_all(&mut context.pool()).await?;
+    Ok(())
+  }
 }
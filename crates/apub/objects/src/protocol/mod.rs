@@ -5,6 +5,7 @@ pub mod note;
 pub mod page;
 pub mod person;
 pub mod private_message;
+pub mod rule;
 pub mod tags;
 
 #[cfg(test)]
@@ -13,13 +14,14 @@ mod tests {
     group::Group,
     instance::Instance,
     note::Note,
-    page::Page,
+    page::{Attachment, Page},
     person::Person,
     private_message::PrivateMessage,
   };
   use crate::utils::test::{test_json, test_parse_lemmy_item};
   use activitypub_federation::protocol::tombstone::Tombstone;
   use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
 
   #[test]
   fn test_parse_objects_lemmy() -> LemmyResult<()> {
@@ -56,6 +58,16 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_parse_objects_pixelfed() -> LemmyResult<()> {
+    let page = test_json::<Page>("../apub/assets/pixelfed/objects/note.json")?
+      .inner()
+      .clone();
+    assert_eq!(3, page.attachment.len());
+    assert!(page.attachment.iter().all(Attachment::is_image));
+    Ok(())
+  }
+
   #[test]
   fn test_parse_objects_lotide() -> LemmyResult<()> {
     test_json::<Group>("../apub/assets/lotide/objects/group.json")?;
@@ -26,6 +26,7 @@ use activitypub_federation::{
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use lemmy_api_utils::{context::LemmyContext, utils::proxy_image_link};
+use lemmy_db_schema::source::post_gallery_image::PostGalleryImage;
 use lemmy_db_views_site::SiteView;
 use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult, UntranslatedError};
 use serde::{Deserialize, Deserializer, Serialize, de::Error};
@@ -62,8 +63,8 @@ pub struct Page {
   pub(crate) media_type: Option<MediaTypeMarkdownOrHtml>,
   #[serde(deserialize_with = "deserialize_skip_error", default)]
   pub(crate) source: Option<Source>,
-  /// most software uses array type for attachment field, so we do the same. nevertheless, we only
-  /// use the first item
+  /// most software uses array type for attachment field, so we do the same. The first item is
+  /// used as post url, if there are multiple items the images are stored as gallery.
   #[serde(default)]
   pub(crate) attachment: Vec<Attachment>,
   pub(crate) image: Option<ImageObject>,
@@ -95,6 +96,8 @@ pub struct Image {
   url: Url,
   /// Used for alt_text
   name: Option<String>,
+  /// Used for the caption of gallery images
+  summary: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -136,6 +139,23 @@ impl Attachment {
     }
   }
 
+  pub(crate) fn caption(&self) -> Option<String> {
+    match self {
+      Attachment::Image(i) => i.summary.clone(),
+      _ => None,
+    }
+  }
+
+  /// Returns true if this is an image, so that it can be part of a gallery.
+  pub(crate) fn is_image(&self) -> bool {
+    let media_type = match self {
+      Attachment::Image(_) => return true,
+      Attachment::Document(d) => &d.media_type,
+      Attachment::Link(l) => &l.media_type,
+    };
+    media_type.as_ref().is_some_and(|m| m.starts_with("image"))
+  }
+
   pub(crate) async fn as_markdown(&self, context: &Data<LemmyContext>) -> LemmyResult<String> {
     let (url, name, media_type) = match self {
       Attachment::Image(i) => (i.url.clone(), i.name.clone(), Some(String::from("image"))),
@@ -181,6 +201,7 @@ impl Attachment {
         kind: Default::default(),
         url,
         name: alt_text,
+        summary: None,
       })
     } else {
       Attachment::Link(Link {
@@ -192,6 +213,17 @@ impl Attachment {
   }
 }
 
+impl From<PostGalleryImage> for Attachment {
+  fn from(image: PostGalleryImage) -> Self {
+    Attachment::Image(Image {
+      kind: Default::default(),
+      url: image.url.into(),
+      name: image.alt_text,
+      summary: image.caption,
+    })
+  }
+}
+
 // Used for community outbox, so that it can be compatible with Pleroma/Mastodon.
 #[async_trait::async_trait]
 impl Activity for Page {
@@ -0,0 +1,53 @@
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::rule::{Rule, RuleInsertForm},
+};
+use lemmy_utils::utils::validation::truncate_rule_title;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+enum CommunityRuleType {
+  #[default]
+  CommunityRule,
+}
+
+/// A rule of a community, which can be referenced when reporting content in the community.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApubCommunityRule {
+  #[serde(rename = "type")]
+  kind: CommunityRuleType,
+  pub id: Url,
+  pub name: String,
+  pub content: Option<String>,
+  #[serde(default)]
+  pub position: i32,
+}
+
+impl ApubCommunityRule {
+  pub fn to_json(rule: Rule) -> Self {
+    ApubCommunityRule {
+      kind: Default::default(),
+      id: rule.ap_id.into(),
+      name: rule.title,
+      content: rule.description,
+      position: rule.position,
+    }
+  }
+
+  pub fn to_insert_form(&self, community_id: CommunityId) -> RuleInsertForm {
+    RuleInsertForm {
+      description: self.content.clone(),
+      position: Some(self.position),
+      deleted: Some(false),
+      ..RuleInsertForm::new(
+        self.id.clone().into(),
+        Some(community_id),
+        truncate_rule_title(&self.name),
+      )
+    }
+  }
+}
@@ -7,7 +7,10 @@ use lemmy_utils::{error::LemmyResult, settings::structs::FederationWorkerConfig}
 use stats::receive_print_stats;
 use std::{collections::HashMap, time::Duration};
 use tokio::{
-  sync::mpsc::{UnboundedSender, unbounded_channel},
+  sync::{
+    mpsc::{UnboundedSender, unbounded_channel},
+    watch,
+  },
   task::JoinHandle,
   time::sleep,
 };
@@ -41,14 +44,14 @@ pub struct SendManager {
   context: FederationConfig<LemmyContext>,
   stats_sender: UnboundedSender<FederationQueueStateWithDomain>,
   exit_print: JoinHandle<()>,
-  federation_worker_config: FederationWorkerConfig,
+  federation_worker_config: watch::Receiver<FederationWorkerConfig>,
 }
 
 impl SendManager {
   fn new(
     opts: Opts,
     context: FederationConfig<LemmyContext>,
-    federation_worker_config: FederationWorkerConfig,
+    federation_worker_config: watch::Receiver<FederationWorkerConfig>,
   ) -> Self {
     assert!(opts.process_count > 0);
     assert!(opts.process_index > 0);
@@ -71,7 +74,7 @@ impl SendManager {
   pub fn run(
     opts: Opts,
     context: FederationConfig<LemmyContext>,
-    config: FederationWorkerConfig,
+    config: watch::Receiver<FederationWorkerConfig>,
   ) -> CancellableTask {
     CancellableTask::spawn(WORKER_EXIT_TIMEOUT, move |cancel| {
       let opts = opts.clone();
@@ -235,9 +238,9 @@ mod test {
         .and_then(|s| s.parse().ok())
         .unwrap_or(1);
 
-      let federation_worker_config = FederationWorkerConfig {
+      let (_, federation_worker_config) = watch::channel(FederationWorkerConfig {
         concurrent_sends_per_instance,
-      };
+      });
       let pool = &mut context.pool();
       let instances = vec![
         Instance::read_or_create(pool, "alpha.com").await?,
@@ -13,6 +13,7 @@ use lemmy_utils::{
   FEDERATION_CONTEXT,
   error::{LemmyError, LemmyResult},
   federate_retry_sleep_duration,
+  metrics::federation_send,
 };
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
@@ -125,6 +126,7 @@ impl SendRetryTask<'_> {
       tracing::debug!("sending out {}", task);
       let mut fail_count = initial_fail_count;
       while let Err(e) = task.sign_and_send(&context).await {
+        federation_send(&domain, false);
         fail_count += 1;
         report.send(SendActivityResult::Failure {
           fail_count,
@@ -147,6 +149,7 @@ impl SendRetryTask<'_> {
           }
         }
       }
+      federation_send(&domain, true);
     }
     report.send(SendActivityResult::Success(SendSuccessInfo {
       activity_id: activity.id,
@@ -1,15 +1,19 @@
 use crate::util::{FederationQueueStateWithDomain, get_latest_activity_id};
-use chrono::Local;
+use chrono::{Local, Utc};
 use lemmy_db_schema::newtypes::ActivityId;
 use lemmy_db_schema_file::InstanceId;
 use lemmy_diesel_utils::connection::{ActualDbPool, DbPool};
-use lemmy_utils::{error::LemmyResult, federate_retry_sleep_duration};
+use lemmy_utils::{
+  error::LemmyResult,
+  federate_retry_sleep_duration,
+  metrics::federation_queue_state,
+};
 use std::{collections::HashMap, time::Duration};
 use tokio::{sync::mpsc::UnboundedReceiver, time::interval};
 use tracing::{debug, info, warn};
 
-/// every 60s, print the state for every instance. exits if the receiver is done (all senders
-/// dropped)
+/// every 60s, print the state for every instance and update the queue metrics. exits if the
+/// receiver is done (all senders dropped)
 pub(crate) async fn receive_print_stats(
   pool: ActualDbPool,
   mut receiver: UnboundedReceiver<FederationQueueStateWithDomain>,
@@ -63,6 +67,15 @@ async fn print_stats_with_error(
     let stat = &ele.state;
     let domain = &ele.domain;
     let behind = last_id.0 - stat.last_successful_id.map(|e| e.0).unwrap_or(0);
+    // Without pending activities the instance is up to date, no matter how old the last one is
+    let lag = if behind > 0 {
+      stat
+        .last_successful_published_time_at
+        .and_then(|published_at| (Utc::now() - published_at).to_std().ok())
+    } else {
+      Some(Duration::ZERO)
+    };
+    federation_queue_state(domain, behind, lag);
     if stat.fail_count > 0 {
       info!(
         "{domain}: Warning. {behind} behind, {} consecutive fails, current retry delay {:.2?}",
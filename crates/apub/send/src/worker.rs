@@ -27,7 +27,10 @@ use lemmy_utils::{
 };
 use std::{cmp::max, collections::BinaryHeap, ops::Add, time::Duration};
 use tokio::{
-  sync::mpsc::{self, UnboundedSender},
+  sync::{
+    mpsc::{self, UnboundedSender},
+    watch,
+  },
   time::sleep,
 };
 use tokio_util::sync::CancellationToken;
@@ -62,7 +65,8 @@ pub(crate) struct InstanceWorker {
   instance: Instance,
   stop: CancellationToken,
   federation_lib_config: FederationConfig<LemmyContext>,
-  federation_worker_config: FederationWorkerConfig,
+  /// Updated when the settings are reloaded
+  federation_worker_config: watch::Receiver<FederationWorkerConfig>,
   state: FederationQueueState,
   last_state_insert: DateTime<Utc>,
   pool: ActualDbPool,
@@ -84,7 +88,7 @@ impl InstanceWorker {
   pub(crate) async fn init_and_loop(
     instance: Instance,
     config: FederationConfig<LemmyContext>,
-    federation_worker_config: FederationWorkerConfig,
+    federation_worker_config: watch::Receiver<FederationWorkerConfig>,
     stop: CancellationToken,
     stats_sender: UnboundedSender<FederationQueueStateWithDomain>,
   ) -> LemmyResult<()> {
@@ -128,7 +132,11 @@ impl InstanceWorker {
       // too many in flight
       let need_wait_for_event = (self.in_flight != 0 && self.state.fail_count > 0)
         || self.successfuls.len() >= MAX_SUCCESSFULS
-        || self.in_flight >= self.federation_worker_config.concurrent_sends_per_instance;
+        || self.in_flight
+          >= self
+            .federation_worker_config
+            .borrow()
+            .concurrent_sends_per_instance;
       if need_wait_for_event || self.receive_send_result.len() > MIN_ACTIVITY_SEND_RESULTS_TO_HANDLE
       {
         // if len() > 0 then this does not block and allows us to write to db more often
@@ -519,9 +527,9 @@ mod test {
         .and_then(|s| s.parse().ok())
         .unwrap_or(10);
 
-      let fed_config = FederationWorkerConfig {
+      let (_, fed_config) = watch::channel(FederationWorkerConfig {
         concurrent_sends_per_instance,
-      };
+      });
       spawn(InstanceWorker::init_and_loop(
         instance.clone(),
         context.clone(),
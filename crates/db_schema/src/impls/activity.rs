@@ -3,7 +3,8 @@ use crate::{
   newtypes::ActivityId,
   source::activity::{ReceivedActivity, SentActivity, SentActivityForm},
 };
-use diesel::{ExpressionMethods, QueryDsl, dsl::insert_into};
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, QueryDsl, delete, dsl::insert_into};
 use diesel_async::RunQueryDsl;
 use lemmy_diesel_utils::{
   connection::{DbPool, get_conn},
@@ -40,6 +41,29 @@ impl SentActivity {
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
+
+  pub async fn delete_older_than(
+    pool: &mut DbPool<'_>,
+    cutoff: DateTime<Utc>,
+  ) -> LemmyResult<usize> {
+    use lemmy_db_schema_file::schema::sent_activity::dsl::{published_at, sent_activity};
+    let conn = &mut get_conn(pool).await?;
+    delete(sent_activity.filter(published_at.lt(cutoff)))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+
+  pub async fn count_older_than(pool: &mut DbPool<'_>, cutoff: DateTime<Utc>) -> LemmyResult<i64> {
+    use lemmy_db_schema_file::schema::sent_activity::dsl::{published_at, sent_activity};
+    let conn = &mut get_conn(pool).await?;
+    sent_activity
+      .filter(published_at.lt(cutoff))
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 impl ReceivedActivity {
@@ -59,6 +83,29 @@ impl ReceivedActivity {
       Err(LemmyErrorType::CouldntCreate.into())
     }
   }
+
+  pub async fn delete_older_than(
+    pool: &mut DbPool<'_>,
+    cutoff: DateTime<Utc>,
+  ) -> LemmyResult<usize> {
+    use lemmy_db_schema_file::schema::received_activity::dsl::{published_at, received_activity};
+    let conn = &mut get_conn(pool).await?;
+    delete(received_activity.filter(published_at.lt(cutoff)))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+
+  pub async fn count_older_than(pool: &mut DbPool<'_>, cutoff: DateTime<Utc>) -> LemmyResult<i64> {
+    use lemmy_db_schema_file::schema::received_activity::dsl::{published_at, received_activity};
+    let conn = &mut get_conn(pool).await?;
+    received_activity
+      .filter(published_at.lt(cutoff))
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 #[cfg(test)]
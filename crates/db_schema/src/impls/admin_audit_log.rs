@@ -0,0 +1,186 @@
+use crate::{
+  newtypes::AdminAuditLogId,
+  source::admin_audit_log::{AdminAuditLog, AdminAuditLogInsertForm, admin_audit_log_keys as key},
+  utils::limit_fetch,
+};
+use diesel::{ExpressionMethods, QueryDsl, insert_into};
+use diesel_async::RunQueryDsl;
+use i_love_jesus::SortDirection;
+use lemmy_db_schema_file::{PersonId, enums::AdminAuditKind, schema::admin_audit_log};
+use lemmy_diesel_utils::{
+  connection::{DbPool, get_conn},
+  pagination::{
+    CursorData,
+    PagedResponse,
+    PaginationCursor,
+    PaginationCursorConversion,
+    paginate_response,
+  },
+};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use serde::Serialize;
+use serde_json::{Map, Value, to_value};
+
+impl AdminAuditLog {
+  pub async fn create(pool: &mut DbPool<'_>, form: &AdminAuditLogInsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_audit_log::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, id: AdminAuditLogId) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    admin_audit_log::table
+      .find(id)
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Lists audit log entries, newest first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    kind: Option<AdminAuditKind>,
+    admin_person_id: Option<PersonId>,
+    page_cursor: Option<PaginationCursor>,
+    limit: Option<i64>,
+  ) -> LemmyResult<PagedResponse<Self>> {
+    let limit = limit_fetch(limit, None)?;
+    let mut query = admin_audit_log::table.limit(limit).into_boxed();
+    if let Some(kind) = kind {
+      query = query.filter(admin_audit_log::kind.eq(kind));
+    }
+    if let Some(admin_person_id) = admin_person_id {
+      query = query.filter(admin_audit_log::admin_person_id.eq(admin_person_id));
+    }
+    let paginated_query = Self::paginate(query, &page_cursor, SortDirection::Desc, pool, None)
+      .await?
+      .then_order_by(key::published_at)
+      .then_order_by(key::id);
+
+    let conn = &mut get_conn(pool).await?;
+    let res = paginated_query
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)?;
+    paginate_response(res, limit, page_cursor)
+  }
+}
+
+impl PaginationCursorConversion for AdminAuditLog {
+  type PaginatedType = AdminAuditLog;
+
+  fn to_cursor(&self) -> CursorData {
+    CursorData::new_id(self.id.0)
+  }
+
+  async fn from_cursor(
+    cursor: CursorData,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Self::PaginatedType> {
+    AdminAuditLog::read(pool, AdminAuditLogId(cursor.id()?)).await
+  }
+}
+
+impl AdminAuditLogInsertForm {
+  pub fn target(mut self, target: impl ToString) -> Self {
+    self.target = Some(target.to_string());
+    self
+  }
+
+  /// For objects which were deleted, or the previous state of an action without a result.
+  pub fn before(mut self, before: &impl Serialize) -> LemmyResult<Self> {
+    self.before = Some(to_value(before)?);
+    Ok(self)
+  }
+
+  /// For objects which were created, or the parameters of an action.
+  pub fn after(mut self, after: &impl Serialize) -> LemmyResult<Self> {
+    self.after = Some(to_value(after)?);
+    Ok(self)
+  }
+
+  /// Only stores the fields which differ between `before` and `after`.
+  pub fn changed<T: Serialize>(mut self, before: &T, after: &T) -> LemmyResult<Self> {
+    let (before, after) = diff(to_value(before)?, to_value(after)?);
+    self.before = Some(before);
+    self.after = Some(after);
+    Ok(self)
+  }
+}
+
+/// Removes all fields which are equal in both values. Nested objects are compared field by
+/// field, any other values are kept as a whole if they differ.
+fn diff(before: Value, after: Value) -> (Value, Value) {
+  match (before, after) {
+    (Value::Object(mut before), Value::Object(mut after)) => {
+      let mut keys: Vec<String> = before.keys().chain(after.keys()).cloned().collect();
+      keys.sort();
+      keys.dedup();
+
+      let mut before_changed = Map::new();
+      let mut after_changed = Map::new();
+      for key in keys {
+        let b = before.remove(&key).unwrap_or(Value::Null);
+        let a = after.remove(&key).unwrap_or(Value::Null);
+        if b != a {
+          let (b, a) = diff(b, a);
+          before_changed.insert(key.clone(), b);
+          after_changed.insert(key, a);
+        }
+      }
+      (Value::Object(before_changed), Value::Object(after_changed))
+    }
+    (before, after) => (before, after),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  #[test]
+  fn test_diff() {
+    let before = json!({
+      "name": "old",
+      "description": "same",
+      "rate_limit": { "post": 6, "comment": 10 },
+      "languages": [1, 2],
+    });
+    let after = json!({
+      "name": "new",
+      "description": "same",
+      "rate_limit": { "post": 6, "comment": 20 },
+      "languages": [1],
+      "icon": "https://example.com/icon.png",
+    });
+    let (before, after) = diff(before, after);
+    assert_eq!(
+      json!({
+        "name": "old",
+        "rate_limit": { "comment": 10 },
+        "languages": [1, 2],
+        "icon": null,
+      }),
+      before
+    );
+    assert_eq!(
+      json!({
+        "name": "new",
+        "rate_limit": { "comment": 20 },
+        "languages": [1],
+        "icon": "https://example.com/icon.png",
+      }),
+      after
+    );
+
+    let (before, after) = diff(json!("a"), json!("b"));
+    assert_eq!(json!("a"), before);
+    assert_eq!(json!("b"), after);
+  }
+}
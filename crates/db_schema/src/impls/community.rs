@@ -468,6 +468,26 @@ impl CommunityActions {
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
+
+  /// Communities for which the person changed the notification settings.
+  pub async fn list_notification_states(
+    person_id: PersonId,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Vec<(DbUrl, CommunityNotificationsMode)>> {
+    let conn = &mut get_conn(pool).await?;
+
+    community_actions::table
+      .inner_join(community::table)
+      .filter(community_actions::person_id.eq(person_id))
+      .filter(community_actions::notifications.is_not_null())
+      .select((
+        community::ap_id,
+        community_actions::notifications.assume_not_null(),
+      ))
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 impl Bannable for CommunityActions {
@@ -767,6 +787,7 @@ mod tests {
       unresolved_report_count: 0,
       interactions_month: 0,
       local_removed: false,
+      require_alt_text: false,
     };
 
     let community_follower_form = CommunityFollowerForm::new(
@@ -0,0 +1,89 @@
+use crate::{
+  newtypes::{DataExportId, LocalUserId},
+  source::data_export::{DataExport, DataExportInsertForm},
+};
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, delete, insert_into, update};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::data_export;
+use lemmy_diesel_utils::connection::{DbPool, get_conn};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  settings::structs::Settings,
+};
+use std::path::{Path, PathBuf};
+
+impl DataExport {
+  pub async fn create(pool: &mut DbPool<'_>, form: &DataExportInsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(data_export::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, id: DataExportId) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    data_export::table
+      .find(id)
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// The most recently requested export of the user, if any.
+  pub async fn read_latest(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> LemmyResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    data_export::table
+      .filter(data_export::local_user_id.eq(local_user_id))
+      .order_by(data_export::published_at.desc())
+      .first(conn)
+      .await
+      .optional()
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Marks the export as completed, with an error message if it failed.
+  pub async fn finish(
+    pool: &mut DbPool<'_>,
+    id: DataExportId,
+    error: Option<String>,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    update(data_export::table.find(id))
+      .set((
+        data_export::completed_at.eq(Utc::now()),
+        data_export::error.eq(error),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  /// Deletes exports which were requested before the cutoff, and returns them so that their
+  /// archives can be removed.
+  pub async fn delete_older_than(
+    pool: &mut DbPool<'_>,
+    cutoff: DateTime<Utc>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    delete(data_export::table.filter(data_export::published_at.lt(cutoff)))
+      .get_results::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+
+  /// Location of the zip archive which is written for this export.
+  pub fn archive_path(&self, settings: &Settings) -> PathBuf {
+    Path::new(&settings.data_export_path).join(format!("{}.zip", self.id.0))
+  }
+
+  /// Whether the archive was written successfully and can be downloaded.
+  pub fn is_ready(&self) -> bool {
+    self.completed_at.is_some() && self.error.is_none()
+  }
+}
@@ -0,0 +1,315 @@
+use crate::{
+  newtypes::EmailQueueId,
+  source::email_queue::{
+    EmailQueue,
+    EmailQueueInsertForm,
+    EmailQueueUpdateForm,
+    email_queue_keys as key,
+  },
+  utils::limit_fetch,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::{
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+  dsl::{IntervalDsl, count_star, now},
+  insert_into,
+};
+use diesel_async::{RunQueryDsl, scoped_futures::ScopedFutureExt};
+use i_love_jesus::SortDirection;
+use lemmy_db_schema_file::schema::email_queue;
+use lemmy_diesel_utils::{
+  connection::{DbPool, get_conn},
+  pagination::{
+    CursorData,
+    PagedResponse,
+    PaginationCursor,
+    PaginationCursorConversion,
+    paginate_response,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+/// How long a claimed email is reserved for the worker which is sending it. If the worker dies
+/// before marking it as sent or failed, it is retried after this time.
+const CLAIM_LEASE: TimeDelta = TimeDelta::minutes(10);
+
+/// Upper bound for the delay between two attempts to send the same email.
+const MAX_RETRY_DELAY: TimeDelta = TimeDelta::hours(6);
+
+impl Crud for EmailQueue {
+  type InsertForm = EmailQueueInsertForm;
+  type UpdateForm = EmailQueueUpdateForm;
+  type IdType = EmailQueueId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(email_queue::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    email_id: EmailQueueId,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(email_queue::table.find(email_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+impl PaginationCursorConversion for EmailQueue {
+  type PaginatedType = EmailQueue;
+
+  fn to_cursor(&self) -> CursorData {
+    CursorData::new_id(self.id.0)
+  }
+
+  async fn from_cursor(
+    cursor: CursorData,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Self::PaginatedType> {
+    EmailQueue::read(pool, EmailQueueId(cursor.id()?)).await
+  }
+}
+
+impl EmailQueue {
+  /// Reserves up to `limit` emails which are due for sending. Emails which are claimed by another
+  /// worker are skipped.
+  pub async fn claim_due(pool: &mut DbPool<'_>, limit: i64) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let ids = email_queue::table
+            .select(email_queue::id)
+            .filter(email_queue::sent_at.is_null())
+            .filter(email_queue::failed_at.is_null())
+            .filter(email_queue::next_attempt_at.le(now))
+            .order_by(email_queue::next_attempt_at)
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .load::<EmailQueueId>(conn)
+            .await?;
+
+          diesel::update(email_queue::table.filter(email_queue::id.eq_any(ids)))
+            .set(email_queue::next_attempt_at.eq(Utc::now() + CLAIM_LEASE))
+            .get_results::<Self>(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn mark_sent(pool: &mut DbPool<'_>, email_id: EmailQueueId) -> LemmyResult<Self> {
+    let form = EmailQueueUpdateForm {
+      sent_at: Some(Some(Utc::now())),
+      ..Default::default()
+    };
+    Self::update(pool, email_id, &form).await
+  }
+
+  /// Records a failed attempt. The next attempt is scheduled with exponential backoff, or the
+  /// email is marked as failed once `max_attempts` is reached.
+  pub async fn mark_attempt_failed(
+    &self,
+    pool: &mut DbPool<'_>,
+    error: String,
+    max_attempts: i32,
+  ) -> LemmyResult<Self> {
+    let attempts = self.attempts + 1;
+    let form = if attempts >= max_attempts {
+      EmailQueueUpdateForm {
+        attempts: Some(attempts),
+        last_error: Some(Some(error)),
+        failed_at: Some(Some(Utc::now())),
+        ..Default::default()
+      }
+    } else {
+      EmailQueueUpdateForm {
+        attempts: Some(attempts),
+        last_error: Some(Some(error)),
+        next_attempt_at: Some(Utc::now() + retry_delay(attempts)),
+        ..Default::default()
+      }
+    };
+    Self::update(pool, self.id, &form).await
+  }
+
+  /// Schedules a failed or pending email to be sent again. Emails which were already sent can't
+  /// be retried.
+  pub async fn retry(pool: &mut DbPool<'_>, email_id: EmailQueueId) -> LemmyResult<Self> {
+    let form = EmailQueueUpdateForm {
+      attempts: Some(0),
+      next_attempt_at: Some(Utc::now()),
+      failed_at: Some(None),
+      ..Default::default()
+    };
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      email_queue::table
+        .find(email_id)
+        .filter(email_queue::sent_at.is_null()),
+    )
+    .set(&form)
+    .get_result::<Self>(conn)
+    .await
+    .optional()?
+    .ok_or_else(|| LemmyErrorType::EmailAlreadySent.into())
+  }
+
+  /// The number of notification emails queued for this address since the given time.
+  pub async fn count_notifications_since(
+    pool: &mut DbPool<'_>,
+    recipient_email: &str,
+    since: DateTime<Utc>,
+  ) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    email_queue::table
+      .filter(email_queue::is_notification)
+      .filter(email_queue::recipient_email.eq(recipient_email))
+      .filter(email_queue::published_at.gt(since))
+      .select(count_star())
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Lists queued emails, newest first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    failed_only: bool,
+    page_cursor: Option<PaginationCursor>,
+    limit: Option<i64>,
+  ) -> LemmyResult<PagedResponse<Self>> {
+    let limit = limit_fetch(limit, None)?;
+    let mut query = email_queue::table.limit(limit).into_boxed();
+    if failed_only {
+      query = query.filter(email_queue::failed_at.is_not_null());
+    }
+    let paginated_query = Self::paginate(query, &page_cursor, SortDirection::Desc, pool, None)
+      .await?
+      .then_order_by(key::published_at)
+      .then_order_by(key::id);
+
+    let conn = &mut get_conn(pool).await?;
+    let res = paginated_query
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)?;
+    paginate_response(res, limit, page_cursor)
+  }
+
+  /// Deletes sent and failed emails which are older than a month.
+  pub async fn delete_old(pool: &mut DbPool<'_>) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      email_queue::table
+        .filter(
+          email_queue::sent_at
+            .is_not_null()
+            .or(email_queue::failed_at.is_not_null()),
+        )
+        .filter(email_queue::published_at.lt(now - 30.days())),
+    )
+    .execute(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+}
+
+/// Waits one minute after the first failure, doubling the delay with every further attempt.
+fn retry_delay(attempts: i32) -> TimeDelta {
+  let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or_default();
+  2i32
+    .checked_pow(exponent)
+    .map(|factor| TimeDelta::minutes(factor.into()))
+    .filter(|delay| *delay < MAX_RETRY_DELAY)
+    .unwrap_or(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_diesel_utils::connection::build_db_pool_for_tests;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[test]
+  fn test_retry_delay() {
+    assert_eq!(TimeDelta::minutes(1), retry_delay(1));
+    assert_eq!(TimeDelta::minutes(2), retry_delay(2));
+    assert_eq!(TimeDelta::minutes(16), retry_delay(5));
+    assert_eq!(MAX_RETRY_DELAY, retry_delay(20));
+    assert_eq!(MAX_RETRY_DELAY, retry_delay(100));
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_email_queue() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let form = EmailQueueInsertForm {
+      is_notification: Some(true),
+      ..EmailQueueInsertForm::new(
+        "test_email_queue@example.com".to_string().into(),
+        "test_email_queue".to_string(),
+        "subject".to_string(),
+        "<p>body</p>".to_string(),
+      )
+    };
+    let email = EmailQueue::create(pool, &form).await?;
+    let since = Utc::now() - TimeDelta::hours(1);
+    assert_eq!(
+      1,
+      EmailQueue::count_notifications_since(pool, &email.recipient_email, since).await?
+    );
+
+    // Claimed emails are not handed out again until they are due
+    let claimed = EmailQueue::claim_due(pool, 10).await?;
+    assert_eq!(
+      vec![email.id],
+      claimed.iter().map(|e| e.id).collect::<Vec<_>>()
+    );
+    assert!(EmailQueue::claim_due(pool, 10).await?.is_empty());
+
+    // Failing the last allowed attempt marks the email as failed
+    let failed = email
+      .mark_attempt_failed(pool, "connection refused".to_string(), 1)
+      .await?;
+    assert_eq!(1, failed.attempts);
+    assert!(failed.failed_at.is_some());
+    let list = EmailQueue::list(pool, true, None, None).await?;
+    assert_eq!(
+      vec![email.id],
+      list.iter().map(|e| e.id).collect::<Vec<_>>()
+    );
+
+    // After a retry by an admin it can be sent again
+    EmailQueue::retry(pool, email.id).await?;
+    assert_eq!(1, EmailQueue::claim_due(pool, 10).await?.len());
+    let sent = EmailQueue::mark_sent(pool, email.id).await?;
+    assert!(sent.sent_at.is_some());
+    assert!(EmailQueue::list(pool, true, None, None).await?.is_empty());
+
+    // Sent emails can't be retried, that would deliver them twice
+    assert!(EmailQueue::retry(pool, email.id).await.is_err());
+
+    EmailQueue::delete(pool, email.id).await?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,116 @@
+use crate::{
+  newtypes::LocalUserId,
+  source::feed_token::{FeedToken, FeedTokenForm},
+};
+use chrono::Utc;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, delete, insert_into};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::feed_token;
+use lemmy_diesel_utils::connection::{DbPool, get_conn};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl FeedToken {
+  /// Stores a new feed token for the user, replacing the previous one.
+  pub async fn regenerate(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+    token: String,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let form = FeedTokenForm {
+      local_user_id,
+      token: token.into(),
+      published_at: Utc::now(),
+    };
+    insert_into(feed_token::table)
+      .values(&form)
+      .on_conflict(feed_token::local_user_id)
+      .do_update()
+      .set(&form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> LemmyResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    feed_token::table
+      .find(local_user_id)
+      .first(conn)
+      .await
+      .optional()
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Returns the user which the token belongs to.
+  pub async fn validate(pool: &mut DbPool<'_>, token: &str) -> LemmyResult<LocalUserId> {
+    let conn = &mut get_conn(pool).await?;
+    feed_token::table
+      .filter(feed_token::token.eq(token))
+      .select(feed_token::local_user_id)
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotLoggedIn)
+  }
+
+  pub async fn revoke(pool: &mut DbPool<'_>, local_user_id: LocalUserId) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    delete(feed_token::table.find(local_user_id))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use crate::source::{
+    feed_token::FeedToken,
+    instance::Instance,
+    local_user::{LocalUser, LocalUserInsertForm},
+    person::{Person, PersonInsertForm},
+  };
+  use lemmy_diesel_utils::{connection::build_db_pool_for_tests, traits::Crud};
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_feed_token() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld").await?;
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "feed_token_user");
+    let inserted_person = Person::create(pool, &new_person).await?;
+    let new_local_user = LocalUserInsertForm::test_form(inserted_person.id);
+    let inserted_local_user = LocalUser::create(pool, &new_local_user, vec![]).await?;
+    let local_user_id = inserted_local_user.id;
+
+    assert_eq!(None, FeedToken::read(pool, local_user_id).await?);
+
+    let token = FeedToken::regenerate(pool, local_user_id, "first".to_string()).await?;
+    assert_eq!(
+      Some(&token),
+      FeedToken::read(pool, local_user_id).await?.as_ref()
+    );
+    assert_eq!(local_user_id, FeedToken::validate(pool, "first").await?);
+
+    // Regenerating invalidates the previous token
+    FeedToken::regenerate(pool, local_user_id, "second".to_string()).await?;
+    assert!(FeedToken::validate(pool, "first").await.is_err());
+    assert_eq!(local_user_id, FeedToken::validate(pool, "second").await?);
+
+    assert_eq!(1, FeedToken::revoke(pool, local_user_id).await?);
+    assert!(FeedToken::validate(pool, "second").await.is_err());
+
+    Person::delete(pool, inserted_person.id).await?;
+    Instance::delete(pool, inserted_instance.id).await?;
+    Ok(())
+  }
+}
@@ -1,6 +1,8 @@
 use crate::source::images::{
   ImageDetails,
   ImageDetailsInsertForm,
+  ImageHashBlock,
+  ImageHashBlockForm,
   LocalImage,
   LocalImageForm,
   RemoteImage,
@@ -16,7 +18,7 @@ use diesel::{
 use diesel_async::{RunQueryDsl, scoped_futures::ScopedFutureExt};
 use lemmy_db_schema_file::{
   PersonId,
-  schema::{image_details, local_image, remote_image},
+  schema::{image_details, image_hash_block, local_image, remote_image},
 };
 use lemmy_diesel_utils::{
   connection::{DbPool, get_conn},
@@ -127,4 +129,45 @@ impl ImageDetails {
       .await
       .with_lemmy_type(LemmyErrorType::CouldntCreate)
   }
+
+  /// Returns the perceptual hash of an image, if it is known.
+  pub async fn read_phash(pool: &mut DbPool<'_>, link: DbUrl) -> LemmyResult<Option<i64>> {
+    let conn = &mut get_conn(pool).await?;
+    image_details::table
+      .find(link)
+      .select(image_details::phash)
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+impl ImageHashBlock {
+  /// Adds hashes to the blocklist, ignoring those which are already blocked.
+  pub async fn create(pool: &mut DbPool<'_>, forms: &[ImageHashBlockForm]) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(image_hash_block::table)
+      .values(forms)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  pub async fn delete(pool: &mut DbPool<'_>, hash: i64) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(image_hash_block::table.filter(image_hash_block::hash.eq(hash)))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+
+  pub async fn list(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    image_hash_block::table
+      .order_by(image_hash_block::id)
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
@@ -3,9 +3,16 @@ use crate::{
   newtypes::LocalUserId,
   source::login_token::{LoginToken, LoginTokenCreateForm},
 };
-use diesel::{delete, dsl::exists, insert_into, select};
+use chrono::{DateTime, Utc};
+use diesel::{BoolExpressionMethods, delete, dsl::exists, insert_into, select, update};
 use diesel_async::RunQueryDsl;
-use lemmy_db_schema_file::schema::login_token::{dsl::login_token, user_id};
+use lemmy_db_schema_file::schema::login_token::{
+  dsl::login_token,
+  ip,
+  published_at,
+  user_agent,
+  user_id,
+};
 use lemmy_diesel_utils::connection::{DbPool, get_conn};
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 
@@ -54,6 +61,31 @@ impl LoginToken {
       .with_lemmy_type(LemmyErrorType::Deleted)
   }
 
+  /// Removes ip and user agent of logins which are older than the cutoff. The logins stay valid.
+  pub async fn clear_old_ips(pool: &mut DbPool<'_>, cutoff: DateTime<Utc>) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    update(
+      login_token
+        .filter(published_at.lt(cutoff))
+        .filter(ip.is_not_null().or(user_agent.is_not_null())),
+    )
+    .set((ip.eq(None::<String>), user_agent.eq(None::<String>)))
+    .execute(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  pub async fn count_old_ips(pool: &mut DbPool<'_>, cutoff: DateTime<Utc>) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    login_token
+      .filter(published_at.lt(cutoff))
+      .filter(ip.is_not_null().or(user_agent.is_not_null()))
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
   /// Invalidate all logins of given user on password reset/change, or account deletion.
   pub async fn invalidate_all(pool: &mut DbPool<'_>, user_id_: LocalUserId) -> LemmyResult<usize> {
     let conn = &mut get_conn(pool).await?;
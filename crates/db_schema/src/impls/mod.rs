@@ -1,5 +1,6 @@
 pub mod activity;
 pub mod actor_language;
+pub mod admin_audit_log;
 pub mod comment;
 pub mod comment_report;
 pub mod community;
@@ -7,10 +8,13 @@ pub mod community_community_follow;
 pub mod community_report;
 pub mod community_tag;
 pub mod custom_emoji;
+pub mod data_export;
+pub mod email_queue;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
 pub mod federation_queue_state;
+pub mod feed_token;
 pub mod images;
 pub mod instance;
 pub mod keyword_block;
@@ -28,10 +32,13 @@ pub mod oauth_provider;
 pub mod password_reset_request;
 pub mod person;
 pub mod post;
+pub mod post_gallery_image;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
 pub mod registration_application;
+pub mod rule;
+pub mod scheduled_task_status;
 pub mod secret;
 pub mod site;
 pub mod tagline;
@@ -1,5 +1,5 @@
 use crate::{
-  newtypes::{CommunityId, ModlogId},
+  newtypes::{CommunityId, ModlogId, RuleId},
   source::{
     comment::Comment,
     modlog::{Modlog, ModlogInsertForm},
@@ -8,12 +8,23 @@ use crate::{
   },
 };
 use chrono::{DateTime, Utc};
-use diesel::dsl::insert_into;
+use diesel::{
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+  dsl::{exists, insert_into, not},
+};
 use diesel_async::RunQueryDsl;
 #[cfg(feature = "full")]
-use lemmy_db_schema_file::schema::modlog;
+use lemmy_db_schema_file::schema::{comment, modlog, post};
 use lemmy_db_schema_file::{InstanceId, PersonId, enums::ModlogKind};
-use lemmy_diesel_utils::connection::{DbPool, get_conn};
+use lemmy_diesel_utils::{
+  connection::{DbPool, get_conn},
+  utils::now,
+};
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 
 impl Modlog {
@@ -28,6 +39,75 @@ impl Modlog {
       .await
       .with_lemmy_type(LemmyErrorType::CouldntCreate)
   }
+
+  /// Lists post locks and removals which have expired, but are still in effect. That means the
+  /// post is still locked or removed, and no later action of the same kind was taken on it.
+  pub async fn list_expired_post_actions(pool: &mut DbPool<'_>) -> LemmyResult<Vec<(Self, Post)>> {
+    let conn = &mut get_conn(pool).await?;
+    let newer = diesel::alias!(modlog as newer);
+    let superseded = newer
+      .filter(newer.field(modlog::kind).eq(modlog::kind))
+      .filter(
+        newer
+          .field(modlog::target_post_id)
+          .eq(modlog::target_post_id),
+      )
+      .filter(newer.field(modlog::id).gt(modlog::id));
+
+    modlog::table
+      .inner_join(post::table.on(modlog::target_post_id.eq(post::id.nullable())))
+      .filter(not(modlog::is_revert))
+      .filter(modlog::expires_at.lt(now().nullable()))
+      .filter(
+        (modlog::kind.eq(ModlogKind::ModLockPost).and(post::locked)).or(
+          modlog::kind
+            .eq(ModlogKind::ModRemovePost)
+            .and(post::removed),
+        ),
+      )
+      .filter(not(exists(superseded)))
+      .select((Self::as_select(), Post::as_select()))
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Lists comment locks and removals which have expired, but are still in effect. That means the
+  /// comment is still locked or removed, and no later action of the same kind was taken on it.
+  pub async fn list_expired_comment_actions(
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Vec<(Self, Comment)>> {
+    let conn = &mut get_conn(pool).await?;
+    let newer = diesel::alias!(modlog as newer);
+    let superseded = newer
+      .filter(newer.field(modlog::kind).eq(modlog::kind))
+      .filter(
+        newer
+          .field(modlog::target_comment_id)
+          .eq(modlog::target_comment_id),
+      )
+      .filter(newer.field(modlog::id).gt(modlog::id));
+
+    modlog::table
+      .inner_join(comment::table.on(modlog::target_comment_id.eq(comment::id.nullable())))
+      .filter(not(modlog::is_revert))
+      .filter(modlog::expires_at.lt(now().nullable()))
+      .filter(
+        (modlog::kind
+          .eq(ModlogKind::ModLockComment)
+          .and(comment::locked))
+        .or(
+          modlog::kind
+            .eq(ModlogKind::ModRemoveComment)
+            .and(comment::removed),
+        ),
+      )
+      .filter(not(exists(superseded)))
+      .select((Self::as_select(), Comment::as_select()))
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 impl<'a> ModlogInsertForm<'a> {
@@ -57,11 +137,15 @@ impl<'a> ModlogInsertForm<'a> {
     mod_person_id: PersonId,
     post: &Post,
     removed: bool,
+    expires_at: Option<DateTime<Utc>>,
     reason: &'a str,
+    rule_id: Option<RuleId>,
     bulk_action_parent_id: Option<ModlogId>,
   ) -> Self {
     Self {
       reason: Some(reason),
+      expires_at,
+      rule_id,
       target_post_id: Some(post.id),
       target_community_id: Some(post.community_id),
       target_person_id: Some(post.creator_id),
@@ -74,11 +158,15 @@ impl<'a> ModlogInsertForm<'a> {
     comment: &Comment,
     community_id: CommunityId,
     removed: bool,
+    expires_at: Option<DateTime<Utc>>,
     reason: &'a str,
+    rule_id: Option<RuleId>,
     bulk_action_parent_id: Option<ModlogId>,
   ) -> Self {
     Self {
       reason: Some(reason),
+      expires_at,
+      rule_id,
       target_comment_id: Some(comment.id),
       target_post_id: Some(comment.post_id),
       target_community_id: Some(community_id),
@@ -92,10 +180,12 @@ impl<'a> ModlogInsertForm<'a> {
     comment: &Comment,
     community_id: CommunityId,
     removed: bool,
+    expires_at: Option<DateTime<Utc>>,
     reason: &'a str,
   ) -> Self {
     Self {
       reason: Some(reason),
+      expires_at,
       target_comment_id: Some(comment.id),
       target_post_id: Some(comment.post_id),
       target_community_id: Some(community_id),
@@ -107,10 +197,12 @@ impl<'a> ModlogInsertForm<'a> {
     mod_person_id: PersonId,
     post: &Post,
     locked: bool,
+    expires_at: Option<DateTime<Utc>>,
     reason: &'a str,
   ) -> Self {
     Self {
       reason: Some(reason),
+      expires_at,
       target_post_id: Some(post.id),
       target_community_id: Some(post.community_id),
       target_person_id: Some(post.creator_id),
@@ -2,6 +2,7 @@ use crate::{
   newtypes::{CommentId, NotificationId, PostId},
   source::notification::{Notification, NotificationInsertForm},
 };
+use chrono::{DateTime, Utc};
 use diesel::{
   ExpressionMethods,
   QueryDsl,
@@ -105,4 +106,25 @@ impl Notification {
       .await?;
     Ok(())
   }
+
+  pub async fn delete_older_than(
+    pool: &mut DbPool<'_>,
+    cutoff: DateTime<Utc>,
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    delete(notification::table.filter(notification::published_at.lt(cutoff)))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+
+  pub async fn count_older_than(pool: &mut DbPool<'_>, cutoff: DateTime<Utc>) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    notification::table
+      .filter(notification::published_at.lt(cutoff))
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
@@ -23,8 +23,10 @@ use diesel::{
   NullableExpressionMethods,
   OptionalExtension,
   QueryDsl,
-  dsl::{count, insert_into, not, update},
+  dsl::{count, exists, insert_into, not, update},
   expression::SelectableHelper,
+  pg::Pg,
+  sql_types::Integer,
 };
 use diesel_async::RunQueryDsl;
 use diesel_uplete::{UpleteCount, uplete};
@@ -32,7 +34,7 @@ use lemmy_db_schema_file::{
   InstanceId,
   PersonId,
   enums::PostNotificationsMode,
-  schema::{community, local_user, person, post, post_actions},
+  schema::{comment, comment_actions, community, local_user, person, post, post_actions},
 };
 use lemmy_diesel_utils::{
   connection::{DbPool, get_conn},
@@ -44,6 +46,7 @@ use lemmy_diesel_utils::{
   },
 };
 use lemmy_utils::{
+  DB_BATCH_SIZE,
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
   settings::structs::Settings,
 };
@@ -343,6 +346,68 @@ impl Post {
     }
     Ok(())
   }
+
+  /// Deletes remote posts in remote communities which were published before the cutoff, and which
+  /// no local user has voted on, saved, read, hidden or commented on. Posts are deleted in
+  /// batches, so that the table isn't locked for a long time.
+  pub async fn delete_old_remote(
+    pool: &mut DbPool<'_>,
+    cutoff: DateTime<Utc>,
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    let batch_size = usize::try_from(DB_BATCH_SIZE)?;
+    let mut deleted = 0;
+    loop {
+      let batch_ids = Self::old_remote_ids(cutoff).limit(DB_BATCH_SIZE);
+      let count = diesel::delete(post::table.filter(post::id.eq_any(batch_ids)))
+        .execute(conn)
+        .await
+        .with_lemmy_type(LemmyErrorType::Deleted)?;
+      deleted += count;
+      if count < batch_size {
+        return Ok(deleted);
+      }
+    }
+  }
+
+  pub async fn count_old_remote(pool: &mut DbPool<'_>, cutoff: DateTime<Utc>) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    post::table
+      .filter(post::id.eq_any(Self::old_remote_ids(cutoff)))
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  fn old_remote_ids(cutoff: DateTime<Utc>) -> post::BoxedQuery<'static, Pg, Integer> {
+    let local_person_ids = || person::table.filter(person::local).select(person::id);
+    let remote_community_ids = community::table
+      .filter(community::local.eq(false))
+      .select(community::id);
+    let local_post_actions = post_actions::table
+      .filter(post_actions::post_id.eq(post::id))
+      .filter(post_actions::person_id.eq_any(local_person_ids()));
+    let local_comments = comment::table
+      .filter(comment::post_id.eq(post::id))
+      .filter(comment::creator_id.eq_any(local_person_ids()));
+    let local_comment_actions = comment_actions::table
+      .inner_join(comment::table)
+      .filter(comment::post_id.eq(post::id))
+      .filter(comment_actions::person_id.eq_any(local_person_ids()));
+
+    post::table
+      .select(post::id)
+      .filter(post::local.eq(false))
+      .filter(post::published_at.lt(cutoff))
+      .filter(post::featured_community.eq(false))
+      .filter(post::featured_local.eq(false))
+      .filter(post::community_id.eq_any(remote_community_ids))
+      .filter(not(exists(local_post_actions)))
+      .filter(not(exists(local_comments)))
+      .filter(not(exists(local_comment_actions)))
+      .into_boxed()
+  }
 }
 
 impl Likeable for PostActions {
@@ -420,6 +485,46 @@ impl Saveable for PostActions {
 }
 
 impl PostActions {
+  /// Marks posts as unread, and resets the number of read comments, if they were read before the
+  /// cutoff.
+  pub async fn clear_old_read_state(
+    pool: &mut DbPool<'_>,
+    cutoff: DateTime<Utc>,
+  ) -> LemmyResult<()> {
+    let conn = &mut get_conn(pool).await?;
+
+    uplete(post_actions::table.filter(post_actions::read_at.lt(cutoff)))
+      .set_null(post_actions::read_at)
+      .get_result::<UpleteCount>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+
+    uplete(post_actions::table.filter(post_actions::read_comments_at.lt(cutoff)))
+      .set_null(post_actions::read_comments_at)
+      .set_null(post_actions::read_comments_amount)
+      .get_result::<UpleteCount>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+    Ok(())
+  }
+
+  pub async fn count_old_read_state(
+    pool: &mut DbPool<'_>,
+    cutoff: DateTime<Utc>,
+  ) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    post_actions::table
+      .filter(
+        post_actions::read_at
+          .lt(cutoff)
+          .or(post_actions::read_comments_at.lt(cutoff)),
+      )
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
   pub async fn mark_as_unread(
     pool: &mut DbPool<'_>,
     person_id: PersonId,
@@ -562,22 +667,39 @@ impl PostActions {
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
+
+  /// Posts for which the person changed the notification settings.
+  pub async fn list_notification_states(
+    person_id: PersonId,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Vec<(DbUrl, PostNotificationsMode)>> {
+    let conn = &mut get_conn(pool).await?;
+
+    post_actions::table
+      .inner_join(post::table)
+      .filter(post_actions::person_id.eq(person_id))
+      .filter(post_actions::notifications.is_not_null())
+      .select((post::ap_id, post_actions::notifications.assume_not_null()))
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use crate::{
     source::{
-      comment::{Comment, CommentInsertForm, CommentUpdateForm},
+      comment::{Comment, CommentActions, CommentInsertForm, CommentLikeForm, CommentUpdateForm},
       community::{Community, CommunityInsertForm},
       instance::Instance,
       person::{Person, PersonInsertForm},
       post::{Post, PostActions, PostInsertForm, PostLikeForm, PostSavedForm, PostUpdateForm},
     },
     traits::{Likeable, Saveable},
-    utils::RANK_DEFAULT,
+    utils::{RANK_DEFAULT, retention_cutoff},
   };
-  use chrono::DateTime;
+  use chrono::{DateTime, Days, Utc};
   use diesel_uplete::UpleteCount;
   use lemmy_diesel_utils::{connection::build_db_pool_for_tests, traits::Crud};
   use lemmy_utils::error::LemmyResult;
@@ -925,4 +1047,100 @@ mod tests {
 
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_delete_old_remote() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let local_instance = Instance::read_or_create(pool, "my_domain.tld").await?;
+    let remote_instance = Instance::read_or_create(pool, "remote_retention.tld").await?;
+
+    let local_person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(local_instance.id, "retention_local"),
+    )
+    .await?;
+    let remote_person_form = PersonInsertForm {
+      local: Some(false),
+      ..PersonInsertForm::test_form(remote_instance.id, "retention_remote")
+    };
+    let remote_person = Person::create(pool, &remote_person_form).await?;
+
+    let community_form = CommunityInsertForm {
+      local: Some(false),
+      ..CommunityInsertForm::new(
+        remote_instance.id,
+        "retention_community".into(),
+        "nada".to_owned(),
+        "pubkey".to_string(),
+      )
+    };
+    let community = Community::create(pool, &community_form).await?;
+
+    let old = Utc::now() - Days::new(30);
+    let old_post_form = |name: &str| PostInsertForm {
+      local: Some(false),
+      published_at: Some(old),
+      ..PostInsertForm::new(name.into(), remote_person.id, community.id)
+    };
+
+    let untouched = Post::create(pool, &old_post_form("untouched")).await?;
+    let recent = Post::create(
+      pool,
+      &PostInsertForm {
+        local: Some(false),
+        ..PostInsertForm::new("recent".into(), remote_person.id, community.id)
+      },
+    )
+    .await?;
+
+    let voted = Post::create(pool, &old_post_form("voted")).await?;
+    PostActions::like(
+      pool,
+      &PostLikeForm::new(voted.id, local_person.id, Some(true)),
+    )
+    .await?;
+
+    let saved = Post::create(pool, &old_post_form("saved")).await?;
+    PostActions::save(pool, &PostSavedForm::new(saved.id, local_person.id)).await?;
+
+    let commented = Post::create(pool, &old_post_form("commented")).await?;
+    let comment_form = CommentInsertForm::new(local_person.id, commented.id, "local".into());
+    Comment::create(pool, &comment_form, None).await?;
+
+    let comment_voted = Post::create(pool, &old_post_form("comment voted")).await?;
+    let comment_form = CommentInsertForm::new(remote_person.id, comment_voted.id, "remote".into());
+    let remote_comment = Comment::create(pool, &comment_form, None).await?;
+    CommentActions::like(
+      pool,
+      &CommentLikeForm::new(remote_comment.id, local_person.id, Some(true)),
+    )
+    .await?;
+
+    let featured = Post::create(
+      pool,
+      &PostInsertForm {
+        featured_community: Some(true),
+        ..old_post_form("featured")
+      },
+    )
+    .await?;
+
+    let cutoff = retention_cutoff(7);
+    assert_eq!(1, Post::count_old_remote(pool, cutoff).await?);
+    assert_eq!(1, Post::delete_old_remote(pool, cutoff).await?);
+    assert_eq!(0, Post::count_old_remote(pool, cutoff).await?);
+
+    assert!(Post::read(pool, untouched.id).await.is_err());
+    for kept in [recent, voted, saved, commented, comment_voted, featured] {
+      assert!(Post::read(pool, kept.id).await.is_ok());
+    }
+
+    Instance::delete(pool, remote_instance.id).await?;
+    Instance::delete(pool, local_instance.id).await?;
+
+    Ok(())
+  }
 }
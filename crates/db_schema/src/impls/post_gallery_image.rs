@@ -0,0 +1,97 @@
+use crate::{
+  diesel::SelectableHelper,
+  newtypes::PostId,
+  source::post_gallery_image::{PostGalleryImage, PostGalleryImageForm, PostGalleryView},
+};
+use diesel::{
+  ExpressionMethods,
+  QueryDsl,
+  delete,
+  deserialize::FromSql,
+  insert_into,
+  pg::{Pg, PgValue},
+  serialize::ToSql,
+  sql_types::{Json, Nullable},
+};
+use diesel_async::{RunQueryDsl, scoped_futures::ScopedFutureExt};
+use lemmy_db_schema_file::schema::post_gallery_image;
+use lemmy_diesel_utils::connection::{DbPool, get_conn};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl PostGalleryImage {
+  /// Replaces all gallery images of the post.
+  pub async fn replace(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    forms: &[PostGalleryImageForm],
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          delete(post_gallery_image::table.filter(post_gallery_image::post_id.eq(post_id)))
+            .execute(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::Deleted)?;
+
+          insert_into(post_gallery_image::table)
+            .values(forms)
+            .returning(Self::as_select())
+            .get_results(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::CouldntCreate)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn read_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    post_gallery_image::table
+      .filter(post_gallery_image::post_id.eq(post_id))
+      .order_by(post_gallery_image::position)
+      .select(Self::as_select())
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Reads the gallery images of multiple posts, ordered by post and position.
+  pub async fn read_for_posts(
+    pool: &mut DbPool<'_>,
+    post_ids: &[PostId],
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    post_gallery_image::table
+      .filter(post_gallery_image::post_id.eq_any(post_ids))
+      .order_by((post_gallery_image::post_id, post_gallery_image::position))
+      .select(Self::as_select())
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+impl FromSql<Nullable<Json>, Pg> for PostGalleryView {
+  fn from_sql(bytes: PgValue) -> diesel::deserialize::Result<Self> {
+    let value = <serde_json::Value as FromSql<Json, Pg>>::from_sql(bytes)?;
+    Ok(serde_json::from_value::<PostGalleryView>(value)?)
+  }
+  fn from_nullable_sql(
+    bytes: Option<<Pg as diesel::backend::Backend>::RawValue<'_>>,
+  ) -> diesel::deserialize::Result<Self> {
+    match bytes {
+      Some(bytes) => Self::from_sql(bytes),
+      None => Ok(Self(vec![])),
+    }
+  }
+}
+
+impl ToSql<Nullable<Json>, Pg> for PostGalleryView {
+  fn to_sql(&self, out: &mut diesel::serialize::Output<Pg>) -> diesel::serialize::Result {
+    let value = serde_json::to_value(self)?;
+    <serde_json::Value as ToSql<Json, Pg>>::to_sql(&value, &mut out.reborrow())
+  }
+}
@@ -4,7 +4,7 @@ use crate::{
   source::private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
 };
 use chrono::{DateTime, Utc};
-use diesel::{ExpressionMethods, QueryDsl, dsl::insert_into};
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, dsl::insert_into};
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema_file::{PersonId, schema::private_message};
 use lemmy_diesel_utils::{
@@ -99,6 +99,24 @@ impl PrivateMessage {
       .await
       .with_lemmy_type(LemmyErrorType::CouldntUpdate)
   }
+
+  /// All messages which the person sent or received, oldest first. Used for data exports.
+  pub async fn list_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    private_message::table
+      .filter(
+        private_message::creator_id
+          .eq(for_person_id)
+          .or(private_message::recipient_id.eq(for_person_id)),
+      )
+      .order_by(private_message::published_at.asc())
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 #[cfg(test)]
@@ -0,0 +1,212 @@
+use crate::{
+  newtypes::{CommunityId, RuleId},
+  source::rule::{Rule, RuleInsertForm, RuleUpdateForm},
+};
+use diesel::{ExpressionMethods, QueryDsl, insert_into, upsert::excluded};
+use diesel_async::{RunQueryDsl, scoped_futures::ScopedFutureExt};
+use lemmy_db_schema_file::schema::rule;
+use lemmy_diesel_utils::{
+  connection::{DbPool, get_conn},
+  dburl::DbUrl,
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use std::collections::HashSet;
+
+impl Crud for Rule {
+  type InsertForm = RuleInsertForm;
+  type UpdateForm = RuleUpdateForm;
+  type IdType = RuleId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(rule::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    rule_id: RuleId,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(rule::table.find(rule_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+impl Rule {
+  /// Returns the rules of the given community, or of the local instance if `community_id` is
+  /// empty.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    community_id: Option<CommunityId>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let query = rule::table.filter(rule::deleted.eq(false)).into_boxed();
+    let query = match community_id {
+      Some(community_id) => query.filter(rule::community_id.eq(community_id)),
+      None => query.filter(rule::community_id.is_null()),
+    };
+    query
+      .order_by(rule::position.asc())
+      .then_order_by(rule::id.asc())
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn read_apub(pool: &mut DbPool<'_>, ap_id: &DbUrl) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    rule::table
+      .filter(rule::ap_id.eq(ap_id))
+      .filter(rule::deleted.eq(false))
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Replaces the federated rules of a community. Rules which are missing from `forms` are
+  /// marked as deleted. Existing rules of other communities are never overwritten.
+  pub async fn update_many(
+    pool: &mut DbPool<'_>,
+    mut forms: Vec<RuleInsertForm>,
+    existing_rules: Vec<Rule>,
+  ) -> LemmyResult<()> {
+    let conn = &mut get_conn(pool).await?;
+    let new_rule_ids = forms
+      .iter()
+      .map(|rule| rule.ap_id.clone())
+      .collect::<HashSet<_>>();
+    let delete_forms = existing_rules
+      .into_iter()
+      .filter(|rule| !new_rule_ids.contains(&rule.ap_id))
+      .map(|r| RuleInsertForm {
+        ap_id: r.ap_id,
+        community_id: r.community_id,
+        title: r.title,
+        description: None,
+        position: Some(r.position),
+        deleted: Some(true),
+      });
+    forms.extend(delete_forms);
+
+    if forms.is_empty() {
+      return Ok(());
+    }
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          // Needed for the conflict condition, which conflicts with `QueryDsl::filter` elsewhere
+          use diesel::query_dsl::methods::FilterDsl;
+
+          insert_into(rule::table)
+            .values(&forms)
+            .on_conflict(rule::ap_id)
+            .do_update()
+            .set((
+              rule::title.eq(excluded(rule::title)),
+              rule::description.eq(excluded(rule::description)),
+              rule::position.eq(excluded(rule::position)),
+              rule::deleted.eq(excluded(rule::deleted)),
+            ))
+            .filter(rule::community_id.eq(excluded(rule::community_id)))
+            .execute(conn)
+            .await?;
+
+          Ok(())
+        }
+        .scope_boxed()
+      })
+      .await?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::source::{
+    community::{Community, CommunityInsertForm},
+    instance::Instance,
+  };
+  use lemmy_diesel_utils::connection::build_db_pool_for_tests;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_community_rules() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld").await?;
+    let community_form = CommunityInsertForm::new(
+      instance.id,
+      "test_community_rules".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let community = Community::create(pool, &community_form).await?;
+
+    let rule_form = |name: &str, position: i32| -> LemmyResult<RuleInsertForm> {
+      let ap_id = Url::parse(&format!("{}/rule/{name}", community.ap_id))?;
+      Ok(RuleInsertForm {
+        position: Some(position),
+        ..RuleInsertForm::new(ap_id.into(), Some(community.id), name.to_string())
+      })
+    };
+    let second = Rule::create(pool, &rule_form("second", 2)?).await?;
+    let first = Rule::create(pool, &rule_form("first", 1)?).await?;
+
+    // Rules are returned in order of position, and instance rules are listed separately
+    let rules = Rule::list(pool, Some(community.id)).await?;
+    assert_eq!(
+      vec![first.id, second.id],
+      rules.iter().map(|r| r.id).collect::<Vec<_>>()
+    );
+    assert!(Rule::list(pool, None).await?.is_empty());
+
+    // Federated update which drops the first rule and renames the second one
+    let update_form = RuleInsertForm {
+      title: "renamed".to_string(),
+      ..rule_form("second", 0)?
+    };
+    Rule::update_many(pool, vec![update_form], rules).await?;
+    let rules = Rule::list(pool, Some(community.id)).await?;
+    assert_eq!(1, rules.len());
+    assert_eq!(second.id, rules[0].id);
+    assert_eq!("renamed", rules[0].title);
+    assert!(Rule::read(pool, first.id).await?.deleted);
+
+    // Another community can't take over the rule by sending its id
+    let other_form = CommunityInsertForm::new(
+      instance.id,
+      "test_community_rules_other".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let other = Community::create(pool, &other_form).await?;
+    let hijack_form = RuleInsertForm {
+      title: "hijacked".to_string(),
+      ..RuleInsertForm::new(second.ap_id.clone(), Some(other.id), "hijacked".to_string())
+    };
+    Rule::update_many(pool, vec![hijack_form], vec![]).await?;
+    let rule = Rule::read(pool, second.id).await?;
+    assert_eq!(Some(community.id), rule.community_id);
+    assert_eq!("renamed", rule.title);
+
+    Instance::delete(pool, instance.id).await?;
+
+    Ok(())
+  }
+}
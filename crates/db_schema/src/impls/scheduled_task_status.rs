@@ -0,0 +1,207 @@
+use crate::source::scheduled_task_status::{
+  ScheduledTaskStatus,
+  ScheduledTaskStatusInsertForm,
+  ScheduledTaskStatusUpdateForm,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::{
+  ExpressionMethods,
+  QueryDsl,
+  define_sql_function,
+  insert_into,
+  select,
+  sql_types::{Bool, Int4, Text},
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use lemmy_db_schema_file::schema::scheduled_task_status;
+use lemmy_diesel_utils::connection::{DbPool, get_conn};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+define_sql_function!(fn pg_try_advisory_lock(key1: Int4, key2: Int4) -> Bool);
+define_sql_function!(fn pg_advisory_unlock(key1: Int4, key2: Int4) -> Bool);
+define_sql_function!(fn hashtext(x: Text) -> Int4);
+
+/// First key of the advisory locks for scheduled tasks, the second one is the hashed task name.
+const ADVISORY_LOCK_NAMESPACE: i32 = 0x4c45_4d59;
+
+impl ScheduledTaskStatus {
+  /// Adds the task if it doesn't exist yet. Existing tasks keep their interval and status.
+  pub async fn init(pool: &mut DbPool<'_>, name: &str, interval: TimeDelta) -> LemmyResult<()> {
+    let conn = &mut get_conn(pool).await?;
+    let interval_seconds =
+      i32::try_from(interval.num_seconds()).with_lemmy_type(LemmyErrorType::CouldntCreate)?;
+    let form = ScheduledTaskStatusInsertForm {
+      name: name.to_string(),
+      interval_seconds,
+      next_run_at: Utc::now() + interval,
+    };
+    insert_into(scheduled_task_status::table)
+      .values(&form)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)?;
+    Ok(())
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, name: &str) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    scheduled_task_status::table
+      .find(name)
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn list(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    scheduled_task_status::table
+      .order_by(scheduled_task_status::name)
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn list_due(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    scheduled_task_status::table
+      .filter(scheduled_task_status::next_run_at.le(Utc::now()))
+      .order_by(scheduled_task_status::next_run_at)
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Changes the interval, the next run is rescheduled relative to the last one.
+  pub async fn update_interval(
+    pool: &mut DbPool<'_>,
+    name: &str,
+    interval_seconds: i32,
+  ) -> LemmyResult<Self> {
+    let status = Self::read(pool, name).await?;
+    let last_started_at = status.last_started_at.unwrap_or_else(Utc::now);
+    let form = ScheduledTaskStatusUpdateForm {
+      interval_seconds: Some(interval_seconds),
+      next_run_at: Some(last_started_at + TimeDelta::seconds(interval_seconds.into())),
+      ..Default::default()
+    };
+    Self::update(pool, name, &form).await
+  }
+
+  /// Records the result of a run and schedules the next one.
+  pub async fn finish(
+    &self,
+    pool: &mut DbPool<'_>,
+    started_at: DateTime<Utc>,
+    error: Option<String>,
+  ) -> LemmyResult<Self> {
+    let duration_ms = i32::try_from((Utc::now() - started_at).num_milliseconds()).ok();
+    let form = ScheduledTaskStatusUpdateForm {
+      last_started_at: Some(Some(started_at)),
+      last_duration_ms: Some(duration_ms),
+      last_error: Some(error),
+      next_run_at: Some(started_at + TimeDelta::seconds(self.interval_seconds.into())),
+      ..Default::default()
+    };
+    Self::update(pool, &self.name, &form).await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    name: &str,
+    form: &ScheduledTaskStatusUpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(scheduled_task_status::table.find(name))
+      .set(form)
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  /// Takes a session level advisory lock for the task, returns false if another connection holds
+  /// it already. The lock is held until [`Self::unlock`] is called or the connection is closed, so
+  /// `conn` shouldn't be returned to a pool while it is locked.
+  pub async fn try_lock(conn: &mut AsyncPgConnection, name: &str) -> LemmyResult<bool> {
+    select(pg_try_advisory_lock(
+      ADVISORY_LOCK_NAMESPACE,
+      hashtext(name),
+    ))
+    .get_result(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  /// Releases a lock which was taken with [`Self::try_lock`] on the same connection.
+  pub async fn unlock(conn: &mut AsyncPgConnection, name: &str) -> LemmyResult<()> {
+    select(pg_advisory_unlock(ADVISORY_LOCK_NAMESPACE, hashtext(name)))
+      .get_result::<bool>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::source::scheduled_task_status::ScheduledTaskStatus;
+  use chrono::{TimeDelta, Utc};
+  use diesel::{QueryDsl, delete};
+  use diesel_async::RunQueryDsl;
+  use lemmy_db_schema_file::schema::scheduled_task_status;
+  use lemmy_diesel_utils::connection::{
+    build_db_pool_for_tests,
+    build_dedicated_connection,
+    get_conn,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_scheduled_task_status() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let name = "test_scheduled_task_status";
+
+    ScheduledTaskStatus::init(pool, name, TimeDelta::hours(1)).await?;
+    let status = ScheduledTaskStatus::read(pool, name).await?;
+    assert_eq!(3600, status.interval_seconds);
+    assert!(status.next_run_at > Utc::now());
+
+    // Initializing again keeps the configured interval
+    ScheduledTaskStatus::update_interval(pool, name, 60).await?;
+    ScheduledTaskStatus::init(pool, name, TimeDelta::hours(1)).await?;
+    let status = ScheduledTaskStatus::read(pool, name).await?;
+    assert_eq!(60, status.interval_seconds);
+
+    let started_at = Utc::now() - TimeDelta::minutes(5);
+    let status = status
+      .finish(pool, started_at, Some("failed".to_string()))
+      .await?;
+    assert_eq!(Some("failed".to_string()), status.last_error);
+    assert!(status.last_duration_ms.is_some());
+    assert!(
+      ScheduledTaskStatus::list_due(pool)
+        .await?
+        .iter()
+        .any(|s| s.name == name)
+    );
+
+    // The lock can only be held by one connection at a time, until it is unlocked
+    let conn_1 = &mut build_dedicated_connection().await?;
+    let conn_2 = &mut build_dedicated_connection().await?;
+    assert!(ScheduledTaskStatus::try_lock(conn_1, name).await?);
+    assert!(!ScheduledTaskStatus::try_lock(conn_2, name).await?);
+    ScheduledTaskStatus::unlock(conn_1, name).await?;
+    assert!(ScheduledTaskStatus::try_lock(conn_2, name).await?);
+    ScheduledTaskStatus::unlock(conn_2, name).await?;
+
+    let conn = &mut get_conn(pool).await?;
+    delete(scheduled_task_status::table.find(name))
+      .execute(conn)
+      .await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,50 @@
+use crate::newtypes::AdminAuditLogId;
+use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::{PersonId, enums::AdminAuditKind};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use {i_love_jesus::CursorKeysModule, lemmy_db_schema_file::schema::admin_audit_log};
+
+#[skip_serializing_none]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, CursorKeysModule)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = admin_audit_log))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", cursor_keys_module(name = admin_audit_log_keys))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A change to site settings or another security-sensitive action by an admin.
+pub struct AdminAuditLog {
+  pub id: AdminAuditLogId,
+  pub kind: AdminAuditKind,
+  /// Empty if the admin account was deleted.
+  pub admin_person_id: Option<PersonId>,
+  /// Identifies the changed object, for example the id of a tagline or the domain of an instance.
+  pub target: Option<String>,
+  /// Previous values of the changed fields, empty for created objects.
+  #[cfg_attr(feature = "ts-rs", ts(type = "unknown"))]
+  pub before: Option<Value>,
+  /// New values of the changed fields, empty for deleted objects.
+  #[cfg_attr(feature = "ts-rs", ts(type = "unknown"))]
+  pub after: Option<Value>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_audit_log))]
+pub struct AdminAuditLogInsertForm {
+  pub kind: AdminAuditKind,
+  pub admin_person_id: PersonId,
+  #[new(default)]
+  pub target: Option<String>,
+  #[new(default)]
+  pub before: Option<Value>,
+  #[new(default)]
+  pub after: Option<Value>,
+}
@@ -94,6 +94,8 @@ pub struct Community {
   pub report_count: i16,
   pub unresolved_report_count: i16,
   pub local_removed: bool,
+  /// Image posts can only be created with alt text.
+  pub require_alt_text: bool,
 }
 
 #[derive(Debug, Clone, derive_new::new)]
@@ -144,6 +146,8 @@ pub struct CommunityInsertForm {
   pub summary: Option<String>,
   #[new(default)]
   pub local_removed: Option<bool>,
+  #[new(default)]
+  pub require_alt_text: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -172,6 +176,7 @@ pub struct CommunityUpdateForm {
   pub visibility: Option<CommunityVisibility>,
   pub summary: Option<Option<String>>,
   pub local_removed: Option<bool>,
+  pub require_alt_text: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -0,0 +1,30 @@
+use crate::newtypes::{DataExportId, LocalUserId};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::data_export;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = data_export))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A background job which writes all data of a local user into a zip archive.
+pub struct DataExport {
+  pub id: DataExportId,
+  pub local_user_id: LocalUserId,
+  pub published_at: DateTime<Utc>,
+  /// Set once the archive is ready for download, or once the export failed.
+  pub completed_at: Option<DateTime<Utc>>,
+  pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = data_export))]
+pub struct DataExportInsertForm {
+  pub local_user_id: LocalUserId,
+}
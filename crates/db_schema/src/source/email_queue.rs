@@ -0,0 +1,68 @@
+use crate::newtypes::EmailQueueId;
+use chrono::{DateTime, Utc};
+use lemmy_diesel_utils::sensitive::SensitiveString;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use {i_love_jesus::CursorKeysModule, lemmy_db_schema_file::schema::email_queue};
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, CursorKeysModule)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = email_queue))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", cursor_keys_module(name = email_queue_keys))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// An outgoing email, which is sent in the background and retried on failure.
+pub struct EmailQueue {
+  pub id: EmailQueueId,
+  pub recipient_email: SensitiveString,
+  pub recipient_name: String,
+  pub subject: String,
+  /// The html body, a plain text version is generated when sending. Not exposed through the
+  /// API, because it can contain login and password reset links.
+  #[serde(skip)]
+  pub body: String,
+  /// Notification emails are rate limited per recipient.
+  pub is_notification: bool,
+  pub attempts: i32,
+  pub last_error: Option<String>,
+  pub published_at: DateTime<Utc>,
+  pub next_attempt_at: DateTime<Utc>,
+  pub sent_at: Option<DateTime<Utc>>,
+  /// Set once the email couldn't be delivered after the maximum number of attempts.
+  pub failed_at: Option<DateTime<Utc>>,
+  /// Replies to this address are posted as comment or private message. Not exposed through the
+  /// API, because the address is signed for the recipient.
+  #[serde(skip)]
+  pub reply_to: Option<String>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = email_queue))]
+pub struct EmailQueueInsertForm {
+  pub recipient_email: SensitiveString,
+  pub recipient_name: String,
+  pub subject: String,
+  pub body: String,
+  #[new(default)]
+  pub is_notification: Option<bool>,
+  #[new(default)]
+  pub reply_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = email_queue))]
+pub struct EmailQueueUpdateForm {
+  pub attempts: Option<i32>,
+  pub last_error: Option<Option<String>>,
+  pub next_attempt_at: Option<DateTime<Utc>>,
+  pub sent_at: Option<Option<DateTime<Utc>>>,
+  pub failed_at: Option<Option<DateTime<Utc>>>,
+}
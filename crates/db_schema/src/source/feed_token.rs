@@ -0,0 +1,30 @@
+use crate::newtypes::LocalUserId;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::feed_token;
+use lemmy_diesel_utils::sensitive::SensitiveString;
+use serde::{Deserialize, Serialize};
+
+/// Read-only token which gives access to the private feeds of a user (front page, notifications
+/// and modlog). Unlike a login token it can't be used for the API.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = feed_token))]
+#[cfg_attr(feature = "full", diesel(primary_key(local_user_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct FeedToken {
+  #[serde(skip)]
+  pub local_user_id: LocalUserId,
+  pub token: SensitiveString,
+  pub published_at: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = feed_token))]
+pub struct FeedTokenForm {
+  pub local_user_id: LocalUserId,
+  pub token: SensitiveString,
+  pub published_at: DateTime<Utc>,
+}
@@ -7,8 +7,9 @@ use serde_with::skip_serializing_none;
 use std::fmt::Debug;
 #[cfg(feature = "full")]
 use {
+  crate::utils::queries::selects::image_details_alt_text_missing,
   i_love_jesus::CursorKeysModule,
-  lemmy_db_schema_file::schema::{image_details, local_image, remote_image},
+  lemmy_db_schema_file::schema::{image_details, image_hash_block, local_image, remote_image},
 };
 
 #[skip_serializing_none]
@@ -30,6 +31,8 @@ pub struct LocalImage {
   pub person_id: Option<PersonId>,
   /// This means the image is an auto-generated thumbnail, for a post.
   pub thumbnail_for_post_id: Option<PostId>,
+  /// File size in bytes. Zero for images which were uploaded before this was tracked.
+  pub size: i64,
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +42,7 @@ pub struct LocalImageForm {
   pub pictrs_alias: String,
   pub person_id: PersonId,
   pub thumbnail_for_post_id: Option<Option<PostId>>,
+  pub size: i64,
 }
 
 /// Stores all images which are hosted on remote domains. When attempting to proxy an image, it
@@ -68,6 +72,16 @@ pub struct ImageDetails {
   pub height: i32,
   pub content_type: String,
   pub blurhash: Option<String>,
+  /// Perceptual hash of the image, used to check it against the [`ImageHashBlock`] list.
+  #[serde(skip)]
+  pub phash: Option<i64>,
+  /// True if the post which has this image as thumbnail has no alt text.
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = image_details_alt_text_missing()
+    )
+  )]
+  pub alt_text_missing: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -79,4 +93,26 @@ pub struct ImageDetailsInsertForm {
   pub height: i32,
   pub content_type: String,
   pub blurhash: Option<String>,
+  pub phash: Option<i64>,
+}
+
+/// Perceptual hash of a known abusive image. Uploads and remote images which are similar to one
+/// of these hashes are rejected.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = image_hash_block))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+pub struct ImageHashBlock {
+  pub id: i32,
+  pub hash: i64,
+  pub reason: Option<String>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = image_hash_block))]
+pub struct ImageHashBlockForm {
+  pub hash: i64,
+  pub reason: Option<String>,
 }
@@ -113,6 +113,25 @@ pub struct LocalSite {
   /// This affects post and comment images, but not avatars and banners.
   pub image_allow_video_uploads: bool,
   pub image_upload_disabled: bool,
+  /// Maximum total size of image uploads per user in bytes, unlimited if empty. Can be overridden
+  /// for individual users.
+  pub image_upload_quota_bytes: Option<i64>,
+  /// Image posts can only be created with alt text.
+  pub require_alt_text: bool,
+  /// Days after which outgoing activities are deleted.
+  pub sent_activity_retention_days: i32,
+  /// Days after which the ids of incoming activities are deleted. These are only used to ignore
+  /// duplicate activities.
+  pub received_activity_retention_days: i32,
+  /// Days after which ip and user agent of logins are removed, kept forever if empty.
+  pub login_ip_retention_days: Option<i32>,
+  /// Days after which read posts are marked as unread, kept forever if empty.
+  pub post_read_retention_days: Option<i32>,
+  /// Days after which notifications are deleted, kept forever if empty.
+  pub notification_retention_days: Option<i32>,
+  /// Days after which remote posts are deleted if no local user interacted with them, kept
+  /// forever if empty.
+  pub remote_content_retention_days: Option<i32>,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -192,6 +211,22 @@ pub struct LocalSiteInsertForm {
   pub image_allow_video_uploads: Option<bool>,
   #[new(default)]
   pub image_upload_disabled: Option<bool>,
+  #[new(default)]
+  pub image_upload_quota_bytes: Option<i64>,
+  #[new(default)]
+  pub require_alt_text: Option<bool>,
+  #[new(default)]
+  pub sent_activity_retention_days: Option<i32>,
+  #[new(default)]
+  pub received_activity_retention_days: Option<i32>,
+  #[new(default)]
+  pub login_ip_retention_days: Option<i32>,
+  #[new(default)]
+  pub post_read_retention_days: Option<i32>,
+  #[new(default)]
+  pub notification_retention_days: Option<i32>,
+  #[new(default)]
+  pub remote_content_retention_days: Option<i32>,
 }
 
 #[derive(Clone, Default)]
@@ -235,4 +270,12 @@ pub struct LocalSiteUpdateForm {
   pub image_max_upload_size: Option<i32>,
   pub image_allow_video_uploads: Option<bool>,
   pub image_upload_disabled: Option<bool>,
+  pub image_upload_quota_bytes: Option<Option<i64>>,
+  pub require_alt_text: Option<bool>,
+  pub sent_activity_retention_days: Option<i32>,
+  pub received_activity_retention_days: Option<i32>,
+  pub login_ip_retention_days: Option<Option<i32>>,
+  pub post_read_retention_days: Option<Option<i32>>,
+  pub notification_retention_days: Option<Option<i32>>,
+  pub remote_content_retention_days: Option<Option<i32>>,
 }
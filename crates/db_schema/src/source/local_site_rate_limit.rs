@@ -36,6 +36,16 @@ pub struct LocalSiteRateLimit {
   pub updated_at: Option<DateTime<Utc>>,
   pub import_user_settings_max_requests: i32,
   pub import_user_settings_interval_seconds: i32,
+  pub vote_max_requests: i32,
+  pub vote_interval_seconds: i32,
+  pub report_max_requests: i32,
+  pub report_interval_seconds: i32,
+  pub follow_max_requests: i32,
+  pub follow_interval_seconds: i32,
+  pub private_message_max_requests: i32,
+  pub private_message_interval_seconds: i32,
+  pub federation_inbox_max_requests: i32,
+  pub federation_inbox_interval_seconds: i32,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -71,6 +81,26 @@ pub struct LocalSiteRateLimitInsertForm {
   pub import_user_settings_max_requests: Option<i32>,
   #[new(default)]
   pub import_user_settings_interval_seconds: Option<i32>,
+  #[new(default)]
+  pub vote_max_requests: Option<i32>,
+  #[new(default)]
+  pub vote_interval_seconds: Option<i32>,
+  #[new(default)]
+  pub report_max_requests: Option<i32>,
+  #[new(default)]
+  pub report_interval_seconds: Option<i32>,
+  #[new(default)]
+  pub follow_max_requests: Option<i32>,
+  #[new(default)]
+  pub follow_interval_seconds: Option<i32>,
+  #[new(default)]
+  pub private_message_max_requests: Option<i32>,
+  #[new(default)]
+  pub private_message_interval_seconds: Option<i32>,
+  #[new(default)]
+  pub federation_inbox_max_requests: Option<i32>,
+  #[new(default)]
+  pub federation_inbox_interval_seconds: Option<i32>,
 }
 
 #[derive(Clone, Default)]
@@ -91,5 +121,15 @@ pub struct LocalSiteRateLimitUpdateForm {
   pub search_interval_seconds: Option<i32>,
   pub import_user_settings_max_requests: Option<i32>,
   pub import_user_settings_interval_seconds: Option<i32>,
+  pub vote_max_requests: Option<i32>,
+  pub vote_interval_seconds: Option<i32>,
+  pub report_max_requests: Option<i32>,
+  pub report_interval_seconds: Option<i32>,
+  pub follow_max_requests: Option<i32>,
+  pub follow_interval_seconds: Option<i32>,
+  pub private_message_max_requests: Option<i32>,
+  pub private_message_interval_seconds: Option<i32>,
+  pub federation_inbox_max_requests: Option<i32>,
+  pub federation_inbox_interval_seconds: Option<i32>,
   pub updated_at: Option<Option<DateTime<Utc>>>,
 }
@@ -1,10 +1,17 @@
-use crate::newtypes::LocalUserId;
+use crate::newtypes::{LocalUserId, NotificationId};
 use chrono::{DateTime, Utc};
 #[cfg(feature = "full")]
 use lemmy_db_schema_file::schema::local_user;
 use lemmy_db_schema_file::{
   PersonId,
-  enums::{CommentSortType, ListingType, PostListingMode, PostSortType, VoteShow},
+  enums::{
+    CommentSortType,
+    EmailNotificationFrequency,
+    ListingType,
+    PostListingMode,
+    PostSortType,
+    VoteShow,
+  },
 };
 use lemmy_diesel_utils::sensitive::SensitiveString;
 use serde::{Deserialize, Serialize};
@@ -78,6 +85,24 @@ pub struct LocalUser {
   pub show_upvote_percentage: bool,
   pub show_person_votes: bool,
   pub default_items_per_page: i32,
+  /// How often notification emails are sent.
+  pub email_notification_frequency: EmailNotificationFrequency,
+  /// The last time a notification digest was sent. Only unread notifications after this time are
+  /// included in the next digest.
+  #[serde(skip)]
+  pub last_email_digest_at: DateTime<Utc>,
+  /// Total size of the user's image uploads in bytes.
+  pub image_upload_bytes: i64,
+  /// Overrides [[LocalSite.image_upload_quota_bytes]] for this user.
+  pub image_upload_quota_bytes: Option<i64>,
+  /// Rate limits of this user are multiplied with this value.
+  pub rate_limit_multiplier: i32,
+  /// The user is not rate limited at all.
+  pub rate_limit_exempt: bool,
+  /// The last notification included in a digest, to tell apart notifications which were published
+  /// at the same time as [[LocalUser.last_email_digest_at]].
+  #[serde(skip)]
+  pub last_email_digest_notification_id: Option<NotificationId>,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -150,6 +175,8 @@ pub struct LocalUserInsertForm {
   pub show_upvote_percentage: Option<bool>,
   #[new(default)]
   pub show_person_votes: Option<bool>,
+  #[new(default)]
+  pub email_notification_frequency: Option<EmailNotificationFrequency>,
 }
 
 #[derive(Clone, Default)]
@@ -190,4 +217,10 @@ pub struct LocalUserUpdateForm {
   pub show_upvote_percentage: Option<bool>,
   pub show_person_votes: Option<bool>,
   pub default_items_per_page: Option<i32>,
+  pub email_notification_frequency: Option<EmailNotificationFrequency>,
+  pub last_email_digest_at: Option<DateTime<Utc>>,
+  pub image_upload_quota_bytes: Option<Option<i64>>,
+  pub rate_limit_multiplier: Option<i32>,
+  pub rate_limit_exempt: Option<bool>,
+  pub last_email_digest_notification_id: Option<Option<NotificationId>>,
 }
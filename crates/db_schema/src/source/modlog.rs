@@ -1,4 +1,4 @@
-use crate::newtypes::{CommentId, CommunityId, ModlogId, PostId};
+use crate::newtypes::{CommentId, CommunityId, ModlogId, PostId, RuleId};
 use chrono::{DateTime, Utc};
 #[cfg(feature = "full")]
 use i_love_jesus::CursorKeysModule;
@@ -37,6 +37,8 @@ pub struct Modlog {
   pub expires_at: Option<DateTime<Utc>>,
   pub published_at: DateTime<Utc>,
   pub bulk_action_parent_id: Option<ModlogId>,
+  #[serde(skip)]
+  pub rule_id: Option<RuleId>,
 }
 
 #[derive(derive_new::new)]
@@ -62,4 +64,6 @@ pub struct ModlogInsertForm<'a> {
   pub(crate) target_instance_id: Option<InstanceId>,
   #[new(default)]
   pub(crate) expires_at: Option<DateTime<Utc>>,
+  #[new(default)]
+  pub(crate) rule_id: Option<RuleId>,
 }
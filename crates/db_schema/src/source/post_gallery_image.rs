@@ -0,0 +1,53 @@
+use crate::newtypes::PostId;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use diesel::{AsExpression, FromSqlRow, sql_types::Nullable};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::post_gallery_image;
+use lemmy_diesel_utils::dburl::DbUrl;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// An image of a gallery post. Gallery posts have an ordered list of images in addition to the
+/// post url.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Associations, Identifiable)
+)]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_image))]
+#[cfg_attr(feature = "full", diesel(primary_key(post_id, position)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct PostGalleryImage {
+  pub post_id: PostId,
+  /// Position of the image in the gallery, starting at 0.
+  pub position: i32,
+  pub url: DbUrl,
+  pub alt_text: Option<String>,
+  pub caption: Option<String>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_image))]
+pub struct PostGalleryImageForm {
+  pub post_id: PostId,
+  pub position: i32,
+  pub url: DbUrl,
+  pub alt_text: Option<String>,
+  pub caption: Option<String>,
+}
+
+/// We wrap this in a struct so we can implement FromSqlRow<Json> for it
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, PartialEq, Default)]
+#[serde(transparent)]
+#[cfg_attr(feature = "full", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "full", diesel(sql_type = Nullable<diesel::sql_types::Json>))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct PostGalleryView(pub Vec<PostGalleryImage>);
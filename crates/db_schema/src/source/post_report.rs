@@ -1,4 +1,4 @@
-use crate::newtypes::{PostId, PostReportId};
+use crate::newtypes::{PostId, PostReportId, RuleId};
 use chrono::{DateTime, Utc};
 use lemmy_db_schema_file::PersonId;
 #[cfg(feature = "full")]
@@ -35,6 +35,8 @@ pub struct PostReport {
   pub published_at: DateTime<Utc>,
   pub updated_at: Option<DateTime<Utc>>,
   pub violates_instance_rules: bool,
+  /// The rule which the post violates, if any
+  pub rule_id: Option<RuleId>,
 }
 
 #[derive(Clone, Default)]
@@ -48,4 +50,5 @@ pub struct PostReportForm {
   pub original_post_body: Option<String>,
   pub reason: String,
   pub violates_instance_rules: bool,
+  pub rule_id: Option<RuleId>,
 }
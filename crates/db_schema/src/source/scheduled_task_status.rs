@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::scheduled_task_status;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = scheduled_task_status))]
+#[cfg_attr(feature = "full", diesel(primary_key(name)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Status of a periodic background task. It is shared between all server processes, so that each
+/// task only runs in one of them at a time.
+pub struct ScheduledTaskStatus {
+  pub name: String,
+  /// How often the task runs, can be changed by admins.
+  pub interval_seconds: i32,
+  pub last_started_at: Option<DateTime<Utc>>,
+  pub last_duration_ms: Option<i32>,
+  /// Error of the last run, empty if it succeeded.
+  pub last_error: Option<String>,
+  pub next_run_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = scheduled_task_status))]
+pub struct ScheduledTaskStatusInsertForm {
+  pub name: String,
+  pub interval_seconds: i32,
+  pub next_run_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = scheduled_task_status))]
+pub struct ScheduledTaskStatusUpdateForm {
+  pub interval_seconds: Option<i32>,
+  pub last_started_at: Option<Option<DateTime<Utc>>>,
+  pub last_duration_ms: Option<Option<i32>>,
+  pub last_error: Option<Option<String>>,
+  pub next_run_at: Option<DateTime<Utc>>,
+}
@@ -1,6 +1,6 @@
 pub mod queries;
 
-use chrono::TimeDelta;
+use chrono::{DateTime, TimeDelta, Utc};
 use lemmy_utils::{
   error::{LemmyErrorType, LemmyResult},
   settings::structs::Settings,
@@ -13,6 +13,8 @@ pub const SITEMAP_LIMIT: i64 = 50000;
 pub const SITEMAP_DAYS: TimeDelta = TimeDelta::days(31);
 pub const RANK_DEFAULT: f32 = 0.0001;
 pub const DELETED_REPLACEMENT_TEXT: &str = "*Permanently Deleted*";
+/// Data exports and their archives are deleted after this time.
+pub const DATA_EXPORT_RETENTION: TimeDelta = TimeDelta::days(7);
 
 pub fn limit_fetch(limit: Option<i64>, no_limit: Option<bool>) -> LemmyResult<i64> {
   Ok(if no_limit.unwrap_or_default() {
@@ -33,6 +35,13 @@ pub fn limit_fetch_check(limit: i64) -> LemmyResult<i64> {
   }
 }
 
+/// Data which is older than the returned time is removed by a retention policy of `days`.
+pub fn retention_cutoff(days: i32) -> DateTime<Utc> {
+  Utc::now()
+    .checked_sub_signed(TimeDelta::days(days.into()))
+    .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
 pub(crate) fn format_actor_url(
   name: &str,
   domain: &str,
@@ -65,6 +65,12 @@ pub fn comment_creator_is_admin() -> _ {
   )
 }
 
+/// Image details are always joined to the post thumbnail, this checks if the post has alt text.
+#[diesel::dsl::auto_type]
+pub fn image_details_alt_text_missing() -> _ {
+  post::alt_text.nullable().is_null()
+}
+
 #[diesel::dsl::auto_type]
 pub fn post_creator_is_admin() -> _ {
   exists(
@@ -353,6 +359,15 @@ pub fn post_community_tags_fragment() -> _ {
     .single_value()
 }
 
+#[diesel::dsl::auto_type]
+/// Placeholder for the gallery images of a post. These are loaded in a separate query for all
+/// posts in the result, instead of running a subquery for each post.
+pub fn post_gallery_placeholder() -> _ {
+  let sel: SqlLiteral<diesel::sql_types::Nullable<Json>> =
+    diesel::dsl::sql::<diesel::sql_types::Nullable<Json>>("NULL");
+  sel
+}
+
 #[diesel::dsl::auto_type]
 /// Gets the tags available within a specific community
 pub fn community_tags_fragment() -> _ {
@@ -371,3 +371,58 @@ pub enum ModlogKind {
   ModWarnComment,
   ModWarnPost,
 }
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::EmailNotificationFrequencyEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// How often notification emails are sent. Except for `Immediate`, all unread notifications
+/// since the last email are collected into a single digest.
+pub enum EmailNotificationFrequency {
+  #[default]
+  Immediate,
+  Hourly,
+  Daily,
+  Weekly,
+}
+
+#[derive(Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::AdminAuditKind"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// Actions which are recorded in the admin audit log.
+pub enum AdminAuditKind {
+  EditSite,
+  EditUrlBlocklist,
+  CreateOauthProvider,
+  EditOauthProvider,
+  DeleteOauthProvider,
+  CreateCustomEmoji,
+  EditCustomEmoji,
+  DeleteCustomEmoji,
+  CreateTagline,
+  EditTagline,
+  DeleteTagline,
+  AllowInstance,
+  BlockInstance,
+  SetRateLimitOverride,
+  EditScheduledTask,
+  ReloadConfig,
+  AdminLogin,
+  SetImageUploadQuota,
+  BlockImageHash,
+  UnblockImageHash,
+  ImportImageHashBlocks,
+}
@@ -1,6 +1,10 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "admin_audit_kind"))]
+  pub struct AdminAuditKind;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "actor_type_enum"))]
   pub struct ActorTypeEnum;
@@ -21,6 +25,10 @@ pub mod sql_types {
   #[diesel(postgres_type(name = "community_visibility"))]
   pub struct CommunityVisibility;
 
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "email_notification_frequency_enum"))]
+  pub struct EmailNotificationFrequencyEnum;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "federation_mode_enum"))]
   pub struct FederationModeEnum;
@@ -70,6 +78,21 @@ pub mod sql_types {
   pub struct VoteShowEnum;
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AdminAuditKind;
+
+    admin_audit_log (id) {
+        id -> Int4,
+        kind -> AdminAuditKind,
+        admin_person_id -> Nullable<Int4>,
+        target -> Nullable<Text>,
+        before -> Nullable<Jsonb>,
+        after -> Nullable<Jsonb>,
+        published_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use diesel_ltree::sql_types::Ltree;
@@ -124,6 +147,7 @@ diesel::table! {
         published_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
         violates_instance_rules -> Bool,
+        rule_id -> Nullable<Int4>,
     }
 }
 
@@ -178,6 +202,7 @@ diesel::table! {
         report_count -> Int2,
         unresolved_report_count -> Int2,
         local_removed -> Bool,
+        require_alt_text -> Bool,
     }
 }
 
@@ -276,6 +301,34 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    data_export (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        published_at -> Timestamptz,
+        completed_at -> Nullable<Timestamptz>,
+        error -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    email_queue (id) {
+        id -> Int4,
+        recipient_email -> Text,
+        recipient_name -> Text,
+        subject -> Text,
+        body -> Text,
+        is_notification -> Bool,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        published_at -> Timestamptz,
+        next_attempt_at -> Timestamptz,
+        sent_at -> Nullable<Timestamptz>,
+        failed_at -> Nullable<Timestamptz>,
+        reply_to -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     email_verification (id) {
         id -> Int4,
@@ -313,6 +366,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    feed_token (local_user_id) {
+        local_user_id -> Int4,
+        token -> Text,
+        published_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     image_details (link) {
         link -> Text,
@@ -321,6 +382,16 @@ diesel::table! {
         content_type -> Text,
         #[max_length = 50]
         blurhash -> Nullable<Varchar>,
+        phash -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    image_hash_block (id) {
+        id -> Int4,
+        hash -> Int8,
+        reason -> Nullable<Text>,
+        published_at -> Timestamptz,
     }
 }
 
@@ -364,6 +435,7 @@ diesel::table! {
         published_at -> Timestamptz,
         person_id -> Nullable<Int4>,
         thumbnail_for_post_id -> Nullable<Int4>,
+        size -> Int8,
     }
 }
 
@@ -427,6 +499,14 @@ diesel::table! {
         image_max_upload_size -> Int4,
         image_allow_video_uploads -> Bool,
         image_upload_disabled -> Bool,
+        image_upload_quota_bytes -> Nullable<Int8>,
+        require_alt_text -> Bool,
+        sent_activity_retention_days -> Int4,
+        received_activity_retention_days -> Int4,
+        login_ip_retention_days -> Nullable<Int4>,
+        post_read_retention_days -> Nullable<Int4>,
+        notification_retention_days -> Nullable<Int4>,
+        remote_content_retention_days -> Nullable<Int4>,
     }
 }
 
@@ -449,6 +529,16 @@ diesel::table! {
         updated_at -> Nullable<Timestamptz>,
         import_user_settings_max_requests -> Int4,
         import_user_settings_interval_seconds -> Int4,
+        vote_max_requests -> Int4,
+        vote_interval_seconds -> Int4,
+        report_max_requests -> Int4,
+        report_interval_seconds -> Int4,
+        follow_max_requests -> Int4,
+        follow_interval_seconds -> Int4,
+        private_message_max_requests -> Int4,
+        private_message_interval_seconds -> Int4,
+        federation_inbox_max_requests -> Int4,
+        federation_inbox_interval_seconds -> Int4,
     }
 }
 
@@ -468,6 +558,7 @@ diesel::table! {
     use super::sql_types::PostListingModeEnum;
     use super::sql_types::CommentSortTypeEnum;
     use super::sql_types::VoteShowEnum;
+    use super::sql_types::EmailNotificationFrequencyEnum;
 
     local_user (id) {
         id -> Int4,
@@ -507,6 +598,13 @@ diesel::table! {
         show_upvote_percentage -> Bool,
         show_person_votes -> Bool,
         default_items_per_page -> Int4,
+        email_notification_frequency -> EmailNotificationFrequencyEnum,
+        last_email_digest_at -> Timestamptz,
+        image_upload_bytes -> Int8,
+        image_upload_quota_bytes -> Nullable<Int8>,
+        rate_limit_multiplier -> Int4,
+        rate_limit_exempt -> Bool,
+        last_email_digest_notification_id -> Nullable<Int4>,
     }
 }
 
@@ -553,6 +651,7 @@ diesel::table! {
         expires_at -> Nullable<Timestamptz>,
         published_at -> Timestamptz,
         bulk_action_parent_id -> Nullable<Int4>,
+        rule_id -> Nullable<Int4>,
     }
 }
 
@@ -812,6 +911,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_gallery_image (post_id, position) {
+        post_id -> Int4,
+        position -> Int4,
+        url -> Text,
+        alt_text -> Nullable<Text>,
+        caption -> Nullable<Text>,
+        published_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     post_report (id) {
         id -> Int4,
@@ -827,6 +937,7 @@ diesel::table! {
         published_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
         violates_instance_rules -> Bool,
+        rule_id -> Nullable<Int4>,
     }
 }
 
@@ -899,6 +1010,32 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    rule (id) {
+        id -> Int4,
+        ap_id -> Text,
+        community_id -> Nullable<Int4>,
+        #[max_length = 255]
+        title -> Varchar,
+        description -> Nullable<Text>,
+        position -> Int4,
+        published_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted -> Bool,
+    }
+}
+
+diesel::table! {
+    scheduled_task_status (name) {
+        name -> Text,
+        interval_seconds -> Int4,
+        last_started_at -> Nullable<Timestamptz>,
+        last_duration_ms -> Nullable<Int4>,
+        last_error -> Nullable<Text>,
+        next_run_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     search_combined (id) {
         published_at -> Timestamptz,
@@ -977,12 +1114,14 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(admin_audit_log -> person (admin_person_id));
 diesel::joinable!(comment -> language (language_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(comment -> post (post_id));
 diesel::joinable!(comment_actions -> comment (comment_id));
 diesel::joinable!(comment_actions -> person (person_id));
 diesel::joinable!(comment_report -> comment (comment_id));
+diesel::joinable!(comment_report -> rule (rule_id));
 diesel::joinable!(community -> instance (instance_id));
 diesel::joinable!(community_actions -> community (community_id));
 diesel::joinable!(community_language -> community (community_id));
@@ -990,10 +1129,12 @@ diesel::joinable!(community_language -> language (language_id));
 diesel::joinable!(community_report -> community (community_id));
 diesel::joinable!(community_tag -> community (community_id));
 diesel::joinable!(custom_emoji_keyword -> custom_emoji (custom_emoji_id));
+diesel::joinable!(data_export -> local_user (local_user_id));
 diesel::joinable!(email_verification -> local_user (local_user_id));
 diesel::joinable!(federation_allowlist -> instance (instance_id));
 diesel::joinable!(federation_blocklist -> instance (instance_id));
 diesel::joinable!(federation_queue_state -> instance (instance_id));
+diesel::joinable!(feed_token -> local_user (local_user_id));
 diesel::joinable!(instance_actions -> instance (instance_id));
 diesel::joinable!(instance_actions -> person (person_id));
 diesel::joinable!(local_image -> person (person_id));
@@ -1007,6 +1148,7 @@ diesel::joinable!(local_user_keyword_block -> local_user (local_user_id));
 diesel::joinable!(local_user_language -> language (language_id));
 diesel::joinable!(local_user_language -> local_user (local_user_id));
 diesel::joinable!(login_token -> local_user (user_id));
+diesel::joinable!(modlog -> rule (rule_id));
 diesel::joinable!(multi_community -> instance (instance_id));
 diesel::joinable!(multi_community -> person (creator_id));
 diesel::joinable!(multi_community_entry -> community (community_id));
@@ -1035,7 +1177,9 @@ diesel::joinable!(post_actions -> person (person_id));
 diesel::joinable!(post_actions -> post (post_id));
 diesel::joinable!(post_community_tag -> community_tag (community_tag_id));
 diesel::joinable!(post_community_tag -> post (post_id));
+diesel::joinable!(post_gallery_image -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
+diesel::joinable!(post_report -> rule (rule_id));
 diesel::joinable!(private_message_report -> private_message (private_message_id));
 diesel::joinable!(registration_application -> local_user (local_user_id));
 diesel::joinable!(registration_application -> person (admin_id));
@@ -1043,6 +1187,7 @@ diesel::joinable!(report_combined -> comment_report (comment_report_id));
 diesel::joinable!(report_combined -> community_report (community_report_id));
 diesel::joinable!(report_combined -> post_report (post_report_id));
 diesel::joinable!(report_combined -> private_message_report (private_message_report_id));
+diesel::joinable!(rule -> community (community_id));
 diesel::joinable!(search_combined -> comment (comment_id));
 diesel::joinable!(search_combined -> community (community_id));
 diesel::joinable!(search_combined -> multi_community (multi_community_id));
@@ -1053,6 +1198,7 @@ diesel::joinable!(site_language -> language (language_id));
 diesel::joinable!(site_language -> site (site_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+  admin_audit_log,
   comment,
   comment_actions,
   comment_report,
@@ -1061,10 +1207,13 @@ diesel::allow_tables_to_appear_in_same_query!(
   community_language,
   community_report,
   community_tag,
+  data_export,
+  email_queue,
   email_verification,
   federation_allowlist,
   federation_blocklist,
   federation_queue_state,
+  feed_token,
   instance,
   instance_actions,
   language,
@@ -1090,11 +1239,14 @@ diesel::allow_tables_to_appear_in_same_query!(
   post,
   post_actions,
   post_community_tag,
+  post_gallery_image,
   post_report,
   private_message,
   private_message_report,
   registration_application,
   report_combined,
+  rule,
+  scheduled_task_status,
   search_combined,
   site,
   site_language,
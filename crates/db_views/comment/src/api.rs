@@ -1,5 +1,5 @@
 use crate::CommentView;
-use lemmy_db_schema::newtypes::{CommentId, CommunityId, LanguageId, PostId};
+use lemmy_db_schema::newtypes::{CommentId, CommunityId, LanguageId, PostId, RuleId};
 use lemmy_db_schema_file::enums::{CommentSortType, ListingType};
 use lemmy_diesel_utils::pagination::PaginationCursor;
 use serde::{Deserialize, Serialize};
@@ -105,6 +105,10 @@ pub struct LockComment {
   pub comment_id: CommentId,
   pub locked: bool,
   pub reason: String,
+  /// A time that the lock will expire, in unix epoch seconds. Only used when locking.
+  ///
+  /// An i64 unix timestamp is used for a simpler API client implementation.
+  pub expires_at: Option<i64>,
 }
 
 #[skip_serializing_none]
@@ -129,6 +133,12 @@ pub struct RemoveComment {
   /// Setting this will override whatever `removed` was set to,
   /// leave as null or unset to act just on the comment itself.
   pub remove_children: Option<bool>,
+  /// A time that the removal will expire, in unix epoch seconds. Only used when removing.
+  ///
+  /// An i64 unix timestamp is used for a simpler API client implementation.
+  pub expires_at: Option<i64>,
+  /// The community or instance rule which the comment violates.
+  pub rule_id: Option<RuleId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
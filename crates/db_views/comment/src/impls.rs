@@ -48,7 +48,7 @@ use lemmy_db_schema_file::{
   schema::{comment, community, community_actions, local_user_language, person, post},
 };
 use lemmy_diesel_utils::{
-  connection::{DbPool, get_conn},
+  connection::{DbPool, get_conn, get_read_conn},
   pagination::{
     CursorData,
     PagedResponse,
@@ -59,7 +59,10 @@ use lemmy_diesel_utils::{
   traits::Crud,
   utils::{Subpath, now, seconds_to_pg_interval},
 };
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  metrics::db_query_timer,
+};
 
 impl PaginationCursorConversion for CommentView {
   type PaginatedType = Comment;
@@ -114,6 +117,7 @@ impl CommentView {
     my_local_user: Option<&'_ LocalUser>,
     local_instance_id: InstanceId,
   ) -> LemmyResult<Self> {
+    let _timer = db_query_timer("CommentView::read");
     let conn = &mut get_conn(pool).await?;
 
     let mut query = Self::joins(my_local_user.person_id(), local_instance_id)
@@ -315,7 +319,8 @@ impl CommentQuery<'_> {
       Top => pq.then_order_by(key::score),
     };
 
-    let conn = &mut get_conn(pool).await?;
+    let _timer = db_query_timer("CommentQuery::list");
+    let conn = &mut get_read_conn(pool).await?;
     let res = pq.load::<CommentView>(conn).await?;
 
     paginate_response(res, limit, o.page_cursor)
@@ -3,8 +3,8 @@ use lemmy_db_schema::{
   CommunitySortType,
   MultiCommunityListingType,
   MultiCommunitySortType,
-  newtypes::{CommunityId, CommunityTagId, LanguageId, MultiCommunityId},
-  source::site::Site,
+  newtypes::{CommunityId, CommunityTagId, LanguageId, MultiCommunityId, RuleId},
+  source::{rule::Rule, site::Site},
 };
 use lemmy_db_schema_file::{
   PersonId,
@@ -112,6 +112,8 @@ pub struct CreateCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Require alt text for image posts.
+  pub require_alt_text: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -143,6 +145,8 @@ pub struct EditCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Require alt text for image posts.
+  pub require_alt_text: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
@@ -176,6 +180,8 @@ pub struct GetCommunityResponse {
   pub site: Option<Site>,
   pub moderators: Vec<CommunityModeratorView>,
   pub discussion_languages: Vec<LanguageId>,
+  /// The rules of this community, in display order
+  pub rules: Vec<Rule>,
 }
 
 #[skip_serializing_none]
@@ -362,3 +368,40 @@ pub struct DeleteCommunityTag {
   pub tag_id: CommunityTagId,
   pub delete: bool,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Create a rule for a community, or for the local instance if `community_id` is empty.
+///
+/// Instance rules can only be created by admins.
+pub struct CreateRule {
+  pub community_id: Option<CommunityId>,
+  pub title: String,
+  pub description: Option<String>,
+  /// Rules are shown in ascending order of position.
+  pub position: Option<i32>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Make changes to a rule.
+pub struct EditRule {
+  pub rule_id: RuleId,
+  pub title: Option<String>,
+  pub description: Option<String>,
+  pub position: Option<i32>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Delete a rule.
+pub struct DeleteRule {
+  pub rule_id: RuleId,
+  pub delete: bool,
+}
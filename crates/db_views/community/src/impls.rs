@@ -43,7 +43,7 @@ use lemmy_db_schema_file::{
   },
 };
 use lemmy_diesel_utils::{
-  connection::{DbPool, get_conn},
+  connection::{DbPool, get_conn, get_read_conn},
   pagination::{
     CursorData,
     PagedResponse,
@@ -54,7 +54,10 @@ use lemmy_diesel_utils::{
   traits::Crud,
   utils::{LowerKey, now, seconds_to_pg_interval},
 };
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  metrics::db_query_timer,
+};
 
 impl CommunityView {
   #[diesel::dsl::auto_type(no_type_alias)]
@@ -76,6 +79,7 @@ impl CommunityView {
     my_local_user: Option<&'_ LocalUser>,
     is_mod_or_admin: bool,
   ) -> LemmyResult<Self> {
+    let _timer = db_query_timer("CommunityView::read");
     let conn = &mut get_conn(pool).await?;
     let mut query = Self::joins(my_local_user.person_id())
       .filter(community::id.eq(community_id))
@@ -209,7 +213,8 @@ impl CommunityQuery<'_> {
     // finally use unique id as tie breaker
     pq = pq.then_order_by(key::id);
 
-    let conn = &mut get_conn(pool).await?;
+    let _timer = db_query_timer("CommunityQuery::list");
+    let conn = &mut get_read_conn(pool).await?;
     let res = pq
       .load::<CommunityView>(conn)
       .await
@@ -327,7 +332,8 @@ impl MultiCommunityQuery {
     // finally use unique id as tie breaker
     pq = pq.then_order_by(mkey::id);
 
-    let conn = &mut get_conn(pool).await?;
+    let _timer = db_query_timer("MultiCommunityQuery::list");
+    let conn = &mut get_read_conn(pool).await?;
     let res = pq
       .load::<MultiCommunityView>(conn)
       .await
@@ -1,4 +1,5 @@
-use lemmy_diesel_utils::pagination::PaginationCursor;
+use crate::LocalImageView;
+use lemmy_diesel_utils::pagination::{PagedResponse, PaginationCursor};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use url::Url;
@@ -39,6 +40,20 @@ pub struct ListMedia {
   pub limit: Option<i64>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Your image uploads, and how much of your storage quota they use.
+pub struct ListMediaResponse {
+  #[serde(flatten)]
+  pub media: PagedResponse<LocalImageView>,
+  /// Total size of your image uploads in bytes.
+  pub upload_bytes: i64,
+  /// Maximum total size of your image uploads in bytes. Empty if there is no limit.
+  pub upload_quota_bytes: Option<i64>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -47,3 +62,42 @@ pub struct UploadImageResponse {
   pub image_url: Url,
   pub filename: String,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Adds an image to the blocklist, so that it can't be uploaded again and posts with it are
+/// removed. Either the hash or the url of a local or proxied image must be given.
+pub struct BlockImageHash {
+  /// Perceptual hash as 16 hex digits.
+  pub hash: Option<String>,
+  pub image_url: Option<Url>,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct UnblockImageHash {
+  pub hash: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// An entry of the image hash blocklist. The list can be exported and imported into other
+/// instances.
+pub struct ImageHashBlockItem {
+  /// Perceptual hash as 16 hex digits.
+  pub hash: String,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct ImageHashBlocklist {
+  pub image_hash_blocks: Vec<ImageHashBlockItem>,
+}
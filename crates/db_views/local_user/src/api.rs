@@ -1,4 +1,5 @@
 use lemmy_db_schema::LocalUserSortType;
+use lemmy_db_schema_file::PersonId;
 use lemmy_diesel_utils::pagination::PaginationCursor;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -13,3 +14,27 @@ pub struct AdminListUsers {
   pub sort: Option<LocalUserSortType>,
   pub limit: Option<i64>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Sets the image upload quota of a local user, overriding the quota of the site. Leave
+/// `quota_bytes` empty to use the site quota again.
+pub struct SetImageUploadQuota {
+  pub person_id: PersonId,
+  pub quota_bytes: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Gives a trusted local user or bot higher rate limits. The limits of the site are multiplied
+/// with `multiplier`, or not applied at all if `exempt` is set. Fields which are left empty are
+/// not changed.
+pub struct SetRateLimitOverride {
+  pub person_id: PersonId,
+  pub multiplier: Option<i32>,
+  pub exempt: Option<bool>,
+}
@@ -1,5 +1,6 @@
 use crate::LocalUserView;
 use actix_web::{FromRequest, HttpMessage, HttpRequest, dev::Payload};
+use chrono::{DateTime, Utc};
 use diesel::{
   BoolExpressionMethods,
   ExpressionMethods,
@@ -21,6 +22,7 @@ use lemmy_db_schema::{
 use lemmy_db_schema_file::{
   PersonId,
   aliases::creator_home_instance_actions,
+  enums::EmailNotificationFrequency,
   joins::creator_home_instance_actions_join,
   schema::{instance_actions, local_user, oauth_account, person},
 };
@@ -134,6 +136,25 @@ impl LocalUserView {
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
 
+  /// Users with the given email frequency whose last notification digest was sent before
+  /// `last_digest_before`.
+  pub async fn list_for_email_digest(
+    pool: &mut DbPool<'_>,
+    frequency: EmailNotificationFrequency,
+    last_digest_before: DateTime<Utc>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    Self::joins()
+      .filter(local_user::email.is_not_null())
+      .filter(local_user::send_notifications_to_email.eq(true))
+      .filter(local_user::email_notification_frequency.eq(frequency))
+      .filter(local_user::last_email_digest_at.lt(last_digest_before))
+      .select(Self::as_select())
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
   pub async fn create_test_user(
     pool: &mut DbPool<'_>,
     name: &str,
@@ -245,11 +266,12 @@ impl PaginationCursorConversion for LocalUserView {
 mod tests {
 
   use super::*;
+  use chrono::TimeDelta;
   use lemmy_db_schema::{
     assert_length,
     source::{
       instance::{Instance, InstanceActions, InstanceBanForm},
-      local_user::{LocalUser, LocalUserInsertForm},
+      local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
       person::{Person, PersonInsertForm},
     },
     traits::Bannable,
@@ -309,4 +331,56 @@ mod tests {
 
     cleanup(data, pool).await
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn list_for_email_digest() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let bob_form = PersonInsertForm {
+      local: Some(true),
+      ..PersonInsertForm::test_form(data.alice.instance_id, "bob")
+    };
+    let bob = Person::create(pool, &bob_form).await?;
+    let bob_local_user_form = LocalUserInsertForm {
+      email: Some("bob@example.com".to_string()),
+      send_notifications_to_email: Some(true),
+      email_notification_frequency: Some(EmailNotificationFrequency::Daily),
+      ..LocalUserInsertForm::test_form(bob.id)
+    };
+    let bob_local_user = LocalUser::create(pool, &bob_local_user_form, vec![]).await?;
+
+    // The last digest was sent at registration, so a daily digest is only due a day later
+    let now = Utc::now();
+    let daily = EmailNotificationFrequency::Daily;
+    assert_length!(
+      0,
+      LocalUserView::list_for_email_digest(pool, daily, now - TimeDelta::days(1)).await?
+    );
+    let list = LocalUserView::list_for_email_digest(pool, daily, now).await?;
+    assert_length!(1, list);
+    assert_eq!(bob.id, list[0].person.id);
+
+    // Users with a different frequency are not included
+    let hourly = EmailNotificationFrequency::Hourly;
+    assert_length!(
+      0,
+      LocalUserView::list_for_email_digest(pool, hourly, now).await?
+    );
+
+    // Nor are users who disabled notification emails
+    let form = LocalUserUpdateForm {
+      send_notifications_to_email: Some(false),
+      ..Default::default()
+    };
+    LocalUser::update(pool, bob_local_user.id, &form).await?;
+    assert_length!(
+      0,
+      LocalUserView::list_for_email_digest(pool, daily, now).await?
+    );
+
+    cleanup(data, pool).await
+  }
 }
@@ -30,10 +30,10 @@ use lemmy_db_schema_file::{
   PersonId,
   aliases,
   enums::ListingType,
-  schema::{comment, community, community_actions, instance, modlog, person, post},
+  schema::{comment, community, community_actions, instance, modlog, person, post, rule},
 };
 use lemmy_diesel_utils::{
-  connection::{DbPool, get_conn},
+  connection::{DbPool, get_conn, get_read_conn},
   pagination::{
     CursorData,
     PagedResponse,
@@ -42,7 +42,7 @@ use lemmy_diesel_utils::{
     paginate_response,
   },
 };
-use lemmy_utils::error::LemmyResult;
+use lemmy_utils::{error::LemmyResult, metrics::db_query_timer};
 
 impl ModlogView {
   #[diesel::dsl::auto_type(no_type_alias)]
@@ -68,6 +68,7 @@ impl ModlogView {
       .left_join(community::table.on(community::id.nullable().eq(modlog::target_community_id)))
       .left_join(instance::table.on(instance::id.nullable().eq(modlog::target_instance_id)))
       .left_join(community_actions_join)
+      .left_join(rule::table.on(rule::id.nullable().eq(modlog::rule_id)))
   }
 }
 
@@ -177,7 +178,8 @@ impl ModlogQuery<'_> {
         // Tie breaker
         .then_order_by(key::id);
 
-    let conn = &mut get_conn(pool).await?;
+    let _timer = db_query_timer("ModlogQuery::list");
+    let conn = &mut get_read_conn(pool).await?;
     let res = paginated_query.load::<ModlogView>(conn).await?;
 
     let hide_modlog_names = self.hide_modlog_names.unwrap_or_default();
@@ -492,7 +494,7 @@ mod tests {
     let form = ModlogInsertForm::admin_feature_post_site(&data.timmy, &data.post, true);
     Modlog::create(pool, &[form]).await?;
 
-    let form = ModlogInsertForm::mod_lock_post(data.timmy.id, &data.post, true, "reason");
+    let form = ModlogInsertForm::mod_lock_post(data.timmy.id, &data.post, true, None, "reason");
     Modlog::create(pool, &[form]).await?;
 
     let form = ModlogInsertForm::mod_lock_comment(
@@ -500,6 +502,7 @@ mod tests {
       &data.comment,
       data.community.id,
       true,
+      None,
       "reason",
     );
     Modlog::create(pool, &[form]).await?;
@@ -509,8 +512,10 @@ mod tests {
       &data.comment,
       data.community.id,
       true,
+      None,
       "reason",
       None,
+      None,
     );
     Modlog::create(pool, &[form]).await?;
 
@@ -523,7 +528,15 @@ mod tests {
     );
     Modlog::create(pool, &[form]).await?;
 
-    let form = ModlogInsertForm::mod_remove_post(data.timmy.id, &data.post, true, "reason", None);
+    let form = ModlogInsertForm::mod_remove_post(
+      data.timmy.id,
+      &data.post,
+      true,
+      None,
+      "reason",
+      None,
+      None,
+    );
     Modlog::create(pool, &[form]).await?;
 
     let form =
@@ -535,8 +548,15 @@ mod tests {
       ModlogInsertForm::mod_transfer_community(data.jessica.id, data.community_2.id, data.sara.id);
     Modlog::create(pool, &[form]).await?;
 
-    let form =
-      ModlogInsertForm::mod_remove_post(data.jessica.id, &data.post_2, true, "reason", None);
+    let form = ModlogInsertForm::mod_remove_post(
+      data.jessica.id,
+      &data.post_2,
+      true,
+      None,
+      "reason",
+      None,
+      None,
+    );
     Modlog::create(pool, &[form]).await?;
 
     let form = ModlogInsertForm::mod_remove_comment(
@@ -544,8 +564,10 @@ mod tests {
       &data.comment_2,
       data.community_2.id,
       true,
+      None,
       "reason",
       None,
+      None,
     );
     Modlog::create(pool, &[form]).await?;
 
@@ -898,7 +920,15 @@ mod tests {
     let pool = &mut pool.into();
     let data = init_data(pool).await?;
 
-    let form = ModlogInsertForm::mod_remove_post(data.timmy.id, &data.post, true, "reason", None);
+    let form = ModlogInsertForm::mod_remove_post(
+      data.timmy.id,
+      &data.post,
+      true,
+      None,
+      "reason",
+      None,
+      None,
+    );
     Modlog::create(pool, &[form]).await?;
 
     let modlog = ModlogQuery {
@@ -935,21 +965,32 @@ mod tests {
       data.timmy.id,
       &data.post,
       true,
+      None,
       "bulk remove",
+      None,
       Some(parent_id),
     );
     let post_form_2 = ModlogInsertForm::mod_remove_post(
       data.timmy.id,
       &data.post_2,
       true,
+      None,
       "bulk remove",
+      None,
       Some(parent_id),
     );
     Modlog::create(pool, &[post_form_1, post_form_2]).await?;
 
     // Create one individual (non-bulk) post removal for mixed-dataset tests
-    let individual_form =
-      ModlogInsertForm::mod_remove_post(data.timmy.id, &data.post, true, "individual remove", None);
+    let individual_form = ModlogInsertForm::mod_remove_post(
+      data.timmy.id,
+      &data.post,
+      true,
+      None,
+      "individual remove",
+      None,
+      None,
+    );
     Modlog::create(pool, &[individual_form]).await?;
 
     // show_bulk: Some(true) now includes bulk and non-bulk (show all)
@@ -1023,14 +1064,18 @@ mod tests {
       data.timmy.id,
       &data.post,
       true,
+      None,
       "bulk A",
+      None,
       Some(parent_a_id),
     );
     let post_form_2 = ModlogInsertForm::mod_remove_post(
       data.timmy.id,
       &data.post_2,
       true,
+      None,
       "bulk A",
+      None,
       Some(parent_a_id),
     );
     Modlog::create(pool, &[post_form_1, post_form_2]).await?;
@@ -1041,7 +1086,9 @@ mod tests {
       &data.comment,
       data.community.id,
       true,
+      None,
       "bulk B",
+      None,
       Some(parent_b_id),
     );
     let comment_form_2 = ModlogInsertForm::mod_remove_comment(
@@ -1049,7 +1096,9 @@ mod tests {
       &data.comment_2,
       data.community.id,
       true,
+      None,
       "bulk B",
+      None,
       Some(parent_b_id),
     );
     Modlog::create(pool, &[comment_form_1, comment_form_2]).await?;
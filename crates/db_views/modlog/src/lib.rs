@@ -5,6 +5,7 @@ use lemmy_db_schema::source::{
   modlog::Modlog,
   person::Person,
   post::Post,
+  rule::Rule,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -45,4 +46,7 @@ pub struct ModlogView {
   pub target_post: Option<Post>,
   #[cfg_attr(feature = "full", diesel(embed))]
   pub target_comment: Option<Comment>,
+  /// The rule which was referenced for this action, if any
+  #[cfg_attr(feature = "full", diesel(embed))]
+  pub rule: Option<Rule>,
 }
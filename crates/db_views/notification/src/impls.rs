@@ -1,4 +1,5 @@
 use crate::{CommentView, NotificationData, NotificationView, NotificationViewInternal};
+use chrono::{DateTime, Utc};
 use diesel::{
   BoolExpressionMethods,
   ExpressionMethods,
@@ -35,7 +36,10 @@ use lemmy_diesel_utils::{
     paginate_response,
   },
 };
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  metrics::db_query_timer,
+};
 
 impl NotificationView {
   /// Gets the number of unread mentions
@@ -117,6 +121,13 @@ pub struct NotificationQuery {
   pub show_bot_accounts: Option<bool>,
   pub hide_modlog_names: Option<bool>,
   pub creator_id: Option<PersonId>,
+  /// Only return notifications which were created after this time.
+  pub published_after: Option<DateTime<Utc>>,
+  /// Also return notifications which were created exactly at `published_after`, if their id is
+  /// higher than this. Allows paging through notifications by published time and id.
+  pub published_after_id: Option<NotificationId>,
+  /// Return the oldest notifications first, instead of the newest.
+  pub oldest_first: Option<bool>,
   pub page_cursor: Option<PaginationCursor>,
   pub limit: Option<i64>,
   pub no_limit: Option<bool>,
@@ -173,11 +184,29 @@ impl NotificationQuery {
         query = query.filter(notification::creator_id.eq(creator_id));
       }
 
+      if let Some(published_after) = self.published_after {
+        query = match self.published_after_id {
+          Some(published_after_id) => query.filter(
+            notification::published_at.gt(published_after).or(
+              notification::published_at
+                .eq(published_after)
+                .and(notification::id.gt(published_after_id)),
+            ),
+          ),
+          None => query.filter(notification::published_at.gt(published_after)),
+        };
+      }
+
       // Sorting by published
+      let direction = if self.oldest_first.unwrap_or_default() {
+        SortDirection::Asc
+      } else {
+        SortDirection::Desc
+      };
       let paginated_query = Box::pin(NotificationView::paginate(
         query,
         &self.page_cursor,
-        SortDirection::Desc,
+        direction,
         pool,
         None,
       ))
@@ -186,6 +215,7 @@ impl NotificationQuery {
       // Tie breaker
       .then_order_by(notification_keys::id);
 
+      let _timer = db_query_timer("NotificationQuery::list");
       let conn = &mut get_conn(pool).await?;
       let res = paginated_query
         .load::<NotificationViewInternal>(conn)
@@ -211,6 +241,7 @@ fn map_to_enum(v: NotificationViewInternal, hide_modlog_name: bool) -> Option<No
       target_post: v.post,
       target_comment: v.comment,
       target_instance: v.instance,
+      rule: None,
     };
     let m = m.hide_mod_name(hide_modlog_name);
     NotificationData::ModAction(m)
@@ -249,6 +280,7 @@ fn map_to_enum(v: NotificationViewInternal, hide_modlog_name: bool) -> Option<No
       post_actions: v.post_actions,
       person_actions: v.person_actions,
       tags: v.tags,
+      gallery: Default::default(),
       creator_banned_from_community: v.creator_banned_from_community,
       creator_community_ban_expires_at: v.creator_community_ban_expires_at,
       creator_is_admin: v.creator_is_admin,
@@ -75,6 +75,82 @@ async fn test_private_message() -> LemmyResult<()> {
   cleanup(data, pool).await
 }
 
+#[tokio::test]
+#[serial]
+async fn test_oldest_first_published_after() -> LemmyResult<()> {
+  let pool = &build_db_pool_for_tests();
+  let pool = &mut pool.into();
+  let data = init_data(pool).await?;
+
+  let mut notification_ids = vec![];
+  for content in ["first", "second", "third"] {
+    let form = &PrivateMessageInsertForm::new(data.bob.id, data.alice.id, content.to_string());
+    let pm = PrivateMessage::create(pool, form).await?;
+    let form = NotificationInsertForm::new_private_message(&pm);
+    let notification = Notification::create(pool, &[form]).await?;
+    notification_ids.extend(notification.into_iter().map(|n| n.id));
+  }
+
+  // This is how notification digests page through notifications
+  let oldest = NotificationQuery {
+    oldest_first: Some(true),
+    limit: Some(2),
+    ..Default::default()
+  }
+  .list(pool, &data.alice)
+  .await?;
+  assert_eq!(
+    notification_ids[..2].to_vec(),
+    oldest.iter().map(|n| n.notification.id).collect::<Vec<_>>()
+  );
+
+  let after_first = NotificationQuery {
+    oldest_first: Some(true),
+    published_after: Some(oldest[0].notification.published_at),
+    ..Default::default()
+  }
+  .list(pool, &data.alice)
+  .await?;
+  assert_eq!(
+    notification_ids[1..].to_vec(),
+    after_first
+      .iter()
+      .map(|n| n.notification.id)
+      .collect::<Vec<_>>()
+  );
+
+  // Notifications created together have the same time, so the id is needed to page through them
+  let mut forms = vec![];
+  for content in ["fourth", "fifth"] {
+    let form = &PrivateMessageInsertForm::new(data.bob.id, data.alice.id, content.to_string());
+    let pm = PrivateMessage::create(pool, form).await?;
+    forms.push(NotificationInsertForm::new_private_message(&pm));
+  }
+  let same_time = Notification::create(pool, &forms).await?;
+  let (Some(fourth), Some(fifth)) = (same_time.first(), same_time.get(1)) else {
+    panic!("notifications not created");
+  };
+  assert_eq!(fourth.published_at, fifth.published_at);
+
+  let after_fourth = NotificationQuery {
+    oldest_first: Some(true),
+    published_after: Some(fourth.published_at),
+    published_after_id: Some(fourth.id),
+    ..Default::default()
+  }
+  .list(pool, &data.alice)
+  .await?;
+  assert_eq!(
+    vec![fifth.id],
+    after_fourth
+      .iter()
+      .map(|n| n.notification.id)
+      .collect::<Vec<_>>()
+  );
+
+  cleanup(data, pool).await
+}
+
 #[tokio::test]
 #[serial]
 async fn test_post() -> LemmyResult<()> {
@@ -119,7 +195,7 @@ async fn test_post() -> LemmyResult<()> {
 
   // create a notification entry for removed post
   let mod_remove_post_form =
-    ModlogInsertForm::mod_remove_post(data.bob.id, &post, true, "reason", None);
+    ModlogInsertForm::mod_remove_post(data.bob.id, &post, true, None, "reason", None, None);
   let mod_remove_post = &Modlog::create(pool, &[mod_remove_post_form]).await?[0];
   let notif_form =
     NotificationInsertForm::new_mod_action(mod_remove_post.id, data.alice.id, data.bob.id);
@@ -179,8 +255,10 @@ async fn test_modlog() -> LemmyResult<()> {
     &comment,
     community.id,
     true,
+    None,
     "rule 1",
     None,
+    None,
   );
   let modlog = &Modlog::create(pool, &[form]).await?[0];
 
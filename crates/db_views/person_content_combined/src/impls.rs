@@ -42,7 +42,7 @@ use lemmy_db_views_post_comment_combined::{
   PostCommentCombinedViewInternal,
 };
 use lemmy_diesel_utils::{
-  connection::{DbPool, get_conn},
+  connection::{DbPool, get_conn, get_read_conn},
   pagination::{
     CursorData,
     PagedResponse,
@@ -51,7 +51,10 @@ use lemmy_diesel_utils::{
     paginate_response,
   },
 };
-use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  metrics::db_query_timer,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -201,7 +204,8 @@ impl PersonContentCombinedQuery {
     // Tie breaker
     .then_order_by(key::id);
 
-    let conn = &mut get_conn(pool).await?;
+    let _timer = db_query_timer("PersonContentCombinedQuery::list");
+    let conn = &mut get_read_conn(pool).await?;
     let res = paginated_query
       .load::<PostCommentCombinedViewInternal>(conn)
       .await?;
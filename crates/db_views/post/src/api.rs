@@ -1,7 +1,7 @@
 use crate::PostView;
 use lemmy_db_schema::{
   PostFeatureType,
-  newtypes::{CommunityId, CommunityTagId, LanguageId, MultiCommunityId, PostId},
+  newtypes::{CommunityId, CommunityTagId, LanguageId, MultiCommunityId, PostId, RuleId},
 };
 use lemmy_db_schema_file::enums::{ListingType, PostNotificationsMode, PostSortType};
 use lemmy_diesel_utils::{dburl::DbUrl, pagination::PaginationCursor};
@@ -30,6 +30,19 @@ pub struct CreatePost {
   pub tags: Option<Vec<CommunityTagId>>,
   /// Time when this post should be scheduled. Null means publish immediately.
   pub scheduled_publish_time_at: Option<i64>,
+  /// Images for a gallery post, in order. If no url is given, the first image is used as url.
+  pub gallery: Option<Vec<GalleryImage>>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// An image of a gallery post.
+pub struct GalleryImage {
+  pub url: String,
+  pub alt_text: Option<String>,
+  pub caption: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -71,6 +84,8 @@ pub struct EditPost {
   /// Time when this post should be scheduled. Null means publish immediately.
   pub scheduled_publish_time_at: Option<i64>,
   pub tags: Option<Vec<CommunityTagId>>,
+  /// Replaces the gallery images. An empty list removes the gallery.
+  pub gallery: Option<Vec<GalleryImage>>,
 }
 
 #[skip_serializing_none]
@@ -185,6 +200,7 @@ pub struct ListPostLikes {
   pub limit: Option<i64>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
@@ -193,6 +209,10 @@ pub struct LockPost {
   pub post_id: PostId,
   pub locked: bool,
   pub reason: String,
+  /// A time that the lock will expire, in unix epoch seconds. Only used when locking.
+  ///
+  /// An i64 unix timestamp is used for a simpler API client implementation.
+  pub expires_at: Option<i64>,
 }
 
 #[skip_serializing_none]
@@ -250,6 +270,12 @@ pub struct RemovePost {
   /// Setting this will override whatever `removed` was set to,
   /// leave as null or unset to act just on the post itself.
   pub remove_children: Option<bool>,
+  /// A time that the removal will expire, in unix epoch seconds. Only used when removing.
+  ///
+  /// An i64 unix timestamp is used for a simpler API client implementation.
+  pub expires_at: Option<i64>,
+  /// The community or instance rule which the post violates.
+  pub rule_id: Option<RuleId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
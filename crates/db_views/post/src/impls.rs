@@ -24,6 +24,7 @@ use lemmy_db_schema::{
     local_user::LocalUser,
     person::Person,
     post::{Post, PostActions, post_actions_keys as pa_key, post_keys as key},
+    post_gallery_image::PostGalleryImage,
     site::Site,
   },
   utils::{
@@ -64,7 +65,7 @@ use lemmy_db_schema_file::{
   },
 };
 use lemmy_diesel_utils::{
-  connection::{DbPool, get_conn},
+  connection::{DbPool, get_conn, get_read_conn},
   pagination::{
     CursorData,
     PagedResponse,
@@ -75,7 +76,11 @@ use lemmy_diesel_utils::{
   traits::Crud,
   utils::{CoalesceKey, Commented, now, seconds_to_pg_interval},
 };
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  metrics::db_query_timer,
+};
+use std::collections::HashMap;
 use tracing::debug;
 
 impl PaginationCursorConversion for PostView {
@@ -182,6 +187,7 @@ impl PostView {
     local_instance_id: InstanceId,
     is_mod_or_admin: bool,
   ) -> LemmyResult<Self> {
+    let _timer = db_query_timer("PostView::read");
     let conn = &mut get_conn(pool).await?;
     let my_person_id = my_local_user.person_id();
 
@@ -226,11 +232,31 @@ impl PostView {
 
     query = my_local_user.visible_communities_only(query);
 
-    Commented::new(query)
+    let mut post_view: Self = Commented::new(query)
       .text("PostView::read")
       .first(conn)
       .await
-      .with_lemmy_type(LemmyErrorType::NotFound)
+      .with_lemmy_type(LemmyErrorType::NotFound)?;
+    post_view.gallery.0 = PostGalleryImage::read_for_post(&mut conn.into(), post_id).await?;
+    Ok(post_view)
+  }
+
+  /// Loads the gallery images of all posts with a single query.
+  async fn load_galleries(
+    mut post_views: Vec<Self>,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Vec<Self>> {
+    let post_ids: Vec<_> = post_views.iter().map(|p| p.post.id).collect();
+    let mut galleries: HashMap<PostId, Vec<PostGalleryImage>> = HashMap::new();
+    for image in PostGalleryImage::read_for_posts(pool, &post_ids).await? {
+      galleries.entry(image.post_id).or_default().push(image);
+    }
+    for post_view in &mut post_views {
+      if let Some(gallery) = galleries.remove(&post_view.post.id) {
+        post_view.gallery.0 = gallery;
+      }
+    }
+    Ok(post_views)
   }
 
   /// List all the read posts for your person, ordered by the read date.
@@ -263,6 +289,7 @@ impl PostView {
       .load::<Self>(conn)
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)?;
+    let res = Self::load_galleries(res, &mut conn.into()).await?;
     paginate_response(res, limit, page_cursor)
   }
 
@@ -296,6 +323,7 @@ impl PostView {
       .load::<Self>(conn)
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)?;
+    let res = Self::load_galleries(res, &mut conn.into()).await?;
     paginate_response(res, limit, page_cursor)
   }
 }
@@ -600,12 +628,14 @@ impl PostQuery<'_> {
     let query = pq.as_query();
 
     debug!("Post View Query: {:?}", debug_query::<Pg, _>(&query));
-    let conn = &mut get_conn(pool).await?;
+    let _timer = db_query_timer("PostQuery::list");
+    let conn = &mut get_read_conn(pool).await?;
     let res = Commented::new(query)
       .text("PostQuery::list")
       .load::<PostView>(conn)
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)?;
+    let res = PostView::load_galleries(res, &mut conn.into()).await?;
     paginate_response(res, limit, o.page_cursor)
   }
 
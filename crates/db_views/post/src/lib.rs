@@ -5,6 +5,7 @@ use lemmy_db_schema::source::{
   images::ImageDetails,
   person::{Person, PersonActions},
   post::{Post, PostActions},
+  post_gallery_image::PostGalleryView,
 };
 use serde::{Deserialize, Serialize};
 #[cfg(test)]
@@ -26,6 +27,7 @@ use {
     local_user_can_mod_post,
     post_community_tags_fragment,
     post_creator_is_admin,
+    post_gallery_placeholder,
   },
 };
 
@@ -70,6 +72,13 @@ pub struct PostView {
     )
   )]
   pub tags: CommunityTagsView,
+  /// Gallery images of the post. These are only included when reading or listing posts.
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_gallery_placeholder()
+    )
+  )]
+  pub gallery: PostGalleryView,
   #[cfg_attr(feature = "full",
     diesel(
       select_expression = local_user_can_mod_post()
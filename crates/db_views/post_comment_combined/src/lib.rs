@@ -117,6 +117,7 @@ impl InternalToCombinedView for PostCommentCombinedViewInternal {
         person_actions: v.person_actions,
         creator_is_admin: v.item_creator_is_admin,
         tags: v.tags,
+        gallery: Default::default(),
         can_mod: v.can_mod,
         creator_banned: v.creator_banned,
         creator_ban_expires_at: v.creator_ban_expires_at,
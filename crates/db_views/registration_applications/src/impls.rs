@@ -267,6 +267,13 @@ mod tests {
         show_score: sara_local_user.show_score,
         show_upvote_percentage: sara_local_user.show_upvote_percentage,
         show_person_votes: sara_local_user.show_person_votes,
+        email_notification_frequency: sara_local_user.email_notification_frequency,
+        last_email_digest_at: sara_local_user.last_email_digest_at,
+        image_upload_bytes: 0,
+        image_upload_quota_bytes: None,
+        rate_limit_multiplier: 1,
+        rate_limit_exempt: false,
+        last_email_digest_notification_id: None,
       },
       creator: Person {
         id: sara_person.id,
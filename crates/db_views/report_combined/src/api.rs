@@ -10,6 +10,7 @@ use lemmy_db_schema::{
     PostReportId,
     PrivateMessageId,
     PrivateMessageReportId,
+    RuleId,
   },
 };
 use lemmy_diesel_utils::pagination::PaginationCursor;
@@ -64,6 +65,8 @@ pub struct CreateCommentReport {
   /// The comment violates rules of the local instance. This report will only be shown to local
   /// admins, not to community mods and will not be federated.
   pub violates_instance_rules: Option<bool>,
+  /// The specific community or instance rule which the comment violates.
+  pub rule_id: Option<RuleId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
@@ -85,6 +88,8 @@ pub struct CreatePostReport {
   /// The post violates rules of the local instance. This report will only be shown to local
   /// admins, not to community mods and will not be federated.
   pub violates_instance_rules: Option<bool>,
+  /// The specific community or instance rule which the post violates.
+  pub rule_id: Option<RuleId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -58,7 +58,10 @@ use lemmy_diesel_utils::{
     paginate_response,
   },
 };
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  metrics::db_query_timer,
+};
 
 impl ReportCombinedViewInternal {
   pub async fn read_comment_report(
@@ -280,6 +283,7 @@ impl ReportCombinedQuery {
         // Tie breaker
         .then_order_by(key::id);
 
+    let _timer = db_query_timer("ReportCombinedQuery::list");
     let conn = &mut get_conn(pool).await?;
     let res = paginated_query
       .load::<ReportCombinedViewInternal>(conn)
@@ -356,6 +360,7 @@ impl InternalToCombinedView for ReportCombinedViewInternal {
         creator_ban_expires_at: v.creator_ban_expires_at,
         creator_banned_from_community: v.creator_banned_from_community,
         creator_community_ban_expires_at: v.creator_community_ban_expires_at,
+        rule: v.rule,
       }))
     } else if let (
       Some(comment_report),
@@ -387,6 +392,7 @@ impl InternalToCombinedView for ReportCombinedViewInternal {
         creator_ban_expires_at: v.creator_ban_expires_at,
         creator_banned_from_community: v.creator_banned_from_community,
         creator_community_ban_expires_at: v.creator_community_ban_expires_at,
+        rule: v.rule,
       }))
     } else if let (
       Some(private_message_report),
@@ -595,6 +601,7 @@ mod tests {
       original_post_body: None,
       reason: "from sara".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
     let inserted_post_report = PostReport::report(pool, &sara_report_post_form).await?;
 
@@ -605,6 +612,7 @@ mod tests {
       original_comment_text: "A test comment rv".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
     CommentReport::report(pool, &sara_report_comment_form).await?;
 
@@ -817,6 +825,7 @@ mod tests {
       original_post_body: None,
       reason: "from sara".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
 
     PostReport::report(pool, &sara_report_form).await?;
@@ -830,6 +839,7 @@ mod tests {
       original_post_body: None,
       reason: "from jessica".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
 
     let inserted_jessica_report = PostReport::report(pool, &jessica_report_form).await?;
@@ -948,6 +958,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
 
     CommentReport::report(pool, &sara_report_form).await?;
@@ -959,6 +970,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from jessica".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
 
     let inserted_jessica_report = CommentReport::report(pool, &jessica_report_form).await?;
@@ -1145,6 +1157,7 @@ mod tests {
       original_post_body: None,
       reason: "from sara".into(),
       violates_instance_rules: true,
+      rule_id: None,
     };
     PostReport::report(pool, &report_form).await?;
 
@@ -1174,6 +1187,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
     let comment_report = CommentReport::report(pool, &report_form).await?;
 
@@ -1233,6 +1247,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
     CommentReport::report(pool, &sara_report_form).await?;
 
@@ -1243,6 +1258,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from timmy".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
     CommentReport::report(pool, &timmy_report_form).await?;
 
@@ -1289,6 +1305,7 @@ mod tests {
       original_post_body: None,
       reason: "from sara".into(),
       violates_instance_rules: false,
+      rule_id: None,
     };
     let inserted_sara_report = PostReport::report(pool, &sara_report_form).await?;
 
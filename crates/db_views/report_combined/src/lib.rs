@@ -10,6 +10,7 @@ use lemmy_db_schema::source::{
   post_report::PostReport,
   private_message::PrivateMessage,
   private_message_report::PrivateMessageReport,
+  rule::Rule,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -93,6 +94,8 @@ pub struct ReportCombinedViewInternal {
   pub person_actions: Option<PersonActions>,
   #[diesel(embed)]
   pub comment_actions: Option<CommentActions>,
+  #[diesel(embed)]
+  pub rule: Option<Rule>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -144,6 +147,8 @@ pub struct CommentReportView {
   pub creator_ban_expires_at: Option<DateTime<Utc>>,
   pub creator_banned_from_community: bool,
   pub creator_community_ban_expires_at: Option<DateTime<Utc>>,
+  /// The rule which the comment violates, if any
+  pub rule: Option<Rule>,
 }
 
 #[skip_serializing_none]
@@ -185,4 +190,6 @@ pub struct PostReportView {
   pub creator_ban_expires_at: Option<DateTime<Utc>>,
   pub creator_banned_from_community: bool,
   pub creator_community_ban_expires_at: Option<DateTime<Utc>>,
+  /// The rule which the post violates, if any
+  pub rule: Option<Rule>,
 }
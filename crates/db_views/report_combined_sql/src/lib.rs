@@ -31,6 +31,7 @@ use lemmy_db_schema_file::{
     private_message,
     private_message_report,
     report_combined,
+    rule,
   },
 };
 
@@ -125,6 +126,12 @@ pub fn report_combined_joins(my_person_id: PersonId, local_instance_id: Instance
       .and(comment_actions::person_id.eq(my_person_id)),
   );
 
+  let rule_join = rule::table.on(
+    post_report::rule_id
+      .eq(rule::id.nullable())
+      .or(comment_report::rule_id.eq(rule::id.nullable())),
+  );
+
   report_combined::table
     .left_join(post_report::table)
     .left_join(comment_report::table)
@@ -146,4 +153,5 @@ pub fn report_combined_joins(my_person_id: PersonId, local_instance_id: Instance
     .left_join(post_actions_join)
     .left_join(person_actions_join)
     .left_join(comment_actions_join)
+    .left_join(rule_join)
 }
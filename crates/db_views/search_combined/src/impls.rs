@@ -68,7 +68,7 @@ use lemmy_db_schema_file::{
 };
 use lemmy_db_views_community::MultiCommunityView;
 use lemmy_diesel_utils::{
-  connection::{DbPool, get_conn},
+  connection::{DbPool, get_conn, get_read_conn},
   pagination::{
     CursorData,
     PagedResponse,
@@ -80,6 +80,7 @@ use lemmy_diesel_utils::{
 };
 use lemmy_utils::{
   error::{LemmyErrorType, LemmyResult},
+  metrics::db_query_timer,
   utils::validation::clean_url,
 };
 use url::Url;
@@ -418,7 +419,8 @@ impl SearchCombinedQuery {
     // finally use unique id as tie breaker
     .then_order_by(key::id);
 
-    let conn = &mut get_conn(pool).await?;
+    let _timer = db_query_timer("SearchCombinedQuery::list");
+    let conn = &mut get_read_conn(pool).await?;
     let res = paginated_query
       .load::<SearchCombinedViewInternal>(conn)
       .await?;
@@ -476,6 +478,7 @@ impl InternalToCombinedView for SearchCombinedViewInternal {
         person_actions: v.person_actions,
         post_actions: v.post_actions,
         tags: v.tags,
+        gallery: Default::default(),
         can_mod: v.can_mod,
         creator_banned: v.creator_banned,
         creator_ban_expires_at: v.creator_ban_expires_at,
@@ -3,10 +3,19 @@ use crate::SiteView;
 use extism::FromBytes;
 use extism_convert::Json;
 use lemmy_db_schema::{
-  newtypes::{LanguageId, MultiCommunityId, OAuthProviderId, TaglineId},
+  newtypes::{
+    DataExportId,
+    EmailQueueId,
+    LanguageId,
+    MultiCommunityId,
+    OAuthProviderId,
+    TaglineId,
+  },
   source::{
     comment::Comment,
     community::Community,
+    data_export::DataExport,
+    feed_token::FeedToken,
     instance::Instance,
     language::Language,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
@@ -16,13 +25,18 @@ use lemmy_db_schema::{
     person::Person,
     post::Post,
     private_message::PrivateMessage,
+    rule::Rule,
+    scheduled_task_status::ScheduledTaskStatus,
     tagline::Tagline,
   },
 };
 use lemmy_db_schema_file::{
   InstanceId,
+  PersonId,
   enums::{
+    AdminAuditKind,
     CommentSortType,
+    EmailNotificationFrequency,
     FederationMode,
     ImageMode,
     ListingType,
@@ -142,6 +156,16 @@ pub struct CreateSite {
   pub rate_limit_search_interval_seconds: Option<i32>,
   pub rate_limit_import_user_settings_max_requests: Option<i32>,
   pub rate_limit_import_user_settings_interval_seconds: Option<i32>,
+  pub rate_limit_vote_max_requests: Option<i32>,
+  pub rate_limit_vote_interval_seconds: Option<i32>,
+  pub rate_limit_report_max_requests: Option<i32>,
+  pub rate_limit_report_interval_seconds: Option<i32>,
+  pub rate_limit_follow_max_requests: Option<i32>,
+  pub rate_limit_follow_interval_seconds: Option<i32>,
+  pub rate_limit_private_message_max_requests: Option<i32>,
+  pub rate_limit_private_message_interval_seconds: Option<i32>,
+  pub rate_limit_federation_inbox_max_requests: Option<i32>,
+  pub rate_limit_federation_inbox_interval_seconds: Option<i32>,
   pub federation_enabled: Option<bool>,
   pub registration_mode: Option<RegistrationMode>,
   pub oauth_registration: Option<bool>,
@@ -164,6 +188,23 @@ pub struct CreateSite {
   pub image_max_upload_size: Option<i32>,
   pub image_allow_video_uploads: Option<bool>,
   pub image_upload_disabled: Option<bool>,
+  /// Maximum total size of image uploads per user in bytes. Zero removes the limit.
+  pub image_upload_quota_bytes: Option<i64>,
+  /// Require alt text for image posts.
+  pub require_alt_text: Option<bool>,
+  /// Days after which outgoing activities are deleted.
+  pub sent_activity_retention_days: Option<i32>,
+  /// Days after which the ids of incoming activities are deleted.
+  pub received_activity_retention_days: Option<i32>,
+  /// Days after which ip and user agent of logins are removed. Zero keeps them forever.
+  pub login_ip_retention_days: Option<i32>,
+  /// Days after which read posts are marked as unread. Zero keeps them forever.
+  pub post_read_retention_days: Option<i32>,
+  /// Days after which notifications are deleted. Zero keeps them forever.
+  pub notification_retention_days: Option<i32>,
+  /// Days after which remote posts without local interactions are deleted. Zero keeps them
+  /// forever.
+  pub remote_content_retention_days: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -259,6 +300,21 @@ pub struct EditSite {
   /// The number of settings imports or exports allowed in a given time frame.
   pub rate_limit_import_user_settings_max_requests: Option<i32>,
   pub rate_limit_import_user_settings_interval_seconds: Option<i32>,
+  /// The number of votes allowed in a given time frame.
+  pub rate_limit_vote_max_requests: Option<i32>,
+  pub rate_limit_vote_interval_seconds: Option<i32>,
+  /// The number of reports allowed in a given time frame.
+  pub rate_limit_report_max_requests: Option<i32>,
+  pub rate_limit_report_interval_seconds: Option<i32>,
+  /// The number of community follows allowed in a given time frame.
+  pub rate_limit_follow_max_requests: Option<i32>,
+  pub rate_limit_follow_interval_seconds: Option<i32>,
+  /// The number of private messages allowed in a given time frame.
+  pub rate_limit_private_message_max_requests: Option<i32>,
+  pub rate_limit_private_message_interval_seconds: Option<i32>,
+  /// The number of incoming activities allowed per sending server IP in a given time frame.
+  pub rate_limit_federation_inbox_max_requests: Option<i32>,
+  pub rate_limit_federation_inbox_interval_seconds: Option<i32>,
   /// Whether to enable federation.
   pub federation_enabled: Option<bool>,
   /// A list of blocked URLs
@@ -300,6 +356,23 @@ pub struct EditSite {
   pub image_max_upload_size: Option<i32>,
   pub image_allow_video_uploads: Option<bool>,
   pub image_upload_disabled: Option<bool>,
+  /// Maximum total size of image uploads per user in bytes. Zero removes the limit.
+  pub image_upload_quota_bytes: Option<i64>,
+  /// Require alt text for image posts.
+  pub require_alt_text: Option<bool>,
+  /// Days after which outgoing activities are deleted.
+  pub sent_activity_retention_days: Option<i32>,
+  /// Days after which the ids of incoming activities are deleted.
+  pub received_activity_retention_days: Option<i32>,
+  /// Days after which ip and user agent of logins are removed. Zero keeps them forever.
+  pub login_ip_retention_days: Option<i32>,
+  /// Days after which read posts are marked as unread. Zero keeps them forever.
+  pub post_read_retention_days: Option<i32>,
+  /// Days after which notifications are deleted. Zero keeps them forever.
+  pub notification_retention_days: Option<i32>,
+  /// Days after which remote posts without local interactions are deleted. Zero keeps them
+  /// forever.
+  pub remote_content_retention_days: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -348,6 +421,8 @@ pub struct GetSiteResponse {
   /// Useful for estimating when your application will be approved.
   pub last_application_duration_seconds: Option<i64>,
   pub captcha_enabled: bool,
+  /// The rules of this instance, in display order
+  pub rules: Vec<Rule>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -418,6 +493,16 @@ pub struct ListLoginsResponse {
   pub logins: Vec<LoginToken>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// The token for private feeds, like `/feeds/front/{token}.xml`. None if it was not generated yet
+/// or was revoked.
+pub struct FeedTokenResponse {
+  pub feed_token: Option<FeedToken>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -531,6 +616,8 @@ pub struct SaveUserSettings {
   pub show_avatars: Option<bool>,
   /// Sends notifications to your email.
   pub send_notifications_to_email: Option<bool>,
+  /// How often notification emails are sent, either immediately or as a digest.
+  pub email_notification_frequency: Option<EmailNotificationFrequency>,
   /// Whether this account is a bot account. Users can hide these accounts easily if they wish.
   pub bot_account: Option<bool>,
   /// Whether to show bot accounts.
@@ -632,6 +719,84 @@ pub struct ListTaglines {
   pub limit: Option<i64>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Lists outgoing emails, for admins.
+pub struct ListEmailQueue {
+  /// Only show emails which couldn't be delivered.
+  pub failed_only: Option<bool>,
+  pub page_cursor: Option<PaginationCursor>,
+  pub limit: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Number of items which the data retention tasks would remove if they ran now. Empty if the
+/// retention period isn't set.
+pub struct RetentionReport {
+  pub sent_activities: i64,
+  pub received_activities: i64,
+  /// Logins whose ip and user agent would be removed.
+  pub login_ips: Option<i64>,
+  /// Posts which would be marked as unread for a user.
+  pub post_read_states: Option<i64>,
+  pub notifications: Option<i64>,
+  /// Remote posts without local interactions. Their comments are deleted as well.
+  pub remote_posts: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Lists changes to site settings and other admin actions, for admins.
+pub struct ListAdminAuditLog {
+  pub kind: Option<AdminAuditKind>,
+  /// Only show actions of this admin.
+  pub admin_person_id: Option<PersonId>,
+  pub page_cursor: Option<PaginationCursor>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Sends a failed email again.
+pub struct RetryEmail {
+  pub id: EmailQueueId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Status of all background tasks, for admins.
+pub struct ListScheduledTasksResponse {
+  pub scheduled_tasks: Vec<ScheduledTaskStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Changes how often a background task runs.
+pub struct EditScheduledTask {
+  pub name: String,
+  /// Must be at least 60 seconds.
+  pub interval_seconds: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Result of reloading the config file.
+pub struct ReloadConfigResponse {
+  /// Changed settings which only take effect after restarting Lemmy.
+  pub restart_required: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
@@ -745,6 +910,28 @@ pub struct ExportDataResponse {
   pub settings: UserSettingsBackup,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Your most recent data export archive.
+pub struct DataExportResponse {
+  pub data_export: Option<DataExport>,
+  /// Temporary link to download the archive, once it is ready.
+  pub download_url: Option<Url>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Download a data export archive. The parameters come from a signed link, so no login is needed.
+pub struct DownloadDataExport {
+  pub id: DataExportId,
+  /// Unix timestamp after which the link is invalid.
+  pub expires: i64,
+  pub signature: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
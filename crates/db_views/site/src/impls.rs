@@ -57,26 +57,29 @@ impl SiteView {
   pub async fn read_local(pool: &mut DbPool<'_>) -> LemmyResult<Self> {
     static CACHE: CacheLock<SiteView> = LazyLock::new(build_cache);
     CACHE
-      .try_get_with((), async move {
-        let conn = &mut get_conn(pool).await?;
-        let local_site = site::table
-          .inner_join(local_site::table)
-          .inner_join(instance::table)
-          .inner_join(
-            local_site_rate_limit::table
-              .on(local_site::id.eq(local_site_rate_limit::local_site_id)),
-          )
-          .select(Self::as_select())
-          .first(conn)
-          .await
-          .optional()?
-          .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
-        Ok(local_site)
-      })
+      .try_get_with((), Self::read_local_uncached(pool))
       .await
       .map_err(|e: Arc<LemmyError>| anyhow::anyhow!("err getting local site: {e:?}").into())
   }
 
+  /// Like [SiteView::read_local], but always reads the current state from the database. Use this
+  /// inside of transactions which change the site.
+  pub async fn read_local_uncached(pool: &mut DbPool<'_>) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let site_view = site::table
+      .inner_join(local_site::table)
+      .inner_join(instance::table)
+      .inner_join(
+        local_site_rate_limit::table.on(local_site::id.eq(local_site_rate_limit::local_site_id)),
+      )
+      .select(Self::as_select())
+      .first(conn)
+      .await
+      .optional()?
+      .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+    Ok(site_view)
+  }
+
   /// A special site bot user, solely made for following non-local communities for
   /// multi-communities.
   pub async fn read_system_account(pool: &mut DbPool<'_>) -> LemmyResult<Person> {
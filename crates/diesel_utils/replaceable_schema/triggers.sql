@@ -876,3 +876,43 @@ CREATE TRIGGER multi_community_remove_subscribers
     FOR EACH ROW
     WHEN (OLD.follow_state = 'Accepted')
     EXECUTE FUNCTION r.multicommunity_subscribers_decrement ();
+-- Track the total size of images uploaded by each local user. Generated post thumbnails don't
+-- count towards the quota.
+CREATE FUNCTION r.local_image_size_increment ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        local_user
+    SET
+        image_upload_bytes = image_upload_bytes + NEW.size
+    WHERE
+        person_id = NEW.person_id;
+    RETURN NULL;
+END
+$$;
+CREATE FUNCTION r.local_image_size_decrement ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        local_user
+    SET
+        image_upload_bytes = greatest (image_upload_bytes - OLD.size, 0)
+    WHERE
+        person_id = OLD.person_id;
+    RETURN NULL;
+END
+$$;
+CREATE TRIGGER local_image_add_size
+    AFTER INSERT ON local_image
+    FOR EACH ROW
+    WHEN (NEW.thumbnail_for_post_id IS NULL AND NEW.size > 0)
+    EXECUTE FUNCTION r.local_image_size_increment ();
+CREATE TRIGGER local_image_remove_size
+    AFTER DELETE ON local_image
+    FOR EACH ROW
+    WHEN (OLD.thumbnail_for_post_id IS NULL AND OLD.size > 0)
+    EXECUTE FUNCTION r.local_image_size_decrement ();
@@ -255,6 +255,14 @@ pub fn run(options: Options, db_url: &str) -> anyhow::Result<Branch> {
   Ok(output)
 }
 
+/// Checks if the database schema is older than this Lemmy version, without changing anything.
+pub fn has_pending_migrations(db_url: &str) -> anyhow::Result<bool> {
+  let conn = &mut PgConnection::establish(db_url)?;
+  conn
+    .has_pending_migration(migrations())
+    .map_err(convert_err)
+}
+
 fn run_replaceable_schema(conn: &mut PgConnection) -> anyhow::Result<()> {
   conn.transaction(|conn| {
     conn
@@ -161,10 +161,10 @@ pub fn diesel_string_update(opt: Option<&str>) -> Option<Option<String>> {
 }
 
 /// Takes an API optional number, and converts it to an optional diesel DB update. Zero means erase.
-pub fn diesel_opt_number_update(opt: Option<i32>) -> Option<Option<i32>> {
+pub fn diesel_opt_number_update<T: Default + PartialEq>(opt: Option<T>) -> Option<Option<T>> {
   match opt {
     // Zero is an erase
-    Some(0) => Some(None),
+    Some(num) if num == T::default() => Some(None),
     Some(num) => Some(Some(num)),
     None => None,
   }
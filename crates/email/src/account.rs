@@ -27,9 +27,17 @@ pub async fn send_password_reset_email(
   let reset_link = format!("{}/password_change/{}", protocol_and_hostname, &token);
   let email = user_email(user)?;
   let body = lang.password_reset_body(reset_link, &user.person.name);
-  send_email(subject, email, user.person.name.clone(), body, settings);
+  send_email(
+    subject,
+    email,
+    user.person.name.clone(),
+    body,
+    pool,
+    settings,
+  )
+  .await?;
 
-  // Insert the row after successful send, to avoid using daily reset limit while
+  // Insert the row after the email is queued, to avoid using daily reset limit while
   // email sending is broken.
   let local_user_id = user.local_user.id;
   PasswordResetRequest::create(pool, local_user_id, token.clone()).await?;
@@ -66,7 +74,15 @@ pub async fn send_verification_email(
     lang.verify_email_body(&settings.hostname, &user.person.name, verify_link)
   };
 
-  send_email(subject, new_email, user.person.name.clone(), body, settings);
+  send_email(
+    subject,
+    new_email,
+    user.person.name.clone(),
+    body,
+    pool,
+    settings,
+  )
+  .await?;
   Ok(())
 }
 
@@ -89,21 +105,31 @@ pub async fn send_verification_email_if_required(
   }
 }
 
-pub fn send_application_approved_email(
+pub async fn send_application_approved_email(
   user: &LocalUserView,
+  pool: &mut DbPool<'_>,
   settings: &'static Settings,
 ) -> LemmyResult<()> {
   let lang = user_language(&user.local_user);
   let subject = lang.registration_approved_subject(&user.person.name);
   let email = user_email(user)?;
   let body = lang.registration_approved_body(&settings.hostname);
-  send_email(subject, email, user.person.name.clone(), body, settings);
+  send_email(
+    subject,
+    email,
+    user.person.name.clone(),
+    body,
+    pool,
+    settings,
+  )
+  .await?;
   Ok(())
 }
 
-pub fn send_application_denied_email(
+pub async fn send_application_denied_email(
   user: &LocalUserView,
   deny_reason: Option<String>,
+  pool: &mut DbPool<'_>,
   settings: &'static Settings,
 ) -> LemmyResult<()> {
   let lang = user_language(&user.local_user);
@@ -116,12 +142,21 @@ pub fn send_application_denied_email(
     }
     None => lang.registration_denied_body(&settings.hostname),
   };
-  send_email(subject, email, user.person.name.clone(), body, settings);
+  send_email(
+    subject,
+    email,
+    user.person.name.clone(),
+    body,
+    pool,
+    settings,
+  )
+  .await?;
   Ok(())
 }
 
-pub fn send_email_verified_email(
+pub async fn send_email_verified_email(
   user: &LocalUserView,
+  pool: &mut DbPool<'_>,
   settings: &'static Settings,
 ) -> LemmyResult<()> {
   let lang = user_language(&user.local_user);
@@ -133,7 +168,32 @@ pub fn send_email_verified_email(
     email,
     user.person.name.clone(),
     body.to_string(),
+    pool,
     settings,
-  );
+  )
+  .await?;
+  Ok(())
+}
+
+/// Tells the user that their data export archive can be downloaded.
+pub async fn send_data_export_email(
+  user: &LocalUserView,
+  download_link: &str,
+  pool: &mut DbPool<'_>,
+  settings: &'static Settings,
+) -> LemmyResult<()> {
+  let lang = user_language(&user.local_user);
+  let subject = lang.data_export_ready_subject(&settings.hostname);
+  let email = user_email(user)?;
+  let body = lang.data_export_ready_body(&settings.hostname, download_link, &user.person.name);
+  send_email(
+    subject,
+    email,
+    user.person.name.clone(),
+    body,
+    pool,
+    settings,
+  )
+  .await?;
   Ok(())
 }
@@ -0,0 +1,226 @@
+use crate::{inbox_link, send::send_email, user_email, user_language};
+use chrono::{DateTime, TimeDelta, Utc};
+use lemmy_db_schema::{
+  newtypes::NotificationId,
+  source::local_user::{LocalUser, LocalUserUpdateForm},
+};
+use lemmy_db_schema_file::enums::{EmailNotificationFrequency, NotificationType};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_notification::{NotificationData, NotificationView, impls::NotificationQuery};
+use lemmy_diesel_utils::{connection::DbPool, traits::Crud};
+use lemmy_utils::{
+  error::LemmyResult,
+  settings::structs::Settings,
+  utils::markdown::markdown_to_html,
+};
+use tracing::warn;
+
+/// Maximum number of notifications listed in a single digest. The rest are included in the next
+/// digest.
+const DIGEST_SIZE: usize = 50;
+
+/// Digests are checked hourly, so allow some leeway to avoid delaying them by an extra hour.
+const DIGEST_LEEWAY: TimeDelta = TimeDelta::minutes(5);
+
+/// Sends a single email with all new unread notifications to users who don't want to receive
+/// notification emails immediately. Needs to run hourly.
+pub async fn send_notification_digests(
+  pool: &mut DbPool<'_>,
+  settings: &'static Settings,
+) -> LemmyResult<()> {
+  let now = Utc::now();
+  for (frequency, interval) in [
+    (EmailNotificationFrequency::Hourly, TimeDelta::hours(1)),
+    (EmailNotificationFrequency::Daily, TimeDelta::days(1)),
+    (EmailNotificationFrequency::Weekly, TimeDelta::weeks(1)),
+  ] {
+    let last_digest_before = now - interval + DIGEST_LEEWAY;
+    let users = LocalUserView::list_for_email_digest(pool, frequency, last_digest_before).await?;
+    for user in users {
+      send_digest(&user, now, pool, settings)
+        .await
+        .inspect_err(|e| warn!("Failed to send notification digest: {e}"))
+        .ok();
+    }
+  }
+  Ok(())
+}
+
+async fn send_digest(
+  local_user_view: &LocalUserView,
+  now: DateTime<Utc>,
+  pool: &mut DbPool<'_>,
+  settings: &'static Settings,
+) -> LemmyResult<()> {
+  let local_user = &local_user_view.local_user;
+  let notifications: Vec<_> = NotificationQuery {
+    unread_only: Some(true),
+    show_bot_accounts: Some(local_user.show_bot_accounts),
+    hide_modlog_names: Some(true),
+    published_after: Some(local_user.last_email_digest_at),
+    published_after_id: local_user.last_email_digest_notification_id,
+    oldest_first: Some(true),
+    // One more than fits, to find out if there are more
+    limit: Some((DIGEST_SIZE + 1).try_into()?),
+    ..Default::default()
+  }
+  .list(pool, &local_user_view.person)
+  .await?
+  .into_iter()
+  // Notifications which arrive while digests are being sent go into the next digest
+  .filter(|n| n.notification.published_at <= now)
+  .collect();
+  let (notifications, included_until, included_id, has_more) = limit_digest(
+    notifications,
+    |n| (n.notification.published_at, n.notification.id),
+    now,
+  );
+
+  if !notifications.is_empty() && !local_user_view.banned {
+    let lang = user_language(local_user);
+    let inbox_link = inbox_link(settings);
+    let items = notifications
+      .iter()
+      .map(|n| digest_item(n, &inbox_link, settings))
+      .collect::<LemmyResult<Vec<_>>>()?
+      .into_iter()
+      .map(|(kind, creator, link, content)| {
+        let title = match kind {
+          NotificationType::Mention => lang.mention_from_x(creator),
+          NotificationType::Reply => lang.reply_from_x(creator),
+          NotificationType::Subscribed => lang.subscribed().to_string(),
+          NotificationType::PrivateMessage => lang.private_message_from_x(creator),
+          NotificationType::ModAction => lang.mod_action().to_string(),
+        };
+        format!("<li><a href=\"{link}\">{title}</a>{content}</li>")
+      })
+      .collect::<String>();
+
+    let subject = format!(
+      "{} - {} ({})",
+      settings.hostname,
+      lang.notifications(),
+      notifications.len()
+    );
+    let more = if has_more {
+      format!("<p>{}</p>", lang.notification_digest_more())
+    } else {
+      String::new()
+    };
+    let body = format!(
+      "<h1>{}</h1><ul>{items}</ul>{more}<p><a href=\"{inbox_link}\">{inbox_link}</a></p>",
+      lang.notifications()
+    );
+    let email = user_email(local_user_view)?;
+    send_email(
+      subject,
+      email,
+      local_user_view.person.name.clone(),
+      body,
+      pool,
+      settings,
+    )
+    .await?;
+  }
+
+  let form = LocalUserUpdateForm {
+    last_email_digest_at: Some(included_until),
+    last_email_digest_notification_id: Some(included_id),
+    ..Default::default()
+  };
+  LocalUser::update(pool, local_user.id, &form).await?;
+  Ok(())
+}
+
+/// Limits oldest-first notifications to [`DIGEST_SIZE`]. Returns the notifications to send, the
+/// time and id until which notifications were included and whether more are left for the next
+/// digest. The id is needed because multiple notifications can be published at the same time.
+fn limit_digest<T>(
+  mut notifications: Vec<T>,
+  published: impl Fn(&T) -> (DateTime<Utc>, NotificationId),
+  now: DateTime<Utc>,
+) -> (Vec<T>, DateTime<Utc>, Option<NotificationId>, bool) {
+  if notifications.len() <= DIGEST_SIZE {
+    return (notifications, now, None, false);
+  }
+  notifications.truncate(DIGEST_SIZE);
+  match notifications.last().map(published) {
+    Some((included_until, included_id)) => (notifications, included_until, Some(included_id), true),
+    None => (notifications, now, None, true),
+  }
+}
+
+/// Returns kind, creator name, link and html content of a notification.
+fn digest_item(
+  view: &NotificationView,
+  inbox_link: &str,
+  settings: &Settings,
+) -> LemmyResult<(NotificationType, String, String, String)> {
+  let (creator, link, content) = match &view.data {
+    NotificationData::Comment(c) => (
+      c.creator.name.clone(),
+      c.comment.local_url(settings)?.to_string(),
+      Some(c.comment.content.clone()),
+    ),
+    NotificationData::Post(p) => (
+      p.creator.name.clone(),
+      p.post.local_url(settings)?.to_string(),
+      p.post.body.clone(),
+    ),
+    NotificationData::PrivateMessage(pm) => (
+      pm.creator.name.clone(),
+      inbox_link.to_string(),
+      Some(pm.private_message.content.clone()),
+    ),
+    NotificationData::ModAction(m) => (
+      String::new(),
+      inbox_link.to_string(),
+      m.modlog.reason.clone(),
+    ),
+  };
+  let content = content.map(|c| markdown_to_html(&c)).unwrap_or_default();
+  Ok((view.notification.kind, creator, link, content))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_limit_digest() {
+    let now = Utc::now();
+    // Notifications are published in pairs with the same time
+    let published = |count: i32| -> Vec<(DateTime<Utc>, NotificationId)> {
+      (0..count)
+        .map(|i| {
+          (
+            now - TimeDelta::minutes((count - (i + 1) / 2).into()),
+            NotificationId(i),
+          )
+        })
+        .collect()
+    };
+
+    // Everything fits, so the next digest starts now
+    let (included, until, until_id, has_more) = limit_digest(published(3), |p| *p, now);
+    assert_eq!(3, included.len());
+    assert_eq!(now, until);
+    assert_eq!(None, until_id);
+    assert!(!has_more);
+
+    // Overflow is left for the next digest, which starts after the last included notification.
+    // The first notification left over was published at the same time, so it is told apart by id.
+    let all = published(60);
+    let (included, until, until_id, has_more) = limit_digest(all.clone(), |p| *p, now);
+    assert_eq!(DIGEST_SIZE, included.len());
+    assert_eq!(all.get(DIGEST_SIZE - 1), Some(&(until, NotificationId(49))));
+    assert_eq!(Some(NotificationId(49)), until_id);
+    assert!(
+      all
+        .get(DIGEST_SIZE)
+        .is_some_and(|(p, id)| *p == until && id.0 > 49)
+    );
+    assert!(has_more);
+  }
+}
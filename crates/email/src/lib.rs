@@ -10,8 +10,13 @@ use translations::Lang;
 
 pub mod account;
 pub mod admin;
+pub mod digest;
+pub mod lmtp;
 pub mod notifications;
+pub mod queue;
+pub mod reply;
 mod send;
+mod transport;
 
 /// Avoid warnings for unused 0.19 translations
 #[expect(mismatched_lifetime_syntaxes)]
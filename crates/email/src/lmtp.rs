@@ -0,0 +1,233 @@
+use lemmy_utils::error::LemmyResult;
+use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+  io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+  net::{TcpListener, TcpStream},
+  sync::Semaphore,
+  time::timeout,
+};
+use tracing::{info, warn};
+
+/// Maximum size of an incoming email, larger messages are rejected.
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Maximum length of a command line including CRLF (RFC 5321 section 4.5.3.1). Lines of the message
+/// are read in chunks of this size, so they may be longer.
+const MAX_LINE_LENGTH: u64 = 1000;
+
+/// Connections are closed if the client doesn't send a line for this long (RFC 5321 section
+/// 4.5.3.2 recommends at least 5 minutes).
+const READ_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Maximum number of simultaneous connections, further clients have to wait.
+const MAX_CONNECTIONS: usize = 50;
+
+/// Minimal LMTP server (RFC 2033) for receiving replies to notification emails. The mail server
+/// of the instance should deliver emails for the reply address here. The handler is called once
+/// for each recipient with the raw message.
+pub async fn serve_lmtp<H, F>(bind: SocketAddr, hostname: String, handler: H) -> LemmyResult<()>
+where
+  H: Fn(String, Vec<u8>) -> F + Clone + Send + 'static,
+  F: Future<Output = LemmyResult<()>> + Send,
+{
+  let listener = TcpListener::bind(bind).await?;
+  let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+  info!("Listening for email replies with LMTP on {bind}");
+  loop {
+    let permit = connections.clone().acquire_owned().await?;
+    let (stream, _) = listener.accept().await?;
+    let hostname = hostname.clone();
+    let handler = handler.clone();
+    tokio::spawn(async move {
+      if let Err(e) = handle_connection(stream, &hostname, handler).await {
+        warn!("LMTP connection failed: {e}");
+      }
+      drop(permit);
+    });
+  }
+}
+
+async fn handle_connection<H, F>(stream: TcpStream, hostname: &str, handler: H) -> LemmyResult<()>
+where
+  H: Fn(String, Vec<u8>) -> F,
+  F: Future<Output = LemmyResult<()>>,
+{
+  let (reader, mut writer) = stream.into_split();
+  let mut reader = BufReader::new(reader);
+  let mut recipients: Vec<String> = vec![];
+  let mut has_sender = false;
+  let mut line = vec![];
+
+  writer
+    .write_all(format!("220 {hostname} LMTP ready\r\n").as_bytes())
+    .await?;
+  loop {
+    line.clear();
+    if read_line(&mut reader, &mut line).await? == 0 {
+      return Ok(());
+    }
+    if !line.ends_with(b"\n") {
+      writer.write_all(b"500 Line too long\r\n").await?;
+      return Ok(());
+    }
+    let command = String::from_utf8_lossy(&line);
+    let command = command.trim_end();
+    let verb = command
+      .split_whitespace()
+      .next()
+      .unwrap_or_default()
+      .to_ascii_uppercase();
+    let reply = match verb.as_str() {
+      "LHLO" => format!("250-{hostname}\r\n250-PIPELINING\r\n250 SIZE {MAX_MESSAGE_SIZE}\r\n"),
+      "MAIL" => {
+        has_sender = true;
+        recipients.clear();
+        "250 OK\r\n".to_string()
+      }
+      "RCPT" if has_sender => match parse_path(command) {
+        Some(recipient) => {
+          recipients.push(recipient);
+          "250 OK\r\n".to_string()
+        }
+        None => "501 Invalid recipient\r\n".to_string(),
+      },
+      "DATA" if !recipients.is_empty() => {
+        writer.write_all(b"354 Start mail input\r\n").await?;
+        let data = read_data(&mut reader).await?;
+        // LMTP expects one reply per recipient
+        let mut reply = String::new();
+        for recipient in recipients.drain(..) {
+          match &data {
+            Some(data) => match handler(recipient.clone(), data.clone()).await {
+              Ok(()) => reply.push_str("250 OK\r\n"),
+              Err(e) => {
+                warn!("Rejected email reply to {recipient}: {e}");
+                reply.push_str("550 Reply rejected\r\n");
+              }
+            },
+            None => reply.push_str("552 Message too large\r\n"),
+          }
+        }
+        has_sender = false;
+        reply
+      }
+      "RCPT" | "DATA" => "503 Bad sequence of commands\r\n".to_string(),
+      "RSET" => {
+        has_sender = false;
+        recipients.clear();
+        "250 OK\r\n".to_string()
+      }
+      "NOOP" => "250 OK\r\n".to_string(),
+      "QUIT" => {
+        writer.write_all(b"221 Bye\r\n").await?;
+        return Ok(());
+      }
+      _ => "502 Command not implemented\r\n".to_string(),
+    };
+    writer.write_all(reply.as_bytes()).await?;
+  }
+}
+
+/// Reads a line, or the first [`MAX_LINE_LENGTH`] bytes of it. Fails if nothing is received
+/// within [`READ_TIMEOUT`].
+async fn read_line<R>(reader: &mut R, line: &mut Vec<u8>) -> LemmyResult<usize>
+where
+  R: AsyncBufRead + Unpin,
+{
+  let read = reader.take(MAX_LINE_LENGTH).read_until(b'\n', line);
+  Ok(timeout(READ_TIMEOUT, read).await??)
+}
+
+/// Reads the message until the terminating `.` line, and removes dot-stuffing. Returns `None` if
+/// the message exceeds [`MAX_MESSAGE_SIZE`].
+async fn read_data<R>(reader: &mut R) -> LemmyResult<Option<Vec<u8>>>
+where
+  R: AsyncBufRead + Unpin,
+{
+  let mut data = vec![];
+  let mut too_large = false;
+  let mut line = vec![];
+  // Long lines are read in multiple chunks, only the first one can be a terminator or dot-stuffed
+  let mut line_start = true;
+  loop {
+    line.clear();
+    if read_line(reader, &mut line).await? == 0 {
+      return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    if line_start && (line == b".\r\n" || line == b".\n") {
+      break;
+    }
+    let content = if line_start {
+      line.strip_prefix(b".").unwrap_or(&line)
+    } else {
+      &line
+    };
+    if data.len() + content.len() > MAX_MESSAGE_SIZE {
+      too_large = true;
+    } else {
+      data.extend_from_slice(content);
+    }
+    line_start = line.ends_with(b"\n");
+  }
+  Ok((!too_large).then_some(data))
+}
+
+/// Extracts the address from a command like `RCPT TO:<user@example.com>`.
+fn parse_path(command: &str) -> Option<String> {
+  let start = command.find('<')?;
+  let end = command.rfind('>')?;
+  let address = command.get(start + 1..end)?;
+  (!address.is_empty()).then(|| address.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_parse_path() {
+    assert_eq!(
+      Some("reply+c1.2.abc@example.com".to_string()),
+      parse_path("RCPT TO:<reply+c1.2.abc@example.com>")
+    );
+    assert_eq!(None, parse_path("RCPT TO:<>"));
+    assert_eq!(None, parse_path("RCPT TO:user@example.com"));
+  }
+
+  #[tokio::test]
+  async fn test_read_data() -> LemmyResult<()> {
+    let input = b"Subject: test\r\n\r\n..leading dot\r\nbody\r\n.\r\nQUIT\r\n";
+    let mut reader = BufReader::new(&input[..]);
+    let data = read_data(&mut reader).await?;
+    assert_eq!(
+      Some(b"Subject: test\r\n\r\n.leading dot\r\nbody\r\n".to_vec()),
+      data
+    );
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_read_data_long_line() -> LemmyResult<()> {
+    // A dot at a chunk boundary within a long line is kept
+    let long_line = "a".repeat(usize::try_from(MAX_LINE_LENGTH)?);
+    let input = format!("{long_line}.\r\n.\r\n");
+    let mut reader = BufReader::new(input.as_bytes());
+    let data = read_data(&mut reader).await?;
+    assert_eq!(Some(format!("{long_line}.\r\n").into_bytes()), data);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_read_line_limit() -> LemmyResult<()> {
+    let input = "a".repeat(2000);
+    let mut reader = BufReader::new(input.as_bytes());
+    let mut line = vec![];
+    assert_eq!(
+      MAX_LINE_LENGTH,
+      u64::try_from(read_line(&mut reader, &mut line).await?)?
+    );
+    assert!(!line.ends_with(b"\n"));
+    Ok(())
+  }
+}
@@ -1,9 +1,13 @@
-use crate::{inbox_link, send::send_email, user_language};
+use crate::{inbox_link, send::send_rate_limited_email, user_language};
 use lemmy_db_schema::source::{comment::Comment, community::Community, person::Person, post::Post};
-use lemmy_db_schema_file::enums::ModlogKind;
+use lemmy_db_schema_file::enums::{EmailNotificationFrequency, ModlogKind};
 use lemmy_db_views_local_user::LocalUserView;
-use lemmy_diesel_utils::dburl::DbUrl;
-use lemmy_utils::{settings::structs::Settings, utils::markdown::markdown_to_html};
+use lemmy_diesel_utils::{connection::DbPool, dburl::DbUrl};
+use lemmy_utils::{
+  error::LemmyResult,
+  settings::structs::Settings,
+  utils::markdown::markdown_to_html,
+};
 
 pub enum NotificationEmailData<'a> {
   Mention {
@@ -35,14 +39,27 @@ pub enum NotificationEmailData<'a> {
   },
 }
 
-pub fn send_notification_email(
+/// Notification emails are rate limited per recipient, see
+/// [`lemmy_utils::settings::structs::EmailConfig::notification_emails_per_hour`].
+///
+/// If `reply_to` is set, the recipient can answer directly to the email, see
+/// [`crate::reply::reply_address`].
+pub async fn send_notification_email(
   local_user_view: LocalUserView,
   link: DbUrl,
   data: NotificationEmailData,
+  reply_to: Option<String>,
+  pool: &mut DbPool<'_>,
   settings: &'static Settings,
-) {
+) -> LemmyResult<()> {
   if local_user_view.banned || !local_user_view.local_user.send_notifications_to_email {
-    return;
+    return Ok(());
+  }
+  // Otherwise the notification is included in the next digest
+  if local_user_view.local_user.email_notification_frequency
+    != EmailNotificationFrequency::Immediate
+  {
+    return Ok(());
   }
 
   let inbox_link = inbox_link(settings);
@@ -134,12 +151,16 @@ pub fn send_notification_email(
   };
 
   if let Some(user_email) = local_user_view.local_user.email {
-    send_email(
+    send_rate_limited_email(
       subject,
       user_email,
       local_user_view.person.name,
       body,
+      reply_to,
+      pool,
       settings,
-    );
+    )
+    .await?;
   }
+  Ok(())
 }
@@ -0,0 +1,114 @@
+use crate::transport::Transport;
+use lemmy_db_schema::{newtypes::EmailQueueId, source::email_queue::EmailQueue};
+use lemmy_diesel_utils::connection::{ActualDbPool, DbPool};
+use lemmy_utils::{
+  error::LemmyResult,
+  metrics::email_sent,
+  settings::{
+    structs::{EmailConfig, Settings},
+    subscribe_settings,
+  },
+};
+use reqwest_middleware::ClientWithMiddleware;
+use std::time::Duration;
+use tokio::{
+  sync::{Notify, watch},
+  time::timeout,
+};
+use tracing::{info, warn};
+
+/// Wakes up the queue worker when a new email is queued.
+pub(crate) static NEW_EMAIL: Notify = Notify::const_new();
+
+/// How often the queue is checked for emails which are due for a retry, or which were queued
+/// by another Lemmy process.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Number of emails which are claimed from the queue at once.
+const BATCH_SIZE: i64 = 50;
+
+/// Sends emails from the queue until the process exits. Multiple Lemmy processes can run this at
+/// the same time, each email is only claimed by one of them. The transport is recreated when the
+/// settings are reloaded.
+pub async fn run_email_queue(pool: ActualDbPool, client: ClientWithMiddleware) {
+  let mut settings_receiver = subscribe_settings();
+  loop {
+    let settings: &'static Settings = *settings_receiver.borrow_and_update();
+    match &settings.email {
+      Some(email_config) => match Transport::new(email_config, client.clone(), settings) {
+        Ok(transport) => {
+          process_queue(
+            &pool,
+            &transport,
+            email_config,
+            settings,
+            &settings_receiver,
+          )
+          .await
+        }
+        Err(e) => warn!("Failed to initialize email transport: {e}"),
+      },
+      None => info!("Email is not configured, not sending emails"),
+    }
+
+    // Wait for the settings to be reloaded
+    if settings_receiver.changed().await.is_err() {
+      return;
+    }
+  }
+}
+
+/// Sends emails until the settings are reloaded.
+async fn process_queue(
+  pool: &ActualDbPool,
+  transport: &Transport,
+  email_config: &EmailConfig,
+  settings: &Settings,
+  settings_receiver: &watch::Receiver<&'static Settings>,
+) {
+  while !settings_receiver.has_changed().unwrap_or(true) {
+    send_due_emails(&mut pool.into(), transport, email_config, settings)
+      .await
+      .inspect_err(|e| warn!("Failed to process email queue: {e}"))
+      .ok();
+
+    // Wait until a new email is queued, or retries become due
+    timeout(POLL_INTERVAL, NEW_EMAIL.notified()).await.ok();
+  }
+}
+
+/// Puts a failed email back into the queue and sends it right away.
+pub async fn retry_email(pool: &mut DbPool<'_>, email_id: EmailQueueId) -> LemmyResult<EmailQueue> {
+  let email = EmailQueue::retry(pool, email_id).await?;
+  NEW_EMAIL.notify_one();
+  Ok(email)
+}
+
+async fn send_due_emails(
+  pool: &mut DbPool<'_>,
+  transport: &Transport,
+  email_config: &EmailConfig,
+  settings: &Settings,
+) -> LemmyResult<()> {
+  loop {
+    let emails = EmailQueue::claim_due(pool, BATCH_SIZE).await?;
+    if emails.is_empty() {
+      return Ok(());
+    }
+    for email in emails {
+      match transport.send(&email, email_config, settings).await {
+        Ok(()) => {
+          email_sent(true);
+          EmailQueue::mark_sent(pool, email.id).await?;
+        }
+        Err(e) => {
+          email_sent(false);
+          warn!("Failed to send email {}: {e}", email.id.0);
+          email
+            .mark_attempt_failed(pool, e.cause.to_string(), email_config.max_attempts)
+            .await?;
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,232 @@
+use hmac::{Hmac, Mac};
+use lemmy_db_schema::{
+  newtypes::{CommentId, LocalUserId, PostId},
+  source::secret::Secret,
+};
+use lemmy_db_schema_file::PersonId;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  settings::structs::Settings,
+};
+use mail_parser::MessageParser;
+use sha2::Sha256;
+use std::fmt::Write;
+
+/// Number of signature bytes included in the reply address.
+const SIGNATURE_LEN: usize = 8;
+
+/// What a reply to a notification email is posted as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyTarget {
+  /// Reply to this comment.
+  Comment(CommentId),
+  /// Top-level comment in this post.
+  Post(PostId),
+  /// Private message to this person.
+  Person(PersonId),
+}
+
+/// A reply to a notification email, after checking its signature.
+#[derive(Debug)]
+pub struct EmailReply {
+  pub local_user_id: LocalUserId,
+  pub target: ReplyTarget,
+  /// Sender address from the email header.
+  pub from: String,
+  /// Text of the reply, without quoted parts of the original email.
+  pub content: String,
+}
+
+/// Returns the reply-to address for a notification email, or `None` if replying by email is not
+/// enabled. The address contains the target and recipient, signed with the instance secret so
+/// that it cannot be forged.
+pub fn reply_address(
+  target: ReplyTarget,
+  local_user_id: LocalUserId,
+  secret: &Secret,
+  settings: &Settings,
+) -> Option<String> {
+  let reply_address = settings.email.as_ref()?.reply_address.as_ref()?;
+  let (user, domain) = reply_address.split_once('@')?;
+  let payload = match target {
+    ReplyTarget::Comment(id) => format!("c{}.{}", id.0, local_user_id.0),
+    ReplyTarget::Post(id) => format!("p{}.{}", id.0, local_user_id.0),
+    ReplyTarget::Person(id) => format!("u{}.{}", id.0, local_user_id.0),
+  };
+  let signature = sign(&payload, secret).finalize().into_bytes();
+  let signature = signature
+    .iter()
+    .take(SIGNATURE_LEN)
+    .fold(String::new(), |mut out, b| {
+      let _ = write!(out, "{b:02x}");
+      out
+    });
+  Some(format!("{user}+{payload}.{signature}@{domain}"))
+}
+
+/// Parses an incoming email which was sent to `recipient`, and checks that the recipient address
+/// was generated by [`reply_address`].
+pub fn parse_email_reply(recipient: &str, raw: &[u8], secret: &Secret) -> LemmyResult<EmailReply> {
+  let (local_user_id, target) = verify_recipient(recipient, secret)?;
+  let message = MessageParser::default()
+    .parse(raw)
+    .ok_or(LemmyErrorType::InvalidEmailReply)?;
+  let from = message
+    .from()
+    .and_then(|f| f.first())
+    .and_then(|f| f.address())
+    .ok_or(LemmyErrorType::InvalidEmailReply)?
+    .to_string();
+  let body = message
+    .body_text(0)
+    .ok_or(LemmyErrorType::InvalidEmailReply)?;
+  let content = strip_quoted_text(&body);
+  if content.is_empty() {
+    return Err(LemmyErrorType::InvalidEmailReply.into());
+  }
+  Ok(EmailReply {
+    local_user_id,
+    target,
+    from,
+    content,
+  })
+}
+
+fn verify_recipient(recipient: &str, secret: &Secret) -> LemmyResult<(LocalUserId, ReplyTarget)> {
+  let recipient = recipient
+    .trim()
+    .trim_start_matches('<')
+    .trim_end_matches('>');
+  let (local_part, _) = recipient
+    .rsplit_once('@')
+    .ok_or(LemmyErrorType::InvalidEmailReply)?;
+  let (_, token) = local_part
+    .split_once('+')
+    .ok_or(LemmyErrorType::InvalidEmailReply)?;
+  let (payload, signature) = token
+    .rsplit_once('.')
+    .ok_or(LemmyErrorType::InvalidEmailReply)?;
+  let signature = decode_hex(signature).ok_or(LemmyErrorType::InvalidEmailReply)?;
+  if signature.len() != SIGNATURE_LEN {
+    return Err(LemmyErrorType::InvalidEmailReply.into());
+  }
+  sign(payload, secret)
+    .verify_truncated_left(&signature)
+    .map_err(|_| LemmyErrorType::InvalidEmailReply)?;
+
+  let parse = || -> Option<(LocalUserId, ReplyTarget)> {
+    let (target, local_user_id) = payload.split_once('.')?;
+    let local_user_id = LocalUserId(local_user_id.parse().ok()?);
+    let id = target.get(1..)?.parse().ok()?;
+    let target = match target.get(..1)? {
+      "c" => ReplyTarget::Comment(CommentId(id)),
+      "p" => ReplyTarget::Post(PostId(id)),
+      "u" => ReplyTarget::Person(PersonId(id)),
+      _ => return None,
+    };
+    Some((local_user_id, target))
+  };
+  parse().ok_or_else(|| LemmyErrorType::InvalidEmailReply.into())
+}
+
+fn sign(payload: &str, secret: &Secret) -> Hmac<Sha256> {
+  // HMAC accepts keys of any length
+  #[expect(clippy::expect_used)]
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret.jwt_secret.as_bytes()).expect("hmac key");
+  mac.update(payload.as_bytes());
+  mac
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+  if hex.len() % 2 != 0 {
+    return None;
+  }
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+    .collect()
+}
+
+/// Removes the quoted original email and signature, which most email clients add to replies.
+fn strip_quoted_text(body: &str) -> String {
+  let mut lines = vec![];
+  for line in body.lines() {
+    let trimmed = line.trim();
+    if trimmed == "--"
+      || trimmed.ends_with("wrote:")
+      || trimmed == "-----Original Message-----"
+      || (trimmed.len() >= 10 && trimmed.chars().all(|c| c == '_'))
+    {
+      break;
+    }
+    if trimmed.starts_with('>') {
+      continue;
+    }
+    lines.push(line.trim_end());
+  }
+  lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_utils::settings::structs::EmailConfig;
+  use pretty_assertions::assert_eq;
+
+  fn settings() -> Settings {
+    Settings {
+      email: Some(EmailConfig {
+        reply_address: Some("reply@example.com".to_string()),
+        ..Default::default()
+      }),
+      ..Default::default()
+    }
+  }
+
+  fn test_secret(jwt_secret: &str) -> Secret {
+    Secret {
+      id: 1,
+      jwt_secret: jwt_secret.to_string().into(),
+    }
+  }
+
+  #[test]
+  fn test_reply_address() -> LemmyResult<()> {
+    let secret = test_secret("my_secret");
+    let target = ReplyTarget::Comment(CommentId(123));
+    let address = reply_address(target, LocalUserId(45), &secret, &settings())
+      .ok_or(LemmyErrorType::NotFound)?;
+    assert!(address.starts_with("reply+c123.45."));
+    assert!(address.ends_with("@example.com"));
+
+    assert_eq!(
+      (LocalUserId(45), target),
+      verify_recipient(&format!("<{address}>"), &secret)?
+    );
+
+    // Signature doesnt match with a different secret or modified payload
+    assert!(verify_recipient(&address, &test_secret("other_secret")).is_err());
+    let modified = address.replace("c123.45.", "c123.46.");
+    assert!(verify_recipient(&modified, &secret).is_err());
+
+    // Disabled without reply address
+    assert!(reply_address(target, LocalUserId(45), &secret, &Settings::default()).is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_quoted_text() {
+    let body = "Thanks, sounds good!\n\nSecond line  \n\nOn Mon, 1 Jan 2026, Lemmy \
+                <noreply@example.com> wrote:\n> original notification\n> text";
+    assert_eq!(
+      "Thanks, sounds good!\n\nSecond line",
+      strip_quoted_text(body)
+    );
+
+    let body = "> quoted\nInline answer\n-- \nMy signature";
+    assert_eq!("Inline answer", strip_quoted_text(body));
+
+    let body = "Reply\n\n-----Original Message-----\nFrom: Lemmy";
+    assert_eq!("Reply", strip_quoted_text(body));
+  }
+}
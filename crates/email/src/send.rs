@@ -1,68 +1,65 @@
-use lemmy_diesel_utils::sensitive::SensitiveString;
-use lemmy_utils::{
-  error::{LemmyErrorExt, LemmyErrorType},
-  settings::structs::Settings,
-  spawn_try_task,
-};
-use lettre::{
-  Address,
-  AsyncTransport,
-  Message,
-  message::{Mailbox, MultiPart},
-  transport::smtp::extension::ClientId,
-};
-use std::{str::FromStr, sync::OnceLock};
-use uuid::Uuid;
+use crate::queue::NEW_EMAIL;
+use chrono::{TimeDelta, Utc};
+use lemmy_db_schema::source::email_queue::{EmailQueue, EmailQueueInsertForm};
+use lemmy_diesel_utils::{connection::DbPool, sensitive::SensitiveString, traits::Crud};
+use lemmy_utils::{error::LemmyResult, settings::structs::Settings};
+use tracing::warn;
 
-type AsyncSmtpTransport = lettre::AsyncSmtpTransport<lettre::Tokio1Executor>;
-
-pub(crate) fn send_email(
+/// Adds an email to the outgoing queue. It is sent in the background by
+/// [`crate::queue::run_email_queue`].
+pub(crate) async fn send_email(
   subject: String,
   to_email: SensitiveString,
   to_username: String,
   html: String,
+  pool: &mut DbPool<'_>,
   settings: &'static Settings,
-) {
-  spawn_try_task(async move {
-    static MAILER: OnceLock<AsyncSmtpTransport> = OnceLock::new();
-    let email_config = settings.email.clone().ok_or(LemmyErrorType::NoEmailSetup)?;
-
-    #[expect(clippy::expect_used)]
-    let mailer = MAILER.get_or_init(|| {
-      AsyncSmtpTransport::from_url(&email_config.connection)
-        .expect("init email transport")
-        .hello_name(ClientId::Domain(settings.hostname.clone()))
-        .build()
-    });
-
-    // use usize::MAX as the line wrap length, since lettre handles the wrapping for us
-    let plain_text = html2text::from_read(html.as_bytes(), usize::MAX)?;
-
-    let smtp_from_address = &email_config.smtp_from_address;
+) -> LemmyResult<()> {
+  let form = EmailQueueInsertForm::new(to_email, to_username, subject, html);
+  queue_email(form, pool, settings).await
+}
 
-    let email = Message::builder()
-      .from(
-        smtp_from_address
-          .parse()
-          .with_lemmy_type(LemmyErrorType::InvalidEmailAddress(
-            smtp_from_address.into(),
-          ))?,
-      )
-      .to(Mailbox::new(
-        Some(to_username.clone()),
-        Address::from_str(&to_email)
-          .with_lemmy_type(LemmyErrorType::InvalidEmailAddress(to_email.into_inner()))?,
-      ))
-      .message_id(Some(format!("<{}@{}>", Uuid::new_v4(), settings.hostname)))
-      .subject(subject)
-      .multipart(MultiPart::alternative_plain_html(plain_text, html.clone()))
-      .with_lemmy_type(LemmyErrorType::EmailSendFailed)?;
+/// Same as [`send_email`], but the email is skipped if the recipient already got too many
+/// notification emails in the last hour.
+pub(crate) async fn send_rate_limited_email(
+  subject: String,
+  to_email: SensitiveString,
+  to_username: String,
+  html: String,
+  reply_to: Option<String>,
+  pool: &mut DbPool<'_>,
+  settings: &'static Settings,
+) -> LemmyResult<()> {
+  let Some(email_config) = &settings.email else {
+    return Ok(());
+  };
+  let since = Utc::now() - TimeDelta::hours(1);
+  let count = EmailQueue::count_notifications_since(pool, &to_email, since).await?;
+  if count >= email_config.notification_emails_per_hour {
+    return Ok(());
+  }
 
-    mailer
-      .send(email)
-      .await
-      .with_lemmy_type(LemmyErrorType::EmailSendFailed)?;
+  let form = EmailQueueInsertForm {
+    is_notification: Some(true),
+    reply_to,
+    ..EmailQueueInsertForm::new(to_email, to_username, subject, html)
+  };
+  queue_email(form, pool, settings).await
+}
 
-    Ok(())
-  })
+async fn queue_email(
+  form: EmailQueueInsertForm,
+  pool: &mut DbPool<'_>,
+  settings: &'static Settings,
+) -> LemmyResult<()> {
+  if settings.email.is_none() {
+    warn!(
+      "Not sending email \"{}\", email is not configured",
+      form.subject
+    );
+    return Ok(());
+  }
+  EmailQueue::create(pool, &form).await?;
+  NEW_EMAIL.notify_one();
+  Ok(())
 }
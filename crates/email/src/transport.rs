@@ -0,0 +1,161 @@
+use lemmy_db_schema::source::email_queue::EmailQueue;
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  settings::structs::{EmailConfig, EmailTransportType, Settings},
+};
+use lettre::{
+  Address,
+  AsyncFileTransport,
+  AsyncSendmailTransport,
+  AsyncSmtpTransport,
+  AsyncTransport,
+  Message,
+  Tokio1Executor,
+  message::{Mailbox, MultiPart},
+  transport::smtp::extension::ClientId,
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Serialize;
+use std::str::FromStr;
+use url::Url;
+use uuid::Uuid;
+
+/// Delivers emails from the queue, using the transport which is set in [`EmailConfig`].
+pub(crate) enum Transport {
+  Smtp(AsyncSmtpTransport<Tokio1Executor>),
+  Sendmail(AsyncSendmailTransport<Tokio1Executor>),
+  File(AsyncFileTransport<Tokio1Executor>),
+  Http {
+    client: ClientWithMiddleware,
+    url: Url,
+    api_key: Option<String>,
+  },
+}
+
+/// Request body of the http transport.
+#[derive(Serialize)]
+struct HttpEmail<'a> {
+  from: &'a str,
+  to: &'a str,
+  to_name: &'a str,
+  subject: &'a str,
+  html: &'a str,
+  text: &'a str,
+  reply_to: Option<&'a str>,
+}
+
+impl Transport {
+  pub(crate) fn new(
+    email_config: &EmailConfig,
+    client: ClientWithMiddleware,
+    settings: &Settings,
+  ) -> LemmyResult<Self> {
+    Ok(match email_config.transport {
+      EmailTransportType::Smtp => Transport::Smtp(
+        AsyncSmtpTransport::<Tokio1Executor>::from_url(&email_config.connection)
+          .with_lemmy_type(LemmyErrorType::NoEmailSetup)?
+          .hello_name(ClientId::Domain(settings.hostname.clone()))
+          .build(),
+      ),
+      EmailTransportType::Sendmail => Transport::Sendmail(match &email_config.sendmail_command {
+        Some(command) => AsyncSendmailTransport::new_with_command(command),
+        None => AsyncSendmailTransport::new(),
+      }),
+      EmailTransportType::File => {
+        std::fs::create_dir_all(&email_config.file_path)?;
+        Transport::File(AsyncFileTransport::new(&email_config.file_path))
+      }
+      EmailTransportType::Http => Transport::Http {
+        client,
+        url: email_config
+          .http_url
+          .clone()
+          .ok_or(LemmyErrorType::NoEmailSetup)?,
+        api_key: email_config.http_api_key.clone(),
+      },
+    })
+  }
+
+  pub(crate) async fn send(
+    &self,
+    email: &EmailQueue,
+    email_config: &EmailConfig,
+    settings: &Settings,
+  ) -> LemmyResult<()> {
+    // use usize::MAX as the line wrap length, since lettre handles the wrapping for us
+    let plain_text = html2text::from_read(email.body.as_bytes(), usize::MAX)?;
+    let smtp_from_address = &email_config.smtp_from_address;
+
+    if let Transport::Http {
+      client,
+      url,
+      api_key,
+    } = self
+    {
+      let body = HttpEmail {
+        from: smtp_from_address,
+        to: &email.recipient_email,
+        to_name: &email.recipient_name,
+        subject: &email.subject,
+        html: &email.body,
+        text: &plain_text,
+        reply_to: email.reply_to.as_deref(),
+      };
+      let mut request = client.post(url.as_str()).json(&body);
+      if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+      }
+      request.send().await?.error_for_status()?;
+      return Ok(());
+    }
+
+    let mut builder = Message::builder();
+    if let Some(reply_to) = &email.reply_to {
+      builder = builder.reply_to(
+        reply_to
+          .parse()
+          .with_lemmy_type(LemmyErrorType::InvalidEmailAddress(reply_to.clone()))?,
+      );
+    }
+    let message = builder
+      .from(
+        smtp_from_address
+          .parse()
+          .with_lemmy_type(LemmyErrorType::InvalidEmailAddress(
+            smtp_from_address.into(),
+          ))?,
+      )
+      .to(Mailbox::new(
+        Some(email.recipient_name.clone()),
+        Address::from_str(&email.recipient_email).with_lemmy_type(
+          LemmyErrorType::InvalidEmailAddress(email.recipient_email.to_string()),
+        )?,
+      ))
+      .message_id(Some(format!("<{}@{}>", Uuid::new_v4(), settings.hostname)))
+      .subject(email.subject.clone())
+      .multipart(MultiPart::alternative_plain_html(
+        plain_text,
+        email.body.clone(),
+      ))
+      .with_lemmy_type(LemmyErrorType::EmailSendFailed)?;
+
+    match self {
+      Transport::Smtp(t) => t
+        .send(message)
+        .await
+        .map(|_| ())
+        .with_lemmy_type(LemmyErrorType::EmailSendFailed),
+      Transport::Sendmail(t) => t
+        .send(message)
+        .await
+        .with_lemmy_type(LemmyErrorType::EmailSendFailed),
+      Transport::File(t) => t
+        .send(message)
+        .await
+        .map(|_| ())
+        .with_lemmy_type(LemmyErrorType::EmailSendFailed),
+      // Handled above
+      Transport::Http { .. } => Ok(()),
+    }
+  }
+}
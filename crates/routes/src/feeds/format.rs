@@ -0,0 +1,537 @@
+//! Feed contents independent of the output format, and rendering as RSS 2.0, Atom 1.0 or
+//! JSON Feed 1.1.
+use actix_web::HttpResponse;
+use atom_syndication::{Content, Entry, Link, Text};
+use chrono::{DateTime, Utc};
+use rss::{
+  Category,
+  Channel,
+  Enclosure,
+  Guid,
+  Item,
+  extension::{ExtensionBuilder, ExtensionMap, dublincore::DublinCoreExtension},
+};
+use serde::Serialize;
+use serde_with::skip_serializing_none;
+use std::{collections::BTreeMap, sync::LazyLock};
+
+const MEDIA_NAMESPACE: &str = "http://search.yahoo.com/mrss/";
+/// https://www.rssboard.org/comments
+const SLASH_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/slash/";
+/// https://www.rfc-editor.org/rfc/rfc4685
+const THREAD_NAMESPACE: &str = "http://purl.org/syndication/thread/1.0";
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+static RSS_NAMESPACE: LazyLock<BTreeMap<String, String>> = LazyLock::new(|| {
+  BTreeMap::from([
+    (
+      "dc".to_string(),
+      rss::extension::dublincore::NAMESPACE.to_string(),
+    ),
+    ("media".to_string(), MEDIA_NAMESPACE.to_string()),
+    ("slash".to_string(), SLASH_NAMESPACE.to_string()),
+  ])
+});
+
+static ATOM_NAMESPACE: LazyLock<BTreeMap<String, String>> = LazyLock::new(|| {
+  BTreeMap::from([
+    ("media".to_string(), MEDIA_NAMESPACE.to_string()),
+    ("thr".to_string(), THREAD_NAMESPACE.to_string()),
+  ])
+});
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FeedFormat {
+  Rss,
+  Atom,
+  Json,
+}
+
+impl FeedFormat {
+  /// Parses the file extension of a feed url.
+  pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+    match extension {
+      "xml" | "rss" => Some(FeedFormat::Rss),
+      "atom" => Some(FeedFormat::Atom),
+      "json" => Some(FeedFormat::Json),
+      _ => None,
+    }
+  }
+
+  /// Parses a media type from the `Accept` header.
+  pub(crate) fn from_media_type(media_type: &str) -> Option<Self> {
+    match media_type {
+      "application/rss+xml" | "application/xml" | "text/xml" => Some(FeedFormat::Rss),
+      "application/atom+xml" => Some(FeedFormat::Atom),
+      "application/feed+json" | "application/json" => Some(FeedFormat::Json),
+      _ => None,
+    }
+  }
+
+  fn content_type(self) -> &'static str {
+    match self {
+      FeedFormat::Rss => "application/rss+xml",
+      FeedFormat::Atom => "application/atom+xml",
+      FeedFormat::Json => "application/feed+json",
+    }
+  }
+}
+
+pub(crate) struct Feed {
+  /// Permanent, unique identifier of the feed. Used by Atom and JSON Feed.
+  pub(crate) id: String,
+  pub(crate) title: String,
+  /// Website which corresponds to the feed.
+  pub(crate) link: String,
+  /// Url of the feed itself. Not set for private feeds, as it contains the auth token.
+  pub(crate) self_link: Option<String>,
+  pub(crate) description_html: Option<String>,
+  /// Used as feed author, for entries which don't have their own author.
+  pub(crate) site_name: String,
+  pub(crate) entries: Vec<FeedEntry>,
+}
+
+pub(crate) struct FeedEntry {
+  /// Permanent, unique identifier of the entry. For RSS this is used as guid.
+  pub(crate) id: String,
+  pub(crate) title: String,
+  pub(crate) link: String,
+  pub(crate) author: Option<FeedAuthor>,
+  pub(crate) published: DateTime<Utc>,
+  pub(crate) updated: DateTime<Utc>,
+  pub(crate) content_html: Option<String>,
+  pub(crate) category: Option<FeedCategory>,
+  pub(crate) enclosure: Option<FeedEnclosure>,
+  pub(crate) thumbnail: Option<String>,
+  pub(crate) comments: Option<FeedComments>,
+  pub(crate) score: Option<i32>,
+}
+
+pub(crate) struct FeedAuthor {
+  pub(crate) name: String,
+  pub(crate) url: String,
+}
+
+pub(crate) struct FeedCategory {
+  pub(crate) term: String,
+  pub(crate) label: String,
+  pub(crate) url: String,
+}
+
+pub(crate) struct FeedEnclosure {
+  pub(crate) url: String,
+  pub(crate) mime_type: String,
+}
+
+pub(crate) struct FeedComments {
+  pub(crate) url: String,
+  pub(crate) count: Option<i32>,
+}
+
+impl Feed {
+  pub(crate) fn into_response(self, format: FeedFormat) -> HttpResponse {
+    let body = match format {
+      FeedFormat::Rss => self.to_rss(),
+      FeedFormat::Atom => self.to_atom(),
+      FeedFormat::Json => self.to_json_feed(),
+    };
+    HttpResponse::Ok()
+      .content_type(format.content_type())
+      // The format may be chosen based on the Accept header
+      .insert_header(("Vary", "Accept"))
+      .body(body)
+  }
+
+  /// Time of the most recent change in the feed.
+  fn updated(&self) -> DateTime<Utc> {
+    self
+      .entries
+      .iter()
+      .map(|e| e.updated)
+      .max()
+      .unwrap_or_else(Utc::now)
+  }
+
+  fn to_rss(self) -> String {
+    let items = self.entries.into_iter().map(rss_item).collect();
+    let mut channel = Channel {
+      namespaces: RSS_NAMESPACE.clone(),
+      title: self.title,
+      link: self.link,
+      items,
+      ..Default::default()
+    };
+    if let Some(desc) = self.description_html {
+      channel.set_description(desc);
+    }
+    channel.to_string()
+  }
+
+  fn to_atom(self) -> String {
+    let updated = self.updated().fixed_offset();
+    let mut links = vec![Link {
+      href: self.link,
+      ..Default::default()
+    }];
+    if let Some(self_link) = self.self_link {
+      links.push(Link {
+        href: self_link,
+        rel: "self".to_string(),
+        mime_type: Some(FeedFormat::Atom.content_type().to_string()),
+        ..Default::default()
+      });
+    }
+    let feed = atom_syndication::Feed {
+      id: self.id,
+      title: Text::plain(self.title),
+      subtitle: self.description_html.map(Text::html),
+      updated,
+      authors: vec![atom_syndication::Person {
+        name: self.site_name,
+        ..Default::default()
+      }],
+      links,
+      entries: self.entries.into_iter().map(atom_entry).collect(),
+      namespaces: ATOM_NAMESPACE.clone(),
+      ..Default::default()
+    };
+    feed.to_string()
+  }
+
+  fn to_json_feed(self) -> String {
+    let feed = JsonFeed {
+      version: JSON_FEED_VERSION,
+      title: self.title,
+      home_page_url: self.link,
+      feed_url: self.self_link,
+      description: self.description_html,
+      authors: vec![JsonFeedAuthor {
+        name: self.site_name,
+        url: None,
+      }],
+      items: self.entries.into_iter().map(json_feed_item).collect(),
+    };
+    // Serializing these types can't fail
+    serde_json::to_string(&feed).unwrap_or_default()
+  }
+}
+
+fn rss_item(entry: FeedEntry) -> Item {
+  let mut extensions = ExtensionMap::new();
+  // If there's a thumbnail URL, add a media:content tag to display it.
+  // See https://www.rssboard.org/media-rss#media-content for details.
+  if let Some(url) = &entry.thumbnail {
+    let mut thumbnail_ext = ExtensionBuilder::default();
+    thumbnail_ext.name("media:content".to_string());
+    thumbnail_ext.attrs(BTreeMap::from([
+      ("url".to_string(), url.clone()),
+      ("medium".to_string(), "image".to_string()),
+    ]));
+    extensions.insert(
+      "media".to_string(),
+      BTreeMap::from([("content".to_string(), vec![thumbnail_ext.build()])]),
+    );
+  }
+  if let Some(count) = entry.comments.as_ref().and_then(|c| c.count) {
+    let mut comments_ext = ExtensionBuilder::default();
+    comments_ext.name("slash:comments".to_string());
+    comments_ext.value(Some(count.to_string()));
+    extensions.insert(
+      "slash".to_string(),
+      BTreeMap::from([("comments".to_string(), vec![comments_ext.build()])]),
+    );
+  }
+
+  Item {
+    title: Some(entry.title),
+    author: entry
+      .author
+      .as_ref()
+      .map(|a| format!("/u/{} <a href=\"{}\">(link)</a>", a.name, a.url)),
+    dublin_core_ext: entry.author.map(|a| DublinCoreExtension {
+      creators: vec![a.url],
+      ..Default::default()
+    }),
+    pub_date: Some(entry.published.to_rfc2822()),
+    comments: entry.comments.map(|c| c.url),
+    guid: Some(Guid {
+      permalink: entry.id == entry.link,
+      value: entry.id,
+    }),
+    link: Some(entry.link),
+    description: entry.content_html,
+    enclosure: entry.enclosure.map(|e| Enclosure {
+      url: e.url,
+      length: "0".to_string(),
+      mime_type: e.mime_type,
+    }),
+    categories: entry
+      .category
+      .map(|c| Category {
+        name: c.label,
+        domain: Some(c.url),
+      })
+      .into_iter()
+      .collect(),
+    extensions,
+    ..Default::default()
+  }
+}
+
+fn atom_entry(entry: FeedEntry) -> Entry {
+  let mut links = vec![Link {
+    href: entry.link,
+    ..Default::default()
+  }];
+  if let Some(enclosure) = entry.enclosure {
+    links.push(Link {
+      href: enclosure.url,
+      rel: "enclosure".to_string(),
+      mime_type: Some(enclosure.mime_type),
+      ..Default::default()
+    });
+  }
+  let mut extensions = atom_syndication::extension::ExtensionMap::new();
+  if let Some(comments) = entry.comments {
+    links.push(Link {
+      href: comments.url,
+      rel: "replies".to_string(),
+      mime_type: Some("text/html".to_string()),
+      ..Default::default()
+    });
+    if let Some(count) = comments.count {
+      let total = atom_syndication::extension::Extension {
+        name: "thr:total".to_string(),
+        value: Some(count.to_string()),
+        ..Default::default()
+      };
+      extensions.insert(
+        "thr".to_string(),
+        BTreeMap::from([("total".to_string(), vec![total])]),
+      );
+    }
+  }
+  if let Some(url) = entry.thumbnail {
+    let thumbnail = atom_syndication::extension::Extension {
+      name: "media:thumbnail".to_string(),
+      attrs: BTreeMap::from([("url".to_string(), url)]),
+      ..Default::default()
+    };
+    extensions.insert(
+      "media".to_string(),
+      BTreeMap::from([("thumbnail".to_string(), vec![thumbnail])]),
+    );
+  }
+
+  Entry {
+    id: entry.id,
+    title: Text::plain(entry.title),
+    updated: entry.updated.fixed_offset(),
+    published: Some(entry.published.fixed_offset()),
+    authors: entry
+      .author
+      .map(|a| atom_syndication::Person {
+        name: a.name,
+        uri: Some(a.url),
+        ..Default::default()
+      })
+      .into_iter()
+      .collect(),
+    categories: entry
+      .category
+      .map(|c| atom_syndication::Category {
+        term: c.term,
+        scheme: Some(c.url),
+        label: Some(c.label),
+      })
+      .into_iter()
+      .collect(),
+    links,
+    content: entry.content_html.map(|html| Content {
+      value: Some(html),
+      content_type: Some("html".to_string()),
+      ..Default::default()
+    }),
+    extensions,
+    ..Default::default()
+  }
+}
+
+fn json_feed_item(entry: FeedEntry) -> JsonFeedItem {
+  let extension = (entry.comments.is_some() || entry.score.is_some()).then(|| LemmyExtension {
+    comments_url: entry.comments.as_ref().map(|c| c.url.clone()),
+    comments: entry.comments.as_ref().and_then(|c| c.count),
+    score: entry.score,
+  });
+  JsonFeedItem {
+    id: entry.id,
+    url: entry.link,
+    title: entry.title,
+    content_html: entry.content_html,
+    image: entry.thumbnail,
+    date_published: entry.published.to_rfc3339(),
+    date_modified: entry.updated.to_rfc3339(),
+    authors: entry
+      .author
+      .map(|a| JsonFeedAuthor {
+        name: a.name,
+        url: Some(a.url),
+      })
+      .into_iter()
+      .collect(),
+    tags: entry.category.map(|c| c.term).into_iter().collect(),
+    attachments: entry
+      .enclosure
+      .map(|e| JsonFeedAttachment {
+        url: e.url,
+        mime_type: e.mime_type,
+      })
+      .into_iter()
+      .collect(),
+    lemmy: extension,
+  }
+}
+
+/// https://www.jsonfeed.org/version/1.1/
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct JsonFeed {
+  version: &'static str,
+  title: String,
+  home_page_url: String,
+  feed_url: Option<String>,
+  description: Option<String>,
+  authors: Vec<JsonFeedAuthor>,
+  items: Vec<JsonFeedItem>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct JsonFeedItem {
+  id: String,
+  url: String,
+  title: String,
+  content_html: Option<String>,
+  image: Option<String>,
+  date_published: String,
+  date_modified: String,
+  authors: Vec<JsonFeedAuthor>,
+  tags: Vec<String>,
+  attachments: Vec<JsonFeedAttachment>,
+  #[serde(rename = "_lemmy")]
+  lemmy: Option<LemmyExtension>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+  name: String,
+  url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAttachment {
+  url: String,
+  mime_type: String,
+}
+
+/// Custom extension with comment count and score. Extension names need to start with an
+/// underscore.
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct LemmyExtension {
+  comments_url: Option<String>,
+  comments: Option<i32>,
+  score: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+  use pretty_assertions::assert_eq;
+
+  fn test_feed() -> LemmyResult<Feed> {
+    let published = DateTime::parse_from_rfc3339("2026-01-02T10:00:00Z")?.to_utc();
+    let updated = DateTime::parse_from_rfc3339("2026-01-03T12:30:00Z")?.to_utc();
+    Ok(Feed {
+      id: "https://lemmy.example/c/test".to_string(),
+      title: "Lemmy - test".to_string(),
+      link: "https://lemmy.example/c/test".to_string(),
+      self_link: Some("https://lemmy.example/feeds/c/test.atom".to_string()),
+      description_html: Some("<p>Test community</p>".to_string()),
+      site_name: "Lemmy".to_string(),
+      entries: vec![FeedEntry {
+        id: "https://lemmy.example/post/1".to_string(),
+        title: "First post".to_string(),
+        link: "https://lemmy.example/post/1".to_string(),
+        author: Some(FeedAuthor {
+          name: "alice".to_string(),
+          url: "https://lemmy.example/u/alice".to_string(),
+        }),
+        published,
+        updated,
+        content_html: Some("<p>Hello</p>".to_string()),
+        category: Some(FeedCategory {
+          term: "test".to_string(),
+          label: "Test community".to_string(),
+          url: "https://lemmy.example/c/test".to_string(),
+        }),
+        enclosure: None,
+        thumbnail: None,
+        comments: Some(FeedComments {
+          url: "https://lemmy.example/post/1".to_string(),
+          count: Some(5),
+        }),
+        score: Some(12),
+      }],
+    })
+  }
+
+  #[test]
+  fn test_atom_feed() -> LemmyResult<()> {
+    let atom = test_feed()?.to_atom();
+    let parsed = atom.parse::<atom_syndication::Feed>()?;
+
+    assert_eq!("https://lemmy.example/c/test", parsed.id);
+    // Feed is updated at the time of the most recent entry
+    assert_eq!("2026-01-03T12:30:00+00:00", parsed.updated.to_rfc3339());
+    let entry = parsed.entries.first().ok_or(LemmyErrorType::NotFound)?;
+    assert_eq!("https://lemmy.example/post/1", entry.id);
+    assert_eq!(
+      Some("2026-01-02T10:00:00+00:00".to_string()),
+      entry.published.map(|p| p.to_rfc3339())
+    );
+    assert!(atom.contains("<thr:total>5</thr:total>"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_json_feed() -> LemmyResult<()> {
+    let json = test_feed()?.to_json_feed();
+    let parsed: serde_json::Value = serde_json::from_str(&json)?;
+
+    assert_eq!(JSON_FEED_VERSION, parsed["version"]);
+    assert_eq!(
+      "https://lemmy.example/feeds/c/test.atom",
+      parsed["feed_url"]
+    );
+    let item = &parsed["items"][0];
+    assert_eq!("https://lemmy.example/post/1", item["id"]);
+    assert_eq!("2026-01-03T12:30:00+00:00", item["date_modified"]);
+    assert_eq!(5, item["_lemmy"]["comments"]);
+    assert_eq!(12, item["_lemmy"]["score"]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_rss_feed() -> LemmyResult<()> {
+    let rss = test_feed()?.to_rss();
+    let parsed = rss.parse::<Channel>()?;
+
+    let item = parsed.items.first().ok_or(LemmyErrorType::NotFound)?;
+    assert_eq!(Some("https://lemmy.example/post/1"), item.link());
+    assert!(rss.contains("<slash:comments>5</slash:comments>"));
+    Ok(())
+  }
+}
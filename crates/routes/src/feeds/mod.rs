@@ -1,26 +1,40 @@
+mod format;
 mod negotiate_content;
 use actix_web::{Error, HttpRequest, HttpResponse, Result, error::ErrorBadRequest, web};
-use chrono::{DateTime, Utc};
+use chrono::Utc;
+use format::{Feed, FeedAuthor, FeedCategory, FeedComments, FeedEnclosure, FeedEntry};
 use lemmy_api_utils::{
   context::LemmyContext,
-  utils::{check_private_instance, local_user_view_from_jwt},
+  utils::{check_local_user_deleted, check_private_instance, local_user_view_from_jwt},
 };
 use lemmy_db_schema::{
   PersonContentType,
+  newtypes::PostId,
   source::{
     community::Community,
+    feed_token::FeedToken,
     multi_community::MultiCommunity,
     notification::Notification,
     person::Person,
+    post::Post,
   },
   traits::ApubActor,
 };
-use lemmy_db_schema_file::enums::{ListingType, ModlogKind, NotificationType, PostSortType};
+use lemmy_db_schema_file::enums::{
+  CommentSortType,
+  ListingType,
+  ModlogKind,
+  NotificationType,
+  PostSortType,
+};
+use lemmy_db_views_comment::{CommentView, impls::CommentQuery};
+use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_modlog::{ModlogView, impls::ModlogQuery};
 use lemmy_db_views_notification::{NotificationData, NotificationView, impls::NotificationQuery};
 use lemmy_db_views_person_content_combined::impls::PersonContentCombinedQuery;
 use lemmy_db_views_post::{PostView, impls::PostQuery};
 use lemmy_db_views_site::SiteView;
+use lemmy_diesel_utils::traits::Crud;
 use lemmy_email::{translations::Lang, user_language};
 use lemmy_utils::{
   cache_header::cache_1hour,
@@ -28,17 +42,9 @@ use lemmy_utils::{
   settings::structs::Settings,
   utils::markdown::markdown_to_html,
 };
-use negotiate_content::get_lang_or_negotiate;
-use rss::{
-  Category,
-  Channel,
-  EnclosureBuilder,
-  Guid,
-  Item,
-  extension::{ExtensionBuilder, ExtensionMap, dublincore::DublinCoreExtension},
-};
+use negotiate_content::{get_lang_or_negotiate, negotiate_format};
 use serde::Deserialize;
-use std::{collections::BTreeMap, sync::LazyLock};
+use tracing::warn;
 
 const RSS_FETCH_LIMIT: i64 = 20;
 
@@ -57,44 +63,73 @@ impl Params {
   }
 }
 
+#[derive(Deserialize)]
+struct CommentParams {
+  sort: Option<CommentSortType>,
+  limit: Option<i64>,
+}
+
+impl CommentParams {
+  /// Comment feeds are sorted by new by default, so that readers see every new comment.
+  fn sort_type(&self) -> CommentSortType {
+    self.sort.unwrap_or(CommentSortType::New)
+  }
+  fn get_limit(&self) -> i64 {
+    self.limit.unwrap_or(RSS_FETCH_LIMIT)
+  }
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
   cfg.service(
     web::scope("/feeds")
-      .route("/u/{user_name}.xml", web::get().to(get_feed_user))
-      .route("/c/{community_name}.xml", web::get().to(get_feed_community))
       .route(
-        "/m/{multi_name}.xml",
+        "/u/{user_name}.{format:xml|atom|json}",
+        web::get().to(get_feed_user),
+      )
+      .route(
+        "/c/{community_name}.{format:xml|atom|json}",
+        web::get().to(get_feed_community),
+      )
+      .route(
+        "/c/{community_name}/comments.{format:xml|atom|json}",
+        web::get().to(get_feed_community_comments),
+      )
+      .route(
+        "/post/{post_id}/comments.{format:xml|atom|json}",
+        web::get().to(get_feed_post_comments),
+      )
+      .route(
+        "/m/{multi_name}.{format:xml|atom|json}",
         web::get().to(get_feed_multi_community),
       )
-      .route("/front/{jwt}.xml", web::get().to(get_feed_front))
-      .route("/modlog/{jwt}.xml", web::get().to(get_feed_modlog))
-      .route("/notifications/{jwt}.xml", web::get().to(get_feed_notifs))
+      .route(
+        "/front/{token}.{format:xml|atom|json}",
+        web::get().to(get_feed_front),
+      )
+      .route(
+        "/modlog/{token}.{format:xml|atom|json}",
+        web::get().to(get_feed_modlog),
+      )
+      .route(
+        "/notifications/{token}.{format:xml|atom|json}",
+        web::get().to(get_feed_notifs),
+      )
       // Also redirect inbox to notifications. This should probably be deprecated tho.
       .service(web::redirect(
-        "/inbox/{jwt}.xml",
-        "/notifications/{jwt}.xml",
+        "/inbox/{token}.xml",
+        "/notifications/{token}.xml",
       ))
-      .route("/all.xml", web::get().to(get_all_feed).wrap(cache_1hour()))
       .route(
-        "/local.xml",
+        "/all.{format:xml|atom|json}",
+        web::get().to(get_all_feed).wrap(cache_1hour()),
+      )
+      .route(
+        "/local.{format:xml|atom|json}",
         web::get().to(get_local_feed).wrap(cache_1hour()),
       ),
   );
 }
 
-static RSS_NAMESPACE: LazyLock<BTreeMap<String, String>> = LazyLock::new(|| {
-  let mut h = BTreeMap::new();
-  h.insert(
-    "dc".to_string(),
-    rss::extension::dublincore::NAMESPACE.to_string(),
-  );
-  h.insert(
-    "media".to_string(),
-    "http://search.yahoo.com/mrss/".to_string(),
-  );
-  h
-});
-
 async fn get_all_feed(
   req: HttpRequest,
   web::Query(info): web::Query<Params>,
@@ -103,6 +138,7 @@ async fn get_all_feed(
   let lang = get_lang_or_negotiate(&req, &context).await?;
 
   get_feed_data(
+    &req,
     &context,
     ListingType::All,
     info.sort_type(),
@@ -120,6 +156,7 @@ async fn get_local_feed(
   let lang = get_lang_or_negotiate(&req, &context).await?;
 
   get_feed_data(
+    &req,
     &context,
     ListingType::Local,
     info.sort_type(),
@@ -130,6 +167,7 @@ async fn get_local_feed(
 }
 
 async fn get_feed_data(
+  req: &HttpRequest,
   context: &LemmyContext,
   listing_type: ListingType,
   sort_type: PostSortType,
@@ -160,8 +198,21 @@ async fn get_feed_data(
   );
 
   let link = context.settings().get_protocol_and_hostname();
-  let items = create_post_items(posts, context.settings(), lang)?;
-  Ok(send_feed_response(title, link, None, items, site_view))
+  let id = if listing_type == ListingType::Local {
+    format!("{link}/feeds/local")
+  } else {
+    format!("{link}/feeds/all")
+  };
+  let feed = Feed {
+    id,
+    title,
+    self_link: Some(feed_url(req, context.settings())),
+    description_html: feed_description(None, &site_view),
+    site_name: site_view.site.name,
+    link,
+    entries: create_post_items(posts, context.settings(), lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(req)))
 }
 
 async fn get_feed_user(
@@ -199,11 +250,16 @@ async fn get_feed_user(
     .collect::<Vec<PostView>>();
 
   let title = format!("{} - {}", site_view.site.name, person.name);
-  let link = person.ap_id.to_string();
-  let items = create_post_items(posts, context.settings(), lang)?;
-  Ok(send_feed_response(
-    title, link, person.bio, items, site_view,
-  ))
+  let feed = Feed {
+    id: person.ap_id.to_string(),
+    title,
+    link: person.ap_id.to_string(),
+    self_link: Some(feed_url(&req, context.settings())),
+    description_html: feed_description(person.bio, &site_view),
+    site_name: site_view.site.name,
+    entries: create_post_items(posts, context.settings(), lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(&req)))
 }
 
 /// Takes a user/community name either in the format `name` or `name@example.com`. Splits
@@ -246,15 +302,98 @@ async fn get_feed_community(
   .items;
 
   let title = format!("{} - {}", site_view.site.name, community.name);
-  let link = community.ap_id.to_string();
-  let items = create_post_items(posts, context.settings(), lang)?;
-  Ok(send_feed_response(
+  let feed = Feed {
+    id: community.ap_id.to_string(),
     title,
-    link,
-    community.summary,
-    items,
-    site_view,
-  ))
+    link: community.ap_id.to_string(),
+    self_link: Some(feed_url(&req, context.settings())),
+    description_html: feed_description(community.summary, &site_view),
+    site_name: site_view.site.name,
+    entries: create_post_items(posts, context.settings(), lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(&req)))
+}
+
+async fn get_feed_community_comments(
+  req: HttpRequest,
+  web::Query(info): web::Query<CommentParams>,
+  name: web::Path<String>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let (name, domain) = split_name(&name);
+  let community = Community::read_from_name(&mut context.pool(), name, domain, false)
+    .await?
+    .ok_or(ErrorBadRequest("not_found"))?;
+
+  if !community.visibility.can_view_without_login() {
+    return Err(ErrorBadRequest("not_found"));
+  }
+
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  check_private_instance(&None, &site_view.local_site)?;
+  let lang = get_lang_or_negotiate(&req, &context).await?;
+
+  let comments = CommentQuery {
+    sort: Some(info.sort_type()),
+    community_id: Some(community.id),
+    limit: Some(info.get_limit()),
+    ..Default::default()
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await?
+  .items;
+
+  let title = format!("{} - {}", site_view.site.name, community.name);
+  let feed = Feed {
+    id: format!("{}#comments", community.ap_id),
+    title,
+    link: community.ap_id.to_string(),
+    self_link: Some(feed_url(&req, context.settings())),
+    description_html: feed_description(community.summary, &site_view),
+    site_name: site_view.site.name,
+    entries: create_comment_items(comments, context.settings(), lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(&req)))
+}
+
+async fn get_feed_post_comments(
+  req: HttpRequest,
+  web::Query(info): web::Query<CommentParams>,
+  post_id: web::Path<i32>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let post = Post::read(&mut context.pool(), PostId(post_id.into_inner())).await?;
+  let community = Community::read(&mut context.pool(), post.community_id).await?;
+  if post.deleted || post.removed || !community.visibility.can_view_without_login() {
+    return Err(ErrorBadRequest("not_found"));
+  }
+
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  check_private_instance(&None, &site_view.local_site)?;
+  let lang = get_lang_or_negotiate(&req, &context).await?;
+
+  let comments = CommentQuery {
+    sort: Some(info.sort_type()),
+    post_id: Some(post.id),
+    limit: Some(info.get_limit()),
+    ..Default::default()
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await?
+  .items;
+
+  let post_url = post.local_url(context.settings())?;
+  let title = format!("{} - {}", site_view.site.name, post.name);
+  let feed = Feed {
+    id: format!("{post_url}#comments"),
+    title,
+    link: post_url.to_string(),
+    self_link: Some(feed_url(&req, context.settings())),
+    description_html: feed_description(None, &site_view),
+    site_name: site_view.site.name,
+    entries: create_comment_items(comments, context.settings(), lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(&req)))
 }
 
 async fn get_feed_multi_community(
@@ -283,15 +422,16 @@ async fn get_feed_multi_community(
   .items;
 
   let title = format!("{} - {}", site_view.site.name, multi_community.name);
-  let link = multi_community.ap_id.to_string();
-  let items = create_post_items(posts, context.settings(), lang)?;
-  Ok(send_feed_response(
+  let feed = Feed {
+    id: multi_community.ap_id.to_string(),
     title,
-    link,
-    multi_community.summary,
-    items,
-    site_view,
-  ))
+    link: multi_community.ap_id.to_string(),
+    self_link: Some(feed_url(&req, context.settings())),
+    description_html: feed_description(multi_community.summary, &site_view),
+    site_name: site_view.site.name,
+    entries: create_post_items(posts, context.settings(), lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(&req)))
 }
 
 async fn get_feed_front(
@@ -299,9 +439,9 @@ async fn get_feed_front(
   web::Query(info): web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  let jwt: String = req.match_info().get("jwt").unwrap_or("none").parse()?;
+  let token: String = req.match_info().get("token").unwrap_or("none").parse()?;
   let site_view = SiteView::read_local(&mut context.pool()).await?;
-  let local_user = local_user_view_from_jwt(&jwt, &context).await?;
+  let local_user = local_user_view_from_feed_token(&token, &context).await?;
   let lang = user_language(&local_user.local_user);
 
   check_private_instance(&Some(local_user.clone()), &site_view.local_site)?;
@@ -318,34 +458,54 @@ async fn get_feed_front(
   .items;
 
   let title = format!("{} - {}", site_view.site.name, lang.subscribed());
-  let link = context.settings().get_protocol_and_hostname();
-  let items = create_post_items(posts, context.settings(), lang)?;
-  Ok(send_feed_response(title, link, None, items, site_view))
-}
-
-fn send_feed_response(
-  title: String,
-  link: String,
-  description: Option<String>,
-  items: Vec<Item>,
-  site_view: SiteView,
-) -> HttpResponse {
-  let mut channel = Channel {
-    namespaces: RSS_NAMESPACE.clone(),
+  // The feed url contains the auth token, so it is not exposed as self link
+  let feed = Feed {
+    id: format!("{}#subscribed", local_user.person.ap_id),
     title,
-    link,
-    items,
-    ..Default::default()
+    link: context.settings().get_protocol_and_hostname(),
+    self_link: None,
+    description_html: feed_description(None, &site_view),
+    site_name: site_view.site.name,
+    entries: create_post_items(posts, context.settings(), lang)?,
   };
+  Ok(feed.into_response(negotiate_format(&req)))
+}
 
-  let description = description.or(site_view.site.summary);
-  if let Some(desc) = description {
-    channel.set_description(markdown_to_html(&desc));
+/// Reads the user for a private feed. Feed tokens only give access to feeds, unlike login tokens
+/// which are still accepted during the deprecation period.
+async fn local_user_view_from_feed_token(
+  token: &str,
+  context: &LemmyContext,
+) -> LemmyResult<LocalUserView> {
+  match FeedToken::validate(&mut context.pool(), token).await {
+    Ok(local_user_id) => {
+      let local_user_view = LocalUserView::read(&mut context.pool(), local_user_id).await?;
+      check_local_user_deleted(&local_user_view)?;
+      Ok(local_user_view)
+    }
+    Err(_)
+      if context
+        .settings()
+        .login_token_feeds_valid_until
+        .is_some_and(|until| Utc::now() < until) =>
+    {
+      warn!("Private feed accessed with login token, which is deprecated");
+      local_user_view_from_jwt(token, context).await
+    }
+    Err(e) => Err(e),
   }
+}
 
-  HttpResponse::Ok()
-    .content_type("application/rss+xml")
-    .body(channel.to_string())
+/// Url of a public feed, in the requested format.
+fn feed_url(req: &HttpRequest, settings: &Settings) -> String {
+  format!("{}{}", settings.get_protocol_and_hostname(), req.path())
+}
+
+/// Converts the feed description to html, falling back to the site summary.
+fn feed_description(description: Option<String>, site_view: &SiteView) -> Option<String> {
+  description
+    .or_else(|| site_view.site.summary.clone())
+    .map(|d| markdown_to_html(&d))
 }
 
 async fn get_feed_notifs(
@@ -353,9 +513,9 @@ async fn get_feed_notifs(
   _info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  let jwt: String = req.match_info().get("jwt").unwrap_or("none").parse()?;
+  let token: String = req.match_info().get("token").unwrap_or("none").parse()?;
   let site_view = SiteView::read_local(&mut context.pool()).await?;
-  let local_user = local_user_view_from_jwt(&jwt, &context).await?;
+  let local_user = local_user_view_from_feed_token(&token, &context).await?;
   let show_bot_accounts = Some(local_user.local_user.show_bot_accounts);
   let lang = user_language(&local_user.local_user);
 
@@ -371,9 +531,16 @@ async fn get_feed_notifs(
 
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
   let title = format!("{} - {}", site_view.site.name, lang.notifications());
-  let link = format!("{protocol_and_hostname}/notifications");
-  let items = create_reply_and_mention_items(notifications, &context, lang)?;
-  Ok(send_feed_response(title, link, None, items, site_view))
+  let feed = Feed {
+    id: format!("{}#notifications", local_user.person.ap_id),
+    title,
+    link: format!("{protocol_and_hostname}/notifications"),
+    self_link: None,
+    description_html: feed_description(None, &site_view),
+    site_name: site_view.site.name,
+    entries: create_reply_and_mention_items(notifications, &context, lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(&req)))
 }
 
 /// Gets your ModeratorView modlog
@@ -382,9 +549,9 @@ async fn get_feed_modlog(
   _info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  let jwt: String = req.match_info().get("jwt").unwrap_or("none").parse()?;
+  let token: String = req.match_info().get("token").unwrap_or("none").parse()?;
   let site_view = SiteView::read_local(&mut context.pool()).await?;
-  let local_user = local_user_view_from_jwt(&jwt, &context).await?;
+  let local_user = local_user_view_from_feed_token(&token, &context).await?;
   let lang = user_language(&local_user.local_user);
   check_private_instance(&Some(local_user.clone()), &site_view.local_site)?;
 
@@ -400,64 +567,84 @@ async fn get_feed_modlog(
 
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
   let title = format!("{} - {}", local_user.person.name, lang.modlog());
-  let link = format!("{protocol_and_hostname}/modlog");
-  let items = create_modlog_items(modlog, context.settings(), lang)?;
-  Ok(send_feed_response(title, link, None, items, site_view))
+  let feed = Feed {
+    id: format!("{}#modlog", local_user.person.ap_id),
+    title,
+    link: format!("{protocol_and_hostname}/modlog"),
+    self_link: None,
+    description_html: feed_description(None, &site_view),
+    site_name: site_view.site.name,
+    entries: create_modlog_items(modlog, context.settings(), lang)?,
+  };
+  Ok(feed.into_response(negotiate_format(&req)))
 }
 
 fn create_reply_and_mention_items(
   notifs: Vec<NotificationView>,
   context: &LemmyContext,
   lang: Lang,
-) -> LemmyResult<Vec<Item>> {
-  let reply_items: Vec<Item> = notifs
-    .iter()
+) -> LemmyResult<Vec<FeedEntry>> {
+  let settings = context.settings();
+  let reply_items: Vec<FeedEntry> = notifs
+    .into_iter()
     .flat_map(|v| {
-      match &v.data {
+      // Returns creator, id, url, published, updated and content of the notification
+      let (creator, id, url, published, updated, content) = match v.data {
         NotificationData::Post(post) => {
-          let mention_url = post.post.local_url(context.settings()).ok()?;
-          Some(build_item(
-            &post.creator,
-            &post.post.published_at,
-            mention_url.as_str(),
-            &post.post.body.clone().unwrap_or_default(),
-            &v.notification,
-            context.settings(),
-            lang,
-          ))
+          let mention_url = post.post.local_url(settings).ok()?.to_string();
+          (
+            post.creator,
+            mention_url.clone(),
+            mention_url,
+            post.post.published_at,
+            post.post.updated_at,
+            post.post.body.unwrap_or_default(),
+          )
         }
         NotificationData::Comment(comment) => {
-          let reply_url = comment.comment.local_url(context.settings()).ok()?;
-          Some(build_item(
-            &comment.creator,
-            &comment.comment.published_at,
-            reply_url.as_str(),
-            &comment.comment.content,
-            &v.notification,
-            context.settings(),
-            lang,
-          ))
-        }
-        NotificationData::PrivateMessage(pm) => {
-          let notifs_url = format!(
-            "{}/notifications",
-            context.settings().get_protocol_and_hostname()
-          );
-          Some(build_item(
-            &pm.creator,
-            &pm.private_message.published_at,
-            &notifs_url,
-            &pm.private_message.content,
-            &v.notification,
-            context.settings(),
-            lang,
-          ))
+          let reply_url = comment.comment.local_url(settings).ok()?.to_string();
+          (
+            comment.creator,
+            reply_url.clone(),
+            reply_url,
+            comment.comment.published_at,
+            comment.comment.updated_at,
+            comment.comment.content,
+          )
         }
+        // Private messages have no page of their own, so the ap_id is used as unique id
+        NotificationData::PrivateMessage(pm) => (
+          pm.creator,
+          pm.private_message.ap_id.to_string(),
+          format!("{}/notifications", settings.get_protocol_and_hostname()),
+          pm.private_message.published_at,
+          pm.private_message.updated_at,
+          pm.private_message.content,
+        ),
         // skip modlog items
-        NotificationData::ModAction(_) => None,
-      }
+        NotificationData::ModAction(_) => return None,
+      };
+      Some(
+        build_item(&creator, &v.notification, lang, settings).map(|(title, author)| FeedEntry {
+          id,
+          title,
+          author: Some(author),
+          published,
+          updated: updated.unwrap_or(published),
+          content_html: Some(markdown_to_html(&content)),
+          category: None,
+          enclosure: None,
+          thumbnail: None,
+          comments: Some(FeedComments {
+            url: url.clone(),
+            count: None,
+          }),
+          score: None,
+          link: url,
+        }),
+      )
     })
-    .collect::<LemmyResult<Vec<Item>>>()?;
+    .collect::<LemmyResult<Vec<FeedEntry>>>()?;
 
   Ok(reply_items)
 }
@@ -466,14 +653,14 @@ fn create_modlog_items(
   modlog: Vec<ModlogView>,
   settings: &Settings,
   lang: Lang,
-) -> LemmyResult<Vec<Item>> {
+) -> LemmyResult<Vec<FeedEntry>> {
   // All of these go to your modlog url
   let modlog_url = format!(
     "{}/modlog?listing_type=ModeratorView",
     settings.get_protocol_and_hostname()
   );
 
-  let modlog_items: Vec<Item> = modlog
+  let modlog_items: Vec<FeedEntry> = modlog
     .iter()
     .map(|r| {
       let u = |x: Option<String>| x.unwrap_or_else(|| "unknown".to_string());
@@ -657,7 +844,7 @@ fn create_modlog_items(
         ),
       }
     })
-    .collect::<LemmyResult<Vec<Item>>>()?;
+    .collect::<LemmyResult<Vec<FeedEntry>>>()?;
 
   Ok(modlog_items)
 }
@@ -667,48 +854,41 @@ fn build_modlog_item<T: Into<String>>(
   url: &str,
   action: T,
   settings: &Settings,
-) -> LemmyResult<Item> {
-  let guid = Some(Guid {
-    permalink: true,
-    value: view.modlog.id.0.to_string(),
-  });
+) -> LemmyResult<FeedEntry> {
   let author = if let Some(mod_) = &view.moderator {
-    Some(format!(
-      "/u/{} <a href=\"{}\">(link)</a>",
-      mod_.name,
-      mod_.actor_url(settings)?
-    ))
+    Some(FeedAuthor {
+      name: mod_.name.clone(),
+      url: mod_.actor_url(settings)?.to_string(),
+    })
   } else {
     None
   };
 
-  Ok(Item {
-    title: Some(action.into()),
+  Ok(FeedEntry {
+    // All entries link to the same page, so the modlog id is used to make it unique
+    id: format!("{url}#{}", view.modlog.id.0),
+    title: action.into(),
+    link: url.to_owned(),
     author,
-    pub_date: Some(view.modlog.published_at.to_rfc2822()),
-    link: Some(url.to_owned()),
-    guid,
-    description: view.modlog.reason.clone(),
-    ..Default::default()
+    published: view.modlog.published_at,
+    updated: view.modlog.published_at,
+    content_html: view.modlog.reason.clone(),
+    category: None,
+    enclosure: None,
+    thumbnail: None,
+    comments: None,
+    score: None,
   })
 }
 
+/// Returns title and author of a notification entry.
 fn build_item(
   creator: &Person,
-  published: &DateTime<Utc>,
-  url: &str,
-  content: &str,
   notification: &Notification,
-  settings: &Settings,
   lang: Lang,
-) -> LemmyResult<Item> {
+  settings: &Settings,
+) -> LemmyResult<(String, FeedAuthor)> {
   // TODO add images
-  let guid = Some(Guid {
-    permalink: true,
-    value: url.to_owned(),
-  });
-  let description = Some(markdown_to_html(content));
-
   let title = match notification.kind {
     NotificationType::Mention => lang.mention_from_x(creator.name.clone()),
     NotificationType::Reply => lang.reply_from_x(creator.name.clone()),
@@ -716,40 +896,23 @@ fn build_item(
     NotificationType::PrivateMessage => lang.private_message_from_x(creator.name.clone()),
     NotificationType::ModAction => lang.mod_action().to_string(),
   };
-  Ok(Item {
-    title: Some(title),
-    author: Some(format!(
-      "/u/{} <a href=\"{}\">(link)</a>",
-      creator.name,
-      creator.actor_url(settings)?
-    )),
-    pub_date: Some(published.to_rfc2822()),
-    comments: Some(url.to_owned()),
-    link: Some(url.to_owned()),
-    guid,
-    description,
-    ..Default::default()
-  })
+  let author = FeedAuthor {
+    name: creator.name.clone(),
+    url: creator.actor_url(settings)?.to_string(),
+  };
+  Ok((title, author))
 }
 
 fn create_post_items(
   posts: Vec<PostView>,
   settings: &Settings,
   lang: Lang,
-) -> LemmyResult<Vec<Item>> {
-  let mut items: Vec<Item> = Vec::new();
+) -> LemmyResult<Vec<FeedEntry>> {
+  let mut items: Vec<FeedEntry> = Vec::new();
 
   for p in posts {
     let post_url = p.post.local_url(settings)?;
     let community_url = &p.community.actor_url(settings)?;
-    let dublin_core_ext = Some(DublinCoreExtension {
-      creators: vec![p.creator.ap_id.to_string()],
-      ..DublinCoreExtension::default()
-    });
-    let guid = Some(Guid {
-      permalink: true,
-      value: post_url.to_string(),
-    });
     let mut description = lang.submitted_post_with_meta_info(
       p.creator.actor_url(settings)?,
       &p.community.name,
@@ -762,7 +925,7 @@ fn create_post_items(
 
     // If its a url post, add it to the description
     // and see if we can parse it as a media enclosure.
-    let enclosure_opt = p.post.url.map(|url| {
+    let enclosure = p.post.url.map(|url| {
       let mime_type = p
         .post
         .url_content_type
@@ -776,11 +939,10 @@ fn create_post_items(
       };
       description.push_str(&link_html);
 
-      let mut enclosure_bld = EnclosureBuilder::default();
-      enclosure_bld.url(url.as_str().to_string());
-      enclosure_bld.mime_type(mime_type);
-      enclosure_bld.length("0".to_string());
-      enclosure_bld.build()
+      FeedEnclosure {
+        url: url.to_string(),
+        mime_type,
+      }
     });
 
     if let Some(body) = p.post.body {
@@ -788,44 +950,74 @@ fn create_post_items(
       description.push_str(&html);
     }
 
-    let mut extensions = ExtensionMap::new();
-
-    // If there's a thumbnail URL, add a media:content tag to display it.
-    // See https://www.rssboard.org/media-rss#media-content for details.
-    if let Some(url) = p.post.thumbnail_url {
-      let mut thumbnail_ext = ExtensionBuilder::default();
-      thumbnail_ext.name("media:content".to_string());
-      thumbnail_ext.attrs(BTreeMap::from([
-        ("url".to_string(), url.to_string()),
-        ("medium".to_string(), "image".to_string()),
-      ]));
-
-      extensions.insert(
-        "media".to_string(),
-        BTreeMap::from([("content".to_string(), vec![thumbnail_ext.build()])]),
-      );
-    }
-    let category = Category {
-      name: p.community.title,
-      domain: Some(p.community.ap_id.to_string()),
-    };
-
-    let i = Item {
-      title: Some(p.post.name),
-      pub_date: Some(p.post.published_at.to_rfc2822()),
-      comments: Some(post_url.to_string()),
-      guid,
-      description: Some(description),
-      dublin_core_ext,
-      link: Some(post_url.to_string()),
-      extensions,
-      enclosure: enclosure_opt,
-      categories: vec![category],
-      ..Default::default()
-    };
-
-    items.push(i);
+    items.push(FeedEntry {
+      id: post_url.to_string(),
+      title: p.post.name,
+      link: post_url.to_string(),
+      author: Some(FeedAuthor {
+        name: p.creator.name,
+        url: p.creator.ap_id.to_string(),
+      }),
+      published: p.post.published_at,
+      updated: p.post.updated_at.unwrap_or(p.post.published_at),
+      content_html: Some(description),
+      category: Some(FeedCategory {
+        term: p.community.name,
+        label: p.community.title,
+        url: p.community.ap_id.to_string(),
+      }),
+      enclosure,
+      thumbnail: p.post.thumbnail_url.map(|u| u.to_string()),
+      comments: Some(FeedComments {
+        url: post_url.to_string(),
+        count: Some(p.post.comments),
+      }),
+      score: Some(p.post.score),
+    });
   }
 
   Ok(items)
 }
+
+fn create_comment_items(
+  comments: Vec<CommentView>,
+  settings: &Settings,
+  lang: Lang,
+) -> LemmyResult<Vec<FeedEntry>> {
+  comments
+    .into_iter()
+    // Content of deleted and removed comments is not available
+    .filter(|c| !c.comment.deleted && !c.comment.removed)
+    .map(|c| {
+      let comment_url = c.comment.local_url(settings)?.to_string();
+      Ok(FeedEntry {
+        id: comment_url.clone(),
+        title: format!(
+          "{} - {}",
+          lang.reply_from_x(c.creator.name.clone()),
+          c.post.name
+        ),
+        link: comment_url.clone(),
+        author: Some(FeedAuthor {
+          name: c.creator.name,
+          url: c.creator.ap_id.to_string(),
+        }),
+        published: c.comment.published_at,
+        updated: c.comment.updated_at.unwrap_or(c.comment.published_at),
+        content_html: Some(markdown_to_html(&c.comment.content)),
+        category: Some(FeedCategory {
+          term: c.community.name,
+          label: c.community.title,
+          url: c.community.ap_id.to_string(),
+        }),
+        enclosure: None,
+        thumbnail: None,
+        comments: Some(FeedComments {
+          url: comment_url,
+          count: Some(c.comment.child_count),
+        }),
+        score: Some(c.comment.score),
+      })
+    })
+    .collect()
+}
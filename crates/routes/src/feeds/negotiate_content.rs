@@ -1,3 +1,4 @@
+use super::format::FeedFormat;
 use actix_web::{Error, HttpRequest, http::header::*, web};
 use lemmy_api_utils::{
   context::LemmyContext,
@@ -32,6 +33,29 @@ fn negotiate_lang(req: &HttpRequest) -> Option<Lang> {
   })
 }
 
+/// Returns the format given by the file extension. For `.xml`, which was the only format before
+/// Atom and JSON Feed were added, the `Accept` header is also considered.
+pub(crate) fn negotiate_format(req: &HttpRequest) -> FeedFormat {
+  let format = req
+    .match_info()
+    .get("format")
+    .and_then(FeedFormat::from_extension)
+    .unwrap_or(FeedFormat::Rss);
+  if format == FeedFormat::Rss {
+    negotiate_accept(req).unwrap_or(format)
+  } else {
+    format
+  }
+}
+
+fn negotiate_accept(req: &HttpRequest) -> Option<FeedFormat> {
+  let accept = Accept::parse(req).ok()?;
+  accept
+    .ranked()
+    .iter()
+    .find_map(|m| FeedFormat::from_media_type(m.essence_str()))
+}
+
 #[cfg(test)]
 #[expect(clippy::unwrap_used)]
 mod tests {
@@ -96,4 +120,45 @@ mod tests {
 
     assert!(resolved_lang.is_some());
   }
+
+  #[test]
+  fn test_negotiate_format_extension() {
+    let req = TestRequest::default()
+      .param("format", "atom")
+      .insert_header((ACCEPT, "application/feed+json"))
+      .to_http_request();
+    assert_eq!(negotiate_format(&req), FeedFormat::Atom);
+
+    let req = TestRequest::default()
+      .param("format", "json")
+      .to_http_request();
+    assert_eq!(negotiate_format(&req), FeedFormat::Json);
+
+    let req = TestRequest::default()
+      .param("format", "xml")
+      .to_http_request();
+    assert_eq!(negotiate_format(&req), FeedFormat::Rss);
+  }
+
+  #[test]
+  fn test_negotiate_format_accept_header() {
+    let req = TestRequest::default()
+      .param("format", "xml")
+      .insert_header((
+        ACCEPT,
+        "application/feed+json;q=0.5, application/atom+xml, */*;q=0.1",
+      ))
+      .to_http_request();
+    assert_eq!(negotiate_format(&req), FeedFormat::Atom);
+
+    // Browsers prefer html, which is not available
+    let req = TestRequest::default()
+      .param("format", "xml")
+      .insert_header((
+        ACCEPT,
+        "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+      ))
+      .to_http_request();
+    assert_eq!(negotiate_format(&req), FeedFormat::Rss);
+  }
 }
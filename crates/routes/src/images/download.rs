@@ -4,14 +4,23 @@ use actix_web::{
   HttpResponse,
   Responder,
   body::{BodyStream, BoxBody},
-  http::StatusCode,
+  http::{
+    StatusCode,
+    header::{CacheControl, CacheDirective},
+  },
   web::{Data, *},
 };
-use lemmy_api_utils::context::LemmyContext;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  media::{is_native_media, read_image, read_proxied_image},
+};
 use lemmy_db_schema::source::images::RemoteImage;
 use lemmy_db_views_local_image::api::{ImageGetParams, ImageProxyParams};
 use lemmy_db_views_site::SiteView;
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  rate_limit::client_ip,
+};
 use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use std::str::FromStr;
 use strum::{Display, EnumString};
@@ -25,6 +34,20 @@ pub async fn get_image(
 ) -> LemmyResult<HttpResponse> {
   let name = &filename.into_inner();
 
+  if is_native_media(context.settings()) {
+    let image = read_image(
+      name,
+      params.max_size,
+      params.file_type.as_deref(),
+      context.settings(),
+    )
+    .await?;
+    return Ok(match image {
+      Some((data, content_type)) => image_response(data, content_type),
+      None => HttpResponse::NotFound().finish(),
+    });
+  }
+
   // If there are no query params, the URL is original
   let pictrs_url = context.settings().pictrs()?.url;
   let processed_url = if params.file_type.is_none() && params.max_size.is_none() {
@@ -55,23 +78,6 @@ pub async fn image_proxy(
   // for arbitrary purposes.
   RemoteImage::validate(&mut context.pool(), url.clone().into()).await?;
 
-  let pictrs_config = context.settings().pictrs()?;
-  let processed_url = if params.file_type.is_none() && params.max_size.is_none() {
-    format!("{}image/original?proxy={}", pictrs_config.url, encoded_url)
-  } else {
-    let file_type = file_type(params.file_type, url.path()).unwrap_or_default();
-
-    let mut url = format!(
-      "{}image/process.{}?proxy={}",
-      pictrs_config.url, file_type, encoded_url
-    );
-
-    if let Some(size) = params.max_size {
-      url = format!("{url}&thumbnail={size}",);
-    }
-    url
-  };
-
   let proxy_bypass_domains = SiteView::read_local(&mut context.pool())
     .await?
     .local_site
@@ -85,7 +91,28 @@ pub async fn image_proxy(
   if bypass_proxy {
     // Bypass proxy and redirect user to original image
     Ok(Either::Left(Redirect::to(url.to_string()).respond_to(&req)))
+  } else if is_native_media(context.settings()) {
+    let (data, content_type) =
+      read_proxied_image(&url, params.max_size, params.file_type.as_deref(), &context).await?;
+    Ok(Either::Right(image_response(data, content_type)))
   } else {
+    let pictrs_config = context.settings().pictrs()?;
+    let processed_url = if params.file_type.is_none() && params.max_size.is_none() {
+      format!("{}image/original?proxy={}", pictrs_config.url, encoded_url)
+    } else {
+      let file_type = file_type(params.file_type, url.path()).unwrap_or_default();
+
+      let mut url = format!(
+        "{}image/process.{}?proxy={}",
+        pictrs_config.url, file_type, encoded_url
+      );
+
+      if let Some(size) = params.max_size {
+        url = format!("{url}&thumbnail={size}",);
+      }
+      url
+    };
+
     // Proxy the image data through Lemmy
     Ok(Either::Right(
       do_get_image(processed_url, req, &context).await?,
@@ -93,6 +120,19 @@ pub async fn image_proxy(
   }
 }
 
+/// Response for images from the native media backend. Stored images never change, so they can be
+/// cached indefinitely.
+fn image_response(data: Bytes, content_type: &str) -> HttpResponse {
+  HttpResponse::Ok()
+    .content_type(content_type)
+    .insert_header(CacheControl(vec![
+      CacheDirective::Public,
+      CacheDirective::MaxAge(31_536_000),
+      CacheDirective::Extension("immutable".to_string(), None),
+    ]))
+    .body(data)
+}
+
 pub(super) async fn do_get_image(
   url: String,
   req: HttpRequest,
@@ -100,12 +140,8 @@ pub(super) async fn do_get_image(
 ) -> LemmyResult<HttpResponse> {
   let mut client_req = adapt_request(&req, url, context);
 
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
-  }
-
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
+  if let Some(addr) = client_ip(&req) {
+    client_req = client_req.header("X-Forwarded-For", addr);
   }
 
   let res = client_req.send().await?;
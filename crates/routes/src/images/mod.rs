@@ -1,5 +1,8 @@
 use actix_web::web::*;
-use lemmy_api_utils::context::LemmyContext;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  media::{is_native_media, media_health},
+};
 use lemmy_db_views_site::api::SuccessResponse;
 use lemmy_utils::error::LemmyResult;
 
@@ -9,6 +12,10 @@ pub mod upload;
 mod utils;
 
 pub async fn pictrs_health(context: Data<LemmyContext>) -> LemmyResult<Json<SuccessResponse>> {
+  if is_native_media(context.settings()) {
+    media_health(context.settings()).await?;
+    return Ok(Json(SuccessResponse::default()));
+  }
   let pictrs_config = context.settings().pictrs()?;
   let url = format!("{}healthz", pictrs_config.url);
 
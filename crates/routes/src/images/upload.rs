@@ -1,15 +1,22 @@
-use super::utils::{adapt_request, delete_old_image, make_send};
+use super::utils::delete_old_image;
 use UploadType::*;
+use actix_multipart::{Multipart, MultipartError};
 use actix_web::{self, HttpRequest, web::*};
+use bytes::Bytes;
+use futures::TryStreamExt;
 use lemmy_api_utils::{
   context::LemmyContext,
-  request::PictrsResponse,
-  utils::{is_admin, is_mod_or_admin},
+  image_hash::check_image_hash,
+  image_metadata::strip_metadata,
+  media::{delete_image, is_native_media, store_image},
+  request::{PictrsResponse, pictrs_image_hash, pictrs_image_size, purge_image_from_pictrs},
+  utils::{image_upload_quota, is_admin, is_mod_or_admin},
 };
 use lemmy_db_schema::source::{
   community::{Community, CommunityUpdateForm},
   images::{LocalImage, LocalImageForm},
   local_site::LocalSite,
+  local_user::LocalUser,
   person::{Person, PersonUpdateForm},
   site::{Site, SiteUpdateForm},
 };
@@ -18,9 +25,13 @@ use lemmy_db_views_local_image::api::UploadImageResponse;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::SiteView;
 use lemmy_diesel_utils::traits::Crud;
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
-use reqwest::Body;
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  rate_limit::client_ip,
+};
+use reqwest::multipart::{Form, Part};
 use std::time::Duration;
+use tokio::task::spawn_blocking;
 
 pub enum UploadType {
   Avatar,
@@ -189,11 +200,36 @@ async fn do_upload_image(
   local_site: &LocalSite,
   context: &Data<LemmyContext>,
 ) -> LemmyResult<UploadImageResponse> {
+  check_image_upload_quota(&local_user_view.local_user, local_site)?;
+  if is_native_media(context.settings()) {
+    return do_upload_image_native(req, body, upload_type, local_user_view, local_site, context)
+      .await;
+  }
   let pictrs_url = context.settings().pictrs()?.url;
   let max_upload_size = local_site.image_max_upload_size.to_string();
   let image_url = format!("{}image", pictrs_url);
 
-  let mut client_req = adapt_request(&req, image_url, context);
+  // Read the upload first so that metadata can be removed before passing it to pict-rs.
+  let allow_video = matches!(upload_type, Other) && local_site.image_allow_video_uploads;
+  let mut form = Form::new();
+  for file in read_multipart(&req, body, context).await? {
+    let data = spawn_blocking(move || strip_metadata(file.data, allow_video)).await??;
+    let mut part = Part::stream(data);
+    if let Some(file_name) = file.file_name {
+      part = part.file_name(file_name);
+    }
+    if let Some(content_type) = file.content_type {
+      part = part.mime_str(&content_type)?;
+    }
+    form = form.part("images[]", part);
+  }
+
+  let mut client_req = context
+    .pictrs_client()
+    .post(image_url)
+    .timeout(Duration::from_secs(
+      local_site.image_upload_timeout_seconds.try_into()?,
+    ));
 
   // Set pictrs parameters to downscale images and restrict file types.
   // https://git.asonix.dog/asonix/pict-rs/#api
@@ -223,15 +259,12 @@ async fn do_upload_image(
       client_req.query(&query)
     }
   };
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string())
+  if let Some(addr) = client_ip(&req) {
+    client_req = client_req.header("X-Forwarded-For", addr)
   };
   // Make HTTP request to pict-rs with the user provided image data.
   let res = client_req
-    .timeout(Duration::from_secs(
-      local_site.image_upload_timeout_seconds.try_into()?,
-    ))
-    .body(Body::wrap_stream(make_send(body)))
+    .multipart(form)
     .send()
     .await
     // Dont check for status code here and dont call `error_for_status()`. If the upload failed,
@@ -241,21 +274,42 @@ async fn do_upload_image(
     ))?;
 
   let mut images = res.json::<PictrsResponse>().await?;
+  let mut uploaded_bytes = 0;
   for image in &images.files {
     // Pictrs allows uploading multiple images in a single request. Lemmy doesnt need this,
     // but still a user may upload multiple and so we need to store all links in db for
     // to allow deletion via web ui.
+    let hash_and_size = async {
+      let phash = pictrs_image_hash(&image.file, context).await?;
+      check_image_hash(phash, context).await?;
+      // The size is only known after the upload, so the quota is checked again here
+      let size = pictrs_image_size(&image.file, context).await?;
+      uploaded_bytes += size;
+      check_uploaded_size(&local_user_view.local_user, local_site, uploaded_bytes)?;
+      LemmyResult::Ok((phash, size))
+    }
+    .await;
+    let (phash, size) = match hash_and_size {
+      Ok(hash_and_size) => hash_and_size,
+      Err(e) => {
+        purge_image_from_pictrs(&image.file, context).await.ok();
+        return Err(e);
+      }
+    };
+
     let form = LocalImageForm {
       pictrs_alias: image.file.clone(),
       person_id: local_user_view.person.id,
       thumbnail_for_post_id: None,
+      size,
     };
 
     let protocol_and_hostname = context.settings().get_protocol_and_hostname();
     let thumbnail_url = image.image_url(&protocol_and_hostname)?;
 
     // Also store the details for the image
-    let details_form = image.details.build_image_details_form(&thumbnail_url);
+    let mut details_form = image.details.build_image_details_form(&thumbnail_url);
+    details_form.phash = Some(phash);
     LocalImage::create(&mut context.pool(), &form, &details_form).await?;
   }
   let image = images
@@ -269,3 +323,168 @@ async fn do_upload_image(
     filename: image.file,
   })
 }
+
+/// Rejects the upload if the user has already used up their storage quota.
+fn check_image_upload_quota(local_user: &LocalUser, local_site: &LocalSite) -> LemmyResult<()> {
+  if let Some(quota) = image_upload_quota(local_user, local_site)
+    && local_user.image_upload_bytes >= quota
+  {
+    return Err(LemmyErrorType::ImageUploadQuotaExceeded.into());
+  }
+  Ok(())
+}
+
+/// Rejects uploaded images which don't fit into the remaining storage quota. `uploaded_bytes` is
+/// the total size of the images from the current upload.
+fn check_uploaded_size(
+  local_user: &LocalUser,
+  local_site: &LocalSite,
+  uploaded_bytes: i64,
+) -> LemmyResult<()> {
+  if let Some(quota) = image_upload_quota(local_user, local_site)
+    && local_user.image_upload_bytes.saturating_add(uploaded_bytes) > quota
+  {
+    return Err(LemmyErrorType::ImageUploadQuotaExceeded.into());
+  }
+  Ok(())
+}
+
+/// Same as [`do_upload_image`] but processes and stores the image without pict-rs. Clients send
+/// the same multipart form in both cases.
+async fn do_upload_image_native(
+  req: HttpRequest,
+  body: Payload,
+  upload_type: UploadType,
+  local_user_view: &LocalUserView,
+  local_site: &LocalSite,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<UploadImageResponse> {
+  let (max_size, allow_animation) = match upload_type {
+    Avatar => (local_site.image_max_avatar_size, false),
+    Banner => (local_site.image_max_banner_size, false),
+    Other => (local_site.image_max_upload_size, true),
+  };
+  let mut image = None;
+  let mut uploaded_bytes = 0;
+  for file in read_multipart(&req, body, context).await? {
+    // Like with pict-rs, all uploaded images are stored but only the last one is returned
+    let stored = store_image(
+      file.data,
+      max_size,
+      allow_animation,
+      local_user_view.person.id,
+      None,
+      context,
+    )
+    .await?;
+    // Images are downscaled during processing, so the quota is checked with the stored size
+    uploaded_bytes += stored.size;
+    if let Err(e) = check_uploaded_size(&local_user_view.local_user, local_site, uploaded_bytes) {
+      delete_image(&stored.alias, context).await.ok();
+      return Err(e);
+    }
+    image = Some(stored);
+  }
+  let image = image.ok_or(LemmyErrorType::PictrsInvalidImageUpload(
+    "No image in upload".to_string(),
+  ))?;
+
+  Ok(UploadImageResponse {
+    image_url: image.url,
+    filename: image.alias,
+  })
+}
+
+/// A file from the multipart upload form.
+struct UploadedFile {
+  file_name: Option<String>,
+  content_type: Option<String>,
+  data: Bytes,
+}
+
+/// Maximum number of fields in the multipart upload form. Clients only send a single image, but
+/// pict-rs accepts multiple.
+const MAX_UPLOAD_FILES: usize = 4;
+
+/// Reads all files from the multipart upload form into memory. Fails if there are too many of
+/// them, or if they are larger than the configured maximum upload size in total.
+async fn read_multipart(
+  req: &HttpRequest,
+  body: Payload,
+  context: &LemmyContext,
+) -> LemmyResult<Vec<UploadedFile>> {
+  let max_bytes = context.settings().media.max_upload_bytes;
+  let invalid_upload = |e: MultipartError| LemmyErrorType::PictrsInvalidImageUpload(e.to_string());
+
+  let mut multipart = Multipart::new(req.headers(), body);
+  let mut files = vec![];
+  let mut total_bytes = 0;
+  while let Some(mut field) = multipart.try_next().await.map_err(invalid_upload)? {
+    if files.len() >= MAX_UPLOAD_FILES {
+      return Err(
+        LemmyErrorType::PictrsInvalidImageUpload("Too many files in upload".to_string()).into(),
+      );
+    }
+    let file_name = field
+      .content_disposition()
+      .and_then(|c| c.get_filename())
+      .map(ToString::to_string);
+    let content_type = field.content_type().map(ToString::to_string);
+    let mut data = vec![];
+    while let Some(chunk) = field.try_next().await.map_err(invalid_upload)? {
+      data.extend_from_slice(&chunk);
+      total_bytes += chunk.len();
+      if total_bytes > max_bytes {
+        return Err(LemmyErrorType::ImageTooLarge.into());
+      }
+    }
+    files.push(UploadedFile {
+      file_name,
+      content_type,
+      data: data.into(),
+    });
+  }
+  Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_image_upload_quota() {
+    let local_site = LocalSite {
+      image_upload_quota_bytes: Some(1000),
+      ..Default::default()
+    };
+    let mut local_user = LocalUser {
+      image_upload_bytes: 800,
+      ..Default::default()
+    };
+
+    // Uploads are allowed while there is space left, but the stored images must fit
+    assert!(check_image_upload_quota(&local_user, &local_site).is_ok());
+    assert!(check_uploaded_size(&local_user, &local_site, 200).is_ok());
+    assert!(check_uploaded_size(&local_user, &local_site, 201).is_err());
+
+    local_user.image_upload_bytes = 1000;
+    assert!(check_image_upload_quota(&local_user, &local_site).is_err());
+
+    // The user quota overrides the site quota
+    local_user.image_upload_quota_bytes = Some(2000);
+    assert_eq!(Some(2000), image_upload_quota(&local_user, &local_site));
+    assert!(check_image_upload_quota(&local_user, &local_site).is_ok());
+    assert!(check_uploaded_size(&local_user, &local_site, 1000).is_ok());
+    assert!(check_uploaded_size(&local_user, &local_site, 1001).is_err());
+
+    // Admins are not limited
+    local_user.admin = true;
+    assert_eq!(None, image_upload_quota(&local_user, &local_site));
+    assert!(check_uploaded_size(&local_user, &local_site, i64::MAX).is_ok());
+
+    // Neither are users if there is no site quota
+    let local_user = LocalUser::default();
+    assert_eq!(None, image_upload_quota(&local_user, &LocalSite::default()));
+  }
+}
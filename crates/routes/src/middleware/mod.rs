@@ -1,2 +1,3 @@
 pub mod idempotency;
+pub mod response_cache;
 pub mod session;
@@ -0,0 +1,407 @@
+use actix_web::{
+  Error,
+  HttpMessage,
+  HttpResponse,
+  body::{BoxBody, EitherBody, MessageBody, to_bytes},
+  dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+  error::ErrorInternalServerError,
+  http::{
+    Method,
+    StatusCode,
+    header::{CONTENT_TYPE, ETAG, HeaderValue, IF_NONE_MATCH},
+  },
+};
+use bytes::Bytes;
+use futures_util::future::LocalBoxFuture;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_utils::CACHE_DURATION_ANONYMOUS_API;
+use moka::future::Cache;
+use sha2::{Digest, Sha256};
+use std::{
+  future::{Ready, ready},
+  rc::Rc,
+};
+use url::form_urlencoded;
+
+/// Responses of these endpoints are the same for all logged out users, so they are cached. Requests
+/// with any other query parameters than the listed ones are not cached, so that made up parameters
+/// can't be used to fill the cache.
+const CACHED_PATHS: [(&str, &[&str]); 4] = [
+  ("/api/v4/site", &[]),
+  (
+    "/api/v4/post/list",
+    &[
+      "type_",
+      "sort",
+      "time_range_seconds",
+      "community_id",
+      "community_name",
+      "multi_community_id",
+      "multi_community_name",
+      "show_hidden",
+      "show_read",
+      "show_nsfw",
+      "hide_media",
+      "no_comments_only",
+      "page_cursor",
+      "limit",
+    ],
+  ),
+  (
+    "/api/v4/comment/list",
+    &[
+      "type_",
+      "sort",
+      "time_range_seconds",
+      "max_depth",
+      "page_cursor",
+      "limit",
+      "community_id",
+      "community_name",
+      "post_id",
+      "parent_id",
+    ],
+  ),
+  (
+    "/api/v4/community/list",
+    &[
+      "type_",
+      "sort",
+      "time_range_seconds",
+      "show_nsfw",
+      "page_cursor",
+      "limit",
+    ],
+  ),
+];
+
+/// Successful writes to these endpoints change the content of cached responses, so the cache is
+/// cleared. Changes which arrive over federation are only visible after the cache expires.
+const INVALIDATE_PATHS: &[&str] = &[
+  "/api/v4/site",
+  "/api/v4/site/icon",
+  "/api/v4/site/banner",
+  "/api/v4/community",
+  "/api/v4/community/remove",
+  "/api/v4/community/transfer",
+  "/api/v4/community/icon",
+  "/api/v4/community/banner",
+  "/api/v4/community/hide",
+  "/api/v4/community/tag",
+  "/api/v4/post",
+  "/api/v4/post/remove",
+  "/api/v4/post/lock",
+  "/api/v4/post/feature",
+  "/api/v4/post/mod_edit",
+  "/api/v4/comment",
+  "/api/v4/comment/remove",
+  "/api/v4/comment/lock",
+  "/api/v4/comment/distinguish",
+  "/api/v4/admin/purge/person",
+  "/api/v4/admin/purge/community",
+  "/api/v4/admin/purge/post",
+  "/api/v4/admin/purge/comment",
+  "/api/v4/admin/tagline",
+  "/api/v4/custom_emoji",
+  "/api/v3/post",
+  "/api/v3/post/delete",
+  "/api/v3/comment",
+  "/api/v3/comment/delete",
+];
+
+/// Upper limit for the size of all cached responses together.
+const MAX_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+struct CachedResponse {
+  body: Bytes,
+  content_type: Option<HeaderValue>,
+  etag: String,
+}
+
+/// Server side cache for API responses to logged out users.
+#[derive(Clone)]
+pub struct ResponseCache {
+  cache: Cache<String, CachedResponse>,
+}
+
+impl Default for ResponseCache {
+  fn default() -> Self {
+    Self {
+      cache: Cache::builder()
+        .weigher(|key: &String, value: &CachedResponse| {
+          u32::try_from(key.len() + value.body.len()).unwrap_or(u32::MAX)
+        })
+        .max_capacity(MAX_CACHE_BYTES)
+        .time_to_live(CACHE_DURATION_ANONYMOUS_API)
+        .build(),
+    }
+  }
+}
+
+pub struct ResponseCacheMiddleware {
+  response_cache: ResponseCache,
+}
+
+impl ResponseCacheMiddleware {
+  pub fn new(response_cache: ResponseCache) -> Self {
+    Self { response_cache }
+  }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ResponseCacheMiddleware
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = Error;
+  type InitError = ();
+  type Transform = ResponseCacheService<S>;
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(ResponseCacheService {
+      service: Rc::new(service),
+      response_cache: self.response_cache.clone(),
+    }))
+  }
+}
+
+pub struct ResponseCacheService<S> {
+  service: Rc<S>,
+  response_cache: ResponseCache,
+}
+
+impl<S, B> Service<ServiceRequest> for ResponseCacheService<S>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let svc = self.service.clone();
+    let cache = self.response_cache.cache.clone();
+    let path = req.path().to_string();
+    let is_anonymous = req.extensions().get::<LocalUserView>().is_none();
+
+    if req.method() != Method::GET {
+      let invalidate = INVALIDATE_PATHS.contains(&path.as_str());
+      return Box::pin(async move {
+        let res = svc.call(req).await?;
+        if invalidate && res.status().is_success() {
+          cache.invalidate_all();
+        }
+        Ok(res.map_into_left_body())
+      });
+    }
+    let key = match cache_key(&path, req.query_string()) {
+      Some(key) if is_anonymous => key,
+      _ => {
+        return Box::pin(
+          async move { svc.call(req).await.map(ServiceResponse::map_into_left_body) },
+        );
+      }
+    };
+    let if_none_match = req.headers().get(IF_NONE_MATCH).cloned();
+    Box::pin(async move {
+      if let Some(cached) = cache.get(&key).await {
+        let (req, _pl) = req.into_parts();
+        let res = cached_response(&cached, if_none_match.as_ref());
+        return Ok(ServiceResponse::new(req, res.map_into_right_body()));
+      }
+
+      let res = svc.call(req).await?;
+      if res.status() != StatusCode::OK {
+        return Ok(res.map_into_left_body());
+      }
+      let content_type = res.headers().get(CONTENT_TYPE).cloned();
+      let (req, res) = res.into_parts();
+      let body = to_bytes(res.into_body()).await.map_err(|e| {
+        let e: Box<dyn std::error::Error> = e.into();
+        ErrorInternalServerError(e)
+      })?;
+      let cached = CachedResponse {
+        etag: format!("\"{:x}\"", Sha256::digest(&body)),
+        body,
+        content_type,
+      };
+      cache.insert(key, cached.clone()).await;
+      let res = cached_response(&cached, if_none_match.as_ref());
+      Ok(ServiceResponse::new(req, res.map_into_right_body()))
+    })
+  }
+}
+
+/// Returns `None` if the response must not be cached, because of the path or an unknown query
+/// parameter. Query parameters are sorted so that equivalent requests share a cache entry.
+fn cache_key(path: &str, query: &str) -> Option<String> {
+  let (_, allowed_params) = CACHED_PATHS.iter().find(|(p, _)| *p == path)?;
+  let mut params: Vec<_> = form_urlencoded::parse(query.as_bytes())
+    .filter(|(_, v)| !v.is_empty())
+    .collect();
+  if params
+    .iter()
+    .any(|(k, _)| !allowed_params.contains(&k.as_ref()))
+  {
+    return None;
+  }
+  params.sort();
+  let query = form_urlencoded::Serializer::new(String::new())
+    .extend_pairs(params)
+    .finish();
+  Some(format!("{path}?{query}"))
+}
+
+fn cached_response(
+  cached: &CachedResponse,
+  if_none_match: Option<&HeaderValue>,
+) -> HttpResponse<BoxBody> {
+  if etag_matches(if_none_match, &cached.etag) {
+    return HttpResponse::NotModified()
+      .insert_header((ETAG, cached.etag.clone()))
+      .finish();
+  }
+  let mut res = HttpResponse::Ok();
+  res.insert_header((ETAG, cached.etag.clone()));
+  if let Some(content_type) = &cached.content_type {
+    res.insert_header((CONTENT_TYPE, content_type.clone()));
+  }
+  res.body(cached.body.clone())
+}
+
+/// Weak comparison as used for `If-None-Match`, which may contain a list of tags.
+fn etag_matches(if_none_match: Option<&HeaderValue>, etag: &str) -> bool {
+  let Some(value) = if_none_match.and_then(|v| v.to_str().ok()) else {
+    return false;
+  };
+  value
+    .split(',')
+    .map(|t| t.trim().trim_start_matches("W/"))
+    .any(|t| t == "*" || t == etag)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use actix_web::{
+    App,
+    test::{TestRequest, call_and_read_body, call_service, init_service},
+    web::{get, post},
+  };
+  use lemmy_db_schema::source::person::Person;
+  use lemmy_diesel_utils::{connection::build_db_pool_for_tests, traits::Crud};
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+  };
+
+  #[test]
+  fn test_cache_key() {
+    assert_eq!(
+      cache_key("/api/v4/post/list", "type_=Local&sort=Hot"),
+      cache_key("/api/v4/post/list", "sort=Hot&type_=Local&page_cursor=")
+    );
+    assert_ne!(
+      cache_key("/api/v4/post/list", "sort=Hot"),
+      cache_key("/api/v4/post/list", "sort=New")
+    );
+    assert_eq!(
+      Some("/api/v4/site?".to_string()),
+      cache_key("/api/v4/site", "")
+    );
+    // Unknown parameters and paths are not cached
+    assert_eq!(None, cache_key("/api/v4/post/list", "sort=Hot&random=123"));
+    assert_eq!(None, cache_key("/api/v4/site", "random=123"));
+    assert_eq!(None, cache_key("/api/v4/post", ""));
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_response_cache_middleware() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let user = LocalUserView::create_test_user(pool, "response_cache_user", "", false).await?;
+
+    // The handler returns how often it was called before
+    let calls = Arc::new(AtomicUsize::new(0));
+    let handler_calls = calls.clone();
+    let login_user = user.clone();
+    let app = init_service(
+      App::new()
+        .wrap(ResponseCacheMiddleware::new(ResponseCache::default()))
+        .wrap_fn(move |req, srv| {
+          if req.headers().contains_key("x-logged-in") {
+            req.extensions_mut().insert(login_user.clone());
+          }
+          srv.call(req)
+        })
+        .route(
+          "/api/v4/post/list",
+          get().to(move || {
+            let calls = handler_calls.clone();
+            async move { HttpResponse::Ok().body(calls.fetch_add(1, Ordering::SeqCst).to_string()) }
+          }),
+        )
+        .route(
+          "/api/v4/post",
+          post().to(|| async { HttpResponse::Ok().finish() }),
+        ),
+    )
+    .await;
+    let list = || TestRequest::get().uri("/api/v4/post/list?sort=Hot");
+
+    // Logged out requests are answered from the cache
+    assert_eq!("0", call_and_read_body(&app, list().to_request()).await);
+    assert_eq!("0", call_and_read_body(&app, list().to_request()).await);
+    assert_eq!(1, calls.load(Ordering::SeqCst));
+
+    // Logged in requests bypass the cache
+    let req = list().insert_header(("x-logged-in", "1")).to_request();
+    assert_eq!("1", call_and_read_body(&app, req).await);
+    assert_eq!("0", call_and_read_body(&app, list().to_request()).await);
+
+    // Requests with unknown parameters are not cached
+    let req = || TestRequest::get().uri("/api/v4/post/list?sort=Hot&random=1");
+    assert_eq!("2", call_and_read_body(&app, req().to_request()).await);
+    assert_eq!("3", call_and_read_body(&app, req().to_request()).await);
+
+    // A write clears the cache
+    let res = call_service(&app, TestRequest::post().uri("/api/v4/post").to_request()).await;
+    assert!(res.status().is_success());
+    assert_eq!("4", call_and_read_body(&app, list().to_request()).await);
+    assert_eq!("4", call_and_read_body(&app, list().to_request()).await);
+
+    Person::delete(pool, user.person.id).await?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_etag_matches() {
+    let etag = "\"abc\"";
+    assert!(!etag_matches(None, etag));
+    assert!(etag_matches(
+      Some(&HeaderValue::from_static("\"abc\"")),
+      etag
+    ));
+    assert!(etag_matches(
+      Some(&HeaderValue::from_static("\"xyz\", W/\"abc\"")),
+      etag
+    ));
+    assert!(etag_matches(Some(&HeaderValue::from_static("*")), etag));
+    assert!(!etag_matches(
+      Some(&HeaderValue::from_static("\"xyz\"")),
+      etag
+    ));
+  }
+}
@@ -11,6 +11,7 @@ use lemmy_api_utils::{
   context::LemmyContext,
   utils::{local_user_view_from_jwt, read_auth_token},
 };
+use lemmy_utils::rate_limit::RateLimitAccount;
 use std::{future::ready, rc::Rc};
 
 #[derive(Clone)]
@@ -73,6 +74,12 @@ where
         // to use `/api/v4/account/validate_auth` for that.
         let local_user_view = local_user_view_from_jwt(jwt, &context).await.ok();
         if let Some(local_user_view) = local_user_view {
+          let local_user = &local_user_view.local_user;
+          req.extensions_mut().insert(RateLimitAccount {
+            local_user_id: local_user.id.0,
+            multiplier: u32::try_from(local_user.rate_limit_multiplier).unwrap_or(1),
+            exempt: local_user.rate_limit_exempt,
+          });
           req.extensions_mut().insert(local_user_view);
         }
       }
@@ -1,13 +1,14 @@
 use actix_web::{App, HttpServer, rt::System, web};
 use actix_web_prom::{PrometheusMetrics, PrometheusMetricsBuilder};
 use lemmy_api_utils::context::LemmyContext;
+use lemmy_db_schema::source::scheduled_task_status::ScheduledTaskStatus;
 use lemmy_utils::{
   error::{LemmyErrorType, LemmyResult},
   settings::structs::PrometheusConfig,
 };
-use prometheus::{Encoder, Gauge, Opts, TextEncoder, default_registry};
+use prometheus::{Encoder, Gauge, GaugeVec, Opts, TextEncoder, default_registry};
 use std::{sync::Arc, thread};
-use tracing::error;
+use tracing::{error, warn};
 
 /// Creates a middleware that populates http metrics for each path, method, and status code
 pub fn new_prometheus_metrics() -> LemmyResult<PrometheusMetrics> {
@@ -22,6 +23,7 @@ pub fn new_prometheus_metrics() -> LemmyResult<PrometheusMetrics> {
 struct PromContext {
   lemmy: LemmyContext,
   db_pool_metrics: DbPoolMetrics,
+  scheduled_task_metrics: ScheduledTaskMetrics,
 }
 
 struct DbPoolMetrics {
@@ -30,10 +32,19 @@ struct DbPoolMetrics {
   available: Gauge,
 }
 
+/// Read from the database on each scrape, so that they include runs by all server processes.
+struct ScheduledTaskMetrics {
+  last_run: GaugeVec,
+  last_duration: GaugeVec,
+  last_failed: GaugeVec,
+  next_run: GaugeVec,
+}
+
 pub fn serve_prometheus(config: PrometheusConfig, lemmy_context: LemmyContext) -> LemmyResult<()> {
   let context = Arc::new(PromContext {
     lemmy: lemmy_context,
     db_pool_metrics: create_db_pool_metrics()?,
+    scheduled_task_metrics: create_scheduled_task_metrics()?,
   });
 
   // spawn thread that blocks on handling requests
@@ -62,6 +73,9 @@ pub fn serve_prometheus(config: PrometheusConfig, lemmy_context: LemmyContext) -
 async fn metrics(context: web::Data<Arc<PromContext>>) -> LemmyResult<String> {
   // collect metrics
   collect_db_pool_metrics(&context);
+  if let Err(e) = collect_scheduled_task_metrics(&context).await {
+    warn!("Failed to collect scheduled task metrics: {e}");
+  }
 
   let mut buffer = Vec::new();
   let encoder = TextEncoder::new();
@@ -113,3 +127,78 @@ fn collect_db_pool_metrics(context: &PromContext) {
     .available
     .set(pool_status.available as f64);
 }
+
+// create lemmy_scheduled_task_* metrics and register them with the default registry
+fn create_scheduled_task_metrics() -> LemmyResult<ScheduledTaskMetrics> {
+  let metrics = ScheduledTaskMetrics {
+    last_run: GaugeVec::new(
+      Opts::new(
+        "lemmy_scheduled_task_last_run_timestamp_seconds",
+        "Start time of the last run of the scheduled task",
+      ),
+      &["task"],
+    )?,
+    last_duration: GaugeVec::new(
+      Opts::new(
+        "lemmy_scheduled_task_last_duration_seconds",
+        "Duration of the last run of the scheduled task",
+      ),
+      &["task"],
+    )?,
+    last_failed: GaugeVec::new(
+      Opts::new(
+        "lemmy_scheduled_task_last_run_failed",
+        "Whether the last run of the scheduled task returned an error",
+      ),
+      &["task"],
+    )?,
+    next_run: GaugeVec::new(
+      Opts::new(
+        "lemmy_scheduled_task_next_run_timestamp_seconds",
+        "Time when the scheduled task is due next",
+      ),
+      &["task"],
+    )?,
+  };
+
+  default_registry().register(Box::new(metrics.last_run.clone()))?;
+  default_registry().register(Box::new(metrics.last_duration.clone()))?;
+  default_registry().register(Box::new(metrics.last_failed.clone()))?;
+  default_registry().register(Box::new(metrics.next_run.clone()))?;
+
+  Ok(metrics)
+}
+
+/// try_from does not support conversion from i64 to f64
+#[expect(clippy::as_conversions)]
+async fn collect_scheduled_task_metrics(context: &PromContext) -> LemmyResult<()> {
+  let metrics = &context.scheduled_task_metrics;
+  for status in ScheduledTaskStatus::list(&mut context.lemmy.pool()).await? {
+    let task = [status.name.as_str()];
+    if let Some(last_started_at) = status.last_started_at {
+      metrics
+        .last_run
+        .with_label_values(&task)
+        .set(last_started_at.timestamp() as f64);
+      metrics
+        .last_failed
+        .with_label_values(&task)
+        .set(if status.last_error.is_some() {
+          1.0
+        } else {
+          0.0
+        });
+    }
+    if let Some(last_duration_ms) = status.last_duration_ms {
+      metrics
+        .last_duration
+        .with_label_values(&task)
+        .set(f64::from(last_duration_ms) / 1000.0);
+    }
+    metrics
+      .next_run
+      .with_label_values(&task)
+      .set(status.next_run_at.timestamp() as f64);
+  }
+  Ok(())
+}
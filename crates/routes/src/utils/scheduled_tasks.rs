@@ -1,7 +1,6 @@
 use crate::nodeinfo::{NodeInfo, NodeInfoWellKnown};
 use activitypub_federation::config::Data;
-use chrono::{DateTime, TimeZone, Utc};
-use clokwerk::{AsyncScheduler, TimeUnits as CTimeUnits};
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
 use diesel::{
   BoolExpressionMethods,
   ExpressionMethods,
@@ -18,152 +17,256 @@ use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use diesel_uplete::uplete;
 use lemmy_api_utils::{
   context::LemmyContext,
+  media::delete_expired_proxied_images,
+  notify::notify_mod_action,
   send_activity::{ActivityChannel, SendActivityData},
   utils::send_webmention,
 };
 use lemmy_db_schema::{
   source::{
+    activity::{ReceivedActivity, SentActivity},
+    comment::{Comment, CommentUpdateForm},
     community::Community,
+    data_export::DataExport,
+    email_queue::EmailQueue,
     instance::{Instance, InstanceForm},
     local_user::LocalUser,
-    post::{Post, PostUpdateForm},
+    login_token::LoginToken,
+    modlog::{Modlog, ModlogInsertForm},
+    notification::Notification,
+    person::Person,
+    post::{Post, PostActions, PostUpdateForm},
+    scheduled_task_status::ScheduledTaskStatus,
   },
-  utils::DELETED_REPLACEMENT_TEXT,
+  utils::{DATA_EXPORT_RETENTION, DELETED_REPLACEMENT_TEXT, retention_cutoff},
 };
-use lemmy_db_schema_file::schema::{
-  comment,
-  community,
-  community_actions,
-  federation_blocklist,
-  instance,
-  instance_actions,
-  local_site,
-  local_user,
-  person,
-  post,
-  received_activity,
-  sent_activity,
-  site,
+use lemmy_db_schema_file::{
+  enums::ModlogKind,
+  schema::{
+    comment,
+    community,
+    community_actions,
+    federation_blocklist,
+    instance,
+    instance_actions,
+    local_site,
+    local_user,
+    person,
+    post,
+    site,
+  },
 };
 use lemmy_db_views_site::SiteView;
 use lemmy_diesel_utils::{
-  connection::{DbPool, get_conn},
+  connection::{DbPool, build_dedicated_connection, get_conn},
   traits::Crud,
   utils::{functions::coalesce, now},
 };
+use lemmy_email::digest::send_notification_digests;
 use lemmy_utils::{
   DB_BATCH_SIZE,
   error::{LemmyErrorType, LemmyResult},
+  metrics::scheduled_task_run,
 };
 use reqwest_middleware::ClientWithMiddleware;
-use std::time::Duration;
+use std::{
+  ffi::OsStr,
+  io::ErrorKind,
+  path::Path,
+  str::FromStr,
+  time::{Duration, Instant},
+};
+use strum::{Display, EnumString, VariantArray};
 use tracing::{info, warn};
 
-/// Schedules various cleanup tasks for lemmy in a background thread
-pub async fn setup(context: Data<LemmyContext>) -> LemmyResult<()> {
-  // https://github.com/mdsherry/clokwerk/issues/38
-  let mut scheduler = AsyncScheduler::with_tz(Utc);
-
-  let context_1 = context.clone();
-  // Every 10 minutes update hot ranks, delete expired captchas and publish scheduled posts
-  scheduler.every(CTimeUnits::minutes(10)).run(move || {
-    let context = context_1.clone();
-
-    async move {
-      update_hot_ranks(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to update hot ranks: {e}"))
-        .ok();
-      publish_scheduled_posts(&context)
-        .await
-        .inspect_err(|e| warn!("Failed to publish scheduled posts: {e}"))
-        .ok();
-    }
-  });
-
-  let context_1 = context.clone();
-  // Hourly tasks:
-  // - Update active daily counts
-  // - Expired bans
-  // - Expired instance blocks
-  scheduler.every(CTimeUnits::hour(1)).run(move || {
-    let context = context_1.clone();
-
-    async move {
-      active_counts(&mut context.pool(), ONE_DAY)
-        .await
-        .inspect_err(|e| warn!("Failed to update active counts: {e}"))
-        .ok();
-      update_banned_when_expired(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to update expired bans: {e}"))
-        .ok();
-      delete_instance_block_when_expired(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to delete expired instance bans: {e}"))
-        .ok();
+/// How often the scheduler checks for due tasks.
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tasks which run periodically in the background. Each of them can also be run on demand with
+/// `lemmy_server run-task <name>`.
+#[derive(EnumString, Display, VariantArray, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum ScheduledTask {
+  UpdateHotRanks,
+  PublishScheduledPosts,
+  RevertExpiredModActions,
+  UpdateDailyActiveCounts,
+  UpdateExpiredBans,
+  DeleteExpiredInstanceBlocks,
+  SendNotificationDigests,
+  UpdateActiveCounts,
+  UpdateLocalUserCount,
+  OverwriteDeletedPostsAndComments,
+  DeleteOldDeniedUsers,
+  UpdateInstanceSoftware,
+  ClearOldActivities,
+  DeleteOldEmails,
+  ClearOldLoginIps,
+  ClearOldReadStates,
+  DeleteOldNotifications,
+  DeleteOldRemotePosts,
+  DeleteOldDataExports,
+  DeleteExpiredProxiedImages,
+}
+
+impl ScheduledTask {
+  /// Interval which is used until an admin changes it.
+  pub fn default_interval(self) -> TimeDelta {
+    use ScheduledTask::*;
+    match self {
+      UpdateHotRanks | PublishScheduledPosts | RevertExpiredModActions => TimeDelta::minutes(10),
+      UpdateDailyActiveCounts
+      | UpdateExpiredBans
+      | DeleteExpiredInstanceBlocks
+      | SendNotificationDigests => TimeDelta::hours(1),
+      UpdateActiveCounts
+      | UpdateLocalUserCount
+      | OverwriteDeletedPostsAndComments
+      | DeleteOldDeniedUsers
+      | UpdateInstanceSoftware
+      | ClearOldActivities
+      | DeleteOldEmails
+      | ClearOldLoginIps
+      | ClearOldReadStates
+      | DeleteOldNotifications
+      | DeleteOldRemotePosts
+      | DeleteOldDataExports
+      | DeleteExpiredProxiedImages => TimeDelta::days(1),
     }
-  });
-
-  let context_1 = context.reset_request_count();
-  // Daily tasks:
-  // - Update site and community activity counts
-  // - Update local user count
-  // - Overwrite deleted & removed posts and comments every day
-  // - Delete old denied users
-  // - Update instance software
-  // - Delete old outgoing activities
-  scheduler.every(CTimeUnits::days(1)).run(move || {
-    let context = context_1.reset_request_count();
-
-    async move {
-      all_active_counts(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to update active counts: {e}"))
-        .ok();
-      update_local_user_count(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to update local user count: {e}"))
-        .ok();
-      overwrite_deleted_posts_and_comments(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to overwrite deleted posts/comments: {e}"))
-        .ok();
-      delete_old_denied_users(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to delete old denied users: {e}"))
-        .ok();
-      update_instance_software(&mut context.pool(), context.client())
-        .await
-        .inspect_err(|e| warn!("Failed to update instance software: {e}"))
-        .ok();
-      clear_old_activities(&mut context.pool())
-        .await
-        .inspect_err(|e| warn!("Failed to clear old activities: {e}"))
-        .ok();
+  }
+
+  pub async fn run(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    use ScheduledTask::*;
+    let pool = &mut context.pool();
+    match self {
+      UpdateHotRanks => update_hot_ranks(pool, false).await,
+      PublishScheduledPosts => publish_scheduled_posts(context).await,
+      RevertExpiredModActions => revert_expired_mod_actions(context).await,
+      UpdateDailyActiveCounts => active_counts(pool, ONE_DAY).await,
+      UpdateExpiredBans => update_banned_when_expired(pool).await,
+      DeleteExpiredInstanceBlocks => delete_instance_block_when_expired(pool).await,
+      SendNotificationDigests => send_notification_digests(pool, context.settings()).await,
+      UpdateActiveCounts => all_active_counts(pool).await,
+      UpdateLocalUserCount => update_local_user_count(pool).await,
+      OverwriteDeletedPostsAndComments => overwrite_deleted_posts_and_comments(pool).await,
+      DeleteOldDeniedUsers => delete_old_denied_users(pool).await,
+      UpdateInstanceSoftware => update_instance_software(pool, context.client()).await,
+      ClearOldActivities => clear_old_activities(pool).await,
+      DeleteOldEmails => EmailQueue::delete_old(pool).await.map(|_| ()),
+      ClearOldLoginIps => clear_old_login_ips(pool).await,
+      ClearOldReadStates => clear_old_read_states(pool).await,
+      DeleteOldNotifications => delete_old_notifications(pool).await,
+      DeleteOldRemotePosts => delete_old_remote_posts(pool).await,
+      DeleteOldDataExports => delete_old_data_exports(context).await,
+      DeleteExpiredProxiedImages => delete_expired_proxied_images(context.settings()).await,
     }
-  });
+  }
+}
+
+/// Runs the task if no other server process is running it at the same time, and records the
+/// result. With `only_if_due` the task is skipped if another process ran it since it was due.
+/// Returns false if the task was skipped.
+pub async fn run_exclusive(
+  task: ScheduledTask,
+  context: &Data<LemmyContext>,
+  only_if_due: bool,
+) -> LemmyResult<bool> {
+  let name = task.to_string();
+  // The advisory lock is held by a dedicated connection, while the task itself uses pooled
+  // connections. If this future is dropped, the connection is closed, which also releases the
+  // lock.
+  let lock_conn = &mut build_dedicated_connection().await?;
+  if !ScheduledTaskStatus::try_lock(lock_conn, &name).await? {
+    return Ok(false);
+  }
+  let ran = run_and_record(task, context, only_if_due).await;
+  ScheduledTaskStatus::unlock(lock_conn, &name).await?;
+  ran
+}
+
+async fn run_and_record(
+  task: ScheduledTask,
+  context: &Data<LemmyContext>,
+  only_if_due: bool,
+) -> LemmyResult<bool> {
+  let status = ScheduledTaskStatus::read(&mut context.pool(), &task.to_string()).await?;
+  if only_if_due && status.next_run_at > Utc::now() {
+    return Ok(false);
+  }
+
+  let started_at = Utc::now();
+  let start = Instant::now();
+  let error = task
+    .run(context)
+    .await
+    .inspect_err(|e| warn!("Failed to run scheduled task {task}: {e}"))
+    .err()
+    .map(|e| e.to_string());
+  scheduled_task_run(&task.to_string(), error.is_none(), start.elapsed());
+  status
+    .finish(&mut context.pool(), started_at, error)
+    .await?;
+  Ok(true)
+}
+
+/// Runs the scheduled tasks in the background when they are due. This can safely be done by
+/// multiple server processes, each task is only run by one of them at a time.
+pub async fn setup(context: Data<LemmyContext>) -> LemmyResult<()> {
+  for task in ScheduledTask::VARIANTS {
+    ScheduledTaskStatus::init(
+      &mut context.pool(),
+      &task.to_string(),
+      task.default_interval(),
+    )
+    .await?;
+  }
 
-  // Manually run the scheduler in an event loop
   loop {
-    scheduler.run_pending().await;
-    tokio::time::sleep(Duration::from_millis(1000)).await;
+    match ScheduledTaskStatus::list_due(&mut context.pool()).await {
+      Ok(due) => {
+        for status in due {
+          let Ok(task) = ScheduledTask::from_str(&status.name) else {
+            continue;
+          };
+          // Each task gets its own background task, so that slow ones don't delay the others.
+          let context = context.reset_request_count();
+          tokio::task::spawn(async move {
+            run_exclusive(task, &context, true)
+              .await
+              .inspect_err(|e| warn!("Failed to run scheduled task {task}: {e}"))
+              .ok();
+          });
+        }
+      }
+      Err(e) => warn!("Failed to list due scheduled tasks: {e}"),
+    }
+    tokio::time::sleep(SCHEDULER_INTERVAL).await;
   }
 }
 
+/// Recalculates all active counts, the local user count and the hot ranks of all posts, comments
+/// and communities, including old ones which are normally skipped.
+pub async fn rebuild_aggregates(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  all_active_counts(pool).await?;
+  update_local_user_count(pool).await?;
+  update_hot_ranks(pool, true).await
+}
+
 /// Update the hot_rank columns for the aggregates tables
-/// Runs in batches until all necessary rows are updated once
-async fn update_hot_ranks(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+/// Runs in batches until all necessary rows are updated once. Rows with a hot rank of zero are
+/// skipped unless `all` is set, as the rank only decreases over time.
+async fn update_hot_ranks(pool: &mut DbPool<'_>, all: bool) -> LemmyResult<()> {
   info!("Updating hot ranks for all history...");
 
   let conn = &mut get_conn(pool).await?;
 
-  process_post_aggregates_ranks_in_batches(conn).await?;
+  process_post_aggregates_ranks_in_batches(conn, all).await?;
 
+  let where_clause = if all { "true" } else { "a.hot_rank != 0" };
   process_ranks_in_batches(
     conn,
     "comment",
-    "a.hot_rank != 0",
+    where_clause,
     "SET hot_rank = r.hot_rank(a.score, a.published_at)",
   )
   .await?;
@@ -171,7 +274,7 @@ async fn update_hot_ranks(pool: &mut DbPool<'_>) -> LemmyResult<()> {
   process_ranks_in_batches(
     conn,
     "community",
-    "a.hot_rank != 0",
+    where_clause,
     "SET hot_rank = r.hot_rank(a.subscribers, a.published_at)",
   )
   .await?;
@@ -234,17 +337,25 @@ async fn process_ranks_in_batches(
 
 /// Post aggregates is a special case, since it needs to join to the community_aggregates
 /// table, to get the active monthly user counts.
-async fn process_post_aggregates_ranks_in_batches(conn: &mut AsyncPgConnection) -> LemmyResult<()> {
+async fn process_post_aggregates_ranks_in_batches(
+  conn: &mut AsyncPgConnection,
+  all: bool,
+) -> LemmyResult<()> {
+  let where_clause = if all {
+    "true"
+  } else {
+    "pa.hot_rank != 0 OR pa.hot_rank_active != 0"
+  };
   let process_start_time: DateTime<Utc> = Utc.timestamp_opt(0, 0).single().unwrap_or_default();
 
   let mut processed_rows_count = 0;
   let mut previous_batch_result = Some(process_start_time);
   while let Some(previous_batch_last_published) = previous_batch_result {
-    let updated_rows = sql_query(
+    let updated_rows = sql_query(format!(
       r#"WITH batch AS (SELECT pa.id
            FROM post pa
            WHERE pa.published_at > $1
-           AND (pa.hot_rank != 0 OR pa.hot_rank_active != 0)
+           AND ({where_clause})
            ORDER BY pa.published_at
            LIMIT $2
            FOR UPDATE SKIP LOCKED)
@@ -257,7 +368,7 @@ async fn process_post_aggregates_ranks_in_batches(conn: &mut AsyncPgConnection)
       AND pa.community_id = ca.id
       RETURNING pa.published_at;
 "#,
-    )
+    ))
     .bind::<Timestamptz, _>(previous_batch_last_published)
     .bind::<BigInt, _>(DB_BATCH_SIZE)
     .get_results::<HotRanksUpdateResult>(conn)
@@ -279,24 +390,110 @@ async fn process_post_aggregates_ranks_in_batches(conn: &mut AsyncPgConnection)
 /// Clear old activities (this table gets very large)
 async fn clear_old_activities(pool: &mut DbPool<'_>) -> LemmyResult<()> {
   info!("Clearing old activities...");
-  let conn = &mut get_conn(pool).await?;
+  let local_site = SiteView::read_local(pool).await?.local_site;
 
-  diesel::delete(
-    sent_activity::table.filter(sent_activity::published_at.lt(now() - IntervalDsl::days(7))),
+  SentActivity::delete_older_than(
+    pool,
+    retention_cutoff(local_site.sent_activity_retention_days),
   )
-  .execute(conn)
   .await?;
-
-  diesel::delete(
-    received_activity::table
-      .filter(received_activity::published_at.lt(now() - IntervalDsl::days(7))),
+  ReceivedActivity::delete_older_than(
+    pool,
+    retention_cutoff(local_site.received_activity_retention_days),
   )
-  .execute(conn)
   .await?;
   info!("Done.");
   Ok(())
 }
 
+async fn clear_old_login_ips(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  let local_site = SiteView::read_local(pool).await?.local_site;
+  if let Some(days) = local_site.login_ip_retention_days {
+    let count = LoginToken::clear_old_ips(pool, retention_cutoff(days)).await?;
+    info!("Removed ip and user agent of {count} logins");
+  }
+  Ok(())
+}
+
+async fn clear_old_read_states(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  let local_site = SiteView::read_local(pool).await?.local_site;
+  if let Some(days) = local_site.post_read_retention_days {
+    PostActions::clear_old_read_state(pool, retention_cutoff(days)).await?;
+    info!("Cleared old read states");
+  }
+  Ok(())
+}
+
+async fn delete_old_notifications(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  let local_site = SiteView::read_local(pool).await?.local_site;
+  if let Some(days) = local_site.notification_retention_days {
+    let count = Notification::delete_older_than(pool, retention_cutoff(days)).await?;
+    info!("Deleted {count} old notifications");
+  }
+  Ok(())
+}
+
+async fn delete_old_remote_posts(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  let local_site = SiteView::read_local(pool).await?.local_site;
+  if let Some(days) = local_site.remote_content_retention_days {
+    let count = Post::delete_old_remote(pool, retention_cutoff(days)).await?;
+    info!("Deleted {count} old remote posts without local interactions");
+  }
+  Ok(())
+}
+
+/// Removes expired data exports together with their archives. Rows of deleted accounts are gone
+/// already, so old archive files without a row are also removed.
+async fn delete_old_data_exports(context: &LemmyContext) -> LemmyResult<()> {
+  let cutoff = Utc::now() - DATA_EXPORT_RETENTION;
+  let exports = DataExport::delete_older_than(&mut context.pool(), cutoff).await?;
+
+  let mut files = 0;
+  for export in &exports {
+    if remove_archive(&export.archive_path(context.settings())).await? {
+      files += 1;
+    }
+  }
+
+  let mut dir = match tokio::fs::read_dir(&context.settings().data_export_path).await {
+    Ok(dir) => dir,
+    Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+    Err(e) => return Err(e.into()),
+  };
+  while let Some(entry) = dir.next_entry().await? {
+    // Leave anything alone which wasn't written by a data export
+    if !entry.file_type().await?.is_file() || !is_export_archive(&entry.file_name()) {
+      continue;
+    }
+    let modified: DateTime<Utc> = entry.metadata().await?.modified()?.into();
+    if modified < cutoff && remove_archive(&entry.path()).await? {
+      files += 1;
+    }
+  }
+  info!(
+    "Deleted {} old data exports and {files} archive files",
+    exports.len()
+  );
+  Ok(())
+}
+
+/// Failed exports don't have an archive, so a missing file is not an error.
+async fn remove_archive(path: &Path) -> LemmyResult<bool> {
+  match tokio::fs::remove_file(path).await {
+    Ok(()) => Ok(true),
+    Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Archives are named after the id of their export, see [`DataExport::archive_path`].
+fn is_export_archive(name: &OsStr) -> bool {
+  name
+    .to_str()
+    .and_then(|name| name.strip_suffix(".zip"))
+    .is_some_and(|id| id.parse::<i32>().is_ok())
+}
+
 async fn delete_old_denied_users(pool: &mut DbPool<'_>) -> LemmyResult<()> {
   LocalUser::delete_old_denied_local_users(pool).await?;
   info!("Done.");
@@ -600,6 +797,134 @@ async fn publish_scheduled_posts(context: &Data<LemmyContext>) -> LemmyResult<()
   Ok(())
 }
 
+/// Unlock or restore posts and comments whose lock or removal has expired, and federate the undo.
+///
+/// The revert is done in the name of the moderator who took the original action, so that remote
+/// instances accept it.
+async fn revert_expired_mod_actions(context: &Data<LemmyContext>) -> LemmyResult<()> {
+  info!("Reverting expired locks and removals...");
+  let pool = &mut context.pool();
+
+  // A single failing item must not prevent the remaining ones from being reverted
+  for (action, post) in Modlog::list_expired_post_actions(pool).await? {
+    let action_id = action.id;
+    revert_expired_post_action(action, post, context)
+      .await
+      .inspect_err(|e| warn!("Failed to revert expired mod action {action_id}: {e}"))
+      .ok();
+  }
+
+  for (action, comment) in Modlog::list_expired_comment_actions(pool).await? {
+    let action_id = action.id;
+    revert_expired_comment_action(action, comment, context)
+      .await
+      .inspect_err(|e| warn!("Failed to revert expired mod action {action_id}: {e}"))
+      .ok();
+  }
+
+  info!("Done.");
+  Ok(())
+}
+
+async fn revert_expired_post_action(
+  action: Modlog,
+  post: Post,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let pool = &mut context.pool();
+  let moderator = Person::read(pool, action.mod_id).await?;
+  let reason = action.reason.unwrap_or_default();
+  let (form, send_activity) = if action.kind == ModlogKind::ModLockPost {
+    let form = PostUpdateForm {
+      locked: Some(false),
+      ..Default::default()
+    };
+    let post = Post::update(pool, post.id, &form).await?;
+    (
+      ModlogInsertForm::mod_lock_post(moderator.id, &post, false, None, &reason),
+      SendActivityData::LockPost(post, moderator, false, reason.clone()),
+    )
+  } else {
+    let form = PostUpdateForm {
+      removed: Some(false),
+      ..Default::default()
+    };
+    let post = Post::update(pool, post.id, &form).await?;
+    (
+      ModlogInsertForm::mod_remove_post(moderator.id, &post, false, None, &reason, None, None),
+      SendActivityData::RemovePost {
+        post,
+        moderator,
+        reason: reason.clone(),
+        removed: false,
+        with_replies: false,
+      },
+    )
+  };
+  let action = Modlog::create(pool, &[form]).await?;
+  notify_mod_action(action, context);
+  ActivityChannel::submit_activity(send_activity, context)
+}
+
+async fn revert_expired_comment_action(
+  action: Modlog,
+  comment: Comment,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let pool = &mut context.pool();
+  let moderator = Person::read(pool, action.mod_id).await?;
+  let post = Post::read(pool, comment.post_id).await?;
+  let reason = action.reason.unwrap_or_default();
+  let (form, send_activity) = if action.kind == ModlogKind::ModLockComment {
+    let comments =
+      Comment::update_locked_for_comment_and_children(pool, &comment.path, false).await?;
+    let comment = comments
+      .into_iter()
+      .find(|c| c.id == comment.id)
+      .ok_or(LemmyErrorType::NotFound)?;
+    (
+      ModlogInsertForm::mod_lock_comment(
+        moderator.id,
+        &comment,
+        post.community_id,
+        false,
+        None,
+        &reason,
+      ),
+      SendActivityData::LockComment(comment, moderator, false, reason.clone()),
+    )
+  } else {
+    let form = CommentUpdateForm {
+      removed: Some(false),
+      ..Default::default()
+    };
+    let comment = Comment::update(pool, comment.id, &form).await?;
+    let community = Community::read(pool, post.community_id).await?;
+    (
+      ModlogInsertForm::mod_remove_comment(
+        moderator.id,
+        &comment,
+        community.id,
+        false,
+        None,
+        &reason,
+        None,
+        None,
+      ),
+      SendActivityData::RemoveComment {
+        comment,
+        moderator,
+        community,
+        reason: reason.clone(),
+        with_replies: false,
+      },
+    )
+  };
+  let action = Modlog::create(pool, &[form]).await?;
+  notify_mod_action(action, context);
+  ActivityChannel::submit_activity(send_activity, context)
+}
+
 /// Updates the instance software and version.
 ///
 /// Does so using the /.well-known/nodeinfo protocol described here:
@@ -693,6 +1018,7 @@ async fn build_update_instance_form(
 mod tests {
 
   use super::*;
+  use chrono::TimeDelta;
   use lemmy_api_utils::request::client_builder;
   use lemmy_db_schema::{
     source::{
@@ -712,6 +1038,14 @@ mod tests {
   use reqwest_middleware::ClientBuilder;
   use serial_test::serial;
 
+  #[test]
+  fn test_is_export_archive() {
+    assert!(is_export_archive(OsStr::new("15.zip")));
+    assert!(!is_export_archive(OsStr::new("15.zip.part")));
+    assert!(!is_export_archive(OsStr::new("backup.zip")));
+    assert!(!is_export_archive(OsStr::new("15")));
+  }
+
   #[tokio::test]
   async fn test_nodeinfo_lemmy_ml() -> LemmyResult<()> {
     let client = ClientBuilder::new(client_builder(&Settings::default()).build()?).build();
@@ -732,6 +1066,18 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_scheduled_task_names() -> LemmyResult<()> {
+    for task in ScheduledTask::VARIANTS {
+      assert_eq!(*task, task.to_string().parse::<ScheduledTask>()?);
+    }
+    assert_eq!(
+      "update_hot_ranks",
+      ScheduledTask::UpdateHotRanks.to_string()
+    );
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_scheduled_tasks() -> LemmyResult<()> {
@@ -764,7 +1110,8 @@ mod tests {
     active_counts(pool, ONE_DAY).await?;
     all_active_counts(pool).await?;
     update_local_user_count(pool).await?;
-    update_hot_ranks(pool).await?;
+    update_hot_ranks(pool, false).await?;
+    rebuild_aggregates(pool).await?;
     update_banned_when_expired(pool).await?;
     delete_instance_block_when_expired(pool).await?;
     clear_old_activities(pool).await?;
@@ -772,6 +1119,7 @@ mod tests {
     delete_old_denied_users(pool).await?;
     update_instance_software(pool, context.client()).await?;
     publish_scheduled_posts(&context).await?;
+    revert_expired_mod_actions(&context).await?;
 
     let community_after = Community::read(pool, community.id).await?;
     assert_eq!(
@@ -790,4 +1138,78 @@ mod tests {
     data.delete(pool).await?;
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_revert_expired_mod_actions() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let data = TestData::create(pool).await?;
+    let community = Community::create(
+      pool,
+      &CommunityInsertForm::new(
+        data.instance.id,
+        "name".to_owned(),
+        "title".to_owned(),
+        "pubkey".to_owned(),
+      ),
+    )
+    .await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::new("rupert".to_owned(), "pubkey".to_owned(), data.instance.id),
+    )
+    .await?;
+    let post = Post::create(
+      pool,
+      &PostInsertForm {
+        locked: Some(true),
+        ..PostInsertForm::new("cool down".to_owned(), person.id, community.id)
+      },
+    )
+    .await?;
+
+    let expired = Utc::now() - TimeDelta::hours(1);
+
+    // Lock by a moderator who was purged afterwards, so it can't be reverted
+    let purged_mod = Person::create(
+      pool,
+      &PersonInsertForm::new("purged".to_owned(), "pubkey".to_owned(), data.instance.id),
+    )
+    .await?;
+    let stuck_post = Post::create(
+      pool,
+      &PostInsertForm {
+        locked: Some(true),
+        ..PostInsertForm::new("stuck".to_owned(), person.id, community.id)
+      },
+    )
+    .await?;
+    let form =
+      ModlogInsertForm::mod_lock_post(purged_mod.id, &stuck_post, true, Some(expired), "reason");
+    Modlog::create(pool, &[form]).await?;
+    Person::delete(pool, purged_mod.id).await?;
+
+    let form = ModlogInsertForm::mod_lock_post(person.id, &post, true, Some(expired), "reason");
+    Modlog::create(pool, &[form]).await?;
+    assert_eq!(2, Modlog::list_expired_post_actions(pool).await?.len());
+
+    // The failing item doesn't abort the task
+    revert_expired_mod_actions(&context).await?;
+
+    let post_after = Post::read(pool, post.id).await?;
+    assert!(!post_after.locked);
+    assert!(Post::read(pool, stuck_post.id).await?.locked);
+    // The revert supersedes the original lock, so it is not listed again
+    let remaining = Modlog::list_expired_post_actions(pool).await?;
+    assert_eq!(1, remaining.len());
+    assert_eq!(
+      Some(stuck_post.id),
+      remaining.first().map(|(_, post)| post.id)
+    );
+
+    data.delete(pool).await?;
+    Ok(())
+  }
 }
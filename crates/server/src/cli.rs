@@ -0,0 +1,223 @@
+use crate::{CmdSubcommand, UserSubcommand, create_context, federation_config_builder};
+use activitypub_federation::{
+  config::Data,
+  fetch::{object_id::ObjectId, webfinger::webfinger_resolve_actor},
+};
+use chrono::{TimeDelta, Utc};
+use either::Either;
+use lemmy_api::{
+  local_user::{
+    add_admin::set_admin,
+    ban_person::ban_person_from_site,
+    change_password_after_reset::set_password,
+  },
+  site::purge::person::purge_person_internal,
+};
+use lemmy_api_crud::user::create::create_user_account;
+use lemmy_api_utils::{context::LemmyContext, send_activity::ActivityChannel};
+use lemmy_apub::collections::fetch_community_collections;
+use lemmy_apub_activities::handle_outgoing_activities;
+use lemmy_apub_objects::objects::{
+  SearchableObjects,
+  UserOrCommunity,
+  community::FETCH_COMMUNITY_COLLECTIONS,
+};
+use lemmy_db_schema::{
+  source::{person::Person, secret::Secret},
+  traits::ApubActor,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_person::api::BanPerson;
+use lemmy_db_views_site::SiteView;
+use lemmy_diesel_utils::{
+  connection::{build_db_pool, build_db_pool_without_migrations},
+  schema_setup,
+};
+use lemmy_routes::utils::{
+  scheduled_tasks::{ScheduledTask, rebuild_aggregates, run_exclusive},
+  setup_local_site::setup_local_site,
+};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  settings::SETTINGS,
+};
+use std::{
+  io::{IsTerminal, stdin},
+  str::FromStr,
+};
+use strum::VariantArray;
+use url::Url;
+
+/// Runs a one-off admin command. Actions are performed in the name of the system account, so
+/// they show up in the modlog like those of the API.
+pub(crate) async fn run(subcommand: CmdSubcommand) -> LemmyResult<()> {
+  if let CmdSubcommand::Check = subcommand {
+    return check().await;
+  }
+  if let CmdSubcommand::RunTask { task } = &subcommand
+    && let Err(e) = ScheduledTask::from_str(task)
+  {
+    eprintln!("Unknown task {task}, available tasks:");
+    for t in ScheduledTask::VARIANTS {
+      eprintln!("  {t}");
+    }
+    return Err(e.into());
+  }
+
+  let pool = build_db_pool()?;
+  let secret = Secret::init(&mut (&pool).into()).await?;
+  let site_view = setup_local_site(&mut (&pool).into(), &SETTINGS).await?;
+  let context = create_context(pool, secret, &site_view)?;
+  let federation_config = federation_config_builder(context, &site_view)
+    .build()
+    .await?;
+  FETCH_COMMUNITY_COLLECTIONS
+    .set(fetch_community_collections)
+    .map_err(|_e| LemmyErrorType::Unknown("couldnt set function pointer".into()))?;
+  let context = federation_config.to_request_data();
+
+  // Outgoing activities are only written to the database here, the running server sends them.
+  let outgoing_activities_task = tokio::task::spawn(handle_outgoing_activities(context.clone()));
+
+  let res = match subcommand {
+    CmdSubcommand::User { subcommand } => run_user(subcommand, &context).await,
+    CmdSubcommand::Fetch { query } => fetch(query, &context).await,
+    CmdSubcommand::RebuildAggregates => rebuild_aggregates(&mut context.pool()).await,
+    CmdSubcommand::RunTask { task } => run_task(&task, &context).await,
+    CmdSubcommand::Migration { .. } | CmdSubcommand::Check => Ok(()),
+  };
+
+  ActivityChannel::close(outgoing_activities_task).await?;
+  if res.is_ok() {
+    println!("Done");
+  }
+  res
+}
+
+async fn run_user(subcommand: UserSubcommand, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let system_account = SiteView::read_system_account(&mut context.pool()).await?;
+  match subcommand {
+    UserSubcommand::Create { name, email, admin } => {
+      let password = read_password()?;
+      let user = create_user_account(name, password, email, context).await?;
+      if admin {
+        set_admin(user.person.id, true, &system_account, context).await?;
+      }
+      println!("Created user {}", user.person.ap_id);
+    }
+    UserSubcommand::ResetPassword { name } => {
+      let user = LocalUserView::read_from_name(&mut context.pool(), &name).await?;
+      let password = read_password()?;
+      set_password(user.local_user.id, &password, context).await?;
+    }
+    UserSubcommand::AddAdmin { name } => {
+      let user = LocalUserView::read_from_name(&mut context.pool(), &name).await?;
+      set_admin(user.person.id, true, &system_account, context).await?;
+    }
+    UserSubcommand::RemoveAdmin { name } => {
+      let user = LocalUserView::read_from_name(&mut context.pool(), &name).await?;
+      set_admin(user.person.id, false, &system_account, context).await?;
+    }
+    UserSubcommand::Ban {
+      name,
+      reason,
+      remove_data,
+      expires_days,
+    } => {
+      let expires_at = expires_days
+        .and_then(TimeDelta::try_days)
+        .and_then(|d| Utc::now().checked_add_signed(d))
+        .map(|e| e.timestamp());
+      let data = BanPerson {
+        person_id: read_person(&name, context).await?.id,
+        ban: true,
+        remove_or_restore_data: Some(remove_data),
+        reason,
+        expires_at,
+      };
+      ban_person_from_site(&data, system_account, context).await?;
+    }
+    UserSubcommand::Unban { name, reason } => {
+      let data = BanPerson {
+        person_id: read_person(&name, context).await?.id,
+        ban: false,
+        remove_or_restore_data: None,
+        reason,
+        expires_at: None,
+      };
+      ban_person_from_site(&data, system_account, context).await?;
+    }
+    UserSubcommand::Purge { name, reason } => {
+      let person = read_person(&name, context).await?;
+      purge_person_internal(person.id, &reason, system_account, context).await?;
+    }
+  }
+  Ok(())
+}
+
+/// Reads a person from the database, either `name` for a local person or `name@example.com`.
+async fn read_person(name: &str, context: &Data<LemmyContext>) -> LemmyResult<Person> {
+  let name = name.trim_start_matches('@');
+  let (name, domain) = match name.split_once('@') {
+    Some((name, domain)) => (name, Some(domain)),
+    None => (name, None),
+  };
+  Ok(
+    Person::read_from_name(&mut context.pool(), name, domain, false)
+      .await?
+      .ok_or(LemmyErrorType::NotFound)?,
+  )
+}
+
+async fn run_task(task: &str, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let task = ScheduledTask::from_str(task)?;
+  if !run_exclusive(task, context, false).await? {
+    println!("Task {task} is currently running in another process");
+  }
+  Ok(())
+}
+
+/// Refetches the given object from its home instance, even if it was updated recently.
+async fn fetch(mut query: String, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let url = match Url::parse(&query) {
+    Ok(url) => url,
+    Err(_) => {
+      if query.starts_with('!') || query.starts_with('@') {
+        query.remove(0);
+      }
+      match webfinger_resolve_actor::<LemmyContext, UserOrCommunity>(&query, context).await? {
+        Either::Left(p) => p.ap_id.clone().into(),
+        Either::Right(c) => c.ap_id.clone().into(),
+      }
+    }
+  };
+  ObjectId::<SearchableObjects>::from(url.clone())
+    .dereference_forced(context)
+    .await?;
+  println!("Fetched {url}");
+  Ok(())
+}
+
+/// Loads the config and connects to the database. Nothing is changed, pending migrations are only
+/// reported.
+async fn check() -> LemmyResult<()> {
+  println!("Config loaded, hostname is {}", SETTINGS.hostname);
+  if schema_setup::has_pending_migrations(&SETTINGS.get_database_url_with_options()?)? {
+    println!("Database has pending migrations, they are applied when Lemmy starts");
+  }
+  let pool = build_db_pool_without_migrations()?;
+  let site_view = SiteView::read_local(&mut (&pool).into()).await?;
+  println!("Database connected, site name is {}", site_view.site.name);
+  Ok(())
+}
+
+/// Reads a password from the terminal without echoing it. If stdin is not a terminal, for example
+/// when the password is piped in by a script, the first line of stdin is used instead.
+fn read_password() -> LemmyResult<String> {
+  if stdin().is_terminal() {
+    return Ok(rpassword::prompt_password("Enter password: ")?);
+  }
+  let mut password = String::new();
+  stdin().read_line(&mut password)?;
+  Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -1,4 +1,11 @@
-use activitypub_federation::config::{FederationConfig, FederationMiddleware};
+mod cli;
+mod reload;
+
+use activitypub_federation::config::{
+  FederationConfig,
+  FederationConfigBuilder,
+  FederationMiddleware,
+};
 use actix_web::{
   App,
   HttpResponse,
@@ -9,6 +16,7 @@ use actix_web::{
 };
 use clap::{Parser, Subcommand};
 use lemmy_api::sitemap::get_sitemap;
+use lemmy_api_crud::email_reply::receive_email_reply;
 use lemmy_api_utils::{
   context::LemmyContext,
   request::client_builder,
@@ -25,11 +33,13 @@ use lemmy_apub_objects::objects::{community::FETCH_COMMUNITY_COLLECTIONS, instan
 use lemmy_apub_send::{Opts, SendManager};
 use lemmy_db_schema::source::secret::Secret;
 use lemmy_db_views_site::SiteView;
-use lemmy_diesel_utils::connection::build_db_pool;
+use lemmy_diesel_utils::connection::{ActualDbPool, build_db_pool, build_read_replica_pools};
+use lemmy_email::{lmtp::serve_lmtp, queue::run_email_queue};
 use lemmy_routes::{
   feeds,
   middleware::{
     idempotency::{IdempotencyMiddleware, IdempotencySet},
+    response_cache::{ResponseCache, ResponseCacheMiddleware},
     session::SessionMiddleware,
   },
   nodeinfo,
@@ -44,7 +54,7 @@ use lemmy_routes::{
 use lemmy_utils::{
   VERSION,
   error::{LemmyErrorType, LemmyResult},
-  rate_limit::RateLimit,
+  rate_limit::{RateLimit, client_ip},
   response::jsonify_plain_text_errors,
   settings::{SETTINGS, structs::Settings},
 };
@@ -77,8 +87,8 @@ const ACTIVITY_SENDING_TIMEOUT: Duration = Duration::from_secs(125);
 pub struct CmdArgs {
   /// Don't run scheduled tasks.
   ///
-  /// If you are running multiple Lemmy server processes, you probably want to disable scheduled
-  /// tasks on all but one of the processes, to avoid running the tasks more often than intended.
+  /// Scheduled tasks can safely run in multiple Lemmy server processes, as each task is locked in
+  /// the database while it runs. Use this to keep them away from processes serving requests.
   #[arg(long, default_value_t = false, env = "LEMMY_DISABLE_SCHEDULED_TASKS")]
   disable_scheduled_tasks: bool,
   /// Disables the HTTP server.
@@ -126,6 +136,65 @@ enum CmdSubcommand {
     #[arg(long, default_value_t = 1)]
     number: u64,
   },
+  /// Manage local users and ban or purge any person, then exit.
+  User {
+    #[command(subcommand)]
+    subcommand: UserSubcommand,
+  },
+  /// Refetch a remote actor or object, given its URL or a handle like !community@example.com.
+  Fetch { query: String },
+  /// Recalculate all active user counts, the local user count and the hot ranks of all posts.
+  RebuildAggregates,
+  /// Run a single scheduled task once. Use `list` as task name to show the available tasks.
+  RunTask { task: String },
+  /// Check that the config file can be loaded and the database is reachable, then exit.
+  Check,
+}
+
+/// Actions on a user. Names may be given as `name` for local users or `name@example.com` for
+/// remote users. Passwords are prompted for on the terminal, or read from the first line of stdin
+/// if it is not a terminal.
+#[derive(Subcommand, Debug)]
+enum UserSubcommand {
+  /// Create a new local user, skipping registration applications and email verification.
+  Create {
+    name: String,
+    #[arg(long)]
+    email: Option<String>,
+    /// Make the new user an admin.
+    #[arg(long, default_value_t = false)]
+    admin: bool,
+  },
+  /// Set a new password for a local user, and log out all of their sessions.
+  ResetPassword { name: String },
+  /// Promote a local user to admin.
+  AddAdmin { name: String },
+  /// Revoke admin permissions of a local user.
+  RemoveAdmin { name: String },
+  /// Ban a person from the instance.
+  Ban {
+    name: String,
+    #[arg(long)]
+    reason: String,
+    /// Also remove all posts, comments and communities of the person.
+    #[arg(long, default_value_t = false)]
+    remove_data: bool,
+    /// Lift the ban automatically after this many days.
+    #[arg(long)]
+    expires_days: Option<i64>,
+  },
+  /// Lift the instance ban of a person.
+  Unban {
+    name: String,
+    #[arg(long)]
+    reason: String,
+  },
+  /// Delete a person and all of their content.
+  Purge {
+    name: String,
+    #[arg(long)]
+    reason: String,
+  },
 }
 
 #[derive(Subcommand, Debug, PartialEq, Eq)]
@@ -138,32 +207,14 @@ enum MigrationSubcommand {
 
 /// Placing the main function in lib.rs allows other crates to import it and embed Lemmy
 pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
-  if let Some(CmdSubcommand::Migration {
-    subcommand,
-    all,
-    number,
-  }) = args.subcommand
-  {
-    let mut options = match subcommand {
-      MigrationSubcommand::Run => lemmy_diesel_utils::schema_setup::Options::default().run(),
-      MigrationSubcommand::Revert => lemmy_diesel_utils::schema_setup::Options::default().revert(),
-    }
-    .print_output();
-
-    if !all {
-      options = options.limit(number);
-    }
-
-    lemmy_diesel_utils::schema_setup::run(options, &SETTINGS.get_database_url_with_options()?)?;
-
-    #[cfg(debug_assertions)]
-    if all && subcommand == MigrationSubcommand::Run {
-      println!(
-        "Warning: you probably want this command instead, which requires less crates to be compiled: cargo run --package lemmy_diesel_utils"
-      );
-    }
-
-    return Ok(());
+  match args.subcommand {
+    Some(CmdSubcommand::Migration {
+      subcommand,
+      all,
+      number,
+    }) => return run_migrations(subcommand, all, number),
+    Some(subcommand) => return cli::run(subcommand).await,
+    None => {}
   }
 
   // Print version number to log
@@ -189,47 +240,19 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
     println!("Federation enabled, host is {}", &SETTINGS.hostname);
   }
 
-  // Set up the rate limiter
-  let rate_limit_config =
-    local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
-  let rate_limit_cell = RateLimit::new(rate_limit_config);
-
   println!(
     "Starting HTTP server at {}:{}",
     SETTINGS.bind, SETTINGS.port
   );
 
-  let client = ClientBuilder::new(client_builder(&SETTINGS).build()?)
-    .with(TracingMiddleware::default())
-    .build();
-  let pictrs_client = ClientBuilder::new(client_builder(&SETTINGS).no_proxy().build()?)
-    .with(TracingMiddleware::default())
-    .build();
-  let context = LemmyContext::create(
-    pool.clone(),
-    client.clone(),
-    pictrs_client,
-    secret.clone(),
-    rate_limit_cell,
-  );
+  let context = create_context(pool.clone(), secret, &site_view)?;
+  let client = context.client().clone();
 
   if let Some(prometheus) = SETTINGS.prometheus.clone() {
     serve_prometheus(prometheus, context.clone())?;
   }
 
-  let mut federation_config_builder = FederationConfig::builder();
-  federation_config_builder
-    .domain(SETTINGS.hostname.clone())
-    .app_data(context.clone())
-    .client(client.clone())
-    .http_fetch_limit(FEDERATION_HTTP_FETCH_LIMIT)
-    .debug(cfg!(debug_assertions))
-    .http_signature_compat(true)
-    .url_verifier(Box::new(VerifyUrlData(context.inner_pool().clone())));
-  if site_view.local_site.federation_signed_fetch {
-    let site: ApubSite = site_view.site.clone().into();
-    federation_config_builder.signed_fetch_actor(&site);
-  }
+  let federation_config_builder = federation_config_builder(context, &site_view);
   let federation_config = federation_config_builder.build().await?;
 
   FETCH_COMMUNITY_COLLECTIONS
@@ -240,6 +263,19 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
   let outgoing_activities_task =
     tokio::task::spawn(handle_outgoing_activities(request_data.clone()));
 
+  // Sends queued emails in the background
+  let _email_queue = tokio::task::spawn(run_email_queue(pool.clone(), client.clone()));
+
+  // Receives replies to notification emails
+  if let Some(lmtp_bind) = SETTINGS.email.as_ref().and_then(|e| e.lmtp_bind) {
+    let reply_data = request_data.clone();
+    let _lmtp_server = tokio::task::spawn(serve_lmtp(
+      lmtp_bind,
+      SETTINGS.hostname.clone(),
+      move |recipient, raw| receive_email_reply(recipient, raw, reply_data.clone()),
+    ));
+  }
+
   if !args.disable_scheduled_tasks {
     // Schedules various cleanup tasks for the DB
     let _scheduled_tasks = tokio::task::spawn(scheduled_tasks::setup(request_data.clone()));
@@ -275,9 +311,10 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
         process_count: args.federate_process_count,
       },
       cfg,
-      SETTINGS.federation.clone(),
+      reload::federation_worker_config(),
     )
   });
+  reload::reload_on_sighup()?;
   let mut interrupt = tokio::signal::unix::signal(SignalKind::interrupt())?;
   let mut terminate = tokio::signal::unix::signal(SignalKind::terminate())?;
 
@@ -305,6 +342,76 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
   Ok(())
 }
 
+fn run_migrations(subcommand: MigrationSubcommand, all: bool, number: u64) -> LemmyResult<()> {
+  let mut options = match subcommand {
+    MigrationSubcommand::Run => lemmy_diesel_utils::schema_setup::Options::default().run(),
+    MigrationSubcommand::Revert => lemmy_diesel_utils::schema_setup::Options::default().revert(),
+  }
+  .print_output();
+
+  if !all {
+    options = options.limit(number);
+  }
+
+  lemmy_diesel_utils::schema_setup::run(options, &SETTINGS.get_database_url_with_options()?)?;
+
+  #[cfg(debug_assertions)]
+  if all && subcommand == MigrationSubcommand::Run {
+    println!(
+      "Warning: you probably want this command instead, which requires less crates to be compiled: cargo run --package lemmy_diesel_utils"
+    );
+  }
+
+  Ok(())
+}
+
+/// Creates the context with database pool, http clients and rate limiter.
+fn create_context(
+  pool: ActualDbPool,
+  secret: Secret,
+  site_view: &SiteView,
+) -> LemmyResult<LemmyContext> {
+  // Set up the rate limiter
+  let rate_limit_config =
+    local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
+  let rate_limit_cell = RateLimit::new(rate_limit_config, &SETTINGS.rate_limit)?;
+
+  let client = ClientBuilder::new(client_builder(&SETTINGS).build()?)
+    .with(TracingMiddleware::default())
+    .build();
+  let pictrs_client = ClientBuilder::new(client_builder(&SETTINGS).no_proxy().build()?)
+    .with(TracingMiddleware::default())
+    .build();
+  Ok(LemmyContext::create(
+    pool,
+    build_read_replica_pools()?,
+    client,
+    pictrs_client,
+    secret,
+    rate_limit_cell,
+  ))
+}
+
+fn federation_config_builder(
+  context: LemmyContext,
+  site_view: &SiteView,
+) -> FederationConfigBuilder<LemmyContext> {
+  let mut federation_config_builder = FederationConfig::builder();
+  federation_config_builder
+    .domain(SETTINGS.hostname.clone())
+    .client(context.client().clone())
+    .url_verifier(Box::new(VerifyUrlData(context.inner_pool().clone())))
+    .app_data(context)
+    .http_fetch_limit(FEDERATION_HTTP_FETCH_LIMIT)
+    .debug(cfg!(debug_assertions))
+    .http_signature_compat(true);
+  if site_view.local_site.federation_signed_fetch {
+    let site: ApubSite = site_view.site.clone().into();
+    federation_config_builder.signed_fetch_actor(&site);
+  }
+  federation_config_builder
+}
+
 /// Creates temporary HTTP server which returns status 503 for all requests.
 fn create_startup_server() -> LemmyResult<ServerHandle> {
   let startup_server = HttpServer::new(move || {
@@ -333,6 +440,7 @@ fn create_http_server(
   // These must come before HttpServer creation so they can collect data across threads.
   let prom_api_metrics = new_prometheus_metrics()?;
   let idempotency_set = IdempotencySet::default();
+  let response_cache = ResponseCache::default();
 
   // Create Http server
   let bind = (settings.bind, settings.port);
@@ -343,18 +451,24 @@ fn create_http_server(
     let cors_config = cors_config(&settings);
     let app = App::new()
       .wrap(ErrorHandlers::new().default_handler(jsonify_plain_text_errors))
-      .wrap(middleware::Logger::new(
-        // This is the default log format save for the usage of %{r}a over %a to guarantee to
-        // record the client's (forwarded) IP and not the last peer address, since the latter is
-        // frequently just a reverse proxy
-        "%{r}a '%r' %s %b '%{Referer}i' '%{User-Agent}i' %T",
-      ))
+      .wrap(
+        middleware::Logger::new(
+          // This is the default log format, except that it records the client IP forwarded by
+          // trusted proxies instead of the last peer address, since the latter is frequently
+          // just a reverse proxy
+          "%{client_ip}xi '%r' %s %b '%{Referer}i' '%{User-Agent}i' %T",
+        )
+        .custom_request_replace("client_ip", |req| {
+          client_ip(req.request()).unwrap_or_else(|| "-".to_string())
+        }),
+      )
       .wrap(middleware::Compress::default())
       .wrap(cors_config)
       .wrap(TracingLogger::<DefaultRootSpanBuilder>::new())
       .app_data(Data::new(context.clone()))
       .wrap(FederationMiddleware::new(federation_config.clone()))
       .wrap(IdempotencyMiddleware::new(idempotency_set.clone()))
+      .wrap(ResponseCacheMiddleware::new(response_cache.clone()))
       .wrap(SessionMiddleware::new(context.clone()))
       .wrap(Condition::new(
         SETTINGS.prometheus.is_some(),
@@ -367,7 +481,7 @@ fn create_http_server(
       .configure(|cfg| lemmy_api_routes_v3::config(cfg, &rate_limit))
       .configure(|cfg| {
         if site_view.local_site.federation_enabled {
-          lemmy_apub::http::routes::config(cfg);
+          lemmy_apub::http::routes::config(cfg, &rate_limit);
           webfinger::config(cfg);
         }
       })
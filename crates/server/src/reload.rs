@@ -0,0 +1,34 @@
+use lemmy_utils::{
+  error::LemmyResult,
+  settings::{reload_settings, structs::FederationWorkerConfig, subscribe_settings},
+};
+use tokio::{
+  signal::unix::{SignalKind, signal},
+  sync::watch,
+};
+use tracing::warn;
+
+/// Reloads the config file whenever the process receives SIGHUP.
+pub(crate) fn reload_on_sighup() -> LemmyResult<()> {
+  let mut hangup = signal(SignalKind::hangup())?;
+  tokio::spawn(async move {
+    while hangup.recv().await.is_some() {
+      if let Err(e) = reload_settings() {
+        warn!("Failed to reload config: {e}");
+      }
+    }
+  });
+  Ok(())
+}
+
+/// Passes changes of the federation settings on to the running instance workers.
+pub(crate) fn federation_worker_config() -> watch::Receiver<FederationWorkerConfig> {
+  let mut settings = subscribe_settings();
+  let (sender, receiver) = watch::channel(settings.borrow_and_update().federation.clone());
+  tokio::spawn(async move {
+    while settings.changed().await.is_ok() && !sender.is_closed() {
+      sender.send_replace(settings.borrow_and_update().federation.clone());
+    }
+  });
+  receiver
+}
@@ -33,6 +33,10 @@ pub enum LemmyErrorType {
   NoContentTypeHeader,
   NotAnImageType,
   ImageUploadDisabled,
+  ImageTooLarge,
+  ImageUploadQuotaExceeded,
+  ImageBlocked,
+  InvalidImageHash,
   NotAModOrAdmin,
   NotTopMod,
   NotLoggedIn,
@@ -79,6 +83,9 @@ pub enum LemmyErrorType {
   InvalidBodyField,
   BioLengthOverflow,
   AltTextLengthOverflow,
+  AltTextRequired,
+  TooManyGalleryImages,
+  GalleryCaptionLengthOverflow,
   CouldntParseTotpSecret,
   CouldntGenerateTotp,
   MissingTotpToken,
@@ -88,6 +95,7 @@ pub enum LemmyErrorType {
   BlockedUrl,
   InvalidUrl,
   EmailSendFailed,
+  InvalidEmailReply,
   Slurs,
   RegistrationDenied(String),
   SiteNameRequired,
@@ -102,7 +110,14 @@ pub enum LemmyErrorType {
   BanExpirationInPast,
   InvalidUnixTime,
   InvalidBotAction,
+  InvalidScheduledTaskInterval,
+  InvalidRateLimitMultiplier,
+  InvalidRetentionPeriod,
+  DataExportAlreadyRequested,
+  EmailAlreadySent,
+  InvalidDataExportLink,
   TagNotInCommunity,
+  RuleNotInCommunity,
   CantBlockLocalInstance,
   Unknown(String),
   UrlLengthOverflow,
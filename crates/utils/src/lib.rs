@@ -5,6 +5,7 @@ use std::{cmp::min, sync::LazyLock};
 cfg_if! {
   if #[cfg(feature = "full")] {
     pub mod cache_header;
+    pub mod metrics;
     pub mod rate_limit;
     pub mod response;
     pub mod settings;
@@ -35,6 +36,12 @@ pub const CACHE_DURATION_API: Duration = Duration::from_secs(0);
 #[cfg(not(debug_assertions))]
 pub const CACHE_DURATION_API: Duration = Duration::from_secs(1);
 
+/// How long API responses for logged out users are cached on the server.
+#[cfg(debug_assertions)]
+pub const CACHE_DURATION_ANONYMOUS_API: Duration = Duration::from_secs(0);
+#[cfg(not(debug_assertions))]
+pub const CACHE_DURATION_ANONYMOUS_API: Duration = Duration::from_secs(10);
+
 #[cfg(debug_assertions)]
 pub const CACHE_DURATION_LARGEST_COMMUNITY: Duration = Duration::from_secs(0);
 #[cfg(not(debug_assertions))]
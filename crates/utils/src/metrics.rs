@@ -0,0 +1,253 @@
+//! Prometheus metrics which are recorded in different crates. They are registered in the default
+//! registry on first use, and served together with the http metrics when `prometheus` is
+//! configured.
+
+use prometheus::{
+  HistogramOpts,
+  HistogramTimer,
+  HistogramVec,
+  IntCounterVec,
+  IntGaugeVec,
+  Opts,
+  core::Collector,
+  default_registry,
+  exponential_buckets,
+};
+use std::{sync::LazyLock, time::Duration};
+
+static FEDERATION_SENDS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+  register(IntCounterVec::new(
+    Opts::new(
+      "lemmy_federation_sends_total",
+      "Outgoing activity requests by target instance and result",
+    ),
+    &["instance", "result"],
+  ))
+});
+
+static FEDERATION_ACTIVITIES_BEHIND: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+  register(IntGaugeVec::new(
+    Opts::new(
+      "lemmy_federation_queue_activities_behind",
+      "Number of activities which have not been sent to the instance yet",
+    ),
+    &["instance"],
+  ))
+});
+
+static FEDERATION_LAG: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+  register(IntGaugeVec::new(
+    Opts::new(
+      "lemmy_federation_queue_lag_seconds",
+      "Age of the last activity which was successfully sent to the instance",
+    ),
+    &["instance"],
+  ))
+});
+
+static INBOX_ACTIVITIES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+  register(IntCounterVec::new(
+    Opts::new(
+      "lemmy_federation_inbox_activities_total",
+      "Incoming activities by type",
+    ),
+    &["type"],
+  ))
+});
+
+static INBOX_FAILURES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+  register(IntCounterVec::new(
+    Opts::new(
+      "lemmy_federation_inbox_failures_total",
+      "Incoming activities which were rejected because the signature or the activity failed to \
+       verify, or because processing failed",
+    ),
+    &["type"],
+  ))
+});
+
+static INBOX_FAILURE_REASONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+  register(IntCounterVec::new(
+    Opts::new(
+      "lemmy_federation_inbox_failure_reasons_total",
+      "Incoming activities which were rejected, by reason",
+    ),
+    &["reason"],
+  ))
+});
+
+static DB_QUERY_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+  register(HistogramVec::new(
+    HistogramOpts::new(
+      "lemmy_db_query_duration_seconds",
+      "Duration of view queries, including the wait for a connection",
+    ),
+    &["query"],
+  ))
+});
+
+static SCHEDULED_TASK_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+  register(HistogramVec::new(
+    HistogramOpts::new(
+      "lemmy_scheduled_task_duration_seconds",
+      "Duration of scheduled task runs in this process",
+    )
+    .buckets(buckets(exponential_buckets(0.1, 4.0, 8))),
+    &["task", "result"],
+  ))
+});
+
+static PLUGIN_CALL_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+  register(HistogramVec::new(
+    HistogramOpts::new(
+      "lemmy_plugin_call_duration_seconds",
+      "Duration of plugin function calls",
+    ),
+    &["plugin", "function"],
+  ))
+});
+
+static PLUGIN_CALL_FAILURES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+  register(IntCounterVec::new(
+    Opts::new(
+      "lemmy_plugin_call_failures_total",
+      "Plugin function calls which returned an error",
+    ),
+    &["plugin", "function"],
+  ))
+});
+
+static EMAILS_SENT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+  register(IntCounterVec::new(
+    Opts::new("lemmy_emails_sent_total", "Email send attempts by result"),
+    &["result"],
+  ))
+});
+
+/// Activity types which get their own label value, all others are counted as `Other`. This keeps
+/// remote instances from creating arbitrary numbers of time series.
+const ACTIVITY_TYPES: [&str; 16] = [
+  "Create", "Update", "Delete", "Undo", "Follow", "Accept", "Reject", "Announce", "Like",
+  "Dislike", "Flag", "Block", "Add", "Remove", "Lock", "Resolve",
+];
+
+pub fn federation_send(instance: &str, success: bool) {
+  FEDERATION_SENDS
+    .with_label_values(&[instance, result_label(success)])
+    .inc();
+}
+
+pub fn federation_queue_state(instance: &str, activities_behind: i64, lag: Option<Duration>) {
+  FEDERATION_ACTIVITIES_BEHIND
+    .with_label_values(&[instance])
+    .set(activities_behind);
+  if let Some(lag) = lag {
+    FEDERATION_LAG
+      .with_label_values(&[instance])
+      .set(lag.as_secs().try_into().unwrap_or(i64::MAX));
+  }
+}
+
+/// Why an incoming activity was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InboxFailureReason {
+  /// The HTTP signature or body digest is invalid.
+  Signature,
+  /// The activity or one of its objects failed verification, e.g. because of a domain mismatch or
+  /// a blocked instance.
+  Verify,
+  /// Processing took too long.
+  Timeout,
+  Other,
+}
+
+impl InboxFailureReason {
+  fn label(self) -> &'static str {
+    match self {
+      InboxFailureReason::Signature => "signature",
+      InboxFailureReason::Verify => "verify",
+      InboxFailureReason::Timeout => "timeout",
+      InboxFailureReason::Other => "other",
+    }
+  }
+}
+
+pub fn inbox_activity(kind: &str, failure: Option<InboxFailureReason>) {
+  let kind = activity_type_label(kind);
+  INBOX_ACTIVITIES.with_label_values(&[kind]).inc();
+  if let Some(failure) = failure {
+    INBOX_FAILURES.with_label_values(&[kind]).inc();
+    INBOX_FAILURE_REASONS
+      .with_label_values(&[failure.label()])
+      .inc();
+  }
+}
+
+/// Records the query duration when the returned timer is dropped.
+pub fn db_query_timer(query: &str) -> HistogramTimer {
+  DB_QUERY_DURATION.with_label_values(&[query]).start_timer()
+}
+
+pub fn scheduled_task_run(task: &str, success: bool, duration: Duration) {
+  SCHEDULED_TASK_DURATION
+    .with_label_values(&[task, result_label(success)])
+    .observe(duration.as_secs_f64());
+}
+
+/// Records the call duration when the returned timer is dropped.
+pub fn plugin_call_timer(plugin: &str, function: &str) -> HistogramTimer {
+  PLUGIN_CALL_DURATION
+    .with_label_values(&[plugin, function])
+    .start_timer()
+}
+
+pub fn plugin_call_failed(plugin: &str, function: &str) {
+  PLUGIN_CALL_FAILURES
+    .with_label_values(&[plugin, function])
+    .inc();
+}
+
+pub fn email_sent(success: bool) {
+  EMAILS_SENT
+    .with_label_values(&[result_label(success)])
+    .inc();
+}
+
+fn result_label(success: bool) -> &'static str {
+  if success { "success" } else { "failure" }
+}
+
+fn activity_type_label(kind: &str) -> &str {
+  if ACTIVITY_TYPES.contains(&kind) {
+    kind
+  } else {
+    "Other"
+  }
+}
+
+#[expect(clippy::expect_used)]
+fn buckets(buckets: prometheus::Result<Vec<f64>>) -> Vec<f64> {
+  buckets.expect("valid buckets")
+}
+
+#[expect(clippy::expect_used)]
+fn register<T: Collector + Clone + 'static>(metric: prometheus::Result<T>) -> T {
+  let metric = metric.expect("valid metric");
+  default_registry()
+    .register(Box::new(metric.clone()))
+    .expect("register metric");
+  metric
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_activity_type_label() {
+    assert_eq!("Create", activity_type_label("Create"));
+    assert_eq!("Other", activity_type_label("ChatMessage"));
+    assert_eq!("Other", activity_type_label(""));
+  }
+}
@@ -1,65 +1,36 @@
-//! The content in this file is mostly copy-pasted from library code:
-//! https://github.com/jacob-pro/actix-extensible-rate-limit/blob/master/src/backend/memory.rs
-
-use crate::rate_limit::{ActionType, BucketConfig, input::LemmyInput};
-use actix_extensible_rate_limit::backend::{
-  Backend,
-  Decision,
-  SimpleOutput,
-  memory::DEFAULT_GC_INTERVAL_SECONDS,
+use crate::rate_limit::{
+  ActionType,
+  BucketConfig,
+  input::{LemmyInput, RateLimitKey},
+  store::RateLimitStore,
 };
-use actix_web::rt::{task::JoinHandle, time::Instant};
-use dashmap::DashMap;
+use actix_extensible_rate_limit::backend::{Backend, Decision, SimpleOutput};
+use actix_web::rt::time::Instant;
 use enum_map::EnumMap;
 use std::{
   convert::Infallible,
   sync::{Arc, RwLock},
   time::Duration,
 };
+use tracing::warn;
 
-/// A Fixed Window rate limiter [Backend] that uses [Dashmap](dashmap::DashMap) to store keys
-/// in memory.
+/// A Fixed Window rate limiter [Backend], which keeps the counters in a [RateLimitStore].
 #[derive(Clone)]
 pub struct LemmyBackend {
-  map: Arc<DashMap<LemmyInput, Value>>,
-  gc_handle: Option<Arc<JoinHandle<()>>>,
+  store: Arc<dyn RateLimitStore>,
   pub(super) configs: Arc<RwLock<EnumMap<ActionType, BucketConfig>>>,
 }
 
-struct Value {
-  ttl: Instant,
-  count: u64,
-}
-
 impl LemmyBackend {
-  pub(crate) fn new(configs: EnumMap<ActionType, BucketConfig>, enable_gc: bool) -> Self {
-    let map = Arc::new(DashMap::<LemmyInput, Value>::new());
-    let gc_handle = enable_gc.then(|| {
-      Arc::new(LemmyBackend::garbage_collector(
-        map.clone(),
-        Duration::from_secs(DEFAULT_GC_INTERVAL_SECONDS),
-      ))
-    });
+  pub(crate) fn with_store(
+    configs: EnumMap<ActionType, BucketConfig>,
+    store: Arc<dyn RateLimitStore>,
+  ) -> Self {
     LemmyBackend {
-      map,
-      gc_handle,
+      store,
       configs: Arc::new(RwLock::new(configs)),
     }
   }
-
-  fn garbage_collector(map: Arc<DashMap<LemmyInput, Value>>, interval: Duration) -> JoinHandle<()> {
-    assert!(
-      interval.as_secs_f64() > 0f64,
-      "GC interval must be non-zero"
-    );
-    tokio::spawn(async move {
-      loop {
-        let now = Instant::now();
-        map.retain(|_k, v| v.ttl > now);
-        tokio::time::sleep_until(now + interval).await;
-      }
-    })
-  }
 }
 
 impl Backend<LemmyInput> for LemmyBackend {
@@ -67,7 +38,6 @@ impl Backend<LemmyInput> for LemmyBackend {
   type RollbackToken = LemmyInput;
   type Error = Infallible;
 
-  #[expect(clippy::expect_used)]
   async fn request(
     &self,
     input: LemmyInput,
@@ -75,34 +45,29 @@ impl Backend<LemmyInput> for LemmyBackend {
     #[expect(clippy::expect_used)]
     let config = self.configs.read().expect("read rwlock")[input.1];
 
-    let max_requests: u64 = config.max_requests.into();
+    let multiplier = match input.0 {
+      RateLimitKey::User { multiplier, .. } => multiplier,
+      RateLimitKey::Exempt => {
+        let output = SimpleOutput {
+          limit: config.max_requests.into(),
+          remaining: config.max_requests.into(),
+          reset: Instant::now(),
+        };
+        return Ok((Decision::from_allowed(true), output, input));
+      }
+      RateLimitKey::Ip(_) => 1,
+    };
+    let max_requests = u64::from(config.max_requests).saturating_mul(multiplier.into());
     let interval = Duration::from_secs(config.interval.into());
 
-    let now = Instant::now();
-    let mut count = 1;
-    let mut expiry = now
-      .checked_add(interval)
-      .expect("Interval unexpectedly large");
-    self
-      .map
-      .entry(input)
-      .and_modify(|v| {
-        // If this bucket hasn't yet expired, increment and extract the count/expiry
-        if v.ttl > now {
-          v.count += 1;
-          count = v.count;
-          expiry = v.ttl;
-        } else {
-          // If this bucket has expired we will reset the count to 1 and set a new TTL.
-          v.ttl = expiry;
-          v.count = count;
-        }
-      })
-      .or_insert_with(|| Value {
-        // If the bucket doesn't exist, create it with a count of 1, and set the TTL.
-        ttl: expiry,
-        count,
-      });
+    let (count, expiry) = match self.store.increment(input, interval).await {
+      Ok(res) => res,
+      Err(e) => {
+        // Rather let requests through than fail all of them while a shared store is unavailable
+        warn!("Failed to update rate limit: {e}");
+        (0, Instant::now())
+      }
+    };
     let allow = count <= max_requests;
     let output = SimpleOutput {
       limit: max_requests,
@@ -113,18 +78,13 @@ impl Backend<LemmyInput> for LemmyBackend {
   }
 
   async fn rollback(&self, token: Self::RollbackToken) -> Result<(), Self::Error> {
-    self.map.entry(token).and_modify(|v| {
-      v.count = v.count.saturating_sub(1);
-    });
-    Ok(())
-  }
-}
-
-impl Drop for LemmyBackend {
-  fn drop(&mut self) {
-    if let Some(handle) = &self.gc_handle {
-      handle.abort();
+    if token.0 == RateLimitKey::Exempt {
+      return Ok(());
+    }
+    if let Err(e) = self.store.decrement(token).await {
+      warn!("Failed to roll back rate limit: {e}");
     }
+    Ok(())
   }
 }
 
@@ -133,10 +93,24 @@ mod tests {
   use super::*;
   use crate::{
     error::LemmyResult,
-    rate_limit::{ActionType, input::raw_ip_key},
+    rate_limit::{
+      ActionType,
+      input::{RateLimitAccount, raw_ip_key},
+      store::MemoryStore,
+    },
   };
   use enum_map::enum_map;
 
+  /// Returns the store as well, to check which keys it contains.
+  fn memory_backend(
+    configs: EnumMap<ActionType, BucketConfig>,
+    enable_gc: bool,
+  ) -> (LemmyBackend, MemoryStore) {
+    let store = MemoryStore::new(enable_gc);
+    let backend = LemmyBackend::with_store(configs, Arc::new(store.clone()));
+    (backend, store)
+  }
+
   const MINUTE_SECS: u32 = 60;
   const MINUTE: Duration = Duration::from_secs(60);
 
@@ -170,15 +144,35 @@ mod tests {
           max_requests: 0,
           interval: 0,
         },
+        ActionType::Vote => BucketConfig {
+          max_requests: 0,
+          interval: 0,
+        },
+        ActionType::Report => BucketConfig {
+          max_requests: 0,
+          interval: 0,
+        },
+        ActionType::Follow => BucketConfig {
+          max_requests: 0,
+          interval: 0,
+        },
+        ActionType::PrivateMessage => BucketConfig {
+          max_requests: 0,
+          interval: 0,
+        },
+        ActionType::FederationInbox => BucketConfig {
+          max_requests: 0,
+          interval: 0,
+        },
     }
   }
 
   #[actix_web::test]
   async fn test_allow_deny() -> LemmyResult<()> {
     tokio::time::pause();
-    let backend = LemmyBackend::new(test_config(MINUTE_SECS, 5), true);
+    let (backend, _store) = memory_backend(test_config(MINUTE_SECS, 5), true);
     let key = raw_ip_key(Some("127.0.0.2"));
-    let input = LemmyInput(key, ActionType::Message);
+    let input = LemmyInput(key.into(), ActionType::Message);
     for _ in 0..5 {
       // First 5 should be allowed
       let (allow, _, _) = backend.request(input).await?;
@@ -193,8 +187,8 @@ mod tests {
   #[actix_web::test]
   async fn test_reset() -> LemmyResult<()> {
     tokio::time::pause();
-    let backend = LemmyBackend::new(test_config(MINUTE_SECS, 1), false);
-    let input = LemmyInput(raw_ip_key(Some("127.0.0.3")), ActionType::Message);
+    let (backend, store) = memory_backend(test_config(MINUTE_SECS, 1), false);
+    let input = LemmyInput(raw_ip_key(Some("127.0.0.3")).into(), ActionType::Message);
     // Make first request, should be allowed
     let (decision, _, _) = backend.request(input).await?;
     assert!(decision.is_allowed());
@@ -204,7 +198,7 @@ mod tests {
     // Advance time and try again, should now be allowed
     tokio::time::advance(MINUTE).await;
     // We want to be sure the key hasn't been garbage collected, and we are testing the expiry logic
-    assert!(backend.map.contains_key(&input));
+    assert!(store.map.contains_key(&input));
     let (decision, _, _) = backend.request(input).await?;
     assert!(decision.is_allowed());
     Ok(())
@@ -213,27 +207,27 @@ mod tests {
   #[actix_web::test]
   async fn test_garbage_collection() -> LemmyResult<()> {
     tokio::time::pause();
-    let backend = LemmyBackend::new(test_config(MINUTE_SECS, 1), true);
-    let key1 = LemmyInput(raw_ip_key(Some("127.0.0.4")), ActionType::Message);
-    let key2 = LemmyInput(raw_ip_key(Some("127.0.0.5")), ActionType::Post);
+    let (backend, store) = memory_backend(test_config(MINUTE_SECS, 1), true);
+    let key1 = LemmyInput(raw_ip_key(Some("127.0.0.4")).into(), ActionType::Message);
+    let key2 = LemmyInput(raw_ip_key(Some("127.0.0.5")).into(), ActionType::Post);
     backend.request(key1).await?;
     backend.request(key2).await?;
-    assert!(backend.map.contains_key(&key1));
-    assert!(backend.map.contains_key(&key2));
+    assert!(store.map.contains_key(&key1));
+    assert!(store.map.contains_key(&key2));
     // Advance time such that the garbage collector runs,
     // expired KEY1 should be cleaned, but KEY2 should remain.
     tokio::time::advance(MINUTE).await;
-    assert!(!backend.map.contains_key(&key1));
-    assert!(backend.map.contains_key(&key2));
+    assert!(!store.map.contains_key(&key1));
+    assert!(store.map.contains_key(&key2));
     Ok(())
   }
 
   #[actix_web::test]
   async fn test_output() -> LemmyResult<()> {
     tokio::time::pause();
-    let backend = LemmyBackend::new(test_config(MINUTE_SECS, 2), true);
+    let (backend, _store) = memory_backend(test_config(MINUTE_SECS, 2), true);
     let key = raw_ip_key(Some("127.0.0.6"));
-    let input = LemmyInput(key, ActionType::Message);
+    let input = LemmyInput(key.into(), ActionType::Message);
     // First of 2 should be allowed.
     let (decision, output, _) = backend.request(input).await?;
     assert!(decision.is_allowed());
@@ -258,9 +252,9 @@ mod tests {
   #[actix_web::test]
   async fn test_rollback() -> LemmyResult<()> {
     tokio::time::pause();
-    let backend = LemmyBackend::new(test_config(MINUTE_SECS, 5), true);
+    let (backend, _store) = memory_backend(test_config(MINUTE_SECS, 5), true);
     let key = raw_ip_key(Some("127.0.0.7"));
-    let input = LemmyInput(key, ActionType::Message);
+    let input = LemmyInput(key.into(), ActionType::Message);
     let (_, output, rollback) = backend.request(input).await?;
     assert_eq!(output.remaining, 4);
     backend.rollback(rollback).await?;
@@ -269,4 +263,38 @@ mod tests {
     assert_eq!(output.remaining, 4);
     Ok(())
   }
+
+  #[actix_web::test]
+  async fn test_account_limits() -> LemmyResult<()> {
+    tokio::time::pause();
+    let (backend, store) = memory_backend(test_config(MINUTE_SECS, 2), true);
+    let account = RateLimitAccount {
+      local_user_id: 1,
+      multiplier: 2,
+      exempt: false,
+    };
+    let input = LemmyInput(account.into(), ActionType::Message);
+    for _ in 0..4 {
+      let (decision, output, _) = backend.request(input).await?;
+      assert!(decision.is_allowed());
+      assert_eq!(output.limit, 4);
+    }
+    let (decision, _, _) = backend.request(input).await?;
+    assert!(decision.is_denied());
+
+    // Exempt accounts are never limited and not stored
+    let exempt = RateLimitAccount {
+      local_user_id: 2,
+      multiplier: 1,
+      exempt: true,
+    };
+    let input = LemmyInput(exempt.into(), ActionType::Message);
+    for _ in 0..5 {
+      let (decision, _, rollback) = backend.request(input).await?;
+      assert!(decision.is_allowed());
+      backend.rollback(rollback).await?;
+    }
+    assert!(!store.map.contains_key(&input));
+    Ok(())
+  }
 }
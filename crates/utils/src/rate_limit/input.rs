@@ -1,12 +1,77 @@
-use crate::rate_limit::ActionType;
+use crate::{rate_limit::ActionType, settings::current_settings};
+use actix_web::{
+  HttpRequest,
+  http::header::{FORWARDED, HeaderMap},
+};
+use ipnet::IpNet;
 use std::{
+  fmt::{self, Display, Formatter},
   future::Ready,
   net::{IpAddr, Ipv4Addr, SocketAddr},
   str::FromStr,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub struct LemmyInput(pub(crate) RateLimitIpAddr, pub(crate) ActionType);
+pub struct LemmyInput(pub(crate) RateLimitKey, pub(crate) ActionType);
+
+impl LemmyInput {
+  /// Unique string for the bucket, for use in external stores.
+  pub fn key(&self) -> String {
+    format!("{}:{}", self.1, self.0)
+  }
+}
+
+/// Who a request is counted against.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum RateLimitKey {
+  Ip(RateLimitIpAddr),
+  /// Logged in local user, with the factor for the limits of the bucket.
+  User {
+    local_user_id: i32,
+    multiplier: u32,
+  },
+  /// Logged in local user who is not rate limited at all.
+  Exempt,
+}
+
+impl From<RateLimitIpAddr> for RateLimitKey {
+  fn from(value: RateLimitIpAddr) -> Self {
+    RateLimitKey::Ip(value)
+  }
+}
+
+impl Display for RateLimitKey {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      RateLimitKey::Ip(ip) => write!(f, "{ip}"),
+      RateLimitKey::User { local_user_id, .. } => write!(f, "user:{local_user_id}"),
+      RateLimitKey::Exempt => write!(f, "exempt"),
+    }
+  }
+}
+
+/// Account information of a logged in user. The session middleware inserts this into the request
+/// extensions, so that the user is rate limited per account instead of per IP.
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimitAccount {
+  pub local_user_id: i32,
+  /// Limits are multiplied with this value, for trusted users and bots.
+  pub multiplier: u32,
+  pub exempt: bool,
+}
+
+impl From<RateLimitAccount> for RateLimitKey {
+  fn from(value: RateLimitAccount) -> Self {
+    if value.exempt {
+      RateLimitKey::Exempt
+    } else {
+      RateLimitKey::User {
+        local_user_id: value.local_user_id,
+        multiplier: value.multiplier.max(1),
+      }
+    }
+  }
+}
 
 pub(crate) type LemmyInputFuture = Ready<Result<LemmyInput, actix_web::Error>>;
 
@@ -30,26 +95,100 @@ impl From<IpAddr> for RateLimitIpAddr {
   }
 }
 
+impl Display for RateLimitIpAddr {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      RateLimitIpAddr::V4(addr) => write!(f, "{addr}"),
+      RateLimitIpAddr::V6([a, b, c, d]) => write!(f, "{a:x}:{b:x}:{c:x}:{d:x}::/64"),
+    }
+  }
+}
+
 /// Generate a raw byte key for backend which uses less memory.
 pub(crate) fn raw_ip_key(ip_str: Option<&str>) -> RateLimitIpAddr {
   parse_ip(ip_str).into()
 }
 
 fn parse_ip(addr: Option<&str>) -> IpAddr {
-  if let Some(addr) = addr {
-    if let Ok(ip) = IpAddr::from_str(addr) {
-      return ip;
-    } else if let Ok(socket) = SocketAddr::from_str(addr) {
-      return socket.ip();
-    }
+  addr
+    .and_then(parse_addr)
+    .unwrap_or(Ipv4Addr::new(127, 0, 0, 1).into())
+}
+
+/// Parses an IP address with optional port, as used in forwarding headers.
+fn parse_addr(addr: &str) -> Option<IpAddr> {
+  let addr = addr.trim().trim_matches('"');
+  IpAddr::from_str(addr)
+    .ok()
+    .or_else(|| SocketAddr::from_str(addr).ok().map(|s| s.ip()))
+    .or_else(|| {
+      let addr = addr.strip_prefix('[')?.strip_suffix(']')?;
+      IpAddr::from_str(addr).ok()
+    })
+}
+
+/// Returns the IP of the client which sent the request. Forwarding headers are only used if the
+/// request comes from one of the configured `trusted_proxies`, as anyone else could use them to
+/// choose an arbitrary IP.
+pub fn client_ip(req: &HttpRequest) -> Option<String> {
+  let peer = req.peer_addr()?.ip();
+  let settings = current_settings();
+  let ip = resolve_client_ip(
+    peer,
+    req.headers(),
+    &settings.client_ip_header,
+    &settings.trusted_proxies,
+  );
+  Some(ip.to_string())
+}
+
+fn resolve_client_ip(
+  peer: IpAddr,
+  headers: &HeaderMap,
+  header_name: &str,
+  trusted_proxies: &[IpNet],
+) -> IpAddr {
+  let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+  if !is_trusted(&peer) {
+    return peer;
+  }
+  // Each proxy appends the address it received the request from. Going backwards from the
+  // closest proxy, the first address which isn't a trusted proxy is the client.
+  let chain = forwarded_for(headers, header_name);
+  chain
+    .iter()
+    .rev()
+    .find(|ip| !is_trusted(ip))
+    .or(chain.first())
+    .copied()
+    .unwrap_or(peer)
+}
+
+/// Addresses from the configured header, which is either `Forwarded` or `X-Forwarded-For`.
+fn forwarded_for(headers: &HeaderMap, header_name: &str) -> Vec<IpAddr> {
+  let values = headers.get_all(header_name).filter_map(|h| h.to_str().ok());
+  if header_name.eq_ignore_ascii_case(FORWARDED.as_str()) {
+    values
+      .flat_map(|h| h.split([',', ';']))
+      .filter_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        key.trim().eq_ignore_ascii_case("for").then_some(value)
+      })
+      .filter_map(parse_addr)
+      .collect()
+  } else {
+    values
+      .flat_map(|h| h.split(','))
+      .filter_map(parse_addr)
+      .collect()
   }
-  Ipv4Addr::new(127, 0, 0, 1).into()
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::error::LemmyResult;
+  use actix_web::http::header::HeaderValue;
 
   #[test]
   fn test_get_ip() -> LemmyResult<()> {
@@ -69,4 +208,70 @@ mod tests {
     );
     Ok(())
   }
+
+  #[test]
+  fn test_client_ip() -> LemmyResult<()> {
+    let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse()?, "::1/128".parse()?];
+    let proxy: IpAddr = "10.0.0.2".parse()?;
+    let client: IpAddr = "142.250.187.206".parse()?;
+
+    let xff = "X-Forwarded-For";
+
+    // Without headers the peer is the client
+    let mut headers = HeaderMap::new();
+    assert_eq!(proxy, resolve_client_ip(proxy, &headers, xff, &trusted));
+
+    // Headers from untrusted peers are ignored
+    headers.insert("x-forwarded-for", HeaderValue::from_static("1.2.3.4"));
+    assert_eq!(client, resolve_client_ip(client, &headers, xff, &trusted));
+
+    // Headers from trusted proxies are used, but a spoofed address in front of the chain is
+    // skipped
+    headers.insert(
+      "x-forwarded-for",
+      HeaderValue::from_static("1.2.3.4, 142.250.187.206, 10.0.0.3"),
+    );
+    assert_eq!(client, resolve_client_ip(proxy, &headers, xff, &trusted));
+
+    // A Forwarded header sent by the client is ignored if it isn't the configured one
+    headers.insert(FORWARDED, HeaderValue::from_static("for=1.2.3.4"));
+    assert_eq!(client, resolve_client_ip(proxy, &headers, xff, &trusted));
+
+    // The Forwarded header can be configured instead, then X-Forwarded-For is ignored
+    headers.insert(
+      FORWARDED,
+      HeaderValue::from_static(r#"for="[2a00:1450:4009:81f::200e]:4711";proto=https"#),
+    );
+    assert_eq!(
+      "2a00:1450:4009:81f::200e".parse::<IpAddr>()?,
+      resolve_client_ip("::1".parse()?, &headers, "Forwarded", &trusted)
+    );
+
+    // Only trusted addresses, use the first one
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", HeaderValue::from_static("10.0.0.4"));
+    assert_eq!(
+      "10.0.0.4".parse::<IpAddr>()?,
+      resolve_client_ip(proxy, &headers, xff, &trusted)
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_input_key() {
+    let input = LemmyInput(raw_ip_key(Some("142.250.187.206")).into(), ActionType::Post);
+    assert_eq!("Post:142.250.187.206", input.key());
+    let input = LemmyInput(
+      raw_ip_key(Some("2a00:1450:4009:81f::200e")).into(),
+      ActionType::Message,
+    );
+    assert_eq!("Message:2a00:1450:4009:81f::/64", input.key());
+    let account = RateLimitAccount {
+      local_user_id: 5,
+      multiplier: 3,
+      exempt: false,
+    };
+    let input = LemmyInput(account.into(), ActionType::Vote);
+    assert_eq!("Vote:user:5", input.key());
+  }
 }
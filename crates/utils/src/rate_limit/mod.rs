@@ -1,15 +1,23 @@
-use crate::rate_limit::{
-  backend::LemmyBackend,
-  input::{LemmyInput, LemmyInputFuture, raw_ip_key},
+use crate::{
+  error::{LemmyErrorType, LemmyResult},
+  rate_limit::{
+    backend::LemmyBackend,
+    input::{LemmyInputFuture, RateLimitKey, client_ip, raw_ip_key},
+  },
+  settings::structs::{RateLimitBackend, RateLimitConfig},
 };
 use actix_extensible_rate_limit::{RateLimiter, backend::SimpleOutput};
-use actix_web::dev::ServiceRequest;
+use actix_web::{HttpMessage, dev::ServiceRequest};
 use enum_map::{EnumMap, enum_map};
-use std::future::ready;
+use std::{future::ready, sync::Arc};
 use strum::{AsRefStr, Display};
 
 mod backend;
 mod input;
+mod store;
+
+pub use input::{LemmyInput, RateLimitAccount, client_ip};
+pub use store::{MemoryStore, RateLimitStore, RedisStore};
 
 #[derive(Debug, enum_map::Enum, Copy, Clone, Display, AsRefStr, Eq, PartialEq, Hash)]
 pub enum ActionType {
@@ -20,6 +28,13 @@ pub enum ActionType {
   Comment,
   Search,
   ImportUserSettings,
+  Vote,
+  Report,
+  Follow,
+  PrivateMessage,
+  /// Incoming activities, limited per sending server IP. The HTTP signature isn't verified yet
+  /// when the limit is checked, so the instance from its key id can't be trusted.
+  FederationInbox,
 }
 
 #[derive(PartialEq, Debug, Copy, Clone)]
@@ -34,14 +49,39 @@ pub struct RateLimit {
 }
 
 impl RateLimit {
-  pub fn new(configs: EnumMap<ActionType, BucketConfig>) -> Self {
+  /// Uses the store which is selected in the config file.
+  pub fn new(
+    configs: EnumMap<ActionType, BucketConfig>,
+    settings: &RateLimitConfig,
+  ) -> LemmyResult<Self> {
+    let store: Arc<dyn RateLimitStore> = match (settings.backend, &settings.redis_url) {
+      (RateLimitBackend::Memory, _) => return Ok(Self::in_memory(configs)),
+      (RateLimitBackend::Redis, Some(redis_url)) => Arc::new(RedisStore::new(redis_url)?),
+      (RateLimitBackend::Redis, None) => {
+        return Err(
+          LemmyErrorType::Unknown("rate_limit.redis_url is required for redis backend".into())
+            .into(),
+        );
+      }
+    };
+    Ok(Self::with_store(configs, store))
+  }
+
+  fn in_memory(configs: EnumMap<ActionType, BucketConfig>) -> Self {
+    Self::with_store(configs, Arc::new(MemoryStore::new(true)))
+  }
+
+  pub fn with_store(
+    configs: EnumMap<ActionType, BucketConfig>,
+    store: Arc<dyn RateLimitStore>,
+  ) -> Self {
     Self {
-      backend: LemmyBackend::new(configs, true),
+      backend: LemmyBackend::with_store(configs, store),
     }
   }
 
   pub fn with_debug_config() -> Self {
-    Self::new(enum_map! {
+    Self::in_memory(enum_map! {
       ActionType::Message => BucketConfig {
         max_requests: 180,
         interval: 60,
@@ -70,6 +110,26 @@ impl RateLimit {
         max_requests: 1,
         interval: 24 * 60 * 60,
       },
+      ActionType::Vote => BucketConfig {
+        max_requests: 100,
+        interval: 60,
+      },
+      ActionType::Report => BucketConfig {
+        max_requests: 5,
+        interval: 600,
+      },
+      ActionType::Follow => BucketConfig {
+        max_requests: 20,
+        interval: 600,
+      },
+      ActionType::PrivateMessage => BucketConfig {
+        max_requests: 10,
+        interval: 600,
+      },
+      ActionType::FederationInbox => BucketConfig {
+        max_requests: 1000,
+        interval: 60,
+      },
     })
   }
 
@@ -135,13 +195,49 @@ impl RateLimit {
   {
     self.build_rate_limiter(ActionType::ImportUserSettings)
   }
+  pub fn vote(
+    &self,
+  ) -> RateLimiter<LemmyBackend, SimpleOutput, impl Fn(&ServiceRequest) -> LemmyInputFuture + 'static>
+  {
+    self.build_rate_limiter(ActionType::Vote)
+  }
+  pub fn report(
+    &self,
+  ) -> RateLimiter<LemmyBackend, SimpleOutput, impl Fn(&ServiceRequest) -> LemmyInputFuture + 'static>
+  {
+    self.build_rate_limiter(ActionType::Report)
+  }
+  pub fn follow(
+    &self,
+  ) -> RateLimiter<LemmyBackend, SimpleOutput, impl Fn(&ServiceRequest) -> LemmyInputFuture + 'static>
+  {
+    self.build_rate_limiter(ActionType::Follow)
+  }
+  pub fn private_message(
+    &self,
+  ) -> RateLimiter<LemmyBackend, SimpleOutput, impl Fn(&ServiceRequest) -> LemmyInputFuture + 'static>
+  {
+    self.build_rate_limiter(ActionType::PrivateMessage)
+  }
+  pub fn federation_inbox(
+    &self,
+  ) -> RateLimiter<LemmyBackend, SimpleOutput, impl Fn(&ServiceRequest) -> LemmyInputFuture + 'static>
+  {
+    self.build_rate_limiter(ActionType::FederationInbox)
+  }
 }
 
+/// Logged in users are limited per account, everyone else per IP. Incoming activities are always
+/// limited per IP.
 fn new_input(action_type: ActionType) -> impl Fn(&ServiceRequest) -> LemmyInputFuture + 'static {
   move |req| {
     ready({
-      let info = req.connection_info();
-      let key = raw_ip_key(info.realip_remote_addr());
+      let ip = raw_ip_key(client_ip(req.request()).as_deref());
+      let account = req.extensions().get::<RateLimitAccount>().copied();
+      let key = match (action_type, account) {
+        (ActionType::FederationInbox, _) | (_, None) => RateLimitKey::Ip(ip),
+        (_, Some(account)) => account.into(),
+      };
 
       Ok(LemmyInput(key, action_type))
     })
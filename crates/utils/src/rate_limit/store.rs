@@ -0,0 +1,189 @@
+//! The in-memory store is mostly copy-pasted from library code:
+//! https://github.com/jacob-pro/actix-extensible-rate-limit/blob/master/src/backend/memory.rs
+
+use crate::{error::LemmyResult, rate_limit::input::LemmyInput};
+use actix_extensible_rate_limit::backend::memory::DEFAULT_GC_INTERVAL_SECONDS;
+use actix_web::rt::{task::JoinHandle, time::Instant};
+use dashmap::DashMap;
+use futures::future::{BoxFuture, ready};
+use redis::{Client, Script, aio::ConnectionManager};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::OnceCell;
+
+/// Storage for the rate limit counters. Each key has a fixed window, which starts with the first
+/// request and lasts for the interval of its bucket.
+pub trait RateLimitStore: Send + Sync {
+  /// Increments the counter of the key, and returns the new count and the end of the window.
+  fn increment(
+    &self,
+    key: LemmyInput,
+    interval: Duration,
+  ) -> BoxFuture<'_, LemmyResult<(u64, Instant)>>;
+
+  /// Decrements the counter of the key, used to roll back failed requests.
+  fn decrement(&self, key: LemmyInput) -> BoxFuture<'_, LemmyResult<()>>;
+}
+
+/// Keeps the counters in process memory. This is the default, but each process has its own
+/// limits and they are reset on restart.
+#[derive(Clone)]
+pub struct MemoryStore {
+  pub(super) map: Arc<DashMap<LemmyInput, Value>>,
+  gc_handle: Option<Arc<JoinHandle<()>>>,
+}
+
+pub(super) struct Value {
+  ttl: Instant,
+  count: u64,
+}
+
+impl MemoryStore {
+  pub fn new(enable_gc: bool) -> Self {
+    let map = Arc::new(DashMap::<LemmyInput, Value>::new());
+    let gc_handle = enable_gc.then(|| {
+      Arc::new(MemoryStore::garbage_collector(
+        map.clone(),
+        Duration::from_secs(DEFAULT_GC_INTERVAL_SECONDS),
+      ))
+    });
+    MemoryStore { map, gc_handle }
+  }
+
+  fn garbage_collector(map: Arc<DashMap<LemmyInput, Value>>, interval: Duration) -> JoinHandle<()> {
+    assert!(
+      interval.as_secs_f64() > 0f64,
+      "GC interval must be non-zero"
+    );
+    tokio::spawn(async move {
+      loop {
+        let now = Instant::now();
+        map.retain(|_k, v| v.ttl > now);
+        tokio::time::sleep_until(now + interval).await;
+      }
+    })
+  }
+}
+
+impl RateLimitStore for MemoryStore {
+  #[expect(clippy::expect_used)]
+  fn increment(
+    &self,
+    key: LemmyInput,
+    interval: Duration,
+  ) -> BoxFuture<'_, LemmyResult<(u64, Instant)>> {
+    let now = Instant::now();
+    let mut count = 1;
+    let mut expiry = now
+      .checked_add(interval)
+      .expect("Interval unexpectedly large");
+    self
+      .map
+      .entry(key)
+      .and_modify(|v| {
+        // If this bucket hasn't yet expired, increment and extract the count/expiry
+        if v.ttl > now {
+          v.count += 1;
+          count = v.count;
+          expiry = v.ttl;
+        } else {
+          // If this bucket has expired we will reset the count to 1 and set a new TTL.
+          v.ttl = expiry;
+          v.count = count;
+        }
+      })
+      .or_insert_with(|| Value {
+        // If the bucket doesn't exist, create it with a count of 1, and set the TTL.
+        ttl: expiry,
+        count,
+      });
+    Box::pin(ready(Ok((count, expiry))))
+  }
+
+  fn decrement(&self, key: LemmyInput) -> BoxFuture<'_, LemmyResult<()>> {
+    self.map.entry(key).and_modify(|v| {
+      v.count = v.count.saturating_sub(1);
+    });
+    Box::pin(ready(Ok(())))
+  }
+}
+
+impl Drop for MemoryStore {
+  fn drop(&mut self) {
+    // The store may be cloned, only stop garbage collection when the last clone is dropped
+    if let Some(handle) = &self.gc_handle
+      && Arc::strong_count(handle) == 1
+    {
+      handle.abort();
+    }
+  }
+}
+
+/// Keeps the counters in a Redis-compatible server, so that limits are shared between all Lemmy
+/// processes which use the same server.
+pub struct RedisStore {
+  client: Client,
+  conn: OnceCell<ConnectionManager>,
+}
+
+const REDIS_KEY_PREFIX: &str = "lemmy_rate_limit";
+
+impl RedisStore {
+  /// Only checks the url, the connection is established on first use.
+  pub fn new(url: &str) -> LemmyResult<Self> {
+    Ok(RedisStore {
+      client: Client::open(url)?,
+      conn: OnceCell::new(),
+    })
+  }
+
+  async fn conn(&self) -> LemmyResult<ConnectionManager> {
+    let conn = self
+      .conn
+      .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+      .await?;
+    Ok(conn.clone())
+  }
+}
+
+impl RateLimitStore for RedisStore {
+  fn increment(
+    &self,
+    key: LemmyInput,
+    interval: Duration,
+  ) -> BoxFuture<'_, LemmyResult<(u64, Instant)>> {
+    Box::pin(async move {
+      let mut conn = self.conn().await?;
+      let key = format!("{REDIS_KEY_PREFIX}:{}", key.key());
+      // Creating the key with an expiry first means that the window starts with the first request,
+      // and later increments keep it.
+      let (count, ttl_ms): (u64, i64) = redis::pipe()
+        .atomic()
+        .cmd("SET")
+        .arg(&key)
+        .arg(0)
+        .arg("EX")
+        .arg(interval.as_secs().max(1))
+        .arg("NX")
+        .ignore()
+        .incr(&key, 1)
+        .pttl(&key)
+        .query_async(&mut conn)
+        .await?;
+      let ttl = Duration::from_millis(ttl_ms.try_into().unwrap_or_default());
+      Ok((count, Instant::now() + ttl))
+    })
+  }
+
+  fn decrement(&self, key: LemmyInput) -> BoxFuture<'_, LemmyResult<()>> {
+    Box::pin(async move {
+      let mut conn = self.conn().await?;
+      let key = format!("{REDIS_KEY_PREFIX}:{}", key.key());
+      // Don't recreate the key without expiry if its window ended in the meantime
+      Script::new("if redis.call('EXISTS', KEYS[1]) == 1 then redis.call('DECR', KEYS[1]) end")
+        .key(key)
+        .invoke_async::<()>(&mut conn)
+        .await?;
+      Ok(())
+    })
+  }
+}
@@ -1,8 +1,11 @@
 use crate::{error::LemmyResult, location_info};
 use anyhow::{Context, anyhow};
 use deser_hjson::from_str;
+use serde::Serialize;
 use std::{env, fs, sync::LazyLock};
 use structs::{PictrsConfig, Settings};
+use tokio::sync::watch;
+use tracing::{info, warn};
 use url::Url;
 use urlencoding::encode;
 
@@ -28,12 +31,51 @@ pub static SETTINGS: LazyLock<Settings> = LazyLock::new(|| {
   }
 });
 
+/// The most recently loaded settings. Starts out with [SETTINGS], and is replaced by
+/// [reload_settings].
+static CURRENT_SETTINGS: LazyLock<watch::Sender<&'static Settings>> =
+  LazyLock::new(|| watch::Sender::new(&*SETTINGS));
+
+/// Returns the current settings, including changes from [reload_settings].
+pub fn current_settings() -> &'static Settings {
+  *CURRENT_SETTINGS.borrow()
+}
+
+/// Notifies about every call to [reload_settings], for components which need to reinitialize.
+pub fn subscribe_settings() -> watch::Receiver<&'static Settings> {
+  CURRENT_SETTINGS.subscribe()
+}
+
+/// Reads the config file again. Settings which are only used at startup keep their old value,
+/// the names of those which were changed are returned so that the admin can restart.
+///
+/// Each reload leaks the previous settings, as they are handed out as `&'static`. This is a few
+/// kilobytes per reload.
+pub fn reload_settings() -> LemmyResult<Vec<&'static str>> {
+  if env::var("LEMMY_INITIALIZE_WITH_DEFAULT_SETTINGS").is_ok() {
+    return Err(anyhow!("Settings are not loaded from a file, nothing to reload").into());
+  }
+  let current = current_settings();
+  let mut settings = Settings::init()?;
+  let restart_required = settings.keep_startup_settings(current)?;
+  CURRENT_SETTINGS.send_replace(Box::leak(Box::new(settings)));
+  if restart_required.is_empty() {
+    info!("Reloaded config");
+  } else {
+    warn!(
+      "Reloaded config, changes to these settings require a restart: {}",
+      restart_required.join(", ")
+    );
+  }
+  Ok(restart_required)
+}
+
 impl Settings {
   /// Reads config from configuration file.
   ///
   /// Note: The env var `LEMMY_DATABASE_URL` is parsed in
   /// `lemmy_db_schema/src/lib.rs::get_database_url_from_env()`
-  /// Warning: Only call this once.
+  /// Warning: Only call this once, use [reload_settings] for changes at runtime.
   pub(crate) fn init() -> LemmyResult<Self> {
     let path =
       env::var("LEMMY_CONFIG_LOCATION").unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
@@ -54,6 +96,73 @@ impl Settings {
     }
   }
 
+  /// Copies settings which can't change at runtime from `current`. Email, pictrs, plugins,
+  /// federation and trusted proxies are left as they are.
+  fn keep_startup_settings(&mut self, current: &Settings) -> LemmyResult<Vec<&'static str>> {
+    let mut changed = vec![];
+    keep(
+      "database",
+      &mut self.database,
+      &current.database,
+      &mut changed,
+    )?;
+    keep("media", &mut self.media, &current.media, &mut changed)?;
+    keep("setup", &mut self.setup, &current.setup, &mut changed)?;
+    keep(
+      "hostname",
+      &mut self.hostname,
+      &current.hostname,
+      &mut changed,
+    )?;
+    keep("bind", &mut self.bind, &current.bind, &mut changed)?;
+    keep("port", &mut self.port, &current.port, &mut changed)?;
+    keep(
+      "tls_enabled",
+      &mut self.tls_enabled,
+      &current.tls_enabled,
+      &mut changed,
+    )?;
+    keep(
+      "opentelemetry_url",
+      &mut self.opentelemetry_url,
+      &current.opentelemetry_url,
+      &mut changed,
+    )?;
+    keep(
+      "prometheus",
+      &mut self.prometheus,
+      &current.prometheus,
+      &mut changed,
+    )?;
+    keep(
+      "rate_limit",
+      &mut self.rate_limit,
+      &current.rate_limit,
+      &mut changed,
+    )?;
+    keep(
+      "cors_origin",
+      &mut self.cors_origin,
+      &current.cors_origin,
+      &mut changed,
+    )?;
+    keep(
+      "json_logging",
+      &mut self.json_logging,
+      &current.json_logging,
+      &mut changed,
+    )?;
+    // The LMTP server is only started once, the rest of the email config can change
+    let lmtp_bind = current.email.as_ref().and_then(|e| e.lmtp_bind);
+    if let Some(email) = &mut self.email
+      && email.lmtp_bind != lmtp_bind
+    {
+      email.lmtp_bind = lmtp_bind;
+      changed.push("email.lmtp_bind");
+    }
+    Ok(changed)
+  }
+
   /// Returns either "http" or "https", depending on tls_enabled setting
   fn get_protocol_string(&self) -> &'static str {
     if self.tls_enabled { "https" } else { "http" }
@@ -89,7 +198,22 @@ impl Settings {
 
   /// Sets a few additional config options necessary for starting lemmy
   pub fn get_database_url_with_options(&self) -> LemmyResult<String> {
-    let mut url = Url::parse(&self.get_database_url())?;
+    self.add_database_url_options(&self.get_database_url())
+  }
+
+  /// Urls of the read replicas, with the same options as
+  /// [get_database_url_with_options](Self::get_database_url_with_options).
+  pub fn get_read_replica_urls_with_options(&self) -> LemmyResult<Vec<String>> {
+    self
+      .database
+      .read_replicas
+      .iter()
+      .map(|url| self.add_database_url_options(url))
+      .collect()
+  }
+
+  fn add_database_url_options(&self, url: &str) -> LemmyResult<String> {
+    let mut url = Url::parse(url)?;
 
     // Set `lemmy.protocol_and_hostname` so triggers can use it
     let lemmy_protocol_and_hostname_option =
@@ -111,6 +235,21 @@ impl Settings {
     Ok(url.into())
   }
 }
+
+/// Resets `new` to `current` if they differ, and records the name of the setting.
+fn keep<T: Serialize + Clone>(
+  name: &'static str,
+  new: &mut T,
+  current: &T,
+  changed: &mut Vec<&'static str>,
+) -> LemmyResult<()> {
+  if serde_json::to_value(&*new)? != serde_json::to_value(current)? {
+    *new = current.clone();
+    changed.push(name);
+  }
+  Ok(())
+}
+
 #[expect(clippy::expect_used)]
 /// Necessary to avoid URL expect failures
 fn pictrs_placeholder_url() -> Url {
@@ -128,4 +267,24 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_keep_startup_settings() -> LemmyResult<()> {
+    let current = Settings::default();
+    let mut settings = Settings {
+      hostname: "example.com".to_string(),
+      port: 1234,
+      json_logging: true,
+      ..Default::default()
+    };
+    settings.federation.concurrent_sends_per_instance = 5;
+    let changed = settings.keep_startup_settings(&current)?;
+    assert_eq!(vec!["hostname", "port", "json_logging"], changed);
+    assert_eq!(current.hostname, settings.hostname);
+    assert_eq!(current.port, settings.port);
+    assert!(!settings.json_logging);
+    // Runtime settings are applied
+    assert_eq!(5, settings.federation.concurrent_sends_per_instance);
+    Ok(())
+  }
 }
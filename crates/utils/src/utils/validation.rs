@@ -24,6 +24,9 @@ const POST_BODY_MAX_LENGTH: usize = 50000;
 const BIO_MAX_LENGTH: usize = 1000;
 const URL_MAX_LENGTH: usize = 2000;
 const ALT_TEXT_MAX_LENGTH: usize = 1500;
+const GALLERY_CAPTION_MAX_LENGTH: usize = 1500;
+const RULE_TITLE_MAX_LENGTH: usize = 255;
+const GALLERY_MAX_IMAGES: usize = 20;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_SUMMARY_MAX_LENGTH: usize = 150;
@@ -118,7 +121,13 @@ pub fn is_valid_bio_field(bio: &str) -> LemmyResult<()> {
   max_length_check(bio, BIO_MAX_LENGTH, LemmyErrorType::BioLengthOverflow)
 }
 
-pub fn is_valid_alt_text_field(alt_text: &str) -> LemmyResult<()> {
+/// Checks the alt text length, and that it is not empty if `required` (for image posts in
+/// instances or communities which require alt text).
+pub fn is_valid_alt_text_field(alt_text: Option<&str>, required: bool) -> LemmyResult<()> {
+  let alt_text = alt_text.unwrap_or_default();
+  if required && alt_text.trim().is_empty() {
+    return Err(LemmyErrorType::AltTextRequired.into());
+  }
   max_length_check(
     alt_text,
     ALT_TEXT_MAX_LENGTH,
@@ -128,6 +137,22 @@ pub fn is_valid_alt_text_field(alt_text: &str) -> LemmyResult<()> {
   Ok(())
 }
 
+/// Checks the number of images in a gallery post.
+pub fn is_valid_gallery_length(len: usize) -> LemmyResult<()> {
+  if len > GALLERY_MAX_IMAGES {
+    return Err(LemmyErrorType::TooManyGalleryImages.into());
+  }
+  Ok(())
+}
+
+pub fn is_valid_gallery_caption(caption: &str) -> LemmyResult<()> {
+  max_length_check(
+    caption,
+    GALLERY_CAPTION_MAX_LENGTH,
+    LemmyErrorType::GalleryCaptionLengthOverflow,
+  )
+}
+
 /// Checks the site name length, the limit as defined in the DB.
 pub fn site_name_length_check(name: &str) -> LemmyResult<()> {
   min_length_check(name, SITE_NAME_MIN_LENGTH, LemmyErrorType::SiteNameRequired)?;
@@ -348,6 +373,18 @@ pub fn truncate_summary(text: &str) -> String {
   truncate_for_db(text, SITE_SUMMARY_MAX_LENGTH)
 }
 
+pub fn truncate_alt_text(text: &str) -> String {
+  truncate_for_db(text, ALT_TEXT_MAX_LENGTH)
+}
+
+pub fn truncate_gallery_caption(text: &str) -> String {
+  truncate_for_db(text, GALLERY_CAPTION_MAX_LENGTH)
+}
+
+pub fn truncate_rule_title(text: &str) -> String {
+  truncate_for_db(text, RULE_TITLE_MAX_LENGTH)
+}
+
 pub fn check_api_elements_count(len: usize) -> LemmyResult<()> {
   if len >= MAX_API_PARAM_ELEMENTS {
     return Err(LemmyErrorType::TooManyItems.into());
@@ -360,7 +397,10 @@ mod tests {
   use crate::{
     error::{LemmyErrorType, LemmyResult},
     utils::validation::{
+      ALT_TEXT_MAX_LENGTH,
       BIO_MAX_LENGTH,
+      GALLERY_CAPTION_MAX_LENGTH,
+      GALLERY_MAX_IMAGES,
       SITE_NAME_MAX_LENGTH,
       SITE_SUMMARY_MAX_LENGTH,
       URL_MAX_LENGTH,
@@ -370,8 +410,11 @@ mod tests {
       clean_urls_in_text,
       is_url_blocked,
       is_valid_actor_name,
+      is_valid_alt_text_field,
       is_valid_bio_field,
       is_valid_display_name,
+      is_valid_gallery_caption,
+      is_valid_gallery_length,
       is_valid_matrix_id,
       is_valid_post_title,
       is_valid_url,
@@ -548,6 +591,36 @@ Line3",
     );
   }
 
+  #[test]
+  fn test_valid_alt_text() {
+    assert!(is_valid_alt_text_field(None, false).is_ok());
+    assert!(is_valid_alt_text_field(Some("A cat on a sofa"), true).is_ok());
+
+    let missing = is_valid_alt_text_field(Some("  "), true);
+    assert!(missing.is_err_and(|e| e.error_type.eq(&LemmyErrorType::AltTextRequired)));
+
+    let too_long = (0..=ALT_TEXT_MAX_LENGTH).map(|_| 'A').collect::<String>();
+    let too_long = is_valid_alt_text_field(Some(&too_long), false);
+    assert!(too_long.is_err_and(|e| e.error_type.eq(&LemmyErrorType::AltTextLengthOverflow)));
+  }
+
+  #[test]
+  fn test_valid_gallery() {
+    assert!(is_valid_gallery_length(GALLERY_MAX_IMAGES).is_ok());
+    let too_many = is_valid_gallery_length(GALLERY_MAX_IMAGES + 1);
+    assert!(too_many.is_err_and(|e| e.error_type.eq(&LemmyErrorType::TooManyGalleryImages)));
+
+    assert!(is_valid_gallery_caption("Sunset at the lake").is_ok());
+    let too_long = (0..=GALLERY_CAPTION_MAX_LENGTH)
+      .map(|_| 'A')
+      .collect::<String>();
+    let too_long = is_valid_gallery_caption(&too_long);
+    assert!(too_long.is_err_and(|e| {
+      e.error_type
+        .eq(&LemmyErrorType::GalleryCaptionLengthOverflow)
+    }));
+  }
+
   #[test]
   fn test_valid_site_description() {
     assert!(
@@ -0,0 +1,2 @@
+DROP INDEX idx_modlog_expires_at;
+
@@ -0,0 +1,4 @@
+-- Used by the scheduled task which reverts expired locks and removals.
+CREATE INDEX idx_modlog_expires_at ON modlog (expires_at)
+WHERE (expires_at IS NOT NULL AND NOT is_revert);
+
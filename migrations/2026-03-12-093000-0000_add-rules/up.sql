@@ -0,0 +1,26 @@
+-- Structured rules which can be referenced by reports and mod actions. Rules with a community_id
+-- belong to that community, the others are rules of the local instance.
+CREATE TABLE rule (
+    id serial PRIMARY KEY,
+    ap_id text NOT NULL UNIQUE,
+    community_id int REFERENCES community (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    title varchar(255) NOT NULL,
+    description text,
+    -- Rules are displayed in ascending order of this value
+    position int NOT NULL DEFAULT 0,
+    published_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz,
+    deleted boolean NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX idx_rule_community_id ON rule (community_id);
+
+ALTER TABLE post_report
+    ADD COLUMN rule_id int REFERENCES rule (id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE comment_report
+    ADD COLUMN rule_id int REFERENCES rule (id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE modlog
+    ADD COLUMN rule_id int REFERENCES rule (id) ON UPDATE CASCADE ON DELETE SET NULL;
+
@@ -0,0 +1,28 @@
+-- Outgoing emails are stored here and sent by a background worker, so that failed sends can be
+-- retried and admins can see what was delivered.
+CREATE TABLE email_queue (
+    id serial PRIMARY KEY,
+    recipient_email text NOT NULL,
+    recipient_name text NOT NULL,
+    subject text NOT NULL,
+    -- Html body of the email, the plain text version is generated when sending
+    body text NOT NULL,
+    -- Notification emails are rate limited per recipient
+    is_notification boolean NOT NULL DEFAULT FALSE,
+    attempts int NOT NULL DEFAULT 0,
+    last_error text,
+    published_at timestamptz NOT NULL DEFAULT now(),
+    next_attempt_at timestamptz NOT NULL DEFAULT now(),
+    sent_at timestamptz,
+    -- Set once the maximum number of attempts is reached
+    failed_at timestamptz
+);
+
+CREATE INDEX idx_email_queue_pending ON email_queue (next_attempt_at)
+WHERE
+    sent_at IS NULL AND failed_at IS NULL;
+
+CREATE INDEX idx_email_queue_notification_recipient ON email_queue (recipient_email, published_at)
+WHERE
+    is_notification;
+
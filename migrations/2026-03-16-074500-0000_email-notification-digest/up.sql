@@ -0,0 +1,12 @@
+-- Users can receive notification emails in batches instead of one email per notification
+CREATE TYPE email_notification_frequency_enum AS ENUM (
+    'Immediate',
+    'Hourly',
+    'Daily',
+    'Weekly'
+);
+
+ALTER TABLE local_user
+    ADD COLUMN email_notification_frequency email_notification_frequency_enum NOT NULL DEFAULT 'Immediate',
+    ADD COLUMN last_email_digest_at timestamptz NOT NULL DEFAULT now();
+
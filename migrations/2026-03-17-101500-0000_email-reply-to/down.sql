@@ -0,0 +1,3 @@
+ALTER TABLE email_queue
+    DROP COLUMN reply_to;
+
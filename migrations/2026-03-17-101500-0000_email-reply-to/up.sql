@@ -0,0 +1,4 @@
+-- Signed reply address for notification emails, which allows replying by email
+ALTER TABLE email_queue
+    ADD COLUMN reply_to text;
+
@@ -0,0 +1,10 @@
+ALTER TABLE local_user
+    DROP COLUMN image_upload_bytes,
+    DROP COLUMN image_upload_quota_bytes;
+
+ALTER TABLE local_site
+    DROP COLUMN image_upload_quota_bytes;
+
+ALTER TABLE local_image
+    DROP COLUMN size;
+
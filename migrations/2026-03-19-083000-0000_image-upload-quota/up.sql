@@ -0,0 +1,14 @@
+-- Track the file size of uploaded images, so that per-user storage quotas can be enforced. The size
+-- of existing images is unknown.
+ALTER TABLE local_image
+    ADD COLUMN size bigint NOT NULL DEFAULT 0;
+
+-- Maximum total size of uploads per user, no limit if null
+ALTER TABLE local_site
+    ADD COLUMN image_upload_quota_bytes bigint;
+
+-- Total size of the user's uploads, and an optional quota which overrides the one of local_site
+ALTER TABLE local_user
+    ADD COLUMN image_upload_bytes bigint NOT NULL DEFAULT 0,
+    ADD COLUMN image_upload_quota_bytes bigint;
+
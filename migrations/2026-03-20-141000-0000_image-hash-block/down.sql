@@ -0,0 +1,4 @@
+DROP TABLE image_hash_block;
+
+ALTER TABLE image_details
+    DROP COLUMN phash;
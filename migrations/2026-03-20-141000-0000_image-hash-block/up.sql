@@ -0,0 +1,10 @@
+-- Perceptual hashes of images, used to block re-uploads of known abusive media
+ALTER TABLE image_details
+    ADD COLUMN phash bigint;
+
+CREATE TABLE image_hash_block (
+    id serial PRIMARY KEY,
+    hash bigint NOT NULL UNIQUE,
+    reason text,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
@@ -0,0 +1,5 @@
+ALTER TABLE local_site
+    DROP COLUMN require_alt_text;
+
+ALTER TABLE community
+    DROP COLUMN require_alt_text;
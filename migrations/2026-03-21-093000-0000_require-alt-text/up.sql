@@ -0,0 +1,6 @@
+-- Require alt text for image posts, either instance wide or in single communities
+ALTER TABLE local_site
+    ADD COLUMN require_alt_text boolean NOT NULL DEFAULT FALSE;
+
+ALTER TABLE community
+    ADD COLUMN require_alt_text boolean NOT NULL DEFAULT FALSE;
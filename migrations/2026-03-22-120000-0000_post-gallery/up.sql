@@ -0,0 +1,10 @@
+-- Ordered images of gallery posts, in addition to the post url
+CREATE TABLE post_gallery_image (
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    position int NOT NULL,
+    url text NOT NULL,
+    alt_text text,
+    caption text,
+    published_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (post_id, position)
+);
@@ -0,0 +1,6 @@
+-- Read-only tokens for private feeds, so that the login jwt isn't exposed in feed urls
+CREATE TABLE feed_token (
+    local_user_id int PRIMARY KEY REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE,
+    token text NOT NULL UNIQUE,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
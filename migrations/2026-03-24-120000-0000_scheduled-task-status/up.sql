@@ -0,0 +1,11 @@
+-- Status of the periodic background tasks. Rows are created by the server on startup, the
+-- interval can be changed by admins.
+CREATE TABLE scheduled_task_status (
+    name text PRIMARY KEY,
+    interval_seconds int NOT NULL CHECK (interval_seconds > 0),
+    last_started_at timestamptz,
+    last_duration_ms int,
+    -- Error of the last run, null if it succeeded
+    last_error text,
+    next_run_at timestamptz NOT NULL DEFAULT now()
+);
@@ -0,0 +1,17 @@
+-- Separate rate limits for more actions. Incoming activities are limited per remote instance.
+ALTER TABLE local_site_rate_limit
+    ADD COLUMN vote_max_requests int NOT NULL DEFAULT 100,
+    ADD COLUMN vote_interval_seconds int NOT NULL DEFAULT 60,
+    ADD COLUMN report_max_requests int NOT NULL DEFAULT 5,
+    ADD COLUMN report_interval_seconds int NOT NULL DEFAULT 600,
+    ADD COLUMN follow_max_requests int NOT NULL DEFAULT 20,
+    ADD COLUMN follow_interval_seconds int NOT NULL DEFAULT 600,
+    ADD COLUMN private_message_max_requests int NOT NULL DEFAULT 10,
+    ADD COLUMN private_message_interval_seconds int NOT NULL DEFAULT 600,
+    ADD COLUMN federation_inbox_max_requests int NOT NULL DEFAULT 1000,
+    ADD COLUMN federation_inbox_interval_seconds int NOT NULL DEFAULT 60;
+
+-- Admins can raise the rate limits of trusted users and bots, or exempt them entirely
+ALTER TABLE local_user
+    ADD COLUMN rate_limit_multiplier int NOT NULL DEFAULT 1 CHECK (rate_limit_multiplier >= 1),
+    ADD COLUMN rate_limit_exempt boolean NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+DROP TABLE admin_audit_log;
+
+DROP TYPE admin_audit_kind;
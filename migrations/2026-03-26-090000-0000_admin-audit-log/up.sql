@@ -0,0 +1,42 @@
+-- Changes to site settings and other admin actions which are not covered by the modlog.
+CREATE TYPE admin_audit_kind AS enum (
+    'EditSite',
+    'EditUrlBlocklist',
+    'CreateOauthProvider',
+    'EditOauthProvider',
+    'DeleteOauthProvider',
+    'CreateCustomEmoji',
+    'EditCustomEmoji',
+    'DeleteCustomEmoji',
+    'CreateTagline',
+    'EditTagline',
+    'DeleteTagline',
+    'AllowInstance',
+    'BlockInstance',
+    'SetRateLimitOverride',
+    'EditScheduledTask',
+    'ReloadConfig',
+    'AdminLogin',
+    'SetImageUploadQuota',
+    'BlockImageHash',
+    'UnblockImageHash',
+    'ImportImageHashBlocks'
+);
+
+CREATE TABLE admin_audit_log (
+    id serial PRIMARY KEY,
+    kind admin_audit_kind NOT NULL,
+    -- Kept when the admin account is deleted, so that the log stays complete
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL,
+    -- Identifies the changed object, for example the id of a tagline or the domain of an instance
+    target text,
+    -- Old and new values of the fields which were changed. Only one of them is set for objects
+    -- which were created or deleted.
+    before jsonb,
+    after jsonb,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_admin_audit_log_published ON admin_audit_log (published_at DESC, id DESC);
+
+CREATE INDEX idx_admin_audit_log_admin ON admin_audit_log (admin_person_id);
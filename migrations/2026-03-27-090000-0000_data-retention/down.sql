@@ -0,0 +1,7 @@
+ALTER TABLE local_site
+    DROP COLUMN sent_activity_retention_days,
+    DROP COLUMN received_activity_retention_days,
+    DROP COLUMN login_ip_retention_days,
+    DROP COLUMN post_read_retention_days,
+    DROP COLUMN notification_retention_days,
+    DROP COLUMN remote_content_retention_days;
@@ -0,0 +1,9 @@
+-- Retention periods in days for data which is regularly purged by scheduled tasks. Nullable
+-- columns mean that the data is kept forever.
+ALTER TABLE local_site
+    ADD COLUMN sent_activity_retention_days int NOT NULL DEFAULT 7,
+    ADD COLUMN received_activity_retention_days int NOT NULL DEFAULT 7,
+    ADD COLUMN login_ip_retention_days int,
+    ADD COLUMN post_read_retention_days int,
+    ADD COLUMN notification_retention_days int,
+    ADD COLUMN remote_content_retention_days int;
@@ -0,0 +1,13 @@
+-- Background jobs which build a downloadable archive with all data of a local user.
+CREATE TABLE data_export (
+    id serial PRIMARY KEY,
+    local_user_id int NOT NULL REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE,
+    published_at timestamptz NOT NULL DEFAULT now(),
+    -- Set once the archive is written, or once the job failed
+    completed_at timestamptz,
+    error text
+);
+
+CREATE INDEX idx_data_export_local_user ON data_export (local_user_id, published_at DESC);
+
+CREATE INDEX idx_data_export_published ON data_export (published_at);
@@ -0,0 +1,2 @@
+ALTER TABLE local_user
+    DROP COLUMN last_email_digest_notification_id;
@@ -0,0 +1,4 @@
+-- Together with last_email_digest_at, the last notification included in a digest. Needed so that
+-- notifications published at the same time are neither skipped nor sent twice.
+ALTER TABLE local_user
+    ADD COLUMN last_email_digest_notification_id int;